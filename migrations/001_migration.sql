@@ -1,6 +1,7 @@
 
 CREATE EXTENSION IF NOT EXISTS "uuid-ossp";
 CREATE EXTENSION postgis;
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
 
 
 CREATE TYPE "data_source_type" AS ENUM (
@@ -91,6 +92,12 @@ CREATE TYPE fulfillment_type AS ENUM (
   'self_pickup'
 );
 
+CREATE TYPE catalog_inc_mode_type AS ENUM (
+  'pull',
+  'push_start',
+  'push_stop'
+);
+
 CREATE TABLE IF NOT EXISTS search_request (
   id SERIAL NOT NULL PRIMARY KEY,
   message_id uuid NOT NULL,
@@ -104,7 +111,8 @@ CREATE TABLE IF NOT EXISTS search_request (
   payment_type payment_type,
   domain_category_code TEXT NOT NULL,
   search_type product_search_type NOT NULL,
-  fulfillment_type fulfillment_type
+  fulfillment_type fulfillment_type,
+  catalog_inc_mode catalog_inc_mode_type
 );
 CREATE INDEX idx_search_request_message_txn ON search_request(message_id, transaction_id);
 CREATE INDEX search_request_created_on_idx ON search_request (created_on);
@@ -493,6 +501,7 @@ CREATE TABLE IF NOT EXISTS commerce_data_line(
   refunded_discount_amount DECIMAL(20, 2),
   refunded_gross_total DECIMAL(20, 2),
   unit_price DECIMAL(20, 3) NOT NULL DEFAULT 0.0,
+  expected_unit_price DECIMAL(20, 3),
   gross_total DECIMAL(20, 3) NOT NULL DEFAULT 0.0,
   available_qty DECIMAL(20, 2),
   discount_amount DECIMAL(20, 2) NOT NULL DEFAULT 0.0,
@@ -612,7 +621,8 @@ CREATE TABLE IF NOT EXISTS commerce_payment_data(
   withholding_amount DECIMAL(20, 3),
   settlement_basis settlement_basis_type,
   settlement_details JSONB,
-  seller_payment_detail JSONB
+  seller_payment_detail JSONB,
+  last_reconciled_on TIMESTAMPTZ
 );
 ALTER TABLE commerce_payment_data ADD CONSTRAINT commerce_payment_fk FOREIGN KEY ("commerce_data_id") REFERENCES commerce_data ("id") ON DELETE CASCADE;
 CREATE INDEX commerce_payment_data_id_idx ON commerce_payment_data (commerce_data_id);
@@ -718,8 +728,10 @@ CREATE TABLE IF NOT EXISTS provider_cache(
   terms JSONB,
   identifications JSONB,
   created_on TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
-  updated_on TIMESTAMPTZ
+  updated_on TIMESTAMPTZ,
+  expires_on TIMESTAMPTZ
 );
+CREATE INDEX IF NOT EXISTS provider_cache_expires_on_idx ON provider_cache (expires_on);
 ALTER TABLE provider_cache ADD CONSTRAINT provider_cache_constraint UNIQUE (network_participant_cache_id, provider_id);
 ALTER TABLE provider_cache ADD CONSTRAINT provider_cache_fk FOREIGN KEY ("network_participant_cache_id") REFERENCES network_participant_cache("id") ON DELETE CASCADE;
 
@@ -889,6 +901,8 @@ CREATE TABLE IF NOT EXISTS provider_item_cache (
 ALTER TABLE provider_item_cache ADD CONSTRAINT item_cache_constraint UNIQUE (provider_cache_id, country_code, domain_code, item_id);
 ALTER TABLE provider_item_cache ADD CONSTRAINT item_cache_variant_fk FOREIGN KEY ("variant_cache_id") REFERENCES provider_item_variant_cache("id") ON DELETE CASCADE;
 ALTER TABLE provider_item_cache ADD CONSTRAINT item_cache_constraint_fk FOREIGN KEY ("provider_cache_id") REFERENCES provider_cache("id") ON DELETE CASCADE;
+CREATE INDEX provider_item_cache_item_name_trgm_idx ON provider_item_cache USING GIN (item_name gin_trgm_ops);
+CREATE INDEX provider_item_cache_short_desc_trgm_idx ON provider_item_cache USING GIN (short_desc gin_trgm_ops);
 
 
 
@@ -902,6 +916,24 @@ CREATE TABLE IF NOT EXISTS item_location_cache_relationship(
 ALTER TABLE item_location_cache_relationship ADD CONSTRAINT product_location_cache_relationship_constraint UNIQUE (item_cache_id, location_cache_id);
 ALTER TABLE item_location_cache_relationship ADD CONSTRAINT product_fk FOREIGN KEY ("item_cache_id") REFERENCES provider_item_cache("id") ON DELETE CASCADE;
 ALTER TABLE item_location_cache_relationship ADD CONSTRAINT location_fk FOREIGN KEY ("location_cache_id") REFERENCES provider_location_cache("id") ON DELETE CASCADE;
+CREATE INDEX item_location_cache_relationship_location_idx ON item_location_cache_relationship (location_cache_id);
+
+
+CREATE TABLE IF NOT EXISTS provider_item_price_history(
+      id uuid PRIMARY KEY,
+      subscriber_id TEXT NOT NULL,
+      provider_id TEXT NOT NULL,
+      item_id TEXT NOT NULL,
+      currency currency_code_type NOT NULL,
+      price_with_tax DECIMAL(20, 3) NOT NULL,
+      price_without_tax DECIMAL(20, 3) NOT NULL,
+      offered_price DECIMAL(20, 3),
+      maximum_price DECIMAL(20, 3) NOT NULL,
+      available_qty INTEGER NOT NULL,
+      created_on TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX provider_item_price_history_item_idx ON provider_item_price_history (subscriber_id, provider_id, item_id, created_on DESC);
 
 
 CREATE TABLE IF NOT EXISTS subscribed_search_location (
@@ -909,13 +941,408 @@ CREATE TABLE IF NOT EXISTS subscribed_search_location (
     city_code TEXT NOT NULL,
     country_code country_code_type NOT NULL,
     created_on TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
-    domain_category_code domain_category_type NOT NULL
+    domain_category_code domain_category_type NOT NULL,
+    last_refreshed_on TIMESTAMPTZ
 );
 
 ALTER TABLE subscribed_search_location ADD CONSTRAINT sub_city_code_uq UNIQUE (country_code, city_code, domain_category_code);
 
 
 
+CREATE TYPE quote_version_source AS ENUM (
+  'buyer',
+  'seller'
+);
+
+CREATE TABLE IF NOT EXISTS commerce_quote_version (
+    id uuid PRIMARY KEY,
+    transaction_id uuid NOT NULL,
+    message_id uuid NOT NULL,
+    version INT NOT NULL,
+    source quote_version_source NOT NULL,
+    items JSONB NOT NULL,
+    currency_code currency_code_type,
+    grand_total DECIMAL(20, 3),
+    created_on TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    created_by uuid
+);
+
+ALTER TABLE commerce_quote_version ADD CONSTRAINT commerce_quote_version_uq UNIQUE (transaction_id, version);
+
+CREATE TYPE recurring_order_frequency AS ENUM (
+  'daily',
+  'weekly',
+  'monthly'
+);
+
+CREATE TYPE recurring_order_run_status AS ENUM (
+  'select_sent',
+  'pending_approval',
+  'init_sent',
+  'confirm_sent',
+  'completed',
+  'rejected',
+  'failed'
+);
+
+CREATE TABLE IF NOT EXISTS recurring_order_template (
+    id uuid PRIMARY KEY,
+    name TEXT NOT NULL,
+    business_id uuid NOT NULL,
+    user_id uuid NOT NULL,
+    domain_category_code domain_category_type NOT NULL,
+    order_type commerce_data_type NOT NULL,
+    bpp_id TEXT NOT NULL,
+    provider_id TEXT NOT NULL,
+    payment_type payment_type NOT NULL,
+    ttl TEXT NOT NULL,
+    items JSONB NOT NULL,
+    fulfillments JSONB NOT NULL,
+    billing JSONB NOT NULL,
+    frequency recurring_order_frequency NOT NULL,
+    frequency_interval INT NOT NULL,
+    next_run_on TIMESTAMPTZ NOT NULL,
+    price_tolerance DECIMAL(5, 2) NOT NULL,
+    is_active BOOLEAN NOT NULL DEFAULT true,
+    created_on TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    created_by uuid NOT NULL,
+    updated_on TIMESTAMPTZ,
+    updated_by uuid
+);
+
+CREATE INDEX recurring_order_template_next_run_idx ON recurring_order_template (next_run_on) WHERE is_active;
+
+CREATE TABLE IF NOT EXISTS recurring_order_run (
+    id uuid PRIMARY KEY,
+    template_id uuid NOT NULL REFERENCES recurring_order_template(id) ON DELETE CASCADE,
+    transaction_id uuid NOT NULL,
+    status recurring_order_run_status NOT NULL,
+    remark TEXT,
+    quote_total DECIMAL(20, 3),
+    scheduled_on TIMESTAMPTZ NOT NULL,
+    created_on TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_on TIMESTAMPTZ
+);
+
+ALTER TABLE recurring_order_run ADD CONSTRAINT recurring_order_run_uq UNIQUE (transaction_id);
+CREATE INDEX recurring_order_run_template_idx ON recurring_order_run (template_id, created_on);
+
+CREATE TYPE webhook_event_type AS ENUM (
+  'quote_received',
+  'order_initialized',
+  'order_accepted',
+  'fulfillment_status_changed',
+  'order_updated',
+  'order_cancelled',
+  'payment_updated',
+  'refund_updated'
+);
+
+CREATE TYPE webhook_delivery_status AS ENUM (
+  'pending',
+  'delivered',
+  'failed'
+);
+
+CREATE TABLE IF NOT EXISTS webhook_subscription (
+    id uuid PRIMARY KEY,
+    business_id uuid NOT NULL,
+    url TEXT NOT NULL,
+    secret TEXT NOT NULL,
+    event_types webhook_event_type[] NOT NULL,
+    is_active BOOLEAN NOT NULL DEFAULT true,
+    created_on TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    created_by uuid NOT NULL,
+    updated_on TIMESTAMPTZ,
+    updated_by uuid
+);
+
+CREATE INDEX webhook_subscription_business_idx ON webhook_subscription (business_id) WHERE is_active;
+
+CREATE TABLE IF NOT EXISTS webhook_delivery (
+    id uuid PRIMARY KEY,
+    subscription_id uuid NOT NULL REFERENCES webhook_subscription(id) ON DELETE CASCADE,
+    event_id uuid NOT NULL,
+    event_type webhook_event_type NOT NULL,
+    payload JSONB NOT NULL,
+    status webhook_delivery_status NOT NULL,
+    attempt_count INT NOT NULL DEFAULT 0,
+    next_attempt_on TIMESTAMPTZ NOT NULL,
+    last_attempted_on TIMESTAMPTZ,
+    response_status INT,
+    remark TEXT,
+    delivered_on TIMESTAMPTZ,
+    created_on TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX webhook_delivery_pending_idx ON webhook_delivery (next_attempt_on) WHERE status = 'pending';
+CREATE INDEX webhook_delivery_subscription_idx ON webhook_delivery (subscription_id, created_on);
+
+CREATE TYPE commerce_settlement_status AS ENUM (
+  'pending',
+  'recon_sent',
+  'reconciled',
+  'disputed',
+  'settled'
+);
+
+CREATE TABLE IF NOT EXISTS commerce_settlement (
+    id uuid PRIMARY KEY,
+    commerce_data_id uuid NOT NULL REFERENCES commerce_data(id) ON DELETE CASCADE,
+    commerce_payment_id uuid NOT NULL,
+    buyer_id uuid NOT NULL,
+    bpp_id TEXT NOT NULL,
+    provider_id TEXT NOT NULL,
+    currency_code currency_code_type NOT NULL,
+    order_amount DECIMAL(20, 3) NOT NULL,
+    buyer_finder_fee DECIMAL(20, 3) NOT NULL,
+    withholding_amount DECIMAL(20, 3) NOT NULL,
+    settlement_amount DECIMAL(20, 3) NOT NULL,
+    settlement_basis settlement_basis_type,
+    settlement_window TEXT,
+    status commerce_settlement_status NOT NULL,
+    recon_transaction_id uuid,
+    recon_message_id uuid,
+    recon_sent_on TIMESTAMPTZ,
+    recon_accord BOOLEAN,
+    diff_amount DECIMAL(20, 3),
+    remark TEXT,
+    settlement_reference TEXT,
+    settled_on TIMESTAMPTZ,
+    created_on TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    created_by uuid NOT NULL,
+    updated_on TIMESTAMPTZ,
+    updated_by uuid
+);
+
+ALTER TABLE commerce_settlement ADD CONSTRAINT commerce_settlement_uq UNIQUE (commerce_data_id);
+CREATE INDEX commerce_settlement_recon_idx ON commerce_settlement (recon_transaction_id);
+CREATE INDEX commerce_settlement_buyer_idx ON commerce_settlement (buyer_id, status);
+
+CREATE TYPE commerce_refund_status AS ENUM (
+  'initiated',
+  'processed',
+  'failed'
+);
+
+CREATE TYPE commerce_refund_source AS ENUM (
+  'cancel',
+  'return'
+);
+
+CREATE TABLE IF NOT EXISTS commerce_refund (
+    id uuid PRIMARY KEY,
+    commerce_data_id uuid NOT NULL REFERENCES commerce_data(id) ON DELETE CASCADE,
+    payment_id TEXT NOT NULL,
+    payment_order_id TEXT,
+    source commerce_refund_source NOT NULL,
+    reference_id TEXT NOT NULL,
+    reason TEXT,
+    currency_code currency_code_type NOT NULL,
+    amount DECIMAL(20, 3) NOT NULL,
+    gateway_refund_id TEXT,
+    status commerce_refund_status NOT NULL,
+    remark TEXT,
+    processed_on TIMESTAMPTZ,
+    created_on TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    created_by uuid NOT NULL,
+    updated_on TIMESTAMPTZ
+);
+
+ALTER TABLE commerce_refund ADD CONSTRAINT commerce_refund_uq UNIQUE (commerce_data_id, source, reference_id);
+CREATE INDEX commerce_refund_gateway_idx ON commerce_refund (gateway_refund_id);
+
+CREATE TYPE commerce_payable_status AS ENUM (
+  'open',
+  'paid',
+  'cancelled'
+);
+
+CREATE TABLE IF NOT EXISTS commerce_payable (
+    id uuid PRIMARY KEY,
+    commerce_data_id uuid NOT NULL REFERENCES commerce_data(id) ON DELETE CASCADE,
+    buyer_id uuid NOT NULL,
+    currency_code currency_code_type NOT NULL,
+    amount DECIMAL(20, 3) NOT NULL,
+    settlement_basis settlement_basis_type,
+    settlement_window TEXT,
+    basis_on TIMESTAMPTZ,
+    due_on TIMESTAMPTZ,
+    status commerce_payable_status NOT NULL,
+    paid_on TIMESTAMPTZ,
+    payment_reference TEXT,
+    reminder_count INT NOT NULL DEFAULT 0,
+    last_reminder_on TIMESTAMPTZ,
+    created_on TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    created_by uuid NOT NULL,
+    updated_on TIMESTAMPTZ,
+    updated_by uuid
+);
+
+ALTER TABLE commerce_payable ADD CONSTRAINT commerce_payable_uq UNIQUE (commerce_data_id);
+CREATE INDEX commerce_payable_buyer_idx ON commerce_payable (buyer_id, status, due_on);
+
+CREATE TABLE IF NOT EXISTS payment_notification_quarantine (
+    id uuid PRIMARY KEY,
+    commerce_data_id uuid NOT NULL REFERENCES commerce_data(id) ON DELETE CASCADE,
+    payment_order_id TEXT NOT NULL,
+    payment_id TEXT NOT NULL,
+    reason TEXT NOT NULL,
+    payload JSONB NOT NULL,
+    created_on TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX payment_notification_quarantine_order_idx ON payment_notification_quarantine (commerce_data_id);
+
+CREATE TYPE commerce_payment_order_status AS ENUM (
+  'active',
+  'cancelled'
+);
+
+CREATE TABLE IF NOT EXISTS commerce_payment_order (
+    id uuid PRIMARY KEY,
+    commerce_data_id uuid NOT NULL REFERENCES commerce_data(id) ON DELETE CASCADE,
+    payment_order_id TEXT NOT NULL,
+    payment_type payment_type NOT NULL,
+    currency_code currency_code_type NOT NULL,
+    amount DECIMAL(20, 3) NOT NULL,
+    payment_link TEXT,
+    status commerce_payment_order_status NOT NULL,
+    expires_on TIMESTAMPTZ NOT NULL,
+    cancelled_on TIMESTAMPTZ,
+    created_on TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    created_by uuid NOT NULL
+);
+
+ALTER TABLE commerce_payment_order ADD CONSTRAINT commerce_payment_order_uq UNIQUE (payment_order_id);
+CREATE INDEX commerce_payment_order_commerce_idx ON commerce_payment_order (commerce_data_id);
+
+CREATE TYPE catalog_alert_channel AS ENUM (
+  'email',
+  'chat',
+  'websocket'
+);
+
+CREATE TYPE catalog_alert_type AS ENUM (
+  'new_item',
+  'price_drop',
+  'back_in_stock'
+);
+
+CREATE INDEX provider_item_cache_created_on_idx ON provider_item_cache (created_on);
+CREATE INDEX provider_item_cache_updated_on_idx ON provider_item_cache (updated_on);
+
+CREATE TABLE IF NOT EXISTS saved_search (
+    id uuid PRIMARY KEY,
+    business_id uuid NOT NULL,
+    user_id uuid NOT NULL,
+    name TEXT NOT NULL,
+    search_request JSONB NOT NULL,
+    channels catalog_alert_channel[] NOT NULL,
+    is_active BOOLEAN NOT NULL DEFAULT true,
+    cache_refreshed_on TIMESTAMPTZ,
+    created_on TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_on TIMESTAMPTZ
+);
+
+CREATE INDEX saved_search_user_idx ON saved_search (business_id, user_id);
+CREATE INDEX saved_search_due_idx ON saved_search (cache_refreshed_on) WHERE is_active;
+
+CREATE TABLE IF NOT EXISTS saved_search_item (
+    saved_search_id uuid NOT NULL REFERENCES saved_search(id) ON DELETE CASCADE,
+    subscriber_id TEXT NOT NULL,
+    provider_id TEXT NOT NULL,
+    item_id TEXT NOT NULL,
+    created_on TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+ALTER TABLE saved_search_item ADD CONSTRAINT saved_search_item_uq UNIQUE (saved_search_id, subscriber_id, provider_id, item_id);
+
+CREATE TABLE IF NOT EXISTS item_watch (
+    id uuid PRIMARY KEY,
+    business_id uuid NOT NULL,
+    user_id uuid NOT NULL,
+    subscriber_id TEXT NOT NULL,
+    provider_id TEXT NOT NULL,
+    item_id TEXT NOT NULL,
+    price_threshold DECIMAL(20, 3),
+    notify_on_available BOOLEAN NOT NULL,
+    channels catalog_alert_channel[] NOT NULL,
+    is_active BOOLEAN NOT NULL DEFAULT true,
+    last_evaluated_on TIMESTAMPTZ NOT NULL,
+    created_on TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_on TIMESTAMPTZ
+);
+
+ALTER TABLE item_watch ADD CONSTRAINT item_watch_uq UNIQUE (user_id, subscriber_id, provider_id, item_id);
+CREATE INDEX item_watch_item_idx ON item_watch (subscriber_id, provider_id, item_id) WHERE is_active;
+
+CREATE TABLE IF NOT EXISTS catalog_alert (
+    id uuid PRIMARY KEY,
+    business_id uuid NOT NULL,
+    user_id uuid NOT NULL,
+    alert_type catalog_alert_type NOT NULL,
+    saved_search_id uuid REFERENCES saved_search(id) ON DELETE CASCADE,
+    item_watch_id uuid REFERENCES item_watch(id) ON DELETE CASCADE,
+    subscriber_id TEXT NOT NULL,
+    provider_id TEXT NOT NULL,
+    item_id TEXT NOT NULL,
+    item_name TEXT,
+    currency currency_code_type NOT NULL,
+    price DECIMAL(20, 3) NOT NULL,
+    previous_price DECIMAL(20, 3),
+    dedup_key TEXT NOT NULL,
+    created_on TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+ALTER TABLE catalog_alert ADD CONSTRAINT catalog_alert_dedup_uq UNIQUE (dedup_key);
+CREATE INDEX catalog_alert_user_idx ON catalog_alert (business_id, user_id, created_on DESC);
+
+CREATE TYPE supplier_preference_type AS ENUM (
+  'preferred',
+  'blocked'
+);
+
+CREATE TABLE IF NOT EXISTS favourite_item (
+    id uuid PRIMARY KEY,
+    business_id uuid NOT NULL,
+    user_id uuid NOT NULL,
+    subscriber_id TEXT NOT NULL,
+    provider_id TEXT NOT NULL,
+    item_id TEXT NOT NULL,
+    created_on TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+ALTER TABLE favourite_item ADD CONSTRAINT favourite_item_uq UNIQUE (business_id, user_id, subscriber_id, provider_id, item_id);
+
+CREATE TABLE IF NOT EXISTS favourite_provider (
+    id uuid PRIMARY KEY,
+    business_id uuid NOT NULL,
+    user_id uuid NOT NULL,
+    subscriber_id TEXT NOT NULL,
+    provider_id TEXT NOT NULL,
+    created_on TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+ALTER TABLE favourite_provider ADD CONSTRAINT favourite_provider_uq UNIQUE (business_id, user_id, subscriber_id, provider_id);
+
+CREATE TABLE IF NOT EXISTS supplier_preference (
+    id uuid PRIMARY KEY,
+    business_id uuid NOT NULL,
+    subscriber_id TEXT NOT NULL,
+    provider_id TEXT NOT NULL,
+    preference supplier_preference_type NOT NULL,
+    remark TEXT,
+    created_on TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    created_by uuid NOT NULL,
+    updated_on TIMESTAMPTZ,
+    updated_by uuid
+);
+
+ALTER TABLE supplier_preference ADD CONSTRAINT supplier_preference_uq UNIQUE (business_id, subscriber_id, provider_id);
+
+
+
 -- CREATE TABLE IF NOT EXISTS  buyer_order_status_history(
 --   id uuid PRIMARY KEY,
 --   order_id TEXT NOT NULL,
@@ -1,4 +1,4 @@
-use crate::routes::product;
+use crate::routes::{payment, product};
 use crate::utils::generate_user_token;
 use crate::{elastic_search_client, kafka_client, migration};
 #[tracing::instrument(name = "Run custom command")]
@@ -25,14 +25,27 @@ pub async fn run_custom_commands(args: Vec<String>) -> Result<(), anyhow::Error>
             kafka_client::create_kafka_topic_command().await;
         }
         "generate_elastic_search_indices" => {
-            elastic_search_client::generate_indices().await;
+            let outdated_indices = elastic_search_client::generate_indices().await;
+            product::utils::reindex_elastic_search(outdated_indices).await?;
+        }
+        "reindex_elastic_search" => {
+            product::utils::reindex_elastic_search(
+                elastic_search_client::ElasticSearchIndex::all().to_vec(),
+            )
+            .await?;
         }
         "generate_item_cache" => {
-            product::utils::generate_cache().await?;
+            product::utils::generate_cache(false).await?;
+        }
+        "refresh_item_cache" => {
+            product::utils::generate_cache(true).await?;
         }
         "regenerate_item_cache" => {
             product::utils::regenerate_cache_to_es().await?;
         }
+        "reconcile_pending_payments" => {
+            payment::utils::reconcile_pending_payments_command().await?;
+        }
         _ => {
             eprintln!("Unknown command: {}. Please use a valid command.", command);
         }
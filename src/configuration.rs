@@ -1,3 +1,4 @@
+use crate::elastic_search_client::{SearchBackendType, SearchRankingProfile};
 use crate::{
     chat_client::ChatClient, domain::EmailObject, elastic_search_client::ElasticSearchClient,
     email_client::SmtpEmailClient, kafka_client::KafkaClient, payment_client::PaymentClient,
@@ -7,6 +8,7 @@ use config::{self, ConfigError, Environment};
 use secrecy::{ExposeSecret, SecretString};
 use serde::Deserialize;
 use sqlx::{postgres::PgConnectOptions, ConnectOptions};
+use std::collections::HashMap;
 use uuid::Uuid;
 
 #[derive(Debug, Deserialize, Clone)]
@@ -80,6 +82,10 @@ pub struct Config {
     pub chat: ChatConfig,
     pub kafka: KafkaConfig,
     pub elastic_search: ElasticSearchConfig,
+    #[serde(default)]
+    pub search: SearchConfig,
+    #[serde(default)]
+    pub search_ranking: SearchRankingProfile,
     pub payment: PaymentConfig,
     pub secret: SecretConfig,
 }
@@ -155,12 +161,15 @@ pub struct PaymentConfig {
     token: SecretString,
     base_url: String,
     timeout_milliseconds: u64,
+    /// Webhook signing secrets keyed by payment service id (`PAYMENT__WEBHOOK_SECRETS__<ID>`).
+    #[serde(default)]
+    webhook_secrets: HashMap<String, SecretString>,
 }
 
 impl PaymentConfig {
     pub fn client(self) -> PaymentClient {
         let timeout = self.timeout();
-        PaymentClient::new(self.base_url, self.token, timeout)
+        PaymentClient::new(self.base_url, self.token, timeout, self.webhook_secrets)
     }
     fn timeout(&self) -> std::time::Duration {
         std::time::Duration::from_millis(self.timeout_milliseconds)
@@ -384,3 +393,9 @@ impl ElasticSearchConfig {
         ElasticSearchClient::new(self.url, self.username, self.password, self.env)
     }
 }
+
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct SearchConfig {
+    pub backend: SearchBackendType,
+}
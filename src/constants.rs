@@ -1,6 +1,31 @@
 pub const ONDC_TTL: &str = "PT30S";
 // pub const TEST_DB: &str = "ondc_b2b_buyer";
 pub const DUMMY_DOMAIN: &str = "abc.co";
+pub const RECURRING_ORDER_SCHEDULER_INTERVAL: u64 = 60;
+pub const RECURRING_ORDER_BATCH_SIZE: i64 = 50;
+pub const RECURRING_ORDER_RUN_TIMEOUT_MINUTES: i64 = 30;
+pub const WEBHOOK_DELIVERY_INTERVAL: u64 = 10;
+pub const WEBHOOK_DELIVERY_BATCH_SIZE: i64 = 50;
+pub const WEBHOOK_DELIVERY_TIMEOUT: u64 = 10;
+pub const WEBHOOK_DELIVERY_MAX_ATTEMPTS: i32 = 8;
+pub const WEBHOOK_RETRY_BASE_DELAY: i64 = 30;
+pub const PAYABLE_REMINDER_INTERVAL: u64 = 3600;
+pub const PAYABLE_REMINDER_BATCH_SIZE: i64 = 50;
+pub const PAYABLE_DUE_SOON_DAYS: i64 = 3;
+pub const PAYABLE_REMINDER_GAP_HOURS: i64 = 24;
+pub const PAYMENT_RECONCILE_INTERVAL: u64 = 300;
+pub const PAYMENT_RECONCILE_BATCH_SIZE: i64 = 50;
+pub const PAYMENT_RECONCILE_GRACE_MINUTES: i64 = 15;
+pub const PAYMENT_WEBHOOK_TOLERANCE_SECONDS: i64 = 300;
+pub const PAYMENT_ORDER_EXPIRY_MINUTES: i64 = 30;
+pub const SEARCH_FACET_SIZE: i64 = 50;
+pub const ATTRIBUTE_FACET_PREFIX: &str = "attributes:";
+pub const ITEM_PRICE_HISTORY_MAX_LIMIT: i64 = 500;
+pub const CATALOG_CACHE_EXPIRY_INTERVAL: u64 = 300;
+pub const CATALOG_ALERT_INTERVAL: u64 = 300;
+pub const CATALOG_ALERT_BATCH_SIZE: i64 = 50;
+pub const CATALOG_ALERT_MAX_PAGES: usize = 100;
+pub const SEARCH_PRICE_FACET_BOUNDS: [f64; 6] = [100.0, 500.0, 1000.0, 5000.0, 10000.0, 50000.0];
 use lazy_static::lazy_static;
 use regex::Regex;
 
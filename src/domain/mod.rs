@@ -1,2 +1,3 @@
+pub mod pricing;
 pub mod subscriber_email;
 pub use subscriber_email::EmailObject;
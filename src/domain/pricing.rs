@@ -0,0 +1,31 @@
+use bigdecimal::BigDecimal;
+use lazy_static::lazy_static;
+
+lazy_static! {
+    /// Tolerance used while comparing a quoted unit price against the slab price.
+    static ref PRICE_SLAB_TOLERANCE: BigDecimal = BigDecimal::new(1.into(), 2);
+}
+
+pub trait PriceSlab {
+    fn min_qty(&self) -> &BigDecimal;
+    fn max_qty(&self) -> Option<&BigDecimal>;
+}
+
+/// Returns the slab applicable for the quantity.
+/// When seller slabs overlap, the one with the highest minimum quantity wins.
+pub fn get_applicable_price_slab<'a, T: PriceSlab>(
+    slabs: &'a [T],
+    qty: &BigDecimal,
+) -> Option<&'a T> {
+    slabs
+        .iter()
+        .filter(|slab| qty >= slab.min_qty() && slab.max_qty().is_none_or(|max| qty <= max))
+        .max_by(|a, b| a.min_qty().cmp(b.min_qty()))
+}
+
+pub fn is_slab_price_ignored(
+    expected_unit_price: &BigDecimal,
+    quoted_unit_price: &BigDecimal,
+) -> bool {
+    (expected_unit_price - quoted_unit_price).abs() > *PRICE_SLAB_TOLERANCE
+}
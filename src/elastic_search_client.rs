@@ -9,8 +9,14 @@ use elasticsearch::http::request::JsonBody;
 use elasticsearch::http::transport::SingleNodeConnectionPool;
 use elasticsearch::http::transport::TransportBuilder;
 use elasticsearch::indices::IndicesCreateParts;
+use elasticsearch::indices::IndicesDeleteParts;
 use elasticsearch::indices::IndicesExistsParts;
+use elasticsearch::indices::IndicesGetAliasParts;
+use elasticsearch::indices::IndicesGetMappingParts;
+use elasticsearch::indices::IndicesGetParts;
+use elasticsearch::indices::IndicesRefreshParts;
 use elasticsearch::BulkParts;
+use elasticsearch::CountParts;
 use elasticsearch::DeleteByQueryParts;
 use elasticsearch::Elasticsearch;
 use elasticsearch::SearchParts;
@@ -23,9 +29,41 @@ use serde::Deserialize;
 use serde::Serialize;
 use serde_json::json;
 use serde_json::Value;
+use sha2::{Digest, Sha256};
 use uuid::Uuid;
 
-#[derive(Debug, Deserialize, Serialize, Hash, PartialEq, Eq)]
+#[derive(Debug, Deserialize, Default, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchBackendType {
+    #[default]
+    ElasticSearch,
+    Postgres,
+}
+
+/// Weights of the cached item search relevance ranking.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct SearchRankingProfile {
+    pub distance_weight: f64,
+    pub distance_scale_km: f64,
+    pub rating_factor: f64,
+    pub recommended_boost: f64,
+    pub preferred_supplier_boost: f64,
+}
+
+impl Default for SearchRankingProfile {
+    fn default() -> Self {
+        Self {
+            distance_weight: 2.0,
+            distance_scale_km: 10.0,
+            rating_factor: 1.0,
+            recommended_boost: 1.5,
+            preferred_supplier_boost: 10.0,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Hash, PartialEq, Eq, Clone, Copy)]
 pub enum ElasticSearchIndex {
     ProviderServicabilityHyperLocal,
     ProviderServicabilityCountry,
@@ -38,6 +76,22 @@ pub enum ElasticSearchIndex {
     ProviderItem,
 }
 
+impl ElasticSearchIndex {
+    pub fn all() -> [ElasticSearchIndex; 9] {
+        [
+            ElasticSearchIndex::ProviderServicabilityHyperLocal,
+            ElasticSearchIndex::ProviderServicabilityCountry,
+            ElasticSearchIndex::ProviderServicabilityInterCity,
+            ElasticSearchIndex::ProviderServicabilityGeoJson,
+            ElasticSearchIndex::NetworkParticipant,
+            ElasticSearchIndex::ProviderLocation,
+            ElasticSearchIndex::Provider,
+            ElasticSearchIndex::ProviderItemVariant,
+            ElasticSearchIndex::ProviderItem,
+        ]
+    }
+}
+
 impl fmt::Display for ElasticSearchIndex {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> std::fmt::Result {
         let index_name = match self {
@@ -305,6 +359,20 @@ lazy_static! {
               "mappings": {
                 "dynamic": false,
                 "properties": {
+                  "attributes": {
+                    "type": "nested",
+                    "properties": {
+                      "key": {
+                        "type": "keyword"
+                      },
+                      "label": {
+                        "type": "keyword"
+                      },
+                      "value": {
+                        "type": "keyword"
+                      }
+                    }
+                  },
                   "cancellation_terms": {
                     "type": "object"
                   },
@@ -398,6 +466,12 @@ lazy_static! {
                   "location_ids": {
                     "type": "keyword"
                   },
+                  "location_points": {
+                    "type": "geo_point"
+                  },
+                  "variant_cache_id": {
+                    "type": "keyword"
+                  },
                   "long_desc": {
                     "type": "text"
                   },
@@ -441,6 +515,9 @@ lazy_static! {
                       }
                     }
                   },
+                  "provider_rating": {
+                    "type": "float"
+                  },
                   "network_participant_cache_id": {
                     "type": "text",
                     "fields": {
@@ -513,21 +590,30 @@ impl ElasticSearchClient {
         index: ElasticSearchIndex,
         records: Vec<T>,
         get_id: impl Fn(&T) -> Uuid,
+    ) -> Result<(), anyhow::Error> {
+        self.add_to_index(&self.get_index(&index.to_string()), records, get_id)
+            .await
+    }
+    pub async fn add_to_index<T: Serialize>(
+        &self,
+        index_name: &str,
+        records: Vec<T>,
+        get_id: impl Fn(&T) -> Uuid,
     ) -> Result<(), anyhow::Error> {
         if !records.is_empty() {
             let json_values: Vec<JsonBody<_>> = records
-            .into_iter()
-            .flat_map(|record| {
-                let id = get_id(&record).to_string(); // Extract ID
-                vec![
-                    json!({"index": { "_index": self.get_index(&index.to_string()), "_id": id }}).into(),
-                    json!(record).into(),
-                ]
-            })
-            .collect();
+                .into_iter()
+                .flat_map(|record| {
+                    let id = get_id(&record).to_string(); // Extract ID
+                    vec![
+                        json!({"index": { "_index": index_name, "_id": id }}).into(),
+                        json!(record).into(),
+                    ]
+                })
+                .collect();
             let response = self
                 .client
-                .bulk(BulkParts::Index(&self.get_index(&index.to_string())))
+                .bulk(BulkParts::Index(index_name))
                 .body(json_values)
                 .send()
                 .await?;
@@ -562,80 +648,296 @@ impl ElasticSearchClient {
         Ok(response_body)
     }
 
-    async fn generate_indices(&self) -> Result<(), anyhow::Error> {
-        for (index_name, mapping_json) in INDICES.iter() {
-            let full_index_name = self.get_index(&index_name.to_string()); // Add environment prefix
-
-            let exists_response = self
-                .client
-                .indices()
-                .exists(IndicesExistsParts::Index(&[&full_index_name]))
-                .send()
-                .await?;
-
-            if exists_response.status_code() != 200 {
-                let create_response = self
-                    .client
-                    .indices()
-                    .create(IndicesCreateParts::Index(&full_index_name))
-                    .body(mapping_json)
-                    .send()
-                    .await?;
-
-                if create_response.status_code() != 200 {
-                    let body: Value = create_response.json().await?;
-                    tracing::error!("Response body: {:?}", body);
-                    return Err(anyhow!("Failed to create index"));
+    /// Creates the first version of every missing index behind its alias and
+    /// returns the indices whose live mapping differs from `INDICES`.
+    async fn generate_indices(&self) -> Result<Vec<ElasticSearchIndex>, anyhow::Error> {
+        let mut outdated_indices = vec![];
+        for (index, mapping_json) in INDICES.iter() {
+            let alias_name = self.get_index(&index.to_string()); // Add environment prefix
+            let live_indices = self.get_alias_indices(&alias_name).await?;
+            if let Some(live_index) = live_indices.first() {
+                if self.get_index_mapping_hash(live_index).await?
+                    != Some(get_mapping_hash(mapping_json))
+                {
+                    tracing::info!("Mapping of index '{}' has changed", alias_name);
+                    outdated_indices.push(*index);
                 } else {
-                    tracing::info!("Index '{}' created with mapping", full_index_name);
+                    tracing::info!("Index '{}' already exists", alias_name);
                 }
+            } else if self.index_exists(&alias_name).await? {
+                tracing::info!("Index '{}' is not versioned", alias_name);
+                outdated_indices.push(*index);
             } else {
-                tracing::info!("Index '{}' already exists", full_index_name);
+                let index_name = self.create_index_version(index).await?;
+                self.swap_alias(index, &index_name).await?;
+                tracing::info!("Index '{}' created with mapping", index_name);
             }
         }
-        Ok(())
+        Ok(outdated_indices)
     }
-    pub async fn delete_all_indices(&self) -> Result<(), anyhow::Error> {
-        let indices = [
-            ElasticSearchIndex::ProviderServicabilityHyperLocal,
-            ElasticSearchIndex::ProviderServicabilityCountry,
-            ElasticSearchIndex::ProviderServicabilityInterCity,
-            ElasticSearchIndex::ProviderServicabilityGeoJson,
-            ElasticSearchIndex::NetworkParticipant,
-            ElasticSearchIndex::ProviderLocation,
-            ElasticSearchIndex::Provider,
-            ElasticSearchIndex::ProviderItemVariant,
-            ElasticSearchIndex::ProviderItem,
-        ];
+    async fn index_exists(&self, index_name: &str) -> Result<bool, anyhow::Error> {
+        let response = self
+            .client
+            .indices()
+            .exists(IndicesExistsParts::Index(&[index_name]))
+            .send()
+            .await?;
+        Ok(response.status_code() == 200)
+    }
+    async fn get_alias_indices(&self, alias_name: &str) -> Result<Vec<String>, anyhow::Error> {
+        let response = self
+            .client
+            .indices()
+            .get_alias(IndicesGetAliasParts::Name(&[alias_name]))
+            .send()
+            .await?;
+        if response.status_code() == 404 {
+            return Ok(vec![]);
+        }
+        let body: Value = response.json().await?;
+        Ok(body
+            .as_object()
+            .map(|indices| indices.keys().cloned().collect())
+            .unwrap_or_default())
+    }
+    async fn get_index_versions(&self, alias_name: &str) -> Result<Vec<u32>, anyhow::Error> {
+        let response = self
+            .client
+            .indices()
+            .get(IndicesGetParts::Index(&[&format!("{}_v*", alias_name)]))
+            .send()
+            .await?;
+        let body: Value = response.json().await?;
+        Ok(body
+            .as_object()
+            .map(|indices| {
+                indices
+                    .keys()
+                    .filter_map(|index_name| get_index_version(alias_name, index_name))
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+    async fn get_index_mapping_hash(
+        &self,
+        index_name: &str,
+    ) -> Result<Option<String>, anyhow::Error> {
+        let response = self
+            .client
+            .indices()
+            .get_mapping(IndicesGetMappingParts::Index(&[index_name]))
+            .send()
+            .await?;
+        let body: Value = response.json().await?;
+        Ok(body[index_name]["mappings"]["_meta"]["mapping_hash"]
+            .as_str()
+            .map(|hash| hash.to_owned()))
+    }
+    /// Creates `{env}_{index}_v{n}` with the current mapping, `n` being one more
+    /// than the latest existing version.
+    pub async fn create_index_version(
+        &self,
+        index: &ElasticSearchIndex,
+    ) -> Result<String, anyhow::Error> {
+        let mapping_json = INDICES
+            .get(index)
+            .ok_or_else(|| anyhow!("Mapping not found for index {}", index))?;
+        let alias_name = self.get_index(&index.to_string());
+        let version = self
+            .get_index_versions(&alias_name)
+            .await?
+            .into_iter()
+            .max()
+            .unwrap_or_default()
+            + 1;
+        let index_name = format!("{}_v{}", alias_name, version);
+        let mut versioned_mapping = mapping_json.clone();
+        versioned_mapping["mappings"]["_meta"] =
+            json!({ "mapping_hash": get_mapping_hash(mapping_json) });
 
-        for index in &indices {
-            let index_name = self.get_index(&index.to_string());
-            let delete_query = serde_json::json!({
-                "query": { "match_all": {} } // Deletes all documents from the index
-            });
+        let create_response = self
+            .client
+            .indices()
+            .create(IndicesCreateParts::Index(&index_name))
+            .body(versioned_mapping)
+            .send()
+            .await?;
+        if create_response.status_code() != 200 {
+            let body: Value = create_response.json().await?;
+            tracing::error!("Response body: {:?}", body);
+            return Err(anyhow!("Failed to create index {}", index_name));
+        }
+        Ok(index_name)
+    }
+    pub async fn count_documents(&self, index_name: &str) -> Result<u64, anyhow::Error> {
+        self.client
+            .indices()
+            .refresh(IndicesRefreshParts::Index(&[index_name]))
+            .send()
+            .await?;
+        let response = self
+            .client
+            .count(CountParts::Index(&[index_name]))
+            .send()
+            .await?;
+        let status_code = response.status_code();
+        let response_body = response.json::<Value>().await?;
+        if status_code != 200 {
+            tracing::error!("{:?}", response_body);
+            return Err(anyhow!("Failed to count documents of {}", index_name));
+        }
+        response_body["count"]
+            .as_u64()
+            .ok_or_else(|| anyhow!("Invalid count response for {}", index_name))
+    }
+    /// Atomically points the alias of `index` to `index_name`, dropping any
+    /// unversioned index that still holds the alias name.
+    pub async fn swap_alias(
+        &self,
+        index: &ElasticSearchIndex,
+        index_name: &str,
+    ) -> Result<(), anyhow::Error> {
+        self.swap_aliases(&HashMap::from([(*index, index_name.to_owned())]))
+            .await
+    }
+    /// Points the alias of every index in `targets` to its new index in a single
+    /// `update_aliases` request, so all aliases move together or not at all.
+    pub async fn swap_aliases(
+        &self,
+        targets: &HashMap<ElasticSearchIndex, String>,
+    ) -> Result<(), anyhow::Error> {
+        let mut actions: Vec<Value> = vec![];
+        for (index, index_name) in targets {
+            let alias_name = self.get_index(&index.to_string());
+            let live_indices = self.get_alias_indices(&alias_name).await?;
+            actions.extend(live_indices.iter().map(
+                |live_index| json!({ "remove": { "index": live_index, "alias": alias_name } }),
+            ));
+            if live_indices.is_empty() && self.index_exists(&alias_name).await? {
+                actions.push(json!({ "remove_index": { "index": alias_name } }));
+            }
+            actions.push(json!({ "add": { "index": index_name, "alias": alias_name } }));
+        }
 
-            let response = self
-                .client
-                .delete_by_query(DeleteByQueryParts::Index(&[&index_name]))
-                .body(delete_query)
-                .send()
+        let response = self
+            .client
+            .indices()
+            .update_aliases()
+            .body(json!({ "actions": actions }))
+            .send()
+            .await?;
+        if response.status_code() != 200 {
+            let response_body = response.json::<Value>().await?;
+            tracing::error!("{:?}", response_body);
+            return Err(anyhow!(
+                "Failed to swap aliases of {}",
+                targets.values().cloned().collect::<Vec<_>>().join(",")
+            ));
+        }
+        Ok(())
+    }
+    /// Deletes every version of `index` other than `live_index_name`.
+    pub async fn delete_old_index_versions(
+        &self,
+        index: &ElasticSearchIndex,
+        live_index_name: &str,
+    ) -> Result<(), anyhow::Error> {
+        let alias_name = self.get_index(&index.to_string());
+        let old_index_names: Vec<String> = self
+            .get_index_versions(&alias_name)
+            .await?
+            .into_iter()
+            .map(|version| format!("{}_v{}", alias_name, version))
+            .filter(|index_name| index_name != live_index_name)
+            .collect();
+        if old_index_names.is_empty() {
+            return Ok(());
+        }
+        self.delete_indices(&old_index_names).await
+    }
+    pub async fn delete_indices(&self, index_names: &[String]) -> Result<(), anyhow::Error> {
+        let index_names: Vec<&str> = index_names.iter().map(|name| name.as_str()).collect();
+        let response = self
+            .client
+            .indices()
+            .delete(IndicesDeleteParts::Index(&index_names))
+            .send()
+            .await?;
+        if response.status_code() != 200 {
+            let response_body = response.json::<Value>().await?;
+            tracing::error!("{:?}", response_body);
+            return Err(anyhow!(
+                "Failed to delete indices {}",
+                index_names.join(",")
+            ));
+        }
+        Ok(())
+    }
+    pub async fn delete_all_indices(&self) -> Result<(), anyhow::Error> {
+        for index in ElasticSearchIndex::all() {
+            // Deletes all documents from the index
+            self.delete_by_query(index, json!({ "match_all": {} }))
                 .await?;
+        }
 
-            let status_code = response.status_code();
+        Ok(())
+    }
+    pub async fn delete_by_query(
+        &self,
+        index: ElasticSearchIndex,
+        query: Value,
+    ) -> Result<(), anyhow::Error> {
+        let index_name = self.get_index(&index.to_string());
+        self.delete_by_query_from_index(&index_name, query).await
+    }
+    /// Deletes matching documents from the physical index `index_name`, refreshing it
+    /// first so documents written just before are matched too.
+    pub async fn delete_by_query_from_index(
+        &self,
+        index_name: &str,
+        query: Value,
+    ) -> Result<(), anyhow::Error> {
+        self.client
+            .indices()
+            .refresh(IndicesRefreshParts::Index(&[index_name]))
+            .send()
+            .await?;
+        let response = self
+            .client
+            .delete_by_query(DeleteByQueryParts::Index(&[index_name]))
+            .body(json!({ "query": query }))
+            .send()
+            .await?;
 
-            if status_code != 200 {
-                return Err(anyhow::anyhow!("Failed to delete index {}", index_name));
-            }
+        if response.status_code() != 200 {
+            let response_body = response.json::<serde_json::Value>().await?;
+            tracing::error!("{:?}", response_body);
+            return Err(anyhow::anyhow!(
+                "Failed to delete documents from {}",
+                index_name
+            ));
         }
 
         Ok(())
     }
 }
-pub async fn generate_indices() {
+pub fn get_mapping_hash(mapping_json: &Value) -> String {
+    hex::encode(Sha256::digest(mapping_json.to_string().as_bytes()))
+}
+
+pub fn get_index_version(alias_name: &str, index_name: &str) -> Option<u32> {
+    index_name
+        .strip_prefix(alias_name)?
+        .strip_prefix("_v")?
+        .parse()
+        .ok()
+}
+
+pub async fn generate_indices() -> Vec<ElasticSearchIndex> {
     let configuration = get_configuration().expect("Failed to read configuration.");
     let es_client = configuration.elastic_search.client();
-    let _ = es_client
-        .generate_indices()
-        .await
-        .map_err(|e| tracing::info!("{}", e.to_string()));
+    es_client.generate_indices().await.unwrap_or_else(|e| {
+        tracing::info!("{}", e.to_string());
+        vec![]
+    })
 }
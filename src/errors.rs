@@ -128,6 +128,8 @@ pub enum GenericError {
     DataNotFound(String),
     #[error("{0}")]
     NotImplemented(String),
+    #[error("{0}")]
+    InvalidSignature(String),
 }
 
 impl std::fmt::Debug for GenericError {
@@ -149,6 +151,7 @@ impl ResponseError for GenericError {
             GenericError::InvalidData(_) => StatusCode::INTERNAL_SERVER_ERROR,
             GenericError::DataNotFound(_) => StatusCode::GONE,
             GenericError::NotImplemented(_) => StatusCode::NOT_IMPLEMENTED,
+            GenericError::InvalidSignature(_) => StatusCode::UNAUTHORIZED,
         }
     }
 
@@ -165,6 +168,7 @@ impl ResponseError for GenericError {
             GenericError::InvalidData(error_msg) => error_msg.to_string(),
             GenericError::DataNotFound(error_msg) => error_msg.to_string(),
             GenericError::NotImplemented(error_msg) => error_msg.to_string(),
+            GenericError::InvalidSignature(error_msg) => error_msg.to_string(),
         };
 
         HttpResponse::build(status_code).json(GenericResponse::error(
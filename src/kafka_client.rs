@@ -16,7 +16,7 @@ use serde_json::Value;
 use sqlx::PgPool;
 
 use crate::{
-    configuration::{get_configuration, ONDCConfig},
+    configuration::{get_configuration, ONDCConfig, SearchConfig},
     elastic_search_client::ElasticSearchClient,
     routes::ondc::{
         utils::{process_on_search, send_observability},
@@ -126,6 +126,7 @@ impl KafkaClient {
         websocket_client: Data<WebSocketClient>,
         pool: Data<PgPool>,
         elastic_search_client: Data<ElasticSearchClient>,
+        search_config: Data<SearchConfig>,
     ) -> Result<(), KafkaError> {
         let consumer: StreamConsumer = ClientConfig::new()
             .set("bootstrap.servers", &self.servers)
@@ -153,6 +154,7 @@ impl KafkaClient {
                                     message_data.search_obj,
                                     &websocket_client,
                                     &elastic_search_client,
+                                    &search_config,
                                 )
                                 .await
                                 {
@@ -2,12 +2,13 @@ use bigdecimal::BigDecimal;
 use reqwest::Client;
 use secrecy::{ExposeSecret, SecretString};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::{
-    routes::order::schemas::PaymentStatus,
+    routes::{order::schemas::PaymentStatus, payment::schemas::CommerceRefundStatus},
     schemas::{CurrencyType, GenericResponse},
 };
 #[derive(Debug)]
@@ -15,6 +16,7 @@ pub struct PaymentClient {
     http_client: Client,
     base_url: String,
     authorization_token: SecretString,
+    webhook_secrets: HashMap<String, SecretString>,
 }
 
 #[derive(Debug, Serialize)]
@@ -25,6 +27,26 @@ pub struct PaymentOrderCreateRequest<'a> {
     pub amount: &'a BigDecimal,
     pub currency_type: &'a CurrencyType,
     pub webhook: &'a str,
+    pub expire_by: i64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaymentRefundCreateRequest<'a> {
+    pub source: &'a str,
+    pub refund_no: Uuid,
+    pub payment_id: &'a str,
+    pub amount: &'a BigDecimal,
+    pub currency_type: &'a CurrencyType,
+    pub reason: Option<&'a str>,
+    pub webhook: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaymentOrderCancelRequest<'a> {
+    pub source: &'a str,
+    pub order_id: &'a str,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -58,6 +80,36 @@ impl PaymentServiceStatusType {
     }
 }
 
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum PaymentServiceRefundStatus {
+    Pending,
+    Processed,
+    Failed,
+}
+
+impl PaymentServiceRefundStatus {
+    pub fn refund_status(&self) -> CommerceRefundStatus {
+        match self {
+            PaymentServiceRefundStatus::Pending => CommerceRefundStatus::Initiated,
+            PaymentServiceRefundStatus::Processed => CommerceRefundStatus::Processed,
+            PaymentServiceRefundStatus::Failed => CommerceRefundStatus::Failed,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaymentServiceRefundData {
+    pub id: String,
+    pub entity: String,
+    pub amount: BigDecimal,
+    pub currency: CurrencyType,
+    pub payment_id: String,
+    pub status: PaymentServiceRefundStatus,
+    pub created_at: i64,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PaymentServicePaymentData {
@@ -83,6 +135,10 @@ pub struct PaymentServiceOrderData {
     pub amount_due: BigDecimal,
     pub created_at: u64,
     pub status: PaymentServiceOrderStatus,
+    #[serde(default)]
+    pub expire_by: Option<i64>,
+    #[serde(default)]
+    pub payment_link: Option<String>,
 }
 
 impl PaymentClient {
@@ -91,13 +147,19 @@ impl PaymentClient {
         base_url: String,
         authorization_token: SecretString,
         timeout: std::time::Duration,
+        webhook_secrets: HashMap<String, SecretString>,
     ) -> Self {
         tracing::info!("Establishing connection to the payment server.");
         let http_client = Client::builder().timeout(timeout).build().unwrap();
+        let webhook_secrets = webhook_secrets
+            .into_iter()
+            .map(|(payment_service_id, secret)| (payment_service_id.to_lowercase(), secret))
+            .collect();
         Self {
             http_client,
             base_url,
             authorization_token,
+            webhook_secrets,
         }
     }
 
@@ -105,6 +167,12 @@ impl PaymentClient {
         format!("Bearer {}", self.authorization_token.expose_secret())
     }
 
+    pub fn get_webhook_secret(&self, payment_service_id: &str) -> Option<&str> {
+        self.webhook_secrets
+            .get(&payment_service_id.to_lowercase())
+            .map(|secret| secret.expose_secret())
+    }
+
     pub fn generate_order_create_request<'a>(
         &self,
         order_no: Uuid,
@@ -112,6 +180,7 @@ impl PaymentClient {
         id: &'a str,
         currency_type: &'a CurrencyType,
         webhook: &'a str,
+        expire_by: i64,
     ) -> PaymentOrderCreateRequest<'a> {
         PaymentOrderCreateRequest {
             source: id,
@@ -119,6 +188,7 @@ impl PaymentClient {
             amount,
             currency_type,
             webhook,
+            expire_by,
         }
     }
 
@@ -151,6 +221,91 @@ impl PaymentClient {
             Err(anyhow::anyhow!(response_body.customer_message))
         }
     }
+
+    pub async fn cancel_order(
+        &self,
+        payment_order_id: &str,
+        payment_service_id: &str,
+    ) -> Result<(), anyhow::Error> {
+        let url = format!("{}/order/cancel", self.base_url);
+        let request_body = PaymentOrderCancelRequest {
+            source: payment_service_id,
+            order_id: payment_order_id,
+        };
+        let response = self
+            .http_client
+            .post(&url)
+            .header("Authorization", self.get_auth_token())
+            .header("x-request-id", "internal")
+            .header("x-device-id", "internal")
+            .json(&request_body)
+            .send()
+            .await?;
+
+        let status = response.status();
+        let response_body: GenericResponse<()> = response
+            .json()
+            .await
+            .map_err(|err| anyhow::anyhow!(format!("Failed to parse response: {}", err)))?;
+        if status.is_success() {
+            Ok(())
+        } else {
+            Err(anyhow::anyhow!(response_body.customer_message))
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn generate_refund_create_request<'a>(
+        &self,
+        refund_no: Uuid,
+        payment_id: &'a str,
+        amount: &'a BigDecimal,
+        id: &'a str,
+        currency_type: &'a CurrencyType,
+        reason: Option<&'a str>,
+        webhook: &'a str,
+    ) -> PaymentRefundCreateRequest<'a> {
+        PaymentRefundCreateRequest {
+            source: id,
+            refund_no,
+            payment_id,
+            amount,
+            currency_type,
+            reason,
+            webhook,
+        }
+    }
+
+    pub async fn create_refund(
+        &self,
+        request_body: PaymentRefundCreateRequest<'_>,
+    ) -> Result<PaymentServiceRefundData, anyhow::Error> {
+        let url = format!("{}/refund/create", self.base_url);
+
+        let response = self
+            .http_client
+            .post(&url)
+            .header("Authorization", self.get_auth_token())
+            .header("x-request-id", "internal")
+            .header("x-device-id", "internal")
+            .json(&request_body)
+            .send()
+            .await?;
+
+        let status = response.status();
+        let response_body: GenericResponse<PaymentServiceRefundData> = response
+            .json()
+            .await
+            .map_err(|err| anyhow::anyhow!(format!("Failed to parse response: {}", err)))?;
+        if status.is_success() {
+            response_body
+                .data
+                .ok_or_else(|| anyhow::anyhow!("Payment Refund not found".to_string()))
+        } else {
+            Err(anyhow::anyhow!(response_body.customer_message))
+        }
+    }
+
     pub async fn fetch_payments_by_order_id(
         &self,
         payment_order_id: &str,
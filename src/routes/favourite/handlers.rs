@@ -0,0 +1,338 @@
+use actix_http::StatusCode;
+use actix_web::web;
+use sqlx::PgPool;
+use utoipa::TupleUnit;
+
+use super::schemas::{
+    FavouriteItem, FavouriteItemRequest, FavouriteProvider, FavouriteProviderRequest,
+    SupplierPreference, SupplierPreferenceListRequest, SupplierPreferenceRemoveRequest,
+    SupplierPreferenceUpdateRequest,
+};
+use super::utils::{
+    delete_favourite_item, delete_favourite_provider, delete_supplier_preference,
+    fetch_favourite_items, fetch_favourite_providers, fetch_supplier_preferences,
+    save_favourite_item, save_favourite_provider, save_supplier_preference,
+};
+use crate::errors::GenericError;
+use crate::schemas::GenericResponse;
+use crate::user_client::{BusinessAccount, UserAccount};
+
+#[utoipa::path(
+    post,
+    path = "/favourite/item/add",
+    tag = "Favourite",
+    description="This API bookmarks a cached item for the user.",
+    summary= "Favourite Item Add Request",
+    request_body(content = FavouriteItemRequest, description = "Request Body"),
+    responses(
+        (status=200, description= "Favourite Item Add Response", body= GenericResponse<TupleUnit>),
+        (status=400, description= "Invalid Request body", body= GenericResponse<TupleUnit>),
+        (status=401, description= "Invalid Token", body= GenericResponse<TupleUnit>),
+	    (status=403, description= "Insufficient Previlege", body= GenericResponse<TupleUnit>),
+	    (status=410, description= "Data not found", body= GenericResponse<TupleUnit>),
+        (status=500, description= "Internal Server Error", body= GenericResponse<TupleUnit>),
+	    (status=501, description= "Not Implemented", body= GenericResponse<TupleUnit>),
+    )
+)]
+#[tracing::instrument(name = "favourite item add", skip(pool), fields())]
+pub async fn favourite_item_add(
+    body: FavouriteItemRequest,
+    pool: web::Data<PgPool>,
+    user_account: UserAccount,
+    business_account: BusinessAccount,
+) -> Result<web::Json<GenericResponse<()>>, GenericError> {
+    save_favourite_item(&pool, &body, user_account.id, business_account.id)
+        .await
+        .map_err(|e| GenericError::DatabaseError("Failed to save favourite item".to_string(), e))?;
+    Ok(web::Json(GenericResponse::success(
+        "Successfully added favourite item",
+        StatusCode::OK,
+        Some(()),
+    )))
+}
+
+#[utoipa::path(
+    post,
+    path = "/favourite/item/remove",
+    tag = "Favourite",
+    description="This API removes a bookmarked item of the user.",
+    summary= "Favourite Item Remove Request",
+    request_body(content = FavouriteItemRequest, description = "Request Body"),
+    responses(
+        (status=200, description= "Favourite Item Remove Response", body= GenericResponse<TupleUnit>),
+        (status=400, description= "Invalid Request body", body= GenericResponse<TupleUnit>),
+        (status=401, description= "Invalid Token", body= GenericResponse<TupleUnit>),
+	    (status=403, description= "Insufficient Previlege", body= GenericResponse<TupleUnit>),
+	    (status=410, description= "Data not found", body= GenericResponse<TupleUnit>),
+        (status=500, description= "Internal Server Error", body= GenericResponse<TupleUnit>),
+	    (status=501, description= "Not Implemented", body= GenericResponse<TupleUnit>),
+    )
+)]
+#[tracing::instrument(name = "favourite item remove", skip(pool), fields())]
+pub async fn favourite_item_remove(
+    body: FavouriteItemRequest,
+    pool: web::Data<PgPool>,
+    user_account: UserAccount,
+    business_account: BusinessAccount,
+) -> Result<web::Json<GenericResponse<()>>, GenericError> {
+    delete_favourite_item(&pool, &body, user_account.id, business_account.id)
+        .await
+        .map_err(|e| {
+            GenericError::DatabaseError("Failed to delete favourite item".to_string(), e)
+        })?;
+    Ok(web::Json(GenericResponse::success(
+        "Successfully removed favourite item",
+        StatusCode::OK,
+        Some(()),
+    )))
+}
+
+#[utoipa::path(
+    post,
+    path = "/favourite/item/list",
+    tag = "Favourite",
+    description="This API lists the bookmarked items of the user.",
+    summary= "Favourite Item List Request",
+    responses(
+        (status=200, description= "Favourite Item List Response", body= GenericResponse<Vec<FavouriteItem>>),
+        (status=400, description= "Invalid Request body", body= GenericResponse<TupleUnit>),
+        (status=401, description= "Invalid Token", body= GenericResponse<TupleUnit>),
+	    (status=403, description= "Insufficient Previlege", body= GenericResponse<TupleUnit>),
+	    (status=410, description= "Data not found", body= GenericResponse<TupleUnit>),
+        (status=500, description= "Internal Server Error", body= GenericResponse<TupleUnit>),
+	    (status=501, description= "Not Implemented", body= GenericResponse<TupleUnit>),
+    )
+)]
+#[tracing::instrument(name = "favourite item list", skip(pool), fields())]
+pub async fn favourite_item_list(
+    pool: web::Data<PgPool>,
+    user_account: UserAccount,
+    business_account: BusinessAccount,
+) -> Result<web::Json<GenericResponse<Vec<FavouriteItem>>>, GenericError> {
+    let data = fetch_favourite_items(&pool, user_account.id, business_account.id)
+        .await
+        .map_err(|e| {
+            GenericError::DatabaseError("Failed to fetch favourite items".to_string(), e)
+        })?;
+    Ok(web::Json(GenericResponse::success(
+        "Successfully fetched favourite items",
+        StatusCode::OK,
+        Some(data.into_iter().map(|item| item.schema()).collect()),
+    )))
+}
+
+#[utoipa::path(
+    post,
+    path = "/favourite/provider/add",
+    tag = "Favourite",
+    description="This API bookmarks a provider for the user.",
+    summary= "Favourite Provider Add Request",
+    request_body(content = FavouriteProviderRequest, description = "Request Body"),
+    responses(
+        (status=200, description= "Favourite Provider Add Response", body= GenericResponse<TupleUnit>),
+        (status=400, description= "Invalid Request body", body= GenericResponse<TupleUnit>),
+        (status=401, description= "Invalid Token", body= GenericResponse<TupleUnit>),
+	    (status=403, description= "Insufficient Previlege", body= GenericResponse<TupleUnit>),
+	    (status=410, description= "Data not found", body= GenericResponse<TupleUnit>),
+        (status=500, description= "Internal Server Error", body= GenericResponse<TupleUnit>),
+	    (status=501, description= "Not Implemented", body= GenericResponse<TupleUnit>),
+    )
+)]
+#[tracing::instrument(name = "favourite provider add", skip(pool), fields())]
+pub async fn favourite_provider_add(
+    body: FavouriteProviderRequest,
+    pool: web::Data<PgPool>,
+    user_account: UserAccount,
+    business_account: BusinessAccount,
+) -> Result<web::Json<GenericResponse<()>>, GenericError> {
+    save_favourite_provider(&pool, &body, user_account.id, business_account.id)
+        .await
+        .map_err(|e| {
+            GenericError::DatabaseError("Failed to save favourite provider".to_string(), e)
+        })?;
+    Ok(web::Json(GenericResponse::success(
+        "Successfully added favourite provider",
+        StatusCode::OK,
+        Some(()),
+    )))
+}
+
+#[utoipa::path(
+    post,
+    path = "/favourite/provider/remove",
+    tag = "Favourite",
+    description="This API removes a bookmarked provider of the user.",
+    summary= "Favourite Provider Remove Request",
+    request_body(content = FavouriteProviderRequest, description = "Request Body"),
+    responses(
+        (status=200, description= "Favourite Provider Remove Response", body= GenericResponse<TupleUnit>),
+        (status=400, description= "Invalid Request body", body= GenericResponse<TupleUnit>),
+        (status=401, description= "Invalid Token", body= GenericResponse<TupleUnit>),
+	    (status=403, description= "Insufficient Previlege", body= GenericResponse<TupleUnit>),
+	    (status=410, description= "Data not found", body= GenericResponse<TupleUnit>),
+        (status=500, description= "Internal Server Error", body= GenericResponse<TupleUnit>),
+	    (status=501, description= "Not Implemented", body= GenericResponse<TupleUnit>),
+    )
+)]
+#[tracing::instrument(name = "favourite provider remove", skip(pool), fields())]
+pub async fn favourite_provider_remove(
+    body: FavouriteProviderRequest,
+    pool: web::Data<PgPool>,
+    user_account: UserAccount,
+    business_account: BusinessAccount,
+) -> Result<web::Json<GenericResponse<()>>, GenericError> {
+    delete_favourite_provider(&pool, &body, user_account.id, business_account.id)
+        .await
+        .map_err(|e| {
+            GenericError::DatabaseError("Failed to delete favourite provider".to_string(), e)
+        })?;
+    Ok(web::Json(GenericResponse::success(
+        "Successfully removed favourite provider",
+        StatusCode::OK,
+        Some(()),
+    )))
+}
+
+#[utoipa::path(
+    post,
+    path = "/favourite/provider/list",
+    tag = "Favourite",
+    description="This API lists the bookmarked providers of the user.",
+    summary= "Favourite Provider List Request",
+    responses(
+        (status=200, description= "Favourite Provider List Response", body= GenericResponse<Vec<FavouriteProvider>>),
+        (status=400, description= "Invalid Request body", body= GenericResponse<TupleUnit>),
+        (status=401, description= "Invalid Token", body= GenericResponse<TupleUnit>),
+	    (status=403, description= "Insufficient Previlege", body= GenericResponse<TupleUnit>),
+	    (status=410, description= "Data not found", body= GenericResponse<TupleUnit>),
+        (status=500, description= "Internal Server Error", body= GenericResponse<TupleUnit>),
+	    (status=501, description= "Not Implemented", body= GenericResponse<TupleUnit>),
+    )
+)]
+#[tracing::instrument(name = "favourite provider list", skip(pool), fields())]
+pub async fn favourite_provider_list(
+    pool: web::Data<PgPool>,
+    user_account: UserAccount,
+    business_account: BusinessAccount,
+) -> Result<web::Json<GenericResponse<Vec<FavouriteProvider>>>, GenericError> {
+    let data = fetch_favourite_providers(&pool, user_account.id, business_account.id)
+        .await
+        .map_err(|e| {
+            GenericError::DatabaseError("Failed to fetch favourite providers".to_string(), e)
+        })?;
+    Ok(web::Json(GenericResponse::success(
+        "Successfully fetched favourite providers",
+        StatusCode::OK,
+        Some(data.into_iter().map(|provider| provider.schema()).collect()),
+    )))
+}
+
+#[utoipa::path(
+    post,
+    path = "/favourite/supplier/update",
+    tag = "Favourite",
+    description="This API marks a provider as a preferred or blocked supplier of the business.",
+    summary= "Supplier Preference Update Request",
+    request_body(content = SupplierPreferenceUpdateRequest, description = "Request Body"),
+    responses(
+        (status=200, description= "Supplier Preference Update Response", body= GenericResponse<TupleUnit>),
+        (status=400, description= "Invalid Request body", body= GenericResponse<TupleUnit>),
+        (status=401, description= "Invalid Token", body= GenericResponse<TupleUnit>),
+	    (status=403, description= "Insufficient Previlege", body= GenericResponse<TupleUnit>),
+	    (status=410, description= "Data not found", body= GenericResponse<TupleUnit>),
+        (status=500, description= "Internal Server Error", body= GenericResponse<TupleUnit>),
+	    (status=501, description= "Not Implemented", body= GenericResponse<TupleUnit>),
+    )
+)]
+#[tracing::instrument(name = "supplier preference update", skip(pool), fields())]
+pub async fn supplier_preference_update(
+    body: SupplierPreferenceUpdateRequest,
+    pool: web::Data<PgPool>,
+    user_account: UserAccount,
+    business_account: BusinessAccount,
+) -> Result<web::Json<GenericResponse<()>>, GenericError> {
+    save_supplier_preference(&pool, &body, user_account.id, business_account.id)
+        .await
+        .map_err(|e| {
+            GenericError::DatabaseError("Failed to save supplier preference".to_string(), e)
+        })?;
+    Ok(web::Json(GenericResponse::success(
+        "Successfully updated supplier preference",
+        StatusCode::OK,
+        Some(()),
+    )))
+}
+
+#[utoipa::path(
+    post,
+    path = "/favourite/supplier/remove",
+    tag = "Favourite",
+    description="This API removes a provider from the preferred or blocked supplier list of the business.",
+    summary= "Supplier Preference Remove Request",
+    request_body(content = SupplierPreferenceRemoveRequest, description = "Request Body"),
+    responses(
+        (status=200, description= "Supplier Preference Remove Response", body= GenericResponse<TupleUnit>),
+        (status=400, description= "Invalid Request body", body= GenericResponse<TupleUnit>),
+        (status=401, description= "Invalid Token", body= GenericResponse<TupleUnit>),
+	    (status=403, description= "Insufficient Previlege", body= GenericResponse<TupleUnit>),
+	    (status=410, description= "Data not found", body= GenericResponse<TupleUnit>),
+        (status=500, description= "Internal Server Error", body= GenericResponse<TupleUnit>),
+	    (status=501, description= "Not Implemented", body= GenericResponse<TupleUnit>),
+    )
+)]
+#[tracing::instrument(name = "supplier preference remove", skip(pool), fields())]
+pub async fn supplier_preference_remove(
+    body: SupplierPreferenceRemoveRequest,
+    pool: web::Data<PgPool>,
+    business_account: BusinessAccount,
+) -> Result<web::Json<GenericResponse<()>>, GenericError> {
+    delete_supplier_preference(&pool, business_account.id, &body.bpp_id, &body.provider_id)
+        .await
+        .map_err(|e| {
+            GenericError::DatabaseError("Failed to delete supplier preference".to_string(), e)
+        })?;
+    Ok(web::Json(GenericResponse::success(
+        "Successfully removed supplier preference",
+        StatusCode::OK,
+        Some(()),
+    )))
+}
+
+#[utoipa::path(
+    post,
+    path = "/favourite/supplier/list",
+    tag = "Favourite",
+    description="This API lists the preferred and blocked suppliers of the business.",
+    summary= "Supplier Preference List Request",
+    request_body(content = SupplierPreferenceListRequest, description = "Request Body"),
+    responses(
+        (status=200, description= "Supplier Preference List Response", body= GenericResponse<Vec<SupplierPreference>>),
+        (status=400, description= "Invalid Request body", body= GenericResponse<TupleUnit>),
+        (status=401, description= "Invalid Token", body= GenericResponse<TupleUnit>),
+	    (status=403, description= "Insufficient Previlege", body= GenericResponse<TupleUnit>),
+	    (status=410, description= "Data not found", body= GenericResponse<TupleUnit>),
+        (status=500, description= "Internal Server Error", body= GenericResponse<TupleUnit>),
+	    (status=501, description= "Not Implemented", body= GenericResponse<TupleUnit>),
+    )
+)]
+#[tracing::instrument(name = "supplier preference list", skip(pool), fields())]
+pub async fn supplier_preference_list(
+    body: SupplierPreferenceListRequest,
+    pool: web::Data<PgPool>,
+    business_account: BusinessAccount,
+) -> Result<web::Json<GenericResponse<Vec<SupplierPreference>>>, GenericError> {
+    let data = fetch_supplier_preferences(&pool, business_account.id, body.preference.as_ref())
+        .await
+        .map_err(|e| {
+            GenericError::DatabaseError("Failed to fetch supplier preferences".to_string(), e)
+        })?;
+    Ok(web::Json(GenericResponse::success(
+        "Successfully fetched supplier preferences",
+        StatusCode::OK,
+        Some(
+            data.into_iter()
+                .map(|preference| preference.schema())
+                .collect(),
+        ),
+    )))
+}
@@ -0,0 +1,87 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use super::schemas::{
+    FavouriteItem, FavouriteProvider, SupplierPreference, SupplierPreferenceType,
+};
+
+#[derive(Deserialize, Debug, FromRow)]
+pub struct FavouriteItemModel {
+    pub id: Uuid,
+    pub subscriber_id: String,
+    pub provider_id: String,
+    pub item_id: String,
+    pub provider_name: Option<String>,
+    pub item_name: Option<String>,
+    pub created_on: DateTime<Utc>,
+}
+
+impl FavouriteItemModel {
+    pub fn schema(self) -> FavouriteItem {
+        FavouriteItem {
+            id: self.id,
+            bpp_id: self.subscriber_id,
+            provider_id: self.provider_id,
+            item_id: self.item_id,
+            provider_name: self.provider_name,
+            item_name: self.item_name,
+            created_on: self.created_on,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, FromRow)]
+pub struct FavouriteProviderModel {
+    pub id: Uuid,
+    pub subscriber_id: String,
+    pub provider_id: String,
+    pub provider_name: Option<String>,
+    pub created_on: DateTime<Utc>,
+}
+
+impl FavouriteProviderModel {
+    pub fn schema(self) -> FavouriteProvider {
+        FavouriteProvider {
+            id: self.id,
+            bpp_id: self.subscriber_id,
+            provider_id: self.provider_id,
+            provider_name: self.provider_name,
+            created_on: self.created_on,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, FromRow)]
+pub struct SupplierPreferenceModel {
+    pub id: Uuid,
+    pub subscriber_id: String,
+    pub provider_id: String,
+    pub provider_name: Option<String>,
+    pub preference: SupplierPreferenceType,
+    pub remark: Option<String>,
+    pub created_by: Uuid,
+    pub created_on: DateTime<Utc>,
+}
+
+impl SupplierPreferenceModel {
+    pub fn schema(self) -> SupplierPreference {
+        SupplierPreference {
+            id: self.id,
+            bpp_id: self.subscriber_id,
+            provider_id: self.provider_id,
+            provider_name: self.provider_name,
+            preference: self.preference,
+            remark: self.remark,
+            created_by: self.created_by,
+            created_on: self.created_on,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, FromRow)]
+pub struct SupplierPreferenceCacheModel {
+    pub provider_cache_id: Uuid,
+    pub preference: SupplierPreferenceType,
+}
@@ -0,0 +1,92 @@
+use crate::middleware::{BusinessAccountValidation, BusinessPermissionValidation, RequireAuth};
+use crate::user_client::{CustomerType, PermissionType};
+use actix_web::web;
+
+use super::handlers::{
+    favourite_item_add, favourite_item_list, favourite_item_remove, favourite_provider_add,
+    favourite_provider_list, favourite_provider_remove, supplier_preference_list,
+    supplier_preference_remove, supplier_preference_update,
+};
+pub fn favourite_route(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::resource("/item/add")
+            .route(web::post().to(favourite_item_add))
+            .wrap(BusinessAccountValidation {
+                business_type_list: vec![CustomerType::RetailB2bBuyer],
+            })
+            .wrap(RequireAuth),
+    );
+    cfg.service(
+        web::resource("/item/remove")
+            .route(web::post().to(favourite_item_remove))
+            .wrap(BusinessAccountValidation {
+                business_type_list: vec![CustomerType::RetailB2bBuyer],
+            })
+            .wrap(RequireAuth),
+    );
+    cfg.service(
+        web::resource("/item/list")
+            .route(web::post().to(favourite_item_list))
+            .wrap(BusinessAccountValidation {
+                business_type_list: vec![CustomerType::RetailB2bBuyer],
+            })
+            .wrap(RequireAuth),
+    );
+    cfg.service(
+        web::resource("/provider/add")
+            .route(web::post().to(favourite_provider_add))
+            .wrap(BusinessAccountValidation {
+                business_type_list: vec![CustomerType::RetailB2bBuyer],
+            })
+            .wrap(RequireAuth),
+    );
+    cfg.service(
+        web::resource("/provider/remove")
+            .route(web::post().to(favourite_provider_remove))
+            .wrap(BusinessAccountValidation {
+                business_type_list: vec![CustomerType::RetailB2bBuyer],
+            })
+            .wrap(RequireAuth),
+    );
+    cfg.service(
+        web::resource("/provider/list")
+            .route(web::post().to(favourite_provider_list))
+            .wrap(BusinessAccountValidation {
+                business_type_list: vec![CustomerType::RetailB2bBuyer],
+            })
+            .wrap(RequireAuth),
+    );
+    cfg.service(
+        web::resource("/supplier/update")
+            .route(web::post().to(supplier_preference_update))
+            .wrap(BusinessPermissionValidation {
+                permission_list: vec![PermissionType::UpdateOrder],
+            })
+            .wrap(BusinessAccountValidation {
+                business_type_list: vec![CustomerType::RetailB2bBuyer],
+            })
+            .wrap(RequireAuth),
+    );
+    cfg.service(
+        web::resource("/supplier/remove")
+            .route(web::post().to(supplier_preference_remove))
+            .wrap(BusinessPermissionValidation {
+                permission_list: vec![PermissionType::UpdateOrder],
+            })
+            .wrap(BusinessAccountValidation {
+                business_type_list: vec![CustomerType::RetailB2bBuyer],
+            })
+            .wrap(RequireAuth),
+    );
+    cfg.service(
+        web::resource("/supplier/list")
+            .route(web::post().to(supplier_preference_list))
+            .wrap(BusinessPermissionValidation {
+                permission_list: vec![PermissionType::ReadOrder],
+            })
+            .wrap(BusinessAccountValidation {
+                business_type_list: vec![CustomerType::RetailB2bBuyer],
+            })
+            .wrap(RequireAuth),
+    );
+}
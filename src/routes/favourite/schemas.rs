@@ -0,0 +1,172 @@
+use crate::errors::GenericError;
+use actix_http::Payload;
+use actix_web::{web, FromRequest, HttpRequest};
+use chrono::{DateTime, Utc};
+use futures_util::future::LocalBoxFuture;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Deserialize, Debug, ToSchema, Serialize, sqlx::Type, Clone, PartialEq)]
+#[sqlx(type_name = "supplier_preference_type", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum SupplierPreferenceType {
+    Preferred,
+    Blocked,
+}
+
+#[derive(Deserialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FavouriteItemRequest {
+    pub bpp_id: String,
+    pub provider_id: String,
+    pub item_id: String,
+}
+
+impl FromRequest for FavouriteItemRequest {
+    type Error = GenericError;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let fut = web::Json::<Self>::from_request(req, payload);
+
+        Box::pin(async move {
+            match fut.await {
+                Ok(json) => Ok(json.into_inner()),
+                Err(e) => Err(GenericError::ValidationError(e.to_string())),
+            }
+        })
+    }
+}
+
+#[derive(Deserialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FavouriteProviderRequest {
+    pub bpp_id: String,
+    pub provider_id: String,
+}
+
+impl FromRequest for FavouriteProviderRequest {
+    type Error = GenericError;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let fut = web::Json::<Self>::from_request(req, payload);
+
+        Box::pin(async move {
+            match fut.await {
+                Ok(json) => Ok(json.into_inner()),
+                Err(e) => Err(GenericError::ValidationError(e.to_string())),
+            }
+        })
+    }
+}
+
+#[derive(Deserialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SupplierPreferenceUpdateRequest {
+    pub bpp_id: String,
+    pub provider_id: String,
+    pub preference: SupplierPreferenceType,
+    pub remark: Option<String>,
+}
+
+impl FromRequest for SupplierPreferenceUpdateRequest {
+    type Error = GenericError;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let fut = web::Json::<Self>::from_request(req, payload);
+
+        Box::pin(async move {
+            match fut.await {
+                Ok(json) => Ok(json.into_inner()),
+                Err(e) => Err(GenericError::ValidationError(e.to_string())),
+            }
+        })
+    }
+}
+
+#[derive(Deserialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SupplierPreferenceRemoveRequest {
+    pub bpp_id: String,
+    pub provider_id: String,
+}
+
+impl FromRequest for SupplierPreferenceRemoveRequest {
+    type Error = GenericError;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let fut = web::Json::<Self>::from_request(req, payload);
+
+        Box::pin(async move {
+            match fut.await {
+                Ok(json) => Ok(json.into_inner()),
+                Err(e) => Err(GenericError::ValidationError(e.to_string())),
+            }
+        })
+    }
+}
+
+#[derive(Deserialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SupplierPreferenceListRequest {
+    pub preference: Option<SupplierPreferenceType>,
+}
+
+impl FromRequest for SupplierPreferenceListRequest {
+    type Error = GenericError;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let fut = web::Json::<Self>::from_request(req, payload);
+
+        Box::pin(async move {
+            match fut.await {
+                Ok(json) => Ok(json.into_inner()),
+                Err(e) => Err(GenericError::ValidationError(e.to_string())),
+            }
+        })
+    }
+}
+
+#[derive(Debug, ToSchema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FavouriteItem {
+    #[schema(value_type = String)]
+    pub id: Uuid,
+    pub bpp_id: String,
+    pub provider_id: String,
+    pub item_id: String,
+    pub provider_name: Option<String>,
+    pub item_name: Option<String>,
+    pub created_on: DateTime<Utc>,
+}
+
+#[derive(Debug, ToSchema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FavouriteProvider {
+    #[schema(value_type = String)]
+    pub id: Uuid,
+    pub bpp_id: String,
+    pub provider_id: String,
+    pub provider_name: Option<String>,
+    pub created_on: DateTime<Utc>,
+}
+
+#[derive(Debug, ToSchema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SupplierPreference {
+    #[schema(value_type = String)]
+    pub id: Uuid,
+    pub bpp_id: String,
+    pub provider_id: String,
+    pub provider_name: Option<String>,
+    pub preference: SupplierPreferenceType,
+    pub remark: Option<String>,
+    #[schema(value_type = String)]
+    pub created_by: Uuid,
+    pub created_on: DateTime<Utc>,
+}
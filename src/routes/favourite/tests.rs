@@ -0,0 +1,68 @@
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use crate::{
+        routes::{
+            favourite::{
+                models::SupplierPreferenceCacheModel,
+                schemas::SupplierPreferenceType,
+                utils::{
+                    fetch_favourite_items, fetch_favourite_providers,
+                    fetch_search_supplier_preference, fetch_supplier_preferences,
+                    get_search_supplier_preference,
+                },
+            },
+            product::schemas::SupplierFilterType,
+        },
+        tests::tests::get_test_pool,
+    };
+
+    #[test]
+    fn test_search_supplier_preference() {
+        let preferred_id = Uuid::new_v4();
+        let blocked_id = Uuid::new_v4();
+        let get_models = || {
+            vec![
+                SupplierPreferenceCacheModel {
+                    provider_cache_id: preferred_id,
+                    preference: SupplierPreferenceType::Preferred,
+                },
+                SupplierPreferenceCacheModel {
+                    provider_cache_id: blocked_id,
+                    preference: SupplierPreferenceType::Blocked,
+                },
+            ]
+        };
+        let preference = get_search_supplier_preference(get_models(), None);
+        assert_eq!(preference.preferred_provider_ids, vec![preferred_id]);
+        assert!(preference.included_provider_ids.is_none());
+        assert!(preference.excluded_provider_ids.is_empty());
+        let preference =
+            get_search_supplier_preference(get_models(), Some(&SupplierFilterType::PreferredOnly));
+        assert_eq!(preference.included_provider_ids, Some(vec![preferred_id]));
+        assert!(preference.excluded_provider_ids.is_empty());
+        let preference =
+            get_search_supplier_preference(get_models(), Some(&SupplierFilterType::ExcludeBlocked));
+        assert!(preference.included_provider_ids.is_none());
+        assert_eq!(preference.excluded_provider_ids, vec![blocked_id]);
+    }
+
+    #[tokio::test]
+    async fn test_favourite_sql() {
+        let pool = get_test_pool().await;
+        let items = fetch_favourite_items(&pool, Uuid::new_v4(), Uuid::new_v4()).await;
+        assert!(items.is_ok());
+        let providers = fetch_favourite_providers(&pool, Uuid::new_v4(), Uuid::new_v4()).await;
+        assert!(providers.is_ok());
+        let preferences = fetch_supplier_preferences(
+            &pool,
+            Uuid::new_v4(),
+            Some(&SupplierPreferenceType::Blocked),
+        )
+        .await;
+        assert!(preferences.is_ok());
+        let search_preference = fetch_search_supplier_preference(&pool, Uuid::new_v4(), None).await;
+        assert!(search_preference.is_ok());
+    }
+}
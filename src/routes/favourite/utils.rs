@@ -0,0 +1,387 @@
+use super::models::{
+    FavouriteItemModel, FavouriteProviderModel, SupplierPreferenceCacheModel,
+    SupplierPreferenceModel,
+};
+use super::schemas::{
+    FavouriteItemRequest, FavouriteProviderRequest, SupplierPreferenceType,
+    SupplierPreferenceUpdateRequest,
+};
+use crate::routes::product::schemas::{SearchSupplierPreference, SupplierFilterType};
+use chrono::Utc;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+#[tracing::instrument(name = "save favourite item", skip(pool))]
+pub async fn save_favourite_item(
+    pool: &PgPool,
+    body: &FavouriteItemRequest,
+    user_id: Uuid,
+    business_id: Uuid,
+) -> Result<(), anyhow::Error> {
+    sqlx::query!(
+        r#"
+        INSERT INTO favourite_item (id, business_id, user_id, subscriber_id, provider_id, item_id,
+        created_on)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        ON CONFLICT (business_id, user_id, subscriber_id, provider_id, item_id) DO NOTHING
+        "#,
+        Uuid::new_v4(),
+        business_id,
+        user_id,
+        &body.bpp_id,
+        &body.provider_id,
+        &body.item_id,
+        Utc::now()
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e).context("A database failure occurred while saving favourite item")
+    })?;
+    Ok(())
+}
+
+#[tracing::instrument(name = "delete favourite item", skip(pool))]
+pub async fn delete_favourite_item(
+    pool: &PgPool,
+    body: &FavouriteItemRequest,
+    user_id: Uuid,
+    business_id: Uuid,
+) -> Result<(), anyhow::Error> {
+    sqlx::query!(
+        r#"
+        DELETE FROM favourite_item
+        WHERE business_id = $1 AND user_id = $2 AND subscriber_id = $3 AND provider_id = $4
+        AND item_id = $5
+        "#,
+        business_id,
+        user_id,
+        &body.bpp_id,
+        &body.provider_id,
+        &body.item_id
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e).context("A database failure occurred while deleting favourite item")
+    })?;
+    Ok(())
+}
+
+#[tracing::instrument(name = "fetch favourite items", skip(pool))]
+pub async fn fetch_favourite_items(
+    pool: &PgPool,
+    user_id: Uuid,
+    business_id: Uuid,
+) -> Result<Vec<FavouriteItemModel>, anyhow::Error> {
+    let rows = sqlx::query_as!(
+        FavouriteItemModel,
+        r#"
+        SELECT f.id, f.subscriber_id, f.provider_id, f.item_id,
+        cache.provider_name as "provider_name?", cache.item_name as "item_name?", f.created_on
+        FROM favourite_item AS f
+        LEFT JOIN LATERAL (
+            SELECT pc.name AS provider_name, pic.item_name
+            FROM provider_cache AS pc
+            JOIN network_participant_cache AS npc ON pc.network_participant_cache_id = npc.id
+            LEFT JOIN provider_item_cache AS pic ON pic.provider_cache_id = pc.id
+                AND pic.item_id = f.item_id
+            WHERE npc.subscriber_id = f.subscriber_id AND pc.provider_id = f.provider_id
+            LIMIT 1
+        ) AS cache ON true
+        WHERE f.business_id = $1 AND f.user_id = $2
+        ORDER BY f.created_on DESC
+        "#,
+        business_id,
+        user_id
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e).context("A database failure occurred while fetching favourite items")
+    })?;
+    Ok(rows)
+}
+
+#[tracing::instrument(name = "save favourite provider", skip(pool))]
+pub async fn save_favourite_provider(
+    pool: &PgPool,
+    body: &FavouriteProviderRequest,
+    user_id: Uuid,
+    business_id: Uuid,
+) -> Result<(), anyhow::Error> {
+    sqlx::query!(
+        r#"
+        INSERT INTO favourite_provider (id, business_id, user_id, subscriber_id, provider_id,
+        created_on)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        ON CONFLICT (business_id, user_id, subscriber_id, provider_id) DO NOTHING
+        "#,
+        Uuid::new_v4(),
+        business_id,
+        user_id,
+        &body.bpp_id,
+        &body.provider_id,
+        Utc::now()
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e).context("A database failure occurred while saving favourite provider")
+    })?;
+    Ok(())
+}
+
+#[tracing::instrument(name = "delete favourite provider", skip(pool))]
+pub async fn delete_favourite_provider(
+    pool: &PgPool,
+    body: &FavouriteProviderRequest,
+    user_id: Uuid,
+    business_id: Uuid,
+) -> Result<(), anyhow::Error> {
+    sqlx::query!(
+        r#"
+        DELETE FROM favourite_provider
+        WHERE business_id = $1 AND user_id = $2 AND subscriber_id = $3 AND provider_id = $4
+        "#,
+        business_id,
+        user_id,
+        &body.bpp_id,
+        &body.provider_id
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e)
+            .context("A database failure occurred while deleting favourite provider")
+    })?;
+    Ok(())
+}
+
+#[tracing::instrument(name = "fetch favourite providers", skip(pool))]
+pub async fn fetch_favourite_providers(
+    pool: &PgPool,
+    user_id: Uuid,
+    business_id: Uuid,
+) -> Result<Vec<FavouriteProviderModel>, anyhow::Error> {
+    let rows = sqlx::query_as!(
+        FavouriteProviderModel,
+        r#"
+        SELECT f.id, f.subscriber_id, f.provider_id, cache.name as "provider_name?", f.created_on
+        FROM favourite_provider AS f
+        LEFT JOIN LATERAL (
+            SELECT pc.name
+            FROM provider_cache AS pc
+            JOIN network_participant_cache AS npc ON pc.network_participant_cache_id = npc.id
+            WHERE npc.subscriber_id = f.subscriber_id AND pc.provider_id = f.provider_id
+            LIMIT 1
+        ) AS cache ON true
+        WHERE f.business_id = $1 AND f.user_id = $2
+        ORDER BY f.created_on DESC
+        "#,
+        business_id,
+        user_id
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e)
+            .context("A database failure occurred while fetching favourite providers")
+    })?;
+    Ok(rows)
+}
+
+#[tracing::instrument(name = "save supplier preference", skip(pool))]
+pub async fn save_supplier_preference(
+    pool: &PgPool,
+    body: &SupplierPreferenceUpdateRequest,
+    user_id: Uuid,
+    business_id: Uuid,
+) -> Result<(), anyhow::Error> {
+    sqlx::query!(
+        r#"
+        INSERT INTO supplier_preference (id, business_id, subscriber_id, provider_id, preference,
+        remark, created_on, created_by)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        ON CONFLICT (business_id, subscriber_id, provider_id) DO UPDATE SET
+        preference = EXCLUDED.preference,
+        remark = EXCLUDED.remark,
+        updated_on = EXCLUDED.created_on,
+        updated_by = EXCLUDED.created_by
+        "#,
+        Uuid::new_v4(),
+        business_id,
+        &body.bpp_id,
+        &body.provider_id,
+        &body.preference as &SupplierPreferenceType,
+        body.remark.as_deref(),
+        Utc::now(),
+        user_id
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e)
+            .context("A database failure occurred while saving supplier preference")
+    })?;
+    Ok(())
+}
+
+#[tracing::instrument(name = "delete supplier preference", skip(pool))]
+pub async fn delete_supplier_preference(
+    pool: &PgPool,
+    business_id: Uuid,
+    bpp_id: &str,
+    provider_id: &str,
+) -> Result<(), anyhow::Error> {
+    sqlx::query!(
+        r#"
+        DELETE FROM supplier_preference
+        WHERE business_id = $1 AND subscriber_id = $2 AND provider_id = $3
+        "#,
+        business_id,
+        bpp_id,
+        provider_id
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e)
+            .context("A database failure occurred while deleting supplier preference")
+    })?;
+    Ok(())
+}
+
+#[tracing::instrument(name = "fetch supplier preferences", skip(pool))]
+pub async fn fetch_supplier_preferences(
+    pool: &PgPool,
+    business_id: Uuid,
+    preference: Option<&SupplierPreferenceType>,
+) -> Result<Vec<SupplierPreferenceModel>, anyhow::Error> {
+    let rows = sqlx::query_as!(
+        SupplierPreferenceModel,
+        r#"
+        SELECT sp.id, sp.subscriber_id, sp.provider_id, cache.name as "provider_name?",
+        sp.preference as "preference: SupplierPreferenceType", sp.remark, sp.created_by,
+        sp.created_on
+        FROM supplier_preference AS sp
+        LEFT JOIN LATERAL (
+            SELECT pc.name
+            FROM provider_cache AS pc
+            JOIN network_participant_cache AS npc ON pc.network_participant_cache_id = npc.id
+            WHERE npc.subscriber_id = sp.subscriber_id AND pc.provider_id = sp.provider_id
+            LIMIT 1
+        ) AS cache ON true
+        WHERE sp.business_id = $1
+        AND ($2::supplier_preference_type IS NULL OR sp.preference = $2)
+        ORDER BY sp.created_on DESC
+        "#,
+        business_id,
+        preference as Option<&SupplierPreferenceType>
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e)
+            .context("A database failure occurred while fetching supplier preferences")
+    })?;
+    Ok(rows)
+}
+
+#[tracing::instrument(name = "fetch supplier preference", skip(pool))]
+pub async fn fetch_supplier_preference(
+    pool: &PgPool,
+    business_id: Uuid,
+    bpp_id: &str,
+    provider_id: &str,
+) -> Result<Option<SupplierPreferenceType>, anyhow::Error> {
+    let preference = sqlx::query_scalar!(
+        r#"
+        SELECT preference as "preference: SupplierPreferenceType"
+        FROM supplier_preference
+        WHERE business_id = $1 AND subscriber_id = $2 AND provider_id = $3
+        "#,
+        business_id,
+        bpp_id,
+        provider_id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e)
+            .context("A database failure occurred while fetching supplier preference")
+    })?;
+    Ok(preference)
+}
+
+#[tracing::instrument(name = "fetch supplier preference cache ids", skip(pool))]
+async fn fetch_supplier_preference_cache_ids(
+    pool: &PgPool,
+    business_id: Uuid,
+) -> Result<Vec<SupplierPreferenceCacheModel>, anyhow::Error> {
+    let rows = sqlx::query_as!(
+        SupplierPreferenceCacheModel,
+        r#"
+        SELECT pc.id AS provider_cache_id, sp.preference as "preference: SupplierPreferenceType"
+        FROM supplier_preference AS sp
+        JOIN network_participant_cache AS npc ON npc.subscriber_id = sp.subscriber_id
+        JOIN provider_cache AS pc ON pc.network_participant_cache_id = npc.id
+            AND pc.provider_id = sp.provider_id
+        WHERE sp.business_id = $1
+        "#,
+        business_id
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e)
+            .context("A database failure occurred while fetching supplier preference cache ids")
+    })?;
+    Ok(rows)
+}
+
+pub fn get_search_supplier_preference(
+    preferences: Vec<SupplierPreferenceCacheModel>,
+    supplier_filter: Option<&SupplierFilterType>,
+) -> SearchSupplierPreference {
+    let (preferred, blocked): (Vec<_>, Vec<_>) = preferences
+        .into_iter()
+        .partition(|preference| preference.preference == SupplierPreferenceType::Preferred);
+    let preferred_provider_ids: Vec<Uuid> = preferred
+        .into_iter()
+        .map(|preference| preference.provider_cache_id)
+        .collect();
+    SearchSupplierPreference {
+        included_provider_ids: (supplier_filter == Some(&SupplierFilterType::PreferredOnly))
+            .then(|| preferred_provider_ids.clone()),
+        excluded_provider_ids: if supplier_filter == Some(&SupplierFilterType::ExcludeBlocked) {
+            blocked
+                .into_iter()
+                .map(|preference| preference.provider_cache_id)
+                .collect()
+        } else {
+            vec![]
+        },
+        preferred_provider_ids,
+    }
+}
+
+pub async fn fetch_search_supplier_preference(
+    pool: &PgPool,
+    business_id: Uuid,
+    supplier_filter: Option<&SupplierFilterType>,
+) -> Result<SearchSupplierPreference, anyhow::Error> {
+    let preferences = fetch_supplier_preference_cache_ids(pool, business_id).await?;
+    Ok(get_search_supplier_preference(preferences, supplier_filter))
+}
@@ -1,12 +1,24 @@
+pub mod favourite;
 mod notification;
 pub mod ondc;
 pub mod order;
+pub mod payable;
 pub mod payment;
 pub mod product;
+pub mod recurring_order;
 mod route;
+pub mod saved_search;
+pub mod settlement;
 mod util;
+pub mod webhook;
+use favourite::favourite_route;
 use notification::notification_route;
 use order::order_route;
+use payable::payable_route;
 use product::product_route;
+use recurring_order::recurring_order_route;
 pub use route::*;
+use saved_search::saved_search_route;
+use settlement::settlement_route;
 use util::util_route;
+use webhook::webhook_route;
@@ -10,27 +10,52 @@ use super::schemas::{
     ONDCSelectRequest, WSConfirm, WSConfirmData, WSInit, WSInitData, WSSelect,
 };
 use super::utils::{
-    fetch_ondc_order_request, fetch_ondc_seller_info, get_ondc_order_param_from_commerce,
-    get_ondc_order_param_from_req, get_ondc_seller_location_info_mapping,
-    get_ondc_seller_product_info_mapping, get_product_search_params, validate_on_cancel,
-    validate_on_confirm, validate_on_init, validate_on_select, validate_on_status,
-    validate_on_update,
+    fetch_ondc_order_request, fetch_ondc_seller_info, get_catalog_push_search_params,
+    get_ondc_order_param_from_commerce, get_ondc_order_param_from_req,
+    get_ondc_seller_location_info_mapping, get_ondc_seller_product_info_mapping,
+    get_product_search_params, validate_on_cancel, validate_on_confirm, validate_on_init,
+    validate_on_select, validate_on_status, validate_on_update,
 };
 use super::{
-    KafkaSearchData, ONDCOnCancelRequest, ONDCOnStatusRequest, ONDCOnUpdateRequest,
-    ONDCRequestType, WSCancel, WSStatus, WSUpdate,
+    KafkaSearchData, ONDCOnCancelRequest, ONDCOnReceiverReconRequest, ONDCOnStatusRequest,
+    ONDCOnUpdateRequest, ONDCRequestType, WSCancel, WSStatus, WSUpdate,
 };
 use crate::chat_client::ChatClient;
+use crate::configuration::ONDCConfig;
 use crate::constants::ONDC_TTL;
 use crate::kafka_client::{KafkaClient, KafkaGroupName, KafkaTopicName};
+use crate::payment_client::PaymentClient;
 use crate::routes::ondc::{ONDCActionType, ONDCBuyerErrorCode, ONDCResponse};
+use crate::routes::order::schemas::{CommerceStatusType, QuoteVersionSource};
 use crate::routes::order::utils::{
-    fetch_order_by_id, initialize_order_on_cancel, initialize_order_on_confirm,
-    initialize_order_on_init, initialize_order_on_select, initialize_order_on_status,
-    initialize_order_on_update, send_rfq_accept_chat, send_rfq_cancel_chat,
-    send_rfq_confirmed_chat, send_rfq_init_chat, send_rfq_reject_chat, send_rfq_status_chat,
-    send_rfq_update_chat,
+    fetch_order_by_id, get_latest_quote_version, get_next_quote_version,
+    get_quote_version_items_from_on_select, initialize_order_on_cancel,
+    initialize_order_on_confirm, initialize_order_on_init, initialize_order_on_select,
+    initialize_order_on_status, initialize_order_on_update, save_commerce_quote_version,
+    send_rfq_accept_chat, send_rfq_cancel_chat, send_rfq_confirmed_chat, send_rfq_init_chat,
+    send_rfq_reject_chat, send_rfq_status_chat, send_rfq_update_chat,
 };
+use crate::routes::payable::schemas::CommercePayableStatus;
+use crate::routes::payable::utils::{
+    close_commerce_payable, save_commerce_payable_on_confirm, update_commerce_payable_on_delivery,
+};
+use crate::routes::payment::schemas::{CommerceRefundSource, RefundAmountType};
+use crate::routes::payment::utils::{
+    get_cancel_refund_amount, get_refundable_payment, get_return_refund_amount, initiate_refund,
+};
+use crate::routes::recurring_order::utils::{
+    process_recurring_order_on_confirm, process_recurring_order_on_init,
+    process_recurring_order_on_select, RecurringOrderContext,
+};
+use crate::routes::settlement::utils::{
+    update_commerce_settlement_on_receiver_recon, update_commerce_settlement_on_recon_error,
+};
+use crate::routes::webhook::schemas::WebhookEventType;
+use crate::routes::webhook::utils::emit_webhook_event;
+use crate::schemas::{ONDCNetworkType, StartUpMap};
+use crate::utils::get_np_detail;
+use bigdecimal::BigDecimal;
+use std::str::FromStr;
 
 use crate::user_client::UserClient;
 use crate::user_client::{CustomerType, SettingKey};
@@ -47,10 +72,19 @@ pub async fn on_search(
     websocket_srv: web::Data<WebSocketClient>,
     kafka_client: web::Data<KafkaClient>,
 ) -> Result<web::Json<ONDCResponse<ONDCBuyerErrorCode>>, ONDCBuyerError> {
-    let search_obj =
-        get_product_search_params(&pool, body.context.transaction_id, body.context.message_id)
+    let search_obj = match get_product_search_params(
+        &pool,
+        body.context.transaction_id,
+        body.context.message_id,
+    )
+    .await
+    .map_err(|_| ONDCBuyerError::BuyerInternalServerError { path: None })?
+    {
+        Some(search_obj) => Some(search_obj),
+        None => get_catalog_push_search_params(&pool, body.context.transaction_id)
             .await
-            .map_err(|_| ONDCBuyerError::BuyerInternalServerError { path: None })?;
+            .map_err(|_| ONDCBuyerError::BuyerInternalServerError { path: None })?,
+    };
     let extracted_search_obj =
         search_obj.ok_or(ONDCBuyerError::BuyerResponseSequenceError { path: None })?;
     let data = serde_json::to_string(&KafkaSearchData {
@@ -75,13 +109,17 @@ pub async fn on_search(
     Ok(web::Json(ONDCResponse::successful_response(None)))
 }
 
-#[tracing::instrument(name = "ONDC On select Payload", skip(pool), fields())]
+#[allow(clippy::too_many_arguments)]
+#[tracing::instrument(name = "ONDC On select Payload", skip(pool, kafka_client), fields())]
 pub async fn on_select(
     pool: web::Data<PgPool>,
     body: ONDCOnSelectRequest,
     websocket_srv: web::Data<WebSocketClient>,
     user_client: web::Data<UserClient>,
     chat_client: web::Data<ChatClient>,
+    ondc_obj: web::Data<ONDCConfig>,
+    maps: web::Data<StartUpMap>,
+    kafka_client: web::Data<KafkaClient>,
 ) -> Result<web::Json<ONDCResponse<ONDCBuyerErrorCode>>, ONDCBuyerError> {
     let error = body
         .error
@@ -235,16 +273,41 @@ pub async fn on_select(
             ONDCBuyerError::BuyerInternalServerError { path: None }
         })?;
         if is_rfq {
-            send_rfq_accept_chat(&chat_client, &body, &product_map)
+            let quote_version =
+                get_next_quote_version(&mut transaction, body.context.transaction_id)
+                    .await
+                    .map_err(|_| ONDCBuyerError::BuyerInternalServerError { path: None })?;
+            let grand_total = BigDecimal::from_str(&body.message.order.quote.price.value).ok();
+            save_commerce_quote_version(
+                &mut transaction,
+                body.context.transaction_id,
+                body.context.message_id,
+                quote_version,
+                QuoteVersionSource::Seller,
+                &get_quote_version_items_from_on_select(&body),
+                Some(&body.message.order.quote.price.currency),
+                grand_total.as_ref(),
+                None,
+            )
+            .await
+            .map_err(|e| {
+                tracing::error!("Failed to save quote version in on_select: {:?}", e);
+                ONDCBuyerError::BuyerInternalServerError { path: None }
+            })?;
+            send_rfq_accept_chat(&chat_client, &body, &product_map, quote_version)
                 .await
                 .map_err(|_| ONDCBuyerError::BuyerInternalServerError { path: None })?
         }
-    } else if let Some(error) = body.error {
+    } else if let Some(error) = &body.error {
+        let quote_version = get_latest_quote_version(&pool, body.context.transaction_id)
+            .await
+            .map_err(|_| ONDCBuyerError::BuyerInternalServerError { path: None })?;
         send_rfq_reject_chat(
             &chat_client,
             &error.message,
             body.context.transaction_id,
             &body.message.order.provider.id,
+            quote_version,
         )
         .await
         .map_err(|_| ONDCBuyerError::BuyerInternalServerError { path: None })?
@@ -255,6 +318,13 @@ pub async fn on_select(
         .await
         .context("Failed to commit SQL transaction to store an order")
         .map_err(|_| ONDCBuyerError::BuyerInternalServerError { path: None })?;
+    emit_webhook_event(
+        &pool,
+        ws_params_obj.business_id,
+        WebhookEventType::QuoteReceived,
+        &ws_json,
+    )
+    .await;
     let _ = websocket_srv
         .send_msg(
             ws_params_obj,
@@ -263,15 +333,40 @@ pub async fn on_select(
             None,
         )
         .await;
+
+    let recurring_order_context =
+        RecurringOrderContext::new(pool, ondc_obj, user_client, chat_client, maps, kafka_client);
+    let transaction_id = body.context.transaction_id;
+    let error = body.error.as_ref().map(|e| e.message.to_owned());
+    let quote_items = get_quote_version_items_from_on_select(&body);
+    let quote_total = BigDecimal::from_str(&body.message.order.quote.price.value).ok();
+    tokio::spawn(async move {
+        if let Err(e) = process_recurring_order_on_select(
+            &recurring_order_context,
+            transaction_id,
+            error,
+            quote_items,
+            quote_total,
+        )
+        .await
+        {
+            tracing::error!("Failed to process recurring order on_select: {:?}", e);
+        }
+    });
     Ok(web::Json(ONDCResponse::successful_response(None)))
 }
 
-#[tracing::instrument(name = "ONDC On Init Payload", skip(pool), fields())]
+#[allow(clippy::too_many_arguments)]
+#[tracing::instrument(name = "ONDC On Init Payload", skip(pool, kafka_client), fields())]
 pub async fn on_init(
     pool: web::Data<PgPool>,
     body: ONDCOnInitRequest,
     websocket_srv: web::Data<WebSocketClient>,
     chat_client: web::Data<ChatClient>,
+    user_client: web::Data<UserClient>,
+    ondc_obj: web::Data<ONDCConfig>,
+    maps: web::Data<StartUpMap>,
+    kafka_client: web::Data<KafkaClient>,
 ) -> Result<web::Json<ONDCResponse<ONDCBuyerErrorCode>>, ONDCBuyerError> {
     let task_1 = fetch_ondc_order_request(
         &pool,
@@ -291,7 +386,11 @@ pub async fn on_init(
         order_request_model_opt.ok_or(ONDCBuyerError::BuyerResponseSequenceError { path: None })?;
     let commerce_data =
         commerce_data_opt.ok_or(ONDCBuyerError::BuyerResponseSequenceError { path: None })?;
-    validate_on_init(&commerce_data)?;
+    let bap_detail = get_np_detail(&pool, &maps, &commerce_data.bap.id, &ONDCNetworkType::Bap)
+        .await
+        .map_err(|_| ONDCBuyerError::BuyerInternalServerError { path: None })?
+        .ok_or(ONDCBuyerError::BuyerInternalServerError { path: None })?;
+    validate_on_init(&commerce_data, &body, &bap_detail)?;
     let payment_links: Vec<String> = body
         .message
         .order
@@ -333,15 +432,33 @@ pub async fn on_init(
             .map_err(|_| ONDCBuyerError::BuyerInternalServerError { path: None })?;
     }
 
-    let _ = websocket_srv
-        .send_msg(ws_params_obj, WebSocketActionType::OrderInit, ws_json, None)
-        .await;
-
     transaction
         .commit()
         .await
         .context("Failed to commit SQL transaction to store an order")
         .map_err(|_| ONDCBuyerError::BuyerInternalServerError { path: None })?;
+    emit_webhook_event(
+        &pool,
+        ws_params_obj.business_id,
+        WebhookEventType::OrderInitialized,
+        &ws_json,
+    )
+    .await;
+    let _ = websocket_srv
+        .send_msg(ws_params_obj, WebSocketActionType::OrderInit, ws_json, None)
+        .await;
+
+    let recurring_order_context =
+        RecurringOrderContext::new(pool, ondc_obj, user_client, chat_client, maps, kafka_client);
+    let transaction_id = body.context.transaction_id;
+    let error = body.error.as_ref().map(|e| e.message.to_owned());
+    tokio::spawn(async move {
+        if let Err(e) =
+            process_recurring_order_on_init(&recurring_order_context, transaction_id, error).await
+        {
+            tracing::error!("Failed to process recurring order on_init: {:?}", e);
+        }
+    });
 
     Ok(web::Json(ONDCResponse::successful_response(None)))
 }
@@ -352,6 +469,7 @@ pub async fn on_confirm(
     body: ONDCOnConfirmRequest,
     websocket_srv: web::Data<WebSocketClient>,
     chat_client: web::Data<ChatClient>,
+    maps: web::Data<StartUpMap>,
 ) -> Result<web::Json<ONDCResponse<ONDCBuyerErrorCode>>, ONDCBuyerError> {
     let task1 = fetch_ondc_order_request(
         &pool,
@@ -368,7 +486,11 @@ pub async fn on_confirm(
     let order = res2
         .map_err(|_| ONDCBuyerError::BuyerInternalServerError { path: None })?
         .ok_or(ONDCBuyerError::BuyerResponseSequenceError { path: None })?;
-    validate_on_confirm(&order)?;
+    let bap_detail = get_np_detail(&pool, &maps, &order.bap.id, &ONDCNetworkType::Bap)
+        .await
+        .map_err(|_| ONDCBuyerError::BuyerInternalServerError { path: None })?
+        .ok_or(ONDCBuyerError::BuyerInternalServerError { path: None })?;
+    validate_on_confirm(&order, &body, &bap_detail)?;
     let payment_links: Vec<String> = body
         .message
         .order
@@ -401,6 +523,11 @@ pub async fn on_confirm(
     )
     .await
     .map_err(|_| ONDCBuyerError::BuyerInternalServerError { path: None })?;
+    if body.error.is_none() {
+        save_commerce_payable_on_confirm(&mut transaction, &order, &body, order.created_by)
+            .await
+            .map_err(|_| ONDCBuyerError::BuyerInternalServerError { path: None })?;
+    }
 
     if order.record_type.is_purchase_order() {
         send_rfq_confirmed_chat(&chat_client, body.context.transaction_id, &order)
@@ -408,6 +535,18 @@ pub async fn on_confirm(
             .map_err(|_| ONDCBuyerError::BuyerInternalServerError { path: None })?;
     }
 
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit SQL transaction to store an order")
+        .map_err(|_| ONDCBuyerError::BuyerInternalServerError { path: None })?;
+    emit_webhook_event(
+        &pool,
+        ws_params_obj.business_id,
+        WebhookEventType::OrderAccepted,
+        &ws_json,
+    )
+    .await;
     let _ = websocket_srv
         .send_msg(
             ws_params_obj,
@@ -416,11 +555,15 @@ pub async fn on_confirm(
             None,
         )
         .await;
-    transaction
-        .commit()
-        .await
-        .context("Failed to commit SQL transaction to store an order")
-        .map_err(|_| ONDCBuyerError::BuyerInternalServerError { path: None })?;
+    if let Err(e) = process_recurring_order_on_confirm(
+        &pool,
+        body.context.transaction_id,
+        body.error.as_ref().map(|e| e.message.as_str()),
+    )
+    .await
+    {
+        tracing::error!("Failed to process recurring order on_confirm: {:?}", e);
+    }
     Ok(web::Json(ONDCResponse::successful_response(None)))
 }
 
@@ -477,6 +620,17 @@ pub async fn on_status(
     initialize_order_on_status(&mut transaction, &body, &order)
         .await
         .map_err(|_| ONDCBuyerError::BuyerInternalServerError { path: None })?;
+    let is_delivered = body
+        .message
+        .order
+        .state
+        .get_commerce_status(&order.record_type, Some(proforma_present))
+        == CommerceStatusType::Completed;
+    if body.error.is_none() && is_delivered {
+        update_commerce_payable_on_delivery(&mut transaction, order.id, chrono::Utc::now())
+            .await
+            .map_err(|_| ONDCBuyerError::BuyerInternalServerError { path: None })?;
+    }
     let request_type = if order_request_model.is_some() {
         ONDCRequestType::Solicted
     } else {
@@ -494,6 +648,18 @@ pub async fn on_status(
 
     let ws_json = serde_json::to_value(ws_obj).unwrap();
     let ws_params_obj = get_ondc_order_param_from_commerce(&order);
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit SQL transaction to store an order")
+        .map_err(|_| ONDCBuyerError::BuyerInternalServerError { path: None })?;
+    emit_webhook_event(
+        &pool,
+        ws_params_obj.business_id,
+        WebhookEventType::FulfillmentStatusChanged,
+        &ws_json,
+    )
+    .await;
     let _ = websocket_srv
         .send_msg(
             ws_params_obj,
@@ -502,20 +668,21 @@ pub async fn on_status(
             None,
         )
         .await;
-    transaction
-        .commit()
-        .await
-        .context("Failed to commit SQL transaction to store an order")
-        .map_err(|_| ONDCBuyerError::BuyerInternalServerError { path: None })?;
     Ok(web::Json(ONDCResponse::successful_response(None)))
 }
 
-#[tracing::instrument(name = "ONDC On cancel Payload", skip(pool), fields())]
+#[tracing::instrument(
+    name = "ONDC On cancel Payload",
+    skip(pool, payment_client, user_client),
+    fields()
+)]
 pub async fn on_cancel(
     pool: web::Data<PgPool>,
     body: ONDCOnCancelRequest,
     websocket_srv: web::Data<WebSocketClient>,
     chat_client: web::Data<ChatClient>,
+    payment_client: web::Data<PaymentClient>,
+    user_client: web::Data<UserClient>,
 ) -> Result<web::Json<ONDCResponse<ONDCBuyerErrorCode>>, ONDCBuyerError> {
     let task1 = fetch_ondc_order_request(
         &pool,
@@ -549,16 +716,44 @@ pub async fn on_cancel(
     initialize_order_on_cancel(&mut transaction, &body, &order, &updated_by)
         .await
         .map_err(|_| ONDCBuyerError::BuyerInternalServerError { path: None })?;
+    let is_cancelled = body
+        .message
+        .order
+        .state
+        .get_commerce_status(&order.record_type, None)
+        == CommerceStatusType::Cancelled;
+    if body.error.is_none() && is_cancelled {
+        close_commerce_payable(
+            &mut transaction,
+            order.id,
+            CommercePayableStatus::Cancelled,
+            None,
+        )
+        .await
+        .map_err(|_| ONDCBuyerError::BuyerInternalServerError { path: None })?;
+    }
+    let ws_obj = WSCancel {
+        transaction_id: body.context.transaction_id,
+        message_id: body.context.message_id,
+        error: body
+            .error
+            .as_ref()
+            .map_or_else(|| None, |s| Some(s.message.to_owned())),
+    };
+    let ws_json = serde_json::to_value(ws_obj).unwrap();
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit SQL transaction to store an order")
+        .map_err(|_| ONDCBuyerError::BuyerInternalServerError { path: None })?;
+    emit_webhook_event(
+        &pool,
+        order.buyer_id,
+        WebhookEventType::OrderCancelled,
+        &ws_json,
+    )
+    .await;
     if let Some(order_request_model) = order_request_model {
-        let ws_obj = WSCancel {
-            transaction_id: body.context.transaction_id,
-            message_id: body.context.message_id,
-            error: body
-                .error
-                .as_ref()
-                .map_or_else(|| None, |s| Some(s.message.to_owned())),
-        };
-        let ws_json = serde_json::to_value(ws_obj).unwrap();
         let ws_params_obj = get_ondc_order_param_from_req(&order_request_model);
         let _ = websocket_srv
             .send_msg(
@@ -569,11 +764,26 @@ pub async fn on_cancel(
             )
             .await;
     }
-    transaction
-        .commit()
-        .await
-        .context("Failed to commit SQL transaction to store an order")
-        .map_err(|_| ONDCBuyerError::BuyerInternalServerError { path: None })?;
+    if body.error.is_none() && is_cancelled && get_refundable_payment(&order).is_ok() {
+        let refund_amount = get_cancel_refund_amount(&order, &body.message.order.quote.price.value);
+        if refund_amount > BigDecimal::from(0) {
+            if let Err(e) = initiate_refund(
+                &pool,
+                &payment_client,
+                &user_client,
+                &order,
+                &CommerceRefundSource::Cancel,
+                &order.urn,
+                &RefundAmountType::Exact(refund_amount),
+                Some("Order cancelled"),
+                order.created_by,
+            )
+            .await
+            {
+                tracing::error!("Failed to initiate refund on cancel: {:?}", e);
+            }
+        }
+    }
 
     Ok(web::Json(ONDCResponse::successful_response(None)))
 }
@@ -584,6 +794,8 @@ pub async fn on_update(
     body: ONDCOnUpdateRequest,
     websocket_srv: web::Data<WebSocketClient>,
     chat_client: web::Data<ChatClient>,
+    payment_client: web::Data<PaymentClient>,
+    user_client: web::Data<UserClient>,
 ) -> Result<web::Json<ONDCResponse<ONDCBuyerErrorCode>>, ONDCBuyerError> {
     let task1 = fetch_ondc_order_request(
         &pool,
@@ -617,16 +829,28 @@ pub async fn on_update(
     initialize_order_on_update(&mut transaction, &body, &order, &updated_by)
         .await
         .map_err(|_| ONDCBuyerError::BuyerInternalServerError { path: None })?;
+    let ws_obj = WSUpdate {
+        transaction_id: body.context.transaction_id,
+        message_id: body.context.message_id,
+        error: body
+            .error
+            .as_ref()
+            .map_or_else(|| None, |s| Some(s.message.to_owned())),
+    };
+    let ws_json = serde_json::to_value(ws_obj).unwrap();
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit SQL transaction to store an order")
+        .map_err(|_| ONDCBuyerError::BuyerInternalServerError { path: None })?;
+    emit_webhook_event(
+        &pool,
+        order.buyer_id,
+        WebhookEventType::OrderUpdated,
+        &ws_json,
+    )
+    .await;
     if let Some(order_request_model) = order_request_model {
-        let ws_obj = WSUpdate {
-            transaction_id: body.context.transaction_id,
-            message_id: body.context.message_id,
-            error: body
-                .error
-                .as_ref()
-                .map_or_else(|| None, |s| Some(s.message.to_owned())),
-        };
-        let ws_json = serde_json::to_value(ws_obj).unwrap();
         let ws_params_obj = get_ondc_order_param_from_req(&order_request_model);
         let _ = websocket_srv
             .send_msg(
@@ -637,11 +861,62 @@ pub async fn on_update(
             )
             .await;
     }
-    transaction
-        .commit()
+    if let (None, Some(quote)) = (&body.error, &body.message.order.quote) {
+        let refund_amount = get_return_refund_amount(&order, &quote.price.value);
+        if refund_amount > BigDecimal::from(0) && get_refundable_payment(&order).is_ok() {
+            if let Err(e) = initiate_refund(
+                &pool,
+                &payment_client,
+                &user_client,
+                &order,
+                &CommerceRefundSource::Return,
+                &body.context.message_id.to_string(),
+                &RefundAmountType::SourceTotal(refund_amount),
+                Some("Order items returned"),
+                order.created_by,
+            )
+            .await
+            {
+                tracing::error!("Failed to initiate refund on return: {:?}", e);
+            }
+        }
+    }
+
+    Ok(web::Json(ONDCResponse::successful_response(None)))
+}
+
+#[tracing::instrument(name = "ONDC On Receiver Recon Payload", skip(pool), fields())]
+pub async fn on_receiver_recon(
+    pool: web::Data<PgPool>,
+    body: ONDCOnReceiverReconRequest,
+) -> Result<web::Json<ONDCResponse<ONDCBuyerErrorCode>>, ONDCBuyerError> {
+    fetch_ondc_order_request(
+        &pool,
+        body.context.transaction_id,
+        body.context.message_id,
+        &ONDCActionType::ReceiverRecon,
+    )
+    .await
+    .map_err(|_| ONDCBuyerError::BuyerInternalServerError { path: None })?
+    .ok_or(ONDCBuyerError::BuyerResponseSequenceError { path: None })?;
+
+    if let Some(error) = &body.error {
+        update_commerce_settlement_on_recon_error(
+            &pool,
+            body.context.transaction_id,
+            &error.message,
+        )
+        .await
+        .map_err(|_| ONDCBuyerError::BuyerInternalServerError { path: None })?;
+    } else {
+        update_commerce_settlement_on_receiver_recon(
+            &pool,
+            body.context.transaction_id,
+            &body.message.orders,
+        )
         .await
-        .context("Failed to commit SQL transaction to store an order")
         .map_err(|_| ONDCBuyerError::BuyerInternalServerError { path: None })?;
+    }
 
     Ok(web::Json(ONDCResponse::successful_response(None)))
 }
@@ -1,6 +1,8 @@
 use actix_web::web;
 
-use super::handlers::{on_cancel, on_confirm, on_init, on_search, on_select, on_status, on_update};
+use super::handlers::{
+    on_cancel, on_confirm, on_init, on_receiver_recon, on_search, on_select, on_status, on_update,
+};
 
 pub fn ondc_route(cfg: &mut web::ServiceConfig) {
     cfg.service(web::resource("/on_search").route(web::post().to(on_search)));
@@ -10,4 +12,5 @@ pub fn ondc_route(cfg: &mut web::ServiceConfig) {
     cfg.service(web::resource("/on_status").route(web::post().to(on_status)));
     cfg.service(web::resource("/on_cancel").route(web::post().to(on_cancel)));
     cfg.service(web::resource("/on_update").route(web::post().to(on_update)));
+    cfg.service(web::resource("/on_receiver_recon").route(web::post().to(on_receiver_recon)));
 }
@@ -1,4 +1,4 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, SecondsFormat, Utc};
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
 use std::fmt::{Display, Formatter};
@@ -17,6 +17,7 @@ use crate::{
 };
 
 use super::errors::ONDCBuyerError;
+use crate::domain::pricing::{get_applicable_price_slab, PriceSlab};
 use crate::domain::EmailObject;
 
 use crate::routes::order::models::PaymentSettlementDetailModel;
@@ -26,7 +27,9 @@ use crate::routes::order::schemas::{
     PaymentSettlementCounterparty, PaymentSettlementPhase, PaymentSettlementType, PaymentStatus,
     ServiceableType, SettlementBasis,
 };
-use crate::routes::product::schemas::{FulfillmentType, PaymentType};
+use crate::routes::product::schemas::{
+    CatalogIncMode, FulfillmentType, PaymentType, ProductCatalogInc,
+};
 use crate::schemas::{CurrencyType, FeeType};
 
 use crate::websocket_client::WebSocketActionType;
@@ -42,6 +45,8 @@ use std::str::FromStr;
 pub enum ONDCVersion {
     #[serde(rename = "2.0.2")]
     V2point2,
+    #[serde(rename = "2.0.0")]
+    V2,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
@@ -67,6 +72,8 @@ pub enum ONDCActionType {
     OnIssue, // Pending
     IssueStatus,
     OnIssueStatus, // Pending
+    ReceiverRecon,
+    OnReceiverRecon,
 }
 
 impl Display for ONDCActionType {
@@ -104,33 +111,40 @@ pub enum ONDCDomain {
     #[serde(rename = "ONDC:RET1C")]
     #[sqlx(rename = "ONDC:RET1C")]
     BuildingAndConstructionSupplies,
+    #[serde(rename = "ONDC:NTS10")]
+    #[sqlx(rename = "ONDC:NTS10")]
+    Settlement,
 }
 
 impl ONDCDomain {
-    pub fn get_category_domain(&self) -> CategoryDomain {
+    pub fn get_category_domain(&self) -> Option<CategoryDomain> {
         match self {
-            ONDCDomain::Grocery => CategoryDomain::Grocery,
-            ONDCDomain::Fashion => CategoryDomain::Fashion,
-            ONDCDomain::Bpc => CategoryDomain::Bpc,
-            ONDCDomain::Electronics => CategoryDomain::Electronics,
-            ONDCDomain::Appliances => CategoryDomain::Appliances,
-            ONDCDomain::HomeAndKitchen => CategoryDomain::HomeAndKitchen,
+            ONDCDomain::Grocery => Some(CategoryDomain::Grocery),
+            ONDCDomain::Fashion => Some(CategoryDomain::Fashion),
+            ONDCDomain::Bpc => Some(CategoryDomain::Bpc),
+            ONDCDomain::Electronics => Some(CategoryDomain::Electronics),
+            ONDCDomain::Appliances => Some(CategoryDomain::Appliances),
+            ONDCDomain::HomeAndKitchen => Some(CategoryDomain::HomeAndKitchen),
             ONDCDomain::AutoComponentsAndAccessories => {
-                CategoryDomain::AutoComponentsAndAccessories
+                Some(CategoryDomain::AutoComponentsAndAccessories)
             }
             ONDCDomain::HardwareAndIndustrialEquipments => {
-                CategoryDomain::HardwareAndIndustrialEquipments
+                Some(CategoryDomain::HardwareAndIndustrialEquipments)
             }
             ONDCDomain::BuildingAndConstructionSupplies => {
-                CategoryDomain::BuildingAndConstructionSupplies
+                Some(CategoryDomain::BuildingAndConstructionSupplies)
             }
+            ONDCDomain::Settlement => None,
         }
     }
 }
 
 impl Display for ONDCDomain {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "ONDC:{}", self.get_category_domain())
+        match self.get_category_domain() {
+            Some(category_domain) => write!(f, "ONDC:{}", category_domain),
+            None => write!(f, "ONDC:NTS10"),
+        }
     }
 }
 
@@ -428,6 +442,10 @@ pub enum ONDCTagType {
     BppTerms,
     #[serde(rename = "COMM_CHANNEL")]
     CommChannel,
+    #[serde(rename = "NEGOTIATION")]
+    Negotiation,
+    CatalogInc,
+    Instalment,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -489,6 +507,13 @@ pub enum ONDCTagItemCode {
     DelayInterest,
     AcceptBppTerms,
     ChatUrl,
+    #[serde(rename = "QUOTE_VERSION")]
+    QuoteVersion,
+    #[serde(rename = "NEGOTIATED_PRICE")]
+    NegotiatedPrice,
+    StartTime,
+    EndTime,
+    Mode,
 }
 
 impl std::fmt::Display for ONDCTagItemCode {
@@ -706,6 +731,52 @@ impl ONDCTag {
         }
     }
 
+    pub fn get_payment_instalment_tag(seq: usize) -> ONDCTag {
+        let instalment_type = if seq == 1 { "advance" } else { "balance" };
+        ONDCTag {
+            descriptor: ONDCTagDescriptor {
+                code: ONDCTagType::Instalment,
+            },
+            list: vec![
+                ONDCTagItem::set_tag_item(ONDCTagItemCode::Seq, &seq.to_string()),
+                ONDCTagItem::set_tag_item(ONDCTagItemCode::Type, instalment_type),
+            ],
+        }
+    }
+
+    pub fn get_catalog_inc_tag(catalog_inc: &ProductCatalogInc) -> ONDCTag {
+        let list = match catalog_inc.mode {
+            CatalogIncMode::Pull => vec![
+                ONDCTagItem::set_tag_item(
+                    ONDCTagItemCode::StartTime,
+                    &catalog_inc
+                        .start_time
+                        .map(|time| time.to_rfc3339_opts(SecondsFormat::Millis, true))
+                        .unwrap_or_default(),
+                ),
+                ONDCTagItem::set_tag_item(
+                    ONDCTagItemCode::EndTime,
+                    &catalog_inc
+                        .end_time
+                        .map(|time| time.to_rfc3339_opts(SecondsFormat::Millis, true))
+                        .unwrap_or_default(),
+                ),
+            ],
+            CatalogIncMode::PushStart => {
+                vec![ONDCTagItem::set_tag_item(ONDCTagItemCode::Mode, "start")]
+            }
+            CatalogIncMode::PushStop => {
+                vec![ONDCTagItem::set_tag_item(ONDCTagItemCode::Mode, "stop")]
+            }
+        };
+        ONDCTag {
+            descriptor: ONDCTagDescriptor {
+                code: ONDCTagType::CatalogInc,
+            },
+            list,
+        }
+    }
+
     pub fn get_item_tags(item_terms: &str, packaging_req: &str) -> ONDCTag {
         ONDCTag {
             descriptor: ONDCTagDescriptor {
@@ -770,6 +841,25 @@ impl ONDCTag {
             )],
         }
     }
+
+    pub fn get_negotiation_tag(quote_version: i32, unit_price: Option<&BigDecimal>) -> ONDCTag {
+        let mut list = vec![ONDCTagItem::set_tag_item(
+            ONDCTagItemCode::QuoteVersion,
+            &quote_version.to_string(),
+        )];
+        if let Some(unit_price) = unit_price {
+            list.push(ONDCTagItem::set_tag_item(
+                ONDCTagItemCode::NegotiatedPrice,
+                &unit_price.to_string(),
+            ));
+        }
+        ONDCTag {
+            descriptor: ONDCTagDescriptor {
+                code: ONDCTagType::Negotiation,
+            },
+            list,
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -1171,7 +1261,7 @@ pub struct ONDCOnSearchItem {
     pub price: ONDCOnSearchItemPrice,
     pub quantity: ONDCOnSearchItemQuantity,
     add_ons: Option<Vec<ONDCOnSearchItemAddOns>>,
-    pub time: Option<ONDCTime>,
+    pub time: Option<ONDCOnSearchTime>,
     pub replacement_terms: Vec<ONDCItemReplacementTerm>,
     pub return_terms: Vec<ONDCReturnTerm>,
     pub cancellation_terms: Vec<ONDCItemCancellationTerm>,
@@ -1187,6 +1277,7 @@ pub struct ONDCOnSearchProviderLocation {
     pub state: ONDCOnSearchState,
     pub country: ONDCOnSearchCountry,
     pub area_code: String,
+    pub time: Option<ONDCOnSearchTime>,
 }
 
 #[skip_serializing_none]
@@ -1236,6 +1327,20 @@ pub struct ONDCTime {
     pub range: ONDCRange,
 }
 
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ONDCOnSearchTime {
+    pub label: String,
+    pub range: Option<ONDCRange>,
+    pub timestamp: Option<DateTime<Utc>>,
+}
+
+impl ONDCOnSearchTime {
+    pub fn is_disabled(&self) -> bool {
+        self.label.eq_ignore_ascii_case("disable")
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 struct ONDCOnSearchOffer {
     id: String,
@@ -1265,6 +1370,7 @@ pub struct ONDCOnSearchProvider {
     pub payments: Option<Vec<ONDCOnSearchPayment>>,
     pub rating: Option<String>,
     pub ttl: String,
+    pub time: Option<ONDCOnSearchTime>,
     pub creds: Option<Vec<ONDCCredential>>,
     pub locations: Vec<ONDCOnSearchProviderLocation>,
     pub tags: Vec<ONDCTag>,
@@ -1749,16 +1855,29 @@ pub struct ONDCSellerProductInfo {
     pub currency_code: CurrencyType,
     pub price_slab: Option<sqlx::types::Json<Vec<ONDCSellePriceSlab>>>,
 }
+
+impl PriceSlab for ONDCSellePriceSlab {
+    fn min_qty(&self) -> &BigDecimal {
+        &self.min
+    }
+    fn max_qty(&self) -> Option<&BigDecimal> {
+        self.max.as_ref()
+    }
+}
+
 impl ONDCSellerProductInfo {
+    pub fn get_price_slab(&self, qty: &BigDecimal) -> Option<&ONDCSellePriceSlab> {
+        self.price_slab
+            .as_ref()
+            .and_then(|price_slabs| get_applicable_price_slab(&price_slabs.0, qty))
+    }
     pub fn get_price(&self, qty: &BigDecimal) -> &BigDecimal {
-        if let Some(ref price_slabs) = self.price_slab {
-            for slab in price_slabs.0.iter() {
-                if qty >= &slab.min && (slab.max.is_none() || qty <= slab.max.as_ref().unwrap()) {
-                    return &slab.price_without_tax;
-                }
-            }
-        }
-        &self.unit_price_without_tax
+        self.get_price_slab(qty)
+            .map_or(&self.unit_price_without_tax, |slab| &slab.price_without_tax)
+    }
+    pub fn get_price_with_tax(&self, qty: &BigDecimal) -> &BigDecimal {
+        self.get_price_slab(qty)
+            .map_or(&self.unit_price_with_tax, |slab| &slab.price_with_tax)
     }
 }
 #[derive(Debug, Serialize, Deserialize)]
@@ -1936,6 +2055,8 @@ impl ONDCPaymentCollectedBy {
 pub struct ONDCInitPayment {
     pub r#type: ONDCPaymentType,
     pub collected_by: ONDCPaymentCollectedBy,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub params: Option<ONDCPaymentParams>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -2635,6 +2756,8 @@ pub struct ONDCUpdateOrder {
     pub provider: ONDCUpdateProvider,
     pub payments: Vec<ONDCOnConfirmPayment>,
     pub items: Vec<ONDCUpdateItem>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quote: Option<ONDCQuote>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -2732,3 +2855,105 @@ impl<T: Serialize> ObservabilityData<T> {
         }
     }
 }
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "SCREAMING-KEBAB-CASE")]
+pub enum ONDCReconSettlementStatus {
+    Pending,
+    Settled,
+    NotSettled,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ONDCReconBankDetail {
+    pub account_no: String,
+    pub ifsc_code: String,
+    pub beneficiary_name: String,
+    pub bank_name: String,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ONDCReconProvider {
+    pub id: String,
+    pub name: Option<String>,
+    pub bank_details: Option<ONDCReconBankDetail>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ONDCReconSettlement {
+    pub id: String,
+    pub payment_id: Option<String>,
+    pub status: ONDCReconSettlementStatus,
+    pub amount: ONDCAmount,
+    pub commission: ONDCAmount,
+    pub withholding_amount: ONDCAmount,
+    pub settlement_ref_no: Option<String>,
+    #[serde(serialize_with = "serialize_timestamp_without_nanos")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ONDCReconOrder {
+    pub id: String,
+    pub amount: ONDCAmount,
+    pub settlements: Vec<ONDCReconSettlement>,
+    pub provider: ONDCReconProvider,
+    pub state: ONDCOrderStatus,
+    #[serde(serialize_with = "serialize_timestamp_without_nanos")]
+    pub created_at: DateTime<Utc>,
+    #[serde(serialize_with = "serialize_timestamp_without_nanos")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ONDCReceiverReconMessage {
+    pub orders: Vec<ONDCReconOrder>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ONDCReceiverReconRequest {
+    pub context: ONDCContext,
+    pub message: ONDCReceiverReconMessage,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ONDCOnReceiverReconOrder {
+    pub id: String,
+    pub amount: Option<ONDCAmount>,
+    pub recon_accord: bool,
+    pub diff_amount: Option<ONDCAmount>,
+    pub remark: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ONDCOnReceiverReconMessage {
+    pub orders: Vec<ONDCOnReceiverReconOrder>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ONDCOnReceiverReconRequest {
+    pub context: ONDCContext,
+    pub message: ONDCOnReceiverReconMessage,
+    pub error: Option<ONDCResponseErrorBody<ONDCSellerErrorCode>>,
+}
+
+impl FromRequest for ONDCOnReceiverReconRequest {
+    type Error = ONDCBuyerError;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let fut = web::Json::<Self>::from_request(req, payload);
+
+        Box::pin(async move {
+            match fut.await {
+                Ok(json) => Ok(json.into_inner()),
+                Err(e) => Err(ONDCBuyerError::InvalidResponseError {
+                    path: None,
+                    message: e.to_string(),
+                }),
+            }
+        })
+    }
+}
@@ -2,18 +2,21 @@
 mod tests {
     use uuid::Uuid;
 
-    use crate::routes::ondc::schemas::{ONDCFulfillmentType, ONDCPaymentType};
+    use crate::routes::ondc::schemas::{
+        ONDCFeeType, ONDCFulfillmentType, ONDCPaymentType, ONDCTag,
+    };
     use crate::routes::ondc::utils::{
         fetch_ondc_order_request, fetch_ondc_seller_product_info, get_ondc_search_message_obj,
         get_ondc_search_payment_obj, get_ondc_seller_location_info_mapping,
-        get_product_search_params, get_search_fulfillment_obj,
+        get_product_search_params, get_search_fulfillment_obj, validate_bap_terms_tag,
+        validate_buyer_finder_fee,
     };
     use crate::routes::ondc::ONDCActionType;
     use crate::routes::product::schemas::{
         CategoryDomain, FulfillmentType, PaymentType, ProductFulFillmentLocation,
         ProductSearchRequest, ProductSearchType,
     };
-    use crate::schemas::{CountryCode, RegisteredNetworkParticipant};
+    use crate::schemas::{CountryCode, FeeType, RegisteredNetworkParticipant};
     use crate::tests::tests::{
         get_dummy_business_account, get_dummy_registed_np_detail, get_dummy_user_account,
         get_test_pool,
@@ -89,6 +92,7 @@ mod tests {
             fulfillment_locations: None,
             city_code: "std:080".to_string(),
             update_cache: false,
+            catalog_inc: None,
         };
 
         // seach by item
@@ -176,4 +180,21 @@ mod tests {
         .await;
         assert!(data.is_ok())
     }
+
+    #[test]
+    fn test_buyer_finder_fee_validation() {
+        let bap_detail = get_dummy_registed_np_detail();
+        let path = "message.order.payments[0]";
+        assert!(validate_buyer_finder_fee(&FeeType::Amount, "0", &bap_detail, path).is_ok());
+        assert!(validate_buyer_finder_fee(&FeeType::Amount, "0.00", &bap_detail, path).is_ok());
+        assert!(validate_buyer_finder_fee(&FeeType::Amount, "5", &bap_detail, path).is_err());
+        assert!(validate_buyer_finder_fee(&FeeType::Percent, "0", &bap_detail, path).is_err());
+        assert!(validate_buyer_finder_fee(&FeeType::Amount, "abc", &bap_detail, path).is_err());
+
+        let tags = vec![ONDCTag::get_buyer_fee_tag(ONDCFeeType::Amount, "0.0")];
+        assert!(validate_bap_terms_tag(&tags, &bap_detail).is_ok());
+        let tags = vec![ONDCTag::get_buyer_fee_tag(ONDCFeeType::Percent, "3")];
+        assert!(validate_bap_terms_tag(&tags, &bap_detail).is_err());
+        assert!(validate_bap_terms_tag(&[], &bap_detail).is_ok());
+    }
 }
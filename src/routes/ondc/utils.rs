@@ -4,17 +4,21 @@ use super::{
     ONDCCancelRequest, ONDCConfirmMessage, ONDCConfirmOrder, ONDCConfirmProvider, ONDCContext,
     ONDCContextCity, ONDCContextCountry, ONDCContextLocation, ONDCCredential, ONDCCredentialType,
     ONDCDomain, ONDCFeeType, ONDCItemCancellationFee, ONDCOnConfirmFulfillment,
-    ONDCOnSearchCategory, ONDCOnSearchFulfillmentContact, ONDCOnSearchItem, ONDCSearchStop,
-    ONDCSellePriceSlab, ONDCServicabilityCoordinate, ONDCStatusMessage, ONDCStatusRequest, ONDCTag,
-    ONDCUpdateItem, ONDCUpdateMessage, ONDCUpdateOrder, ONDCUpdateProvider, ONDCUpdateRequest,
-    ONDCVersion, ObservabilityData, OndcUrl,
+    ONDCOnConfirmRequest, ONDCOnInitRequest, ONDCOnSearchCategory, ONDCOnSearchFulfillmentContact,
+    ONDCOnSearchItem, ONDCPaymentType, ONDCSearchStop, ONDCSellePriceSlab,
+    ONDCServicabilityCoordinate, ONDCStatusMessage, ONDCStatusRequest, ONDCTag, ONDCUpdateItem,
+    ONDCUpdateMessage, ONDCUpdateOrder, ONDCUpdateProvider, ONDCUpdateRequest, ONDCVersion,
+    ObservabilityData, OndcUrl,
 };
 
 use crate::chat_client::ChatData;
-use crate::configuration::ONDCConfig;
+use crate::configuration::{ONDCConfig, SearchConfig};
 use crate::elastic_search_client::ElasticSearchClient;
 use crate::kafka_client::{KafkaClient, KafkaGroupName, KafkaTopicName, ObservabilityProducerData};
-use crate::routes::product::utils::{save_cache_to_db, save_cache_to_elastic_search};
+use crate::routes::product::utils::{
+    delete_cache_removals_from_db, delete_cache_removals_from_elastic_search, save_cache_to_db,
+    save_cache_to_elastic_search, set_variant_combinations,
+};
 use crate::user_client::{get_vector_val_from_list, BusinessAccount, UserAccount, VectorType};
 use crate::websocket_client::{NotificationProcessType, WebSocketActionType, WebSocketClient};
 use crate::{constants::ONDC_TTL, routes::product::ProductSearchError};
@@ -47,13 +51,15 @@ use super::schemas::{
     ONDCOnSearchProviderLocation, ONDCOnSearchRequest, ONDCOrderCancellationFee,
     ONDCOrderCancellationTerm, ONDCOrderFulfillmentEnd, ONDCOrderItemQuantity, ONDCOrderStatus,
     ONDCPaymentParams, ONDCPaymentSettlementCounterparty, ONDCPaymentSettlementDetail,
-    ONDCPaymentStatus, ONDCQuantityCountInt, ONDCQuantitySelect, ONDCQuote, ONDCRequestModel,
+    ONDCPaymentStatus, ONDCQuantityCountInt, ONDCQuantitySelect, ONDCQuote,
+    ONDCReceiverReconMessage, ONDCReceiverReconRequest, ONDCReconBankDetail, ONDCReconOrder,
+    ONDCReconProvider, ONDCReconSettlement, ONDCReconSettlementStatus, ONDCRequestModel,
     ONDCSearchCategory, ONDCSearchDescriptor, ONDCSearchFulfillment, ONDCSearchIntent,
     ONDCSearchItem, ONDCSearchLocation, ONDCSearchMessage, ONDCSearchPayment, ONDCSearchRequest,
     ONDCSelectFulfillmentLocation, ONDCSelectMessage, ONDCSelectOrder, ONDCSelectPayment,
     ONDCSelectProvider, ONDCSelectRequest, ONDCSelectedItem, ONDCSellerLocationInfo,
-    ONDCSellerProductInfo, ONDCState, ONDCTagItemCode, ONDCTagType, ONDConfirmRequest,
-    OnSearchContentType, TagTrait,
+    ONDCSellerProductInfo, ONDCSettlementBasis, ONDCState, ONDCTagItemCode, ONDCTagType,
+    ONDConfirmRequest, OnSearchContentType, TagTrait,
 };
 use crate::domain::EmailObject;
 use crate::routes::ondc::schemas::{ONDCCity, ONDCPerson, ONDCSellerInfo};
@@ -63,26 +69,30 @@ use crate::routes::order::schemas::{
     BuyerTerms, CancellationFeeType, Commerce, CommerceBilling, CommerceCancellationFee,
     CommerceCancellationTerm, CommerceFulfillment, CommerceItem, CommercePayment,
     CommerceStatusType, DropOffData, FulfillmentStatusType, OrderCancelRequest,
-    OrderConfirmRequest, OrderDeliveyTerm, OrderInitBilling, OrderInitRequest,
-    OrderSelectFulfillment, OrderSelectItem, OrderSelectRequest, OrderStatusRequest, OrderType,
-    OrderUpdateRequest, PaymentCollectedBy, PickUpData, SelectFulfillmentLocation, SettlementBasis,
-    TradeType, UpdateOrderPaymentRequest,
+    OrderConfirmRequest, OrderDeliveyTerm, OrderInitBilling, OrderInitRequest, OrderNegotiateItem,
+    OrderNegotiateRequest, OrderSelectFulfillment, OrderSelectItem, OrderSelectRequest,
+    OrderStatusRequest, OrderType, OrderUpdateRequest, PaymentCollectedBy,
+    PaymentSettlementCounterparty, PaymentStatus, PickUpData, SelectFulfillmentLocation,
+    SettlementBasis, TradeType, UpdateOrderPaymentRequest,
 };
 use crate::routes::product::schemas::{
-    CategoryDomain, CredentialType, FulfillmentType, PaymentType, ProductFulFillmentLocation,
-    ProductSearchRequest, ProductSearchType, SearchRequestModel, WSCreatorContactData,
-    WSItemCancellation, WSItemCancellationFee, WSItemCancellationTerm, WSItemReplacementTerm,
-    WSItemReturnLocation, WSItemReturnTerm, WSItemReturnTime, WSItemValidity, WSPaymentTypes,
-    WSPriceSlab, WSProductCategory, WSProductCreator, WSSearch, WSSearchBPP, WSSearchCity,
-    WSSearchCountry, WSSearchData, WSSearchItem, WSSearchItemAttribute, WSSearchItemPrice,
-    WSSearchItemQty, WSSearchItemQtyMeasure, WSSearchItemQuantity, WSSearchProvider,
-    WSSearchProviderContact, WSSearchProviderCredential, WSSearchProviderDescription,
-    WSSearchProviderID, WSSearchProviderLocation, WSSearchProviderTerms, WSSearchServicability,
-    WSSearchState, WSSearchVariant, WSSearchVariantAttribute, WSServicabilityData,
+    CatalogIncMode, CatalogRemovalData, CategoryDomain, CredentialType, FulfillmentType,
+    PaymentType, ProductCatalogInc, ProductFulFillmentLocation, ProductSearchRequest,
+    ProductSearchType, SearchRequestModel, WSCreatorContactData, WSItemCancellation,
+    WSItemCancellationFee, WSItemCancellationTerm, WSItemReplacementTerm, WSItemReturnLocation,
+    WSItemReturnTerm, WSItemReturnTime, WSItemValidity, WSPaymentTypes, WSPriceSlab,
+    WSProductCategory, WSProductCreator, WSSearch, WSSearchBPP, WSSearchCity, WSSearchCountry,
+    WSSearchData, WSSearchItem, WSSearchItemAttribute, WSSearchItemPrice, WSSearchItemQty,
+    WSSearchItemQtyMeasure, WSSearchItemQuantity, WSSearchProvider, WSSearchProviderContact,
+    WSSearchProviderCredential, WSSearchProviderDescription, WSSearchProviderID,
+    WSSearchProviderLocation, WSSearchProviderTerms, WSSearchServicability, WSSearchState,
+    WSSearchVariant, WSSearchVariantAttribute, WSServicabilityData,
 };
+use crate::routes::settlement::schemas::CommerceSettlementData;
 use serde_json::Value;
 use sqlx::types::Json;
 
+use crate::routes::payable::utils::get_payable_due_on;
 use crate::schemas::{
     CountryCode, CurrencyType, FeeType, NetworkCall, ONDCNetworkType, RegisteredNetworkParticipant,
     WebSocketParam,
@@ -361,6 +371,21 @@ pub fn get_search_fulfillment_obj(
     None
 }
 
+fn validate_catalog_inc(catalog_inc: &ProductCatalogInc) -> Result<(), ProductSearchError> {
+    if catalog_inc.mode != CatalogIncMode::Pull {
+        return Ok(());
+    }
+    match (catalog_inc.start_time, catalog_inc.end_time) {
+        (Some(start_time), Some(end_time)) if start_time < end_time => Ok(()),
+        (Some(_), Some(_)) => Err(ProductSearchError::ValidationError(
+            "Catalog increment start time must be before end time".to_string(),
+        )),
+        _ => Err(ProductSearchError::ValidationError(
+            "Catalog increment pull requires start time and end time".to_string(),
+        )),
+    }
+}
+
 #[tracing::instrument(name = "get ondc search message obj", skip())]
 pub fn get_ondc_search_message_obj(
     _user_account: &UserAccount,
@@ -378,10 +403,16 @@ pub fn get_ondc_search_message_obj(
         payment_obj = get_ondc_search_payment_obj(&search_request.payment_type);
     }
 
+    let mut tags = get_search_tags(business_account, np_detail)?;
+    if let Some(catalog_inc) = &search_request.catalog_inc {
+        validate_catalog_inc(catalog_inc)?;
+        tags.push(ONDCTag::get_catalog_inc_tag(catalog_inc));
+    }
+
     Ok(ONDCSearchMessage {
         intent: ONDCSearchIntent {
             fulfillment: fulfillment_obj,
-            tags: get_search_tags(business_account, np_detail)?,
+            tags,
             payment: payment_obj,
             item: get_search_by_item(search_request),
 
@@ -507,6 +538,37 @@ pub async fn get_product_search_params(
     Ok(row)
 }
 
+#[tracing::instrument(name = "Fetch catalog push search params", skip(pool))]
+pub async fn get_catalog_push_search_params(
+    pool: &PgPool,
+    transaction_id: Uuid,
+) -> Result<Option<SearchRequestModel>, anyhow::Error> {
+    let row = sqlx::query_as!(
+        SearchRequestModel,
+        r#"SELECT transaction_id, user_id, business_id, device_id, update_cache
+        FROM search_request AS sr
+        WHERE transaction_id = $1 AND catalog_inc_mode = 'push_start'
+        AND NOT EXISTS (
+            SELECT 1 FROM search_request AS stop_request
+            WHERE stop_request.transaction_id = sr.transaction_id
+            AND stop_request.catalog_inc_mode = 'push_stop'
+            AND stop_request.created_on > sr.created_on
+        )
+        ORDER BY created_on DESC LIMIT 1
+        "#,
+        transaction_id,
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e)
+            .context("A database failure occurred while fetching catalog push search request")
+    })?;
+
+    Ok(row)
+}
+
 pub fn get_ondc_order_param_from_req(ondc_req: &ONDCRequestModel) -> WebSocketParam {
     WebSocketParam {
         device_id: None,
@@ -665,6 +727,7 @@ pub fn ws_search_provider_from_ondc_provider(
             value: seller_id_value.to_owned(),
         }],
         credentials,
+        refreshed_on: None,
     }
 }
 
@@ -911,6 +974,44 @@ fn handle_intercity_servicability_in_ondc_search(
     })
 }
 
+pub fn get_catalog_removal_data(on_search_obj: &ONDCOnSearchRequest) -> CatalogRemovalData<'_> {
+    let mut removal_data = CatalogRemovalData::default();
+    if let Some(catalog) = &on_search_obj.message.catalog {
+        for provider_obj in &catalog.providers {
+            if provider_obj
+                .time
+                .as_ref()
+                .is_some_and(|time| time.is_disabled())
+            {
+                removal_data.provider_ids.push(provider_obj.id.as_str());
+                continue;
+            }
+            for location in provider_obj.locations.iter().filter(|location| {
+                location
+                    .time
+                    .as_ref()
+                    .is_some_and(|time| time.is_disabled())
+            }) {
+                removal_data
+                    .location_provider_ids
+                    .push(provider_obj.id.as_str());
+                removal_data.location_ids.push(location.id.as_str());
+            }
+            for item in provider_obj
+                .items
+                .iter()
+                .filter(|item| item.time.as_ref().is_some_and(|time| time.is_disabled()))
+            {
+                removal_data
+                    .item_provider_ids
+                    .push(provider_obj.id.as_str());
+                removal_data.item_ids.push(item.id.as_str());
+            }
+        }
+    }
+    removal_data
+}
+
 #[tracing::instrument(name = "get product from on search request", skip())]
 pub fn get_servicability_from_on_search_request(
     tags: &Vec<ONDCTag>,
@@ -1049,6 +1150,7 @@ fn get_variant_mapping(variants: &Vec<ONDCOnSearchCategory>) -> HashMap<String,
                     id: variant.id.clone(),
                     name: variant.descriptor.name.clone(),
                     attributes,
+                    combinations: vec![],
                 },
             );
         }
@@ -1117,15 +1219,27 @@ pub fn get_product_from_on_search_request(
             if let Some(provider_payment_objs) = &provider_obj.payments {
                 payment_mapping = get_payment_mapping(provider_payment_objs);
             }
+            if provider_obj
+                .time
+                .as_ref()
+                .is_some_and(|time| time.is_disabled())
+            {
+                continue;
+            }
             let location_obj: HashMap<String, WSSearchProviderLocation> = provider_obj
                 .locations
                 .iter()
+                .filter(|f| !f.time.as_ref().is_some_and(|time| time.is_disabled()))
                 .map(|f| (f.id.clone(), get_ws_location_mapping(f)))
                 .collect();
 
             // let provider_payment_obj = &provider.payments;
             let mut product_list: Vec<WSSearchItem> = vec![];
-            for item in &provider_obj.items {
+            for item in provider_obj
+                .items
+                .iter()
+                .filter(|item| !item.time.as_ref().is_some_and(|time| time.is_disabled()))
+            {
                 let tax_rate = get_search_tag_item_value(
                     &item.tags,
                     &ONDCTagType::Origin,
@@ -1171,10 +1285,14 @@ pub fn get_product_from_on_search_request(
                         })
                         .collect()
                 });
-                let validity = item.time.as_ref().map(|e| WSItemValidity {
-                    start: e.range.start,
-                    end: e.range.end,
-                });
+                let validity = item
+                    .time
+                    .as_ref()
+                    .and_then(|e| e.range.as_ref())
+                    .map(|range| WSItemValidity {
+                        start: range.start,
+                        end: range.end,
+                    });
                 let replacement_terms = item
                     .replacement_terms
                     .iter()
@@ -1215,7 +1333,11 @@ pub fn get_product_from_on_search_request(
                     long_desc: item.descriptor.long_desc.clone(),
                     short_desc: item.descriptor.short_desc.clone(),
                     code: item.descriptor.code.clone(),
-                    domain_category: on_search_obj.context.domain.get_category_domain(),
+                    domain_category: on_search_obj
+                        .context
+                        .domain
+                        .get_category_domain()
+                        .ok_or_else(|| anyhow!("Invalid search domain"))?,
                     price: get_price_obj_from_ondc_price_obj(&item.price, &tax)?,
                     parent_item_id: item.parent_item_id.clone(),
                     recommended: item.recommended,
@@ -1237,6 +1359,7 @@ pub fn get_product_from_on_search_request(
                     tax_rate: tax,
                     quantity: get_ws_quantity_from_ondc_quantity(&item.quantity),
                     price_slabs,
+                    applicable_price_slab: None,
                     attributes,
                     matched: item.matched,
                     country_of_origin,
@@ -1249,6 +1372,10 @@ pub fn get_product_from_on_search_request(
                 product_list.push(prod_obj)
             }
             let servicability = get_servicability_from_on_search_request(&provider_obj.tags)?;
+            let mut variants = provider_obj.categories.as_ref().map(get_variant_mapping);
+            if let Some(variants) = variants.as_mut() {
+                set_variant_combinations(variants, &product_list);
+            }
 
             let provider = WSSearchProvider {
                 items: product_list,
@@ -1264,7 +1391,7 @@ pub fn get_product_from_on_search_request(
                 ),
                 servicability,
                 // payments: payment_mapping.clone(),
-                variants: provider_obj.categories.as_ref().map(get_variant_mapping),
+                variants,
             };
             provider_list.push(provider)
         }
@@ -1589,6 +1716,43 @@ pub fn get_ondc_select_payload(
     Ok(ONDCSelectRequest { context, message })
 }
 
+#[tracing::instrument(name = "get ondc negotiate payload", skip(previous_select_payload))]
+pub fn get_ondc_negotiate_payload(
+    previous_select_payload: Value,
+    negotiate_request: &OrderNegotiateRequest,
+    quote_version: i32,
+) -> Result<ONDCSelectRequest, OrderError> {
+    let mut select_payload = serde_json::from_value::<ONDCSelectRequest>(previous_select_payload)
+        .map_err(|e| OrderError::InvalidDataError(e.to_string()))?;
+    select_payload.context.message_id = negotiate_request.message_id;
+    select_payload.context.timestamp = Utc::now();
+
+    let counter_offer_map: HashMap<&str, &OrderNegotiateItem> = negotiate_request
+        .items
+        .iter()
+        .map(|item| (item.item_id.as_str(), item))
+        .collect();
+    for item in select_payload.message.order.items.iter_mut() {
+        let mut tags = item.tags.take().unwrap_or_default();
+        tags.retain(|tag| tag.descriptor.code != ONDCTagType::Negotiation);
+        if let Some(counter_offer) = counter_offer_map.get(item.id.as_str()) {
+            if let Some(qty) = counter_offer.qty {
+                item.quantity.selected.count = qty;
+            }
+            tags.push(ONDCTag::get_negotiation_tag(
+                quote_version,
+                counter_offer.unit_price.as_ref(),
+            ));
+        }
+        item.tags = if tags.is_empty() { None } else { Some(tags) };
+    }
+    let order_tags = &mut select_payload.message.order.tags;
+    order_tags.retain(|tag| tag.descriptor.code != ONDCTagType::Negotiation);
+    order_tags.push(ONDCTag::get_negotiation_tag(quote_version, None));
+
+    Ok(select_payload)
+}
+
 fn get_ondc_seller_slab_from_ws_slab(ws_slabs: &Vec<WSPriceSlab>) -> Vec<ONDCSellePriceSlab> {
     let mut price_slabs = vec![];
     for ws_slab in ws_slabs {
@@ -1841,6 +2005,27 @@ pub async fn fetch_ondc_order_request(
     Ok(row)
 }
 
+#[tracing::instrument(name = "Fetch latest ONDC Order request", skip(pool))]
+pub async fn fetch_latest_ondc_order_request(
+    pool: &PgPool,
+    transaction_id: Uuid,
+    action_type: &ONDCActionType,
+) -> Result<Option<ONDCRequestModel>, anyhow::Error> {
+    let row = sqlx::query_as!(
+        ONDCRequestModel,
+        r#"SELECT transaction_id, message_id, user_id, business_id, device_id, request_payload
+        FROM ondc_buyer_order_req
+        WHERE transaction_id = $1 AND action_type = $2 ORDER BY created_on DESC LIMIT 1
+        "#,
+        transaction_id,
+        &action_type.to_string() as &str
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row)
+}
+
 // #[tracing::instrument(name = "Fetch order request params", skip(pool))]
 // pub async fn fetch_order_params(
 //     pool: &PgPool,
@@ -1918,7 +2103,10 @@ fn get_ondc_billing_from_order_billing(billing: &CommerceBilling) -> ONDCBilling
     }
 }
 
-fn get_ondc_payment_from_order(payments: &Vec<CommercePayment>) -> Vec<ONDCInitPayment> {
+fn get_ondc_payment_from_order(
+    currency_type: &CurrencyType,
+    payments: &Vec<CommercePayment>,
+) -> Vec<ONDCInitPayment> {
     let mut payment_list = vec![];
     for payment in payments {
         payment_list.push(ONDCInitPayment {
@@ -1928,6 +2116,14 @@ fn get_ondc_payment_from_order(payments: &Vec<CommercePayment>) -> Vec<ONDCInitP
                 .clone()
                 .unwrap_or(PaymentCollectedBy::Bpp)
                 .get_ondc_type(),
+            params: payment
+                .payment_amount
+                .as_ref()
+                .map(|amount| ONDCPaymentParams {
+                    amount: amount.to_string(),
+                    currency: currency_type.clone(),
+                    transaction_id: None,
+                }),
         })
     }
     payment_list
@@ -2068,7 +2264,10 @@ fn get_ondc_init_message(
             },
             billing: get_ondc_billing_from_init_billing(&init_request.billing),
             add_ons: None,
-            payments: get_ondc_payment_from_order(&order.payments),
+            payments: get_ondc_payment_from_order(
+                order.currency_type.as_ref().unwrap_or(&CurrencyType::Inr),
+                &order.payments,
+            ),
             items: get_ondc_items_from_order(&order.items),
 
             tags: vec![get_buyer_id_tag(business_account)?],
@@ -2155,7 +2354,12 @@ fn get_ondc_confirm_request_payment(
 ) -> Vec<ONDCOnConfirmPayment> {
     let mut payment_objs = vec![];
     let currency_type = order.currency_type.as_ref().unwrap_or(&CurrencyType::Inr);
+    let mut instalment_seq = 0;
     for payment in &order.payments {
+        let tags = payment.payment_amount.as_ref().map(|_| {
+            instalment_seq += 1;
+            vec![ONDCTag::get_payment_instalment_tag(instalment_seq)]
+        });
         let mut settlement_detail_objs = vec![];
         if payment.collected_by == Some(PaymentCollectedBy::Bpp) {
             settlement_detail_objs.push(ONDCPaymentSettlementDetail {
@@ -2192,15 +2396,22 @@ fn get_ondc_confirm_request_payment(
                 .unwrap_or(PaymentCollectedBy::Bpp)
                 .get_ondc_type(),
             uri: None,
-            tags: None,
+            tags,
             params: ONDCPaymentParams {
-                amount: order.grand_total.clone().unwrap_or_default().to_string(),
+                amount: payment
+                    .payment_amount
+                    .clone()
+                    .or_else(|| order.grand_total.clone())
+                    .unwrap_or_default()
+                    .to_string(),
                 currency: currency_type.clone(),
-                transaction_id: order
-                    .payments
-                    .iter()
-                    .find(|p| p.payment_id.is_some())
-                    .and_then(|e| e.payment_id.to_owned()),
+                transaction_id: payment.payment_id.clone().or_else(|| {
+                    order
+                        .payments
+                        .iter()
+                        .find(|p| p.payment_amount.is_none() && p.payment_id.is_some())
+                        .and_then(|e| e.payment_id.to_owned())
+                }),
             },
             buyer_app_finder_fee_type: payment.buyer_fee_type.clone().unwrap_or(FeeType::Amount),
             buyer_app_finder_fee_amount: payment
@@ -2221,7 +2432,10 @@ fn get_ondc_confirm_request_payment(
                 .clone()
                 .unwrap_or("0.0".to_owned()),
             settlement_details: Some(settlement_detail_objs),
-            status: ONDCPaymentStatus::NotPaid,
+            status: match payment.payment_status {
+                PaymentStatus::Paid => ONDCPaymentStatus::Paid,
+                _ => ONDCPaymentStatus::NotPaid,
+            },
         })
     }
     payment_objs
@@ -2803,6 +3017,7 @@ fn get_ondc_update_message_for_payment(
             },
             payments: get_ondc_confirm_request_payment(order, bap_detail),
             items: get_ondc_update_items(order),
+            quote: None,
         },
     }
 }
@@ -2841,10 +3056,42 @@ pub async fn process_on_search(
     extracted_search_obj: SearchRequestModel,
     websocket_srv: &WebSocketClient,
     elastic_search_client: &ElasticSearchClient,
+    search_config: &SearchConfig,
 ) -> Result<(), anyhow::Error> {
     let final_objs: Option<WSSearchData> =
         get_product_from_on_search_request(&body).map_err(|op| anyhow!("error:{}", op))?;
 
+    let removal_data = get_catalog_removal_data(&body);
+    if extracted_search_obj.update_cache && !removal_data.is_empty() {
+        let domain_category = body
+            .context
+            .domain
+            .get_category_domain()
+            .ok_or_else(|| anyhow!("Invalid search domain"))?;
+        let mut transaction = pool
+            .begin()
+            .await
+            .context("Failed to acquire a Postgres connection from the pool")?;
+        let removed_ids = delete_cache_removals_from_db(
+            &mut transaction,
+            body.context.bpp_id.as_deref().unwrap_or_default(),
+            &body.context.location.country.code,
+            &domain_category,
+            &removal_data,
+        )
+        .await?;
+        transaction
+            .commit()
+            .await
+            .context("Failed to commit SQL transaction to remove disabled products")?;
+        delete_cache_removals_from_elastic_search(
+            elastic_search_client,
+            search_config,
+            removed_ids,
+        )
+        .await?;
+    }
+
     if let Some(final_objs) = final_objs {
         if !final_objs.providers.is_empty() {
             let mut transaction = pool
@@ -2893,10 +3140,15 @@ pub async fn process_on_search(
                     .await
                     .context("Failed to commit SQL transaction to store save products")?;
             } else {
+                let domain_category = body
+                    .context
+                    .domain
+                    .get_category_domain()
+                    .ok_or_else(|| anyhow!("Invalid search domain"))?;
                 let data = save_cache_to_db(
                     &mut transaction,
                     &body.context.location.country.code,
-                    &body.context.domain.get_category_domain(),
+                    &domain_category,
                     &final_objs,
                     body.context.timestamp,
                 )
@@ -2905,7 +3157,8 @@ pub async fn process_on_search(
                     .commit()
                     .await
                     .context("Failed to commit SQL transaction to store save products")?;
-                save_cache_to_elastic_search(pool, elastic_search_client, data).await?;
+                save_cache_to_elastic_search(pool, elastic_search_client, search_config, data)
+                    .await?;
             }
         }
     }
@@ -3003,7 +3256,108 @@ pub fn validate_on_select(order: &Option<Commerce>) -> Result<(), ONDCBuyerError
     Ok(())
 }
 
-pub fn validate_on_init(order: &Commerce) -> Result<(), ONDCBuyerError> {
+pub fn validate_buyer_finder_fee(
+    fee_type: &FeeType,
+    fee_amount: &str,
+    bap_detail: &RegisteredNetworkParticipant,
+    path: &str,
+) -> Result<(), ONDCBuyerError> {
+    let fee_value =
+        BigDecimal::from_str(fee_amount).map_err(|_| ONDCBuyerError::InvalidResponseError {
+            path: Some(path.to_owned()),
+            message: format!("Invalid buyer finder fee amount {}", fee_amount),
+        })?;
+    if fee_type != &bap_detail.fee_type || fee_value != bap_detail.fee_value {
+        return Err(ONDCBuyerError::OrderValidationFailure {
+            message: format!(
+                "Buyer finder fee {} {} does not match the bap terms {} {}",
+                fee_type, fee_amount, bap_detail.fee_type, bap_detail.fee_value
+            ),
+            path: Some(path.to_owned()),
+        });
+    }
+    Ok(())
+}
+
+pub fn validate_bap_terms_tag(
+    tags: &[ONDCTag],
+    bap_detail: &RegisteredNetworkParticipant,
+) -> Result<(), ONDCBuyerError> {
+    let bap_terms = match tags
+        .iter()
+        .find(|tag| tag.descriptor.code == ONDCTagType::BapTerms)
+    {
+        Some(bap_terms) => bap_terms,
+        None => return Ok(()),
+    };
+    let path = "message.order.tags.bap_terms";
+    let fee_type = bap_terms.get_tag_value(&ONDCTagItemCode::FinderFeeType.to_string());
+    let fee_amount = bap_terms.get_tag_value(&ONDCTagItemCode::FinderFeeAmount.to_string());
+    match (fee_type, fee_amount) {
+        (Some(fee_type), Some(fee_amount)) => {
+            if fee_type != bap_detail.fee_type.to_string() {
+                return Err(ONDCBuyerError::OrderValidationFailure {
+                    message: format!(
+                        "Buyer finder fee type {} does not match the bap terms {}",
+                        fee_type, bap_detail.fee_type
+                    ),
+                    path: Some(path.to_owned()),
+                });
+            }
+            validate_buyer_finder_fee(&bap_detail.fee_type, fee_amount, bap_detail, path)
+        }
+        _ => Err(ONDCBuyerError::InvalidResponseError {
+            path: Some(path.to_owned()),
+            message: "Buyer finder fee is missing in bap terms".to_owned(),
+        }),
+    }
+}
+
+fn validate_settlement_terms(
+    payment: &CommercePayment,
+    settlement_basis: &ONDCSettlementBasis,
+    settlement_window: &str,
+    withholding_amount: &str,
+    path: &str,
+) -> Result<(), ONDCBuyerError> {
+    let mut mismatches = vec![];
+    if payment
+        .settlement_basis
+        .as_ref()
+        .is_some_and(|basis| basis != &settlement_basis.get_settlement_basis_from_ondc_type())
+    {
+        mismatches.push("settlement_basis");
+    }
+    if payment
+        .settlement_window
+        .as_deref()
+        .is_some_and(|window| window != settlement_window)
+    {
+        mismatches.push("settlement_window");
+    }
+    if let Some(agreed_amount) = payment.withholding_amount.as_deref() {
+        if BigDecimal::from_str(agreed_amount).ok() != BigDecimal::from_str(withholding_amount).ok()
+        {
+            mismatches.push("withholding_amount");
+        }
+    }
+    if !mismatches.is_empty() {
+        return Err(ONDCBuyerError::OrderValidationFailure {
+            message: format!(
+                "Settlement terms {} do not match the terms agreed on init",
+                mismatches.join(", ")
+            ),
+            path: Some(path.to_owned()),
+        });
+    }
+    Ok(())
+}
+
+pub fn validate_on_init(
+    order: &Commerce,
+    body: &ONDCOnInitRequest,
+    bap_detail: &RegisteredNetworkParticipant,
+) -> Result<(), ONDCBuyerError> {
     if !matches!(
         order.record_status,
         CommerceStatusType::Initialized
@@ -3015,11 +3369,27 @@ pub fn validate_on_init(order: &Commerce) -> Result<(), ONDCBuyerError> {
             path: None,
         });
     }
+    if body.error.is_some() {
+        return Ok(());
+    }
+    for (index, payment) in body.message.order.payments.iter().enumerate() {
+        validate_buyer_finder_fee(
+            &payment.buyer_app_finder_fee_type,
+            &payment.buyer_app_finder_fee_amount,
+            bap_detail,
+            &format!("message.order.payments[{}]", index),
+        )?;
+    }
+    validate_bap_terms_tag(&body.message.order.tags, bap_detail)?;
 
     Ok(())
 }
 
-pub fn validate_on_confirm(order: &Commerce) -> Result<(), ONDCBuyerError> {
+pub fn validate_on_confirm(
+    order: &Commerce,
+    body: &ONDCOnConfirmRequest,
+    bap_detail: &RegisteredNetworkParticipant,
+) -> Result<(), ONDCBuyerError> {
     if !matches!(
         order.record_status,
         CommerceStatusType::Initialized
@@ -3031,6 +3401,41 @@ pub fn validate_on_confirm(order: &Commerce) -> Result<(), ONDCBuyerError> {
             path: None,
         });
     }
+    if body.error.is_some() {
+        return Ok(());
+    }
+    for (index, payment) in body.message.order.payments.iter().enumerate() {
+        let path = format!("message.order.payments[{}]", index);
+        validate_buyer_finder_fee(
+            &payment.buyer_app_finder_fee_type,
+            &payment.buyer_app_finder_fee_amount,
+            bap_detail,
+            &path,
+        )?;
+        if payment.r#type == ONDCPaymentType::PostFulfillment
+            && get_payable_due_on(Utc::now(), Some(&payment.settlement_window)).is_none()
+        {
+            return Err(ONDCBuyerError::InvalidResponseError {
+                path: Some(format!("{}.@ondc/org/settlement_window", path)),
+                message: format!("Invalid settlement window {}", payment.settlement_window),
+            });
+        }
+        let payment_type = payment.r#type.get_payment();
+        if let Some(agreed_payment) = order
+            .payments
+            .iter()
+            .find(|agreed_payment| agreed_payment.payment_type == payment_type)
+        {
+            validate_settlement_terms(
+                agreed_payment,
+                &payment.settlement_basis,
+                &payment.settlement_window,
+                &payment.withholding_amount,
+                &path,
+            )?;
+        }
+    }
+    validate_bap_terms_tag(&body.message.order.tags, bap_detail)?;
 
     Ok(())
 }
@@ -3131,3 +3536,111 @@ pub fn validate_on_cancel(order: &Commerce) -> Result<(), ONDCBuyerError> {
 
     Ok(())
 }
+
+fn get_ondc_recon_settlement(settlement: &CommerceSettlementData) -> ONDCReconSettlement {
+    let currency = settlement.currency_code.clone();
+    ONDCReconSettlement {
+        id: settlement.id.to_string(),
+        payment_id: Some(settlement.commerce_payment_id.to_string()),
+        status: ONDCReconSettlementStatus::Pending,
+        amount: ONDCAmount {
+            currency: currency.clone(),
+            value: settlement.settlement_amount.to_string(),
+        },
+        commission: ONDCAmount {
+            currency: currency.clone(),
+            value: settlement.buyer_finder_fee.to_string(),
+        },
+        withholding_amount: ONDCAmount {
+            currency,
+            value: settlement.withholding_amount.to_string(),
+        },
+        settlement_ref_no: None,
+        updated_at: Utc::now(),
+    }
+}
+
+fn get_ondc_recon_bank_detail(order: &Commerce) -> Option<ONDCReconBankDetail> {
+    order
+        .payments
+        .iter()
+        .filter_map(|payment| payment.settlement_details.as_ref())
+        .flatten()
+        .find(|detail| {
+            matches!(
+                detail.settlement_counterparty,
+                PaymentSettlementCounterparty::SellerApp
+            )
+        })
+        .map(|detail| ONDCReconBankDetail {
+            account_no: detail.settlement_bank_account_no.clone(),
+            ifsc_code: detail.settlement_ifsc_code.clone(),
+            beneficiary_name: detail.beneficiary_name.clone(),
+            bank_name: detail.bank_name.clone(),
+        })
+}
+
+#[tracing::instrument(name = "get ondc receiver recon payload", skip(orders, settlements))]
+pub fn get_ondc_receiver_recon_payload(
+    transaction_id: Uuid,
+    message_id: Uuid,
+    orders: &[Commerce],
+    settlements: &[CommerceSettlementData],
+) -> Result<ONDCReceiverReconRequest, anyhow::Error> {
+    let first_order = orders
+        .first()
+        .ok_or_else(|| anyhow!("Atleast one order is required for recon"))?;
+    let settlement_map: HashMap<Uuid, &CommerceSettlementData> = settlements
+        .iter()
+        .map(|settlement| (settlement.commerce_data_id, settlement))
+        .collect();
+    let mut recon_orders = vec![];
+    for order in orders {
+        let settlement = settlement_map
+            .get(&order.id)
+            .ok_or_else(|| anyhow!("Settlement data is missing for order {}", order.urn))?;
+        recon_orders.push(ONDCReconOrder {
+            id: order.urn.clone(),
+            amount: ONDCAmount {
+                currency: settlement.currency_code.clone(),
+                value: settlement.order_amount.to_string(),
+            },
+            settlements: vec![get_ondc_recon_settlement(settlement)],
+            provider: ONDCReconProvider {
+                id: order.seller.id.clone(),
+                name: order.seller.name.clone(),
+                bank_details: get_ondc_recon_bank_detail(order),
+            },
+            state: order.record_status.get_ondc_order_status(),
+            created_at: order.created_on,
+            updated_at: order.updated_on.unwrap_or(order.created_on),
+        });
+    }
+    let context = ONDCContext {
+        domain: ONDCDomain::Settlement,
+        location: ONDCContextLocation {
+            city: ONDCContextCity {
+                code: first_order.city_code.clone(),
+            },
+            country: ONDCContextCountry {
+                code: first_order.country_code.clone(),
+            },
+        },
+        action: ONDCActionType::ReceiverRecon,
+        version: Some(ONDCVersion::V2),
+        transaction_id,
+        message_id,
+        timestamp: Utc::now(),
+        bap_id: first_order.bap.id.clone(),
+        bap_uri: first_order.bap.uri.clone(),
+        bpp_id: Some(first_order.bpp.id.clone()),
+        bpp_uri: Some(first_order.bpp.uri.clone()),
+        ttl: ONDC_TTL.to_owned(),
+    };
+    Ok(ONDCReceiverReconRequest {
+        context,
+        message: ONDCReceiverReconMessage {
+            orders: recon_orders,
+        },
+    })
+}
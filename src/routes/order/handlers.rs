@@ -7,14 +7,16 @@ use actix_web::{web, HttpResponse};
 use utoipa::TupleUnit;
 // use anyhow::Context;
 use crate::configuration::ONDCConfig;
+use crate::elastic_search_client::ElasticSearchClient;
 use crate::errors::GenericError;
 use crate::routes::ondc::utils::{
-    fetch_ondc_seller_info, get_lookup_data_from_db, get_ondc_cancel_payload,
-    get_ondc_seller_location_info_mapping, get_ondc_seller_product_info_mapping,
-    get_ondc_status_payload, get_ondc_update_payload,
+    fetch_latest_ondc_order_request, fetch_ondc_seller_info, get_lookup_data_from_db,
+    get_ondc_cancel_payload, get_ondc_seller_location_info_mapping,
+    get_ondc_seller_product_info_mapping, get_ondc_status_payload, get_ondc_update_payload,
 };
 use crate::routes::ondc::utils::{
-    get_ondc_confirm_payload, get_ondc_init_payload, get_ondc_select_payload, send_ondc_payload,
+    get_ondc_confirm_payload, get_ondc_init_payload, get_ondc_negotiate_payload,
+    get_ondc_select_payload, send_ondc_payload,
 };
 use crate::routes::ondc::{ONDCActionType, ONDCDomain};
 use crate::user_client::{AllowedPermission, BusinessAccount, PermissionType, UserAccount};
@@ -22,18 +24,26 @@ use crate::user_client::{SettingKey, UserClient};
 use crate::utils::{create_authorization_header, get_np_detail};
 
 use crate::schemas::{GenericResponse, ONDCNetworkType, RequestMetaData, StartUpMap};
+use anyhow::Context;
 use sqlx::PgPool;
 
 use super::schemas::{
-    Commerce, CommerceList, OrderCancelRequest, OrderConfirmRequest, OrderInitRequest,
-    OrderListFilter, OrderListRequest, OrderReadRequest, OrderSelectRequest, OrderStatusRequest,
-    OrderType, OrderUpdateRequest,
+    Commerce, CommerceList, CommerceQuoteVersion, OrderCancelRequest, OrderConfirmRequest,
+    OrderInitRequest, OrderListFilter, OrderListRequest, OrderNegotiateRequest, OrderReadRequest,
+    OrderReorderRequest, OrderReorderResponse, OrderSelectCart, OrderSelectRequest,
+    OrderStatusRequest, OrderType, OrderUpdateRequest, QuoteVersionSource,
 };
 use super::utils::{
-    fetch_order_by_id, get_chat_links, get_order_list, initialize_order_select,
-    save_ondc_order_request, send_rfq_request_chat, validate_cancel_request,
-    validate_confirm_request, validate_init_request, validate_select_request,
-    validate_status_request, validate_update_request,
+    fetch_order_by_id, fetch_quote_versions, get_chat_links, get_next_quote_version,
+    get_order_list, get_order_select_cart, get_payment_schedule_amounts,
+    get_quote_version_items_from_ondc_select, get_reorder_fulfillment_location,
+    get_reorder_item_mapping, get_reorder_items, get_reorder_select_request,
+    initialize_order_select, save_commerce_payment_amounts, save_commerce_quote_version,
+    save_ondc_order_request, send_rfq_negotiate_chat, send_rfq_request_chat,
+    update_order_on_negotiate, validate_cancel_request, validate_confirm_request,
+    validate_init_request, validate_negotiate_request, validate_select_request,
+    validate_select_servicability, validate_select_supplier, validate_status_request,
+    validate_update_request,
 };
 
 #[utoipa::path(
@@ -44,7 +54,7 @@ use super::utils::{
     summary= "Order Select Request",
     request_body(content = OrderSelectRequest, description = "Request Body"),
     responses(
-        (status=202, description= "Order Select Response", body= GenericResponse<TupleUnit>),
+        (status=202, description= "Order Select Response", body= GenericResponse<OrderSelectCart>),
         (status=400, description= "Invalid Request body", body= GenericResponse<TupleUnit>),
         (status=401, description= "Invalid Token", body= GenericResponse<TupleUnit>),
 	    (status=403, description= "Insufficient Previlege", body= GenericResponse<TupleUnit>),
@@ -159,6 +169,8 @@ pub async fn order_select(
         &seller_location_info_mapping,
         &order_data,
     )?;
+    validate_select_servicability(&pool, &body).await?;
+    validate_select_supplier(&pool, business_account.id, &body).await?;
 
     let chat_data = if body.order_type == OrderType::PurchaseOrder {
         Some(
@@ -184,6 +196,22 @@ pub async fn order_select(
         &chat_data,
     )?;
 
+    let item_code_list: Vec<&str> = body
+        .items
+        .iter()
+        .map(|item| item.item_id.as_str())
+        .collect();
+    let seller_product_map = get_ondc_seller_product_info_mapping(
+        &pool,
+        &bpp_detail.subscriber_id,
+        &body.provider_id,
+        &item_code_list,
+        &ondc_select_payload.context.location.country.code,
+    )
+    .await
+    .map_err(|e| GenericError::DatabaseError(e.to_string(), e))?;
+    let cart = get_order_select_cart(&body, &seller_product_map);
+
     let ondc_select_payload_str = serde_json::to_string(&ondc_select_payload).map_err(|e| {
         GenericError::SerializationError(format!("Failed to serialize ONDC select payload: {}", e))
     })?;
@@ -218,25 +246,6 @@ pub async fn order_select(
     };
 
     if body.order_type == OrderType::PurchaseOrder {
-        let item_code_list: Vec<&str> = body
-            .items
-            .iter()
-            .map(|item| item.item_id.as_str())
-            .collect();
-        let seller_product_map = match get_ondc_seller_product_info_mapping(
-            &pool,
-            &bpp_detail.subscriber_id,
-            &body.provider_id,
-            &item_code_list,
-            &ondc_select_payload.context.location.country.code,
-        )
-        .await
-        {
-            Ok(a) => a,
-            Err(e) => return Err(GenericError::DatabaseError(e.to_string(), e)),
-        };
-        // .map_err(|e| return Err(GenericError::DatabaseError(e.to_string(), e)))?;
-
         let task_7 = initialize_order_select(
             &pool,
             &chat_client,
@@ -266,7 +275,7 @@ pub async fn order_select(
     Ok(HttpResponse::Accepted().json(GenericResponse::success(
         "Successfully send select request",
         StatusCode::ACCEPTED,
-        Some(()),
+        Some(cart),
     )))
 }
 
@@ -315,7 +324,7 @@ pub async fn order_init(
         }
     };
 
-    let order = match order {
+    let mut order = match order {
         Some(order_detail) => order_detail,
         None => {
             return Err(GenericError::ValidationError(format!(
@@ -335,6 +344,19 @@ pub async fn order_init(
         ));
     }
     validate_init_request(&order)?;
+    let payment_amounts = match &body.payment_schedule {
+        Some(payment_schedule) => get_payment_schedule_amounts(&order, payment_schedule)?,
+        None => vec![],
+    };
+    save_commerce_payment_amounts(&pool, order.id, &payment_amounts)
+        .await
+        .map_err(|e| GenericError::DatabaseError(e.to_string(), e))?;
+    for payment in order.payments.iter_mut() {
+        payment.payment_amount = payment_amounts
+            .iter()
+            .find(|(payment_type, _)| payment_type == &payment.payment_type)
+            .map(|(_, amount)| amount.clone());
+    }
     let bap_detail = match bap_detail {
         Some(bap_detail) => bap_detail,
         None => {
@@ -871,6 +893,224 @@ pub async fn order_fetch(
     )))
 }
 
+#[utoipa::path(
+    post,
+    path = "/order/negotiate",
+    tag = "Order",
+    description="This API sends a counter offer on the seller quote of a purchase order as a new ONDC select request.",
+    summary= "Order Negotiate Request",
+    request_body(content = OrderNegotiateRequest, description = "Request Body"),
+    responses(
+        (status=202, description= "Order Negotiate Response", body= GenericResponse<TupleUnit>),
+        (status=400, description= "Invalid Request body", body= GenericResponse<TupleUnit>),
+        (status=401, description= "Invalid Token", body= GenericResponse<TupleUnit>),
+	    (status=403, description= "Insufficient Previlege", body= GenericResponse<TupleUnit>),
+	    (status=410, description= "Data not found", body= GenericResponse<TupleUnit>),
+        (status=500, description= "Internal Server Error", body= GenericResponse<TupleUnit>),
+	    (status=501, description= "Not Implemented", body= GenericResponse<TupleUnit>),
+    )
+)]
+#[allow(clippy::too_many_arguments)]
+#[tracing::instrument(name = "order negotiate", skip(pool, kafka_client), fields(transaction_id=body.transaction_id.to_string()))]
+pub async fn order_negotiate(
+    body: OrderNegotiateRequest,
+    pool: web::Data<PgPool>,
+    user_account: UserAccount,
+    business_account: BusinessAccount,
+    meta_data: RequestMetaData,
+    allowed_permission: AllowedPermission,
+    maps: web::Data<StartUpMap>,
+    ondc_obj: web::Data<ONDCConfig>,
+    chat_client: web::Data<ChatClient>,
+    kafka_client: web::Data<KafkaClient>,
+) -> Result<HttpResponse, GenericError> {
+    let task1 = fetch_order_by_id(&pool, body.transaction_id);
+    let task2 = get_np_detail(
+        &pool,
+        &maps,
+        &business_account.subscriber_id,
+        &ONDCNetworkType::Bap,
+    );
+    let task3 =
+        fetch_latest_ondc_order_request(&pool, body.transaction_id, &ONDCActionType::Select);
+
+    let (order, bap_detail, select_model) = match tokio::try_join!(task1, task2, task3) {
+        Ok(res) => res,
+        Err(e) => {
+            return Err(GenericError::DatabaseError(e.to_string(), e));
+        }
+    };
+
+    let order = match order {
+        Some(order_detail) => order_detail,
+        None => {
+            return Err(GenericError::ValidationError(format!(
+                "{} is not found in database",
+                &body.transaction_id
+            )))
+        }
+    };
+
+    if !allowed_permission.validate_commerce_self(
+        order.created_by,
+        order.buyer_id,
+        PermissionType::CreateOrderSelf,
+    ) {
+        return Err(GenericError::InsufficientPrevilegeError(
+            "You do not have sufficent preveliege to negotiate the order".to_owned(),
+        ));
+    }
+    validate_negotiate_request(&order, &body)?;
+    let bap_detail = match bap_detail {
+        Some(bap_detail) => bap_detail,
+        None => {
+            return Err(GenericError::ValidationError(format!(
+                "{} is not a registered ONDC registered domain",
+                &business_account.subscriber_id,
+            )))
+        }
+    };
+    let select_model = match select_model {
+        Some(select_model) => select_model,
+        None => {
+            return Err(GenericError::ValidationError(format!(
+                "Select request is not found for {}",
+                &body.transaction_id
+            )))
+        }
+    };
+
+    let mut transaction = pool
+        .begin()
+        .await
+        .context("Failed to acquire a Postgres connection from the pool")?;
+    let quote_version = get_next_quote_version(&mut transaction, body.transaction_id)
+        .await
+        .map_err(|e| GenericError::DatabaseError(e.to_string(), e))?;
+    let ondc_negotiate_payload =
+        get_ondc_negotiate_payload(select_model.request_payload, &body, quote_version)?;
+    let ondc_negotiate_payload_str =
+        serde_json::to_string(&ondc_negotiate_payload).map_err(|e| {
+            GenericError::SerializationError(format!(
+                "Failed to serialize ONDC negotiate payload: {}",
+                e
+            ))
+        })?;
+    let header = create_authorization_header(&ondc_negotiate_payload_str, &bap_detail, None, None)?;
+    let negotiate_json_obj = serde_json::to_value(&ondc_negotiate_payload)?;
+
+    save_ondc_order_request(
+        &pool,
+        &user_account,
+        &business_account,
+        &meta_data,
+        &negotiate_json_obj,
+        body.transaction_id,
+        body.message_id,
+        ONDCActionType::Select,
+    )
+    .await
+    .map_err(|e| GenericError::DatabaseError(e.to_string(), e))?;
+
+    update_order_on_negotiate(&mut transaction, body.transaction_id, user_account.id)
+        .await
+        .map_err(|e| GenericError::DatabaseError(e.to_string(), e))?;
+    save_commerce_quote_version(
+        &mut transaction,
+        body.transaction_id,
+        body.message_id,
+        quote_version,
+        QuoteVersionSource::Buyer,
+        &get_quote_version_items_from_ondc_select(&ondc_negotiate_payload),
+        order.currency_type.as_ref(),
+        None,
+        Some(user_account.id),
+    )
+    .await
+    .map_err(|e| GenericError::DatabaseError(e.to_string(), e))?;
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit SQL transaction to store a quote version")?;
+
+    send_ondc_payload(
+        &order.bpp.uri,
+        &ondc_negotiate_payload_str,
+        &header,
+        &ONDCActionType::Select,
+        &kafka_client,
+        &bap_detail.subscriber_id,
+        body.transaction_id,
+        ondc_obj.observability.is_enabled,
+    )
+    .await?;
+
+    send_rfq_negotiate_chat(
+        &chat_client,
+        &order,
+        &body,
+        &business_account,
+        quote_version,
+    )
+    .await
+    .map_err(|e| GenericError::UnexpectedCustomError(e.to_string()))?;
+
+    Ok(HttpResponse::Accepted().json(GenericResponse::success(
+        "Successfully send negotiate request",
+        StatusCode::ACCEPTED,
+        Some(()),
+    )))
+}
+
+#[utoipa::path(
+    post,
+    path = "/order/quote/history",
+    tag = "Order",
+    description="This API fetches every quote revision of a purchase order.",
+    summary= "Order Quote History Request",
+    request_body(content = OrderReadRequest, description = "Request Body"),
+    responses(
+        (status=200, description= "Order Quote History Response", body= GenericResponse<Vec<CommerceQuoteVersion>>),
+        (status=400, description= "Invalid Request body", body= GenericResponse<TupleUnit>),
+        (status=401, description= "Invalid Token", body= GenericResponse<TupleUnit>),
+	    (status=403, description= "Insufficient Previlege", body= GenericResponse<TupleUnit>),
+	    (status=410, description= "Data not found", body= GenericResponse<TupleUnit>),
+        (status=500, description= "Internal Server Error", body= GenericResponse<TupleUnit>),
+	    (status=501, description= "Not Implemented", body= GenericResponse<TupleUnit>),
+    )
+)]
+#[tracing::instrument(name = "order quote history", skip(pool), fields(transaction_id = %body.transaction_id))]
+pub async fn order_quote_history(
+    body: OrderReadRequest,
+    pool: web::Data<PgPool>,
+    user_account: UserAccount,
+    business_account: BusinessAccount,
+    allowed_permission: AllowedPermission,
+) -> Result<web::Json<GenericResponse<Vec<CommerceQuoteVersion>>>, GenericError> {
+    let task1 = fetch_order_by_id(&pool, body.transaction_id);
+    let task2 = fetch_quote_versions(&pool, body.transaction_id);
+    let (order, quote_versions) = tokio::try_join!(task1, task2)
+        .map_err(|e| GenericError::DatabaseError("Failed to fetch quote history".to_string(), e))?;
+    let order =
+        order.ok_or_else(|| GenericError::ValidationError("Order not found".to_string()))?;
+
+    if !allowed_permission.validate_commerce_self(
+        order.created_by,
+        order.buyer_id,
+        PermissionType::ReadOrderSelf,
+    ) {
+        return Err(GenericError::InsufficientPrevilegeError(
+            "You do not have sufficent preveliege to read the order".to_owned(),
+        ));
+    }
+
+    Ok(web::Json(GenericResponse::success(
+        "Successfully fetched quote history",
+        StatusCode::OK,
+        Some(quote_versions),
+    )))
+}
+
 #[utoipa::path(
     post,
     path = "/order/list",
@@ -915,3 +1155,90 @@ pub async fn order_list(
         Some(data),
     )))
 }
+
+#[utoipa::path(
+    post,
+    path = "/order/reorder",
+    tag = "Order",
+    description="This API re-validates the lines of an existing order against the current catalog and optionally generates a new ONDC select request.",
+    summary= "Order Reorder Request",
+    request_body(content = OrderReorderRequest, description = "Request Body"),
+    responses(
+        (status=200, description= "Order Reorder Response", body= GenericResponse<OrderReorderResponse>),
+        (status=400, description= "Invalid Request body", body= GenericResponse<TupleUnit>),
+        (status=401, description= "Invalid Token", body= GenericResponse<TupleUnit>),
+	    (status=403, description= "Insufficient Previlege", body= GenericResponse<TupleUnit>),
+	    (status=410, description= "Data not found", body= GenericResponse<TupleUnit>),
+        (status=500, description= "Internal Server Error", body= GenericResponse<TupleUnit>),
+	    (status=501, description= "Not Implemented", body= GenericResponse<TupleUnit>),
+    )
+)]
+#[allow(clippy::too_many_arguments)]
+#[tracing::instrument(name = "order reorder", skip(pool, es_client, kafka_client), fields(transaction_id = %body.transaction_id))]
+pub async fn order_reorder(
+    body: OrderReorderRequest,
+    pool: web::Data<PgPool>,
+    es_client: web::Data<ElasticSearchClient>,
+    ondc_obj: web::Data<ONDCConfig>,
+    user_account: UserAccount,
+    business_account: BusinessAccount,
+    meta_data: RequestMetaData,
+    allowed_permission: AllowedPermission,
+    chat_client: web::Data<ChatClient>,
+    user_client: web::Data<UserClient>,
+    maps: web::Data<StartUpMap>,
+    kafka_client: web::Data<KafkaClient>,
+) -> Result<web::Json<GenericResponse<OrderReorderResponse>>, GenericError> {
+    let order = fetch_order_by_id(&pool, body.transaction_id)
+        .await
+        .map_err(|e| GenericError::DatabaseError("Failed to fetch order".to_string(), e))?
+        .ok_or_else(|| GenericError::ValidationError("Order not found".to_string()))?;
+
+    if order.buyer_id != business_account.id
+        || !allowed_permission.validate_commerce_self(
+            order.created_by,
+            order.buyer_id,
+            PermissionType::CreateOrderSelf,
+        )
+    {
+        return Err(GenericError::InsufficientPrevilegeError(
+            "You do not have sufficent preveliege to reorder".to_owned(),
+        ));
+    }
+
+    let fulfillment_location = get_reorder_fulfillment_location(&order).ok_or_else(|| {
+        GenericError::ValidationError("Invalid fulfillment location in order".to_string())
+    })?;
+    let item_mapping = get_reorder_item_mapping(&es_client, &order, fulfillment_location)
+        .await
+        .map_err(|e| GenericError::UnexpectedCustomError(e.to_string()))?;
+    let items = get_reorder_items(&order, &item_mapping);
+
+    let select_sent = body.send_select && items.iter().any(|item| item.qty > 0);
+    if select_sent {
+        let select_request = get_reorder_select_request(order, &body, &items);
+        order_select(
+            select_request,
+            pool,
+            ondc_obj,
+            user_account,
+            business_account,
+            meta_data,
+            chat_client,
+            user_client,
+            maps,
+            kafka_client,
+        )
+        .await?;
+    }
+
+    Ok(web::Json(GenericResponse::success(
+        "Successfully generated reorder",
+        StatusCode::OK,
+        Some(OrderReorderResponse {
+            transaction_id: body.new_transaction_id,
+            select_sent,
+            items,
+        }),
+    )))
+}
@@ -10,10 +10,11 @@ use sqlx::FromRow;
 use uuid::Uuid;
 
 use super::schemas::{
-    CancellationFeeType, CommerceList, CommerceSeller, CommerceStatusType, DocumentType,
-    FulfillmentCategoryType, FulfillmentStatusType, IncoTermType, MinimalCommerceData, OrderType,
-    PaymentCollectedBy, PaymentSettlementCounterparty, PaymentSettlementPhase,
-    PaymentSettlementType, PaymentStatus, ServiceableType, SettlementBasis, TradeType,
+    CancellationFeeType, CommerceList, CommerceQuoteVersion, CommerceSeller, CommerceStatusType,
+    DocumentType, FulfillmentCategoryType, FulfillmentStatusType, IncoTermType,
+    MinimalCommerceData, OrderType, PaymentCollectedBy, PaymentSettlementCounterparty,
+    PaymentSettlementPhase, PaymentSettlementType, PaymentStatus, QuoteVersionItem,
+    QuoteVersionSource, ServiceableType, SettlementBasis, TradeType,
 };
 use crate::domain::EmailObject;
 #[derive(Debug, Serialize, Deserialize, FromRow)]
@@ -86,6 +87,7 @@ pub struct CommerceItemModel {
     pub tax_rate: BigDecimal,
     pub tax_value: BigDecimal,
     pub unit_price: BigDecimal,
+    pub expected_unit_price: Option<BigDecimal>,
     pub gross_total: BigDecimal,
     pub available_qty: Option<BigDecimal>,
     pub discount_amount: BigDecimal,
@@ -128,6 +130,9 @@ pub struct CommercePaymentModel {
     pub payment_status: PaymentStatus,
     pub payment_order_id: Option<String>,
     pub payment_id: Option<String>,
+    pub payment_amount: Option<BigDecimal>,
+    pub payment_link: Option<String>,
+    pub payment_order_expires_on: Option<DateTime<Utc>>,
 }
 #[derive(Deserialize, Debug)]
 pub struct CommerceFulfillmentModel {
@@ -310,3 +315,35 @@ impl MinimalCommerceModel {
         }
     }
 }
+
+#[derive(Deserialize, Debug)]
+pub struct CommerceQuoteVersionModel {
+    pub version: i32,
+    pub message_id: Uuid,
+    pub source: QuoteVersionSource,
+    pub items: sqlx::types::Json<Vec<QuoteVersionItem>>,
+    pub currency_code: Option<CurrencyType>,
+    pub grand_total: Option<BigDecimal>,
+    pub created_on: DateTime<Utc>,
+}
+
+impl CommerceQuoteVersionModel {
+    pub fn schema(self) -> CommerceQuoteVersion {
+        CommerceQuoteVersion {
+            version: self.version,
+            message_id: self.message_id,
+            source: self.source,
+            items: self.items.0,
+            currency_code: self.currency_code,
+            grand_total: self.grand_total,
+            created_on: self.created_on,
+        }
+    }
+}
+
+#[derive(Debug, FromRow)]
+pub struct LastOrderedItemModel {
+    pub created_on: DateTime<Utc>,
+    pub unit_price: BigDecimal,
+    pub qty: BigDecimal,
+}
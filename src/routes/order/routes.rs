@@ -3,8 +3,8 @@ use crate::user_client::{CustomerType, PermissionType};
 use actix_web::web;
 
 use super::handlers::{
-    order_cancel, order_confirm, order_fetch, order_init, order_list, order_select, order_status,
-    order_update,
+    order_cancel, order_confirm, order_fetch, order_init, order_list, order_negotiate,
+    order_quote_history, order_reorder, order_select, order_status, order_update,
 };
 pub fn order_route(cfg: &mut web::ServiceConfig) {
     cfg.service(
@@ -23,6 +23,38 @@ pub fn order_route(cfg: &mut web::ServiceConfig) {
                 .wrap(RequireAuth),
         ),
     );
+    cfg.service(
+        web::resource("/reorder").route(
+            web::post()
+                .to(order_reorder)
+                .wrap(BusinessPermissionValidation {
+                    permission_list: vec![
+                        PermissionType::CreateOrder,
+                        PermissionType::CreateOrderSelf,
+                    ],
+                })
+                .wrap(BusinessAccountValidation {
+                    business_type_list: vec![CustomerType::RetailB2bBuyer],
+                })
+                .wrap(RequireAuth),
+        ),
+    );
+    cfg.service(
+        web::resource("/negotiate").route(
+            web::post()
+                .to(order_negotiate)
+                .wrap(BusinessPermissionValidation {
+                    permission_list: vec![
+                        PermissionType::CreateOrder,
+                        PermissionType::CreateOrderSelf,
+                    ],
+                })
+                .wrap(BusinessAccountValidation {
+                    business_type_list: vec![CustomerType::RetailB2bBuyer],
+                })
+                .wrap(RequireAuth),
+        ),
+    );
     cfg.service(
         web::resource("/init").route(
             web::post()
@@ -102,6 +134,17 @@ pub fn order_route(cfg: &mut web::ServiceConfig) {
             })
             .wrap(RequireAuth),
     );
+    cfg.service(
+        web::resource("/quote/history")
+            .route(web::post().to(order_quote_history))
+            .wrap(BusinessPermissionValidation {
+                permission_list: vec![PermissionType::ReadOrder, PermissionType::ReadOrderSelf],
+            })
+            .wrap(BusinessAccountValidation {
+                business_type_list: vec![CustomerType::RetailB2bBuyer],
+            })
+            .wrap(RequireAuth),
+    );
     cfg.service(
         web::resource("/list")
             .route(web::post().to(order_list))
@@ -7,7 +7,7 @@ use crate::routes::ondc::schemas::{
 };
 use crate::routes::ondc::{ONDCOrderStatus, ONDCOrderUpdateTarget, ONDCPaymentCollectedBy};
 use crate::routes::product::schemas::FulfillmentType;
-use crate::routes::product::schemas::{CategoryDomain, PaymentType};
+use crate::routes::product::schemas::{CategoryDomain, ItemPriceChange, PaymentType};
 use crate::schemas::DataSource;
 use crate::schemas::{CountryCode, CurrencyType, FeeType};
 use crate::utils::pascal_to_snake_case;
@@ -96,14 +96,14 @@ impl std::fmt::Display for IncoTermType {
     }
 }
 
-#[derive(Deserialize, Debug, ToSchema)]
+#[derive(Deserialize, Debug, ToSchema, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct OrderDeliveyTerm {
     pub inco_terms: IncoTermType,
     pub place_of_delivery: String,
 }
 
-#[derive(Deserialize, Debug, ToSchema)]
+#[derive(Deserialize, Debug, ToSchema, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct OrderSelectFulfillment {
     pub id: String,
@@ -214,6 +214,33 @@ impl FromRequest for OrderSelectRequest {
     }
 }
 
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderSelectCartItem {
+    pub item_id: String,
+    pub qty: i32,
+    pub is_price_slab_applied: bool,
+    #[schema(value_type = f64)]
+    pub unit_price_with_tax: BigDecimal,
+    #[schema(value_type = f64)]
+    pub unit_price_without_tax: BigDecimal,
+    #[schema(value_type = f64)]
+    pub total_with_tax: BigDecimal,
+    #[schema(value_type = f64)]
+    pub total_without_tax: BigDecimal,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderSelectCart {
+    pub currency_code: Option<CurrencyType>,
+    pub items: Vec<OrderSelectCartItem>,
+    #[schema(value_type = f64)]
+    pub total_with_tax: BigDecimal,
+    #[schema(value_type = f64)]
+    pub total_without_tax: BigDecimal,
+}
+
 #[derive(Deserialize, Debug, sqlx::Type, ToSchema, Serialize, PartialEq)]
 #[sqlx(type_name = "commerce_status", rename_all = "snake_case")]
 #[serde(rename_all = "snake_case")]
@@ -287,7 +314,7 @@ impl PaymentCollectedBy {
     }
 }
 
-#[derive(Deserialize, Debug, ToSchema)]
+#[derive(Deserialize, Debug, ToSchema, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct OrderInitBilling {
     pub name: String,
@@ -299,6 +326,14 @@ pub struct OrderInitBilling {
     pub state: String,
 }
 
+#[derive(Deserialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderPaymentSchedule {
+    #[schema(value_type = f64)]
+    pub advance_percentage: BigDecimal,
+    pub balance_payment_type: PaymentType,
+}
+
 #[derive(Deserialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct OrderInitRequest {
@@ -307,6 +342,7 @@ pub struct OrderInitRequest {
     #[schema(value_type = String)]
     pub message_id: Uuid,
     pub billing: OrderInitBilling,
+    pub payment_schedule: Option<OrderPaymentSchedule>,
 }
 
 impl FromRequest for OrderInitRequest {
@@ -348,7 +384,7 @@ pub struct PaymentSettlementDetail {
     pub bank_name: String,
 }
 
-#[derive(Debug, Serialize, Deserialize, sqlx::Type, Clone, ToSchema)]
+#[derive(Debug, Serialize, Deserialize, sqlx::Type, Clone, ToSchema, PartialEq)]
 #[serde(rename_all = "snake_case")]
 #[sqlx(type_name = "settlement_basis_type", rename_all = "snake_case")]
 pub enum SettlementBasis {
@@ -393,6 +429,10 @@ pub struct CommercePayment {
     pub payment_id: Option<String>,
     pub payment_order_id: Option<String>,
     pub payment_status: PaymentStatus,
+    #[schema(value_type = Option<f64>)]
+    pub payment_amount: Option<BigDecimal>,
+    pub payment_link: Option<String>,
+    pub payment_order_expires_on: Option<DateTime<Utc>>,
 }
 
 impl CommercePayment {
@@ -566,6 +606,9 @@ pub struct CommerceItem {
     pub tax_value: BigDecimal,
     #[schema(value_type = f64)]
     pub unit_price: BigDecimal,
+    #[schema(value_type = Option<f64>)]
+    pub expected_unit_price: Option<BigDecimal>,
+    pub is_slab_price_ignored: bool,
     #[schema(value_type = f64)]
     pub gross_total: BigDecimal,
     #[schema(value_type = Option<f64>)]
@@ -622,6 +665,8 @@ pub struct Commerce {
     pub created_by: Uuid,
     #[schema(value_type = Option<f64>)]
     pub grand_total: Option<BigDecimal>,
+    #[schema(value_type = Option<f64>)]
+    pub buyer_finder_fee: Option<BigDecimal>,
     pub bap: BasicNetworkData,
     pub bpp: BasicNetworkData,
     pub quote_ttl: String,
@@ -741,7 +786,7 @@ impl FromRequest for OrderConfirmRequest {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, sqlx::Type, ToSchema, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, sqlx::Type, ToSchema, PartialEq, Clone)]
 #[serde(rename_all = "snake_case")]
 #[sqlx(type_name = "payment_status", rename_all = "snake_case")]
 pub enum PaymentStatus {
@@ -1084,3 +1129,132 @@ pub struct MinimalCommerceData {
     pub created_by: Uuid,
     pub record_type: OrderType,
 }
+
+#[derive(Deserialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderNegotiateItem {
+    pub item_id: String,
+    pub qty: Option<i32>,
+    #[schema(value_type = Option<f64>)]
+    pub unit_price: Option<BigDecimal>,
+}
+
+#[derive(Deserialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderNegotiateRequest {
+    #[schema(value_type = String)]
+    pub transaction_id: Uuid,
+    #[schema(value_type = String)]
+    pub message_id: Uuid,
+    pub items: Vec<OrderNegotiateItem>,
+}
+
+impl FromRequest for OrderNegotiateRequest {
+    type Error = GenericError;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let fut = web::Json::<Self>::from_request(req, payload);
+
+        Box::pin(async move {
+            match fut.await {
+                Ok(json) => Ok(json.into_inner()),
+                Err(e) => Err(GenericError::ValidationError(e.to_string())),
+            }
+        })
+    }
+}
+
+#[derive(Deserialize, Debug, sqlx::Type, ToSchema, Serialize, PartialEq)]
+#[sqlx(type_name = "quote_version_source", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum QuoteVersionSource {
+    Buyer,
+    Seller,
+}
+
+#[derive(Deserialize, Debug, ToSchema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuoteVersionItem {
+    pub item_id: String,
+    pub qty: i32,
+    #[schema(value_type = Option<f64>)]
+    pub unit_price: Option<BigDecimal>,
+    #[schema(value_type = Option<f64>)]
+    pub gross_total: Option<BigDecimal>,
+}
+
+#[derive(Debug, ToSchema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommerceQuoteVersion {
+    pub version: i32,
+    #[schema(value_type = String)]
+    pub message_id: Uuid,
+    pub source: QuoteVersionSource,
+    pub items: Vec<QuoteVersionItem>,
+    pub currency_code: Option<CurrencyType>,
+    #[schema(value_type = Option<f64>)]
+    pub grand_total: Option<BigDecimal>,
+    pub created_on: DateTime<Utc>,
+}
+
+#[derive(Deserialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderReorderRequest {
+    #[schema(value_type = String)]
+    pub transaction_id: Uuid,
+    #[schema(value_type = String)]
+    pub new_transaction_id: Uuid,
+    #[schema(value_type = String)]
+    pub message_id: Uuid,
+    pub send_select: bool,
+}
+
+impl FromRequest for OrderReorderRequest {
+    type Error = GenericError;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let fut = web::Json::<Self>::from_request(req, payload);
+
+        Box::pin(async move {
+            match fut.await {
+                Ok(json) => Ok(json.into_inner()),
+                Err(e) => Err(GenericError::ValidationError(e.to_string())),
+            }
+        })
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReorderItemStatus {
+    Unchanged,
+    PriceChanged,
+    QuantityReduced,
+    Unavailable,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReorderItem {
+    pub item_id: String,
+    pub item_name: String,
+    pub previous_qty: i32,
+    pub qty: i32,
+    pub status: ReorderItemStatus,
+    #[schema(value_type = f64)]
+    pub previous_unit_price: BigDecimal,
+    #[schema(value_type = Option<f64>)]
+    pub current_unit_price: Option<BigDecimal>,
+    pub price_change: Option<ItemPriceChange>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderReorderResponse {
+    #[schema(value_type = String)]
+    pub transaction_id: Uuid,
+    pub select_sent: bool,
+    pub items: Vec<ReorderItem>,
+}
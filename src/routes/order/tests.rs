@@ -5,10 +5,11 @@ mod tests {
 
     use crate::{
         routes::order::{
-            schemas::{OrderListFilter, OrderListRequest},
+            schemas::{OrderListFilter, OrderListRequest, QuoteVersionSource},
             utils::{
-                delete_order, get_commerce_data, get_commerce_data_line, get_commerce_fulfillments,
-                get_commerce_payments, get_order_list,
+                delete_order, fetch_quote_versions, get_commerce_data, get_commerce_data_line,
+                get_commerce_fulfillments, get_commerce_payments, get_latest_quote_version,
+                get_next_quote_version, get_order_list, save_commerce_quote_version,
             },
         },
         tests::tests::get_test_pool,
@@ -65,4 +66,40 @@ mod tests {
         let _ = delete_order(&mut transaction, Uuid::new_v4()).await;
         transaction.commit().await.unwrap();
     }
+
+    #[tokio::test]
+    async fn test_quote_version_sql() {
+        let pool = get_test_pool().await;
+        let transaction_id = Uuid::new_v4();
+        let latest_version = get_latest_quote_version(&pool, transaction_id).await;
+        assert!(latest_version.is_ok());
+        assert!(latest_version.unwrap().is_none());
+        let versions = fetch_quote_versions(&pool, transaction_id).await;
+        assert!(versions.is_ok());
+        assert!(versions.unwrap().is_empty());
+
+        for expected_version in [1, 2] {
+            let mut transaction = pool.begin().await.unwrap();
+            let version = get_next_quote_version(&mut transaction, transaction_id)
+                .await
+                .unwrap();
+            assert_eq!(version, expected_version);
+            save_commerce_quote_version(
+                &mut transaction,
+                transaction_id,
+                Uuid::new_v4(),
+                version,
+                QuoteVersionSource::Buyer,
+                &vec![],
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+            transaction.commit().await.unwrap();
+        }
+        let latest_version = get_latest_quote_version(&pool, transaction_id).await;
+        assert_eq!(latest_version.unwrap(), Some(2));
+    }
 }
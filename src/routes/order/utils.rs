@@ -1,26 +1,32 @@
 use super::errors::OrderError;
 use super::models::{
     CommerceBppTermsModel, CommerceDataModel, CommerceDocumentModel, CommerceFulfillmentModel,
-    CommerceItemModel, CommerceListModel, CommercePaymentModel, DropOffContactModel,
-    DropOffDataModel, DropOffLocationModel, FulfillmentInstruction, MinimalCommerceModel,
-    OrderBillingModel, OrderCancellationFeeModel, OrderCancellationTermModel,
-    PaymentSettlementDetailModel, PickUpContactModel, PickUpDataModel, PickUpLocationModel,
-    SellerPaymentDetailModel, TimeRangeModel,
+    CommerceItemModel, CommerceListModel, CommercePaymentModel, CommerceQuoteVersionModel,
+    DropOffContactModel, DropOffDataModel, DropOffLocationModel, FulfillmentInstruction,
+    LastOrderedItemModel, MinimalCommerceModel, OrderBillingModel, OrderCancellationFeeModel,
+    OrderCancellationTermModel, PaymentSettlementDetailModel, PickUpContactModel, PickUpDataModel,
+    PickUpLocationModel, SellerPaymentDetailModel, TimeRangeModel,
 };
 use super::schemas::{
     BasicNetworkData, BulkCancelFulfillmentData, BulkCancelItemData, BulkConfirmFulfillmentData,
-    BulkStatusFulfillmentData, BuyerTerm, Commerce, CommerceBPPTerms, CommerceBilling,
-    CommerceCancellationFee, CommerceCancellationTerm, CommerceDocument, CommerceFulfillment,
-    CommerceItem, CommerceList, CommercePayment, CommerceSeller, DocumentType, DropOffData,
-    FulfillmentContact, FulfillmentLocation, MinimalCommerceData, OrderListFilter,
-    OrderSelectFulfillment, OrderSelectRequest, PaymentSettlementDetail, PickUpData,
-    PickUpFulfillmentLocation, SelectFulfillmentLocation, SellerPaymentDetail, TimeRange,
-    TradeType,
+    BulkStatusFulfillmentData, BuyerTerm, BuyerTerms, City, Commerce, CommerceBPPTerms,
+    CommerceBilling, CommerceCancellationFee, CommerceCancellationTerm, CommerceDocument,
+    CommerceFulfillment, CommerceItem, CommerceList, CommercePayment, CommerceQuoteVersion,
+    CommerceSeller, Country, DocumentType, DropOffData, FulfillmentContact, FulfillmentLocation,
+    MinimalCommerceData, OrderDeliveyTerm, OrderListFilter, OrderNegotiateRequest,
+    OrderPaymentSchedule, OrderReorderRequest, OrderSelectCart, OrderSelectCartItem,
+    OrderSelectFulfillment, OrderSelectItem, OrderSelectRequest, PaymentSettlementDetail,
+    PickUpData, PickUpFulfillmentLocation, QuoteVersionItem, QuoteVersionSource, ReorderItem,
+    ReorderItemStatus, SelectFulfillmentLocation, SellerPaymentDetail, TimeRange, TradeType,
 };
 use crate::chat_client::{
     ChatClient, ChatData, ChatMessageType, ChatParticipant, SendMessageDataDescription,
 };
 use crate::constants::ONDC_TTL;
+use crate::domain::pricing::is_slab_price_ignored;
+use crate::elastic_search_client::ElasticSearchClient;
+use crate::routes::favourite::schemas::SupplierPreferenceType;
+use crate::routes::favourite::utils::fetch_supplier_preference;
 use crate::routes::ondc::schemas::{
     BreakupTitleType, ONDCBilling, ONDCBreakUp, ONDCConfirmFulfillmentStartLocation, ONDCContact,
     ONDCFulfillment, ONDCFulfillmentCategoryType, ONDCFulfillmentStopType, ONDCFulfillmentTime,
@@ -39,7 +45,12 @@ use crate::routes::order::schemas::{
     CommerceStatusType, DeliveryTerm, FulfillmentCategoryType, FulfillmentStatusType, IncoTermType,
     OrderType, PaymentCollectedBy, PaymentStatus, ServiceableType, SettlementBasis,
 };
-use crate::routes::product::schemas::{CategoryDomain, FulfillmentType, PaymentType};
+use crate::routes::product::schemas::{
+    CategoryDomain, FulfillmentType, ItemFetchRequest, ItemPriceChange, PaymentType,
+    ProductFulFillmentLocation, ServicabilityCheckRequest, WSSearchItem,
+};
+use crate::routes::product::utils::{get_item_detail_from_es, get_location_servicability};
+use crate::routes::settlement::utils::get_buyer_finder_fee;
 use crate::schemas::{
     CountryCode, CurrencyType, FeeType, RegisteredNetworkParticipant, RequestMetaData,
 };
@@ -51,6 +62,7 @@ use crate::utils::{get_gps_string, get_series_no};
 use anyhow::{anyhow, Context};
 use bigdecimal::{BigDecimal, ToPrimitive};
 use chrono::Utc;
+use futures::future::try_join_all;
 use serde_json::Value;
 use sqlx::types::Json;
 use sqlx::{Executor, PgPool, Postgres, QueryBuilder, Transaction};
@@ -322,6 +334,7 @@ pub async fn save_order_select_items(
     let mut qty_list = vec![];
     let mut mrp_list = vec![];
     let mut unit_price_list = vec![];
+    let mut expected_unit_price_list = vec![];
     let mut tax_rate_list = vec![];
     let mut item_req_list = vec![];
     let mut packagin_req_list = vec![];
@@ -342,6 +355,11 @@ pub async fn save_order_select_items(
                     .get_price(&BigDecimal::from(item.qty))
                     .clone(),
             );
+            expected_unit_price_list.push(
+                seller_item_obj
+                    .get_price_slab(&BigDecimal::from(item.qty))
+                    .map(|slab| slab.price_without_tax.clone()),
+            );
             tax_rate_list.push(seller_item_obj.tax_rate.clone());
         } else {
             item_code_list.push(None);
@@ -349,6 +367,7 @@ pub async fn save_order_select_items(
             item_image_list.push("".to_owned());
             mrp_list.push(BigDecimal::from(0));
             unit_price_list.push(BigDecimal::from(0));
+            expected_unit_price_list.push(None);
             tax_rate_list.push(BigDecimal::from(0));
         }
         // let item_name = '';
@@ -365,9 +384,10 @@ pub async fn save_order_select_items(
     let query = sqlx::query!(
         r#"
         INSERT INTO commerce_data_line (id, commerce_data_id, item_id, item_name, item_code, item_image, 
-            qty, location_ids, fulfillment_ids, tax_rate, mrp, unit_price, item_req, packaging_req)
+            qty, location_ids, fulfillment_ids, tax_rate, mrp, unit_price, item_req, packaging_req, expected_unit_price)
             SELECT * FROM UNNEST($1::uuid[], $2::uuid[], $3::text[], $4::text[], $5::text[], $6::text[],
-             $7::decimal[], $8::jsonb[], $9::jsonb[], $10::decimal[], $11::decimal[], $12::decimal[], $13::text[], $14::text[])
+             $7::decimal[], $8::jsonb[], $9::jsonb[], $10::decimal[], $11::decimal[], $12::decimal[], $13::text[], $14::text[],
+             $15::decimal[])
         ON CONFLICT (commerce_data_id, item_code) 
         DO NOTHING
         "#,
@@ -385,6 +405,7 @@ pub async fn save_order_select_items(
         &unit_price_list[..] as &[BigDecimal],
         &item_req_list[..] as &[Option<&str>],
         &packagin_req_list[..] as &[Option<&str>],
+        &expected_unit_price_list[..] as &[Option<BigDecimal>],
     );
 
     transaction.execute(query).await.map_err(|e| {
@@ -534,6 +555,20 @@ pub async fn initialize_order_select(
     .await?;
 
     save_payment_obj_select(&mut transaction, order_id, &select_request.payment_types).await?;
+    let quote_version =
+        get_next_quote_version(&mut transaction, select_request.transaction_id).await?;
+    save_commerce_quote_version(
+        &mut transaction,
+        select_request.transaction_id,
+        select_request.message_id,
+        quote_version,
+        QuoteVersionSource::Buyer,
+        &get_quote_version_items_from_select_request(select_request),
+        Some(currency_code),
+        None,
+        Some(user_account.id),
+    )
+    .await?;
 
     transaction
         .commit()
@@ -763,6 +798,11 @@ pub async fn save_buyer_order_data_on_select(
         created_on = ondc_select_req.context.timestamp;
         updated_by = Some("seller");
     };
+    let domain_category = ondc_on_select_req
+        .context
+        .domain
+        .get_category_domain()
+        .ok_or_else(|| anyhow!("Invalid order domain"))?;
     let order_status = if ondc_on_select_req.error.is_none() {
         CommerceStatusType::QuoteAccepted
     } else {
@@ -788,7 +828,7 @@ pub async fn save_buyer_order_data_on_select(
         order_no,
         &order_type as &OrderType,
         &order_status as &CommerceStatusType,
-        &ondc_on_select_req.context.domain.get_category_domain() as &CategoryDomain,
+        &domain_category as &CategoryDomain,
         &business_account.id,
         &ondc_on_select_req.message.order.provider.id,
         &provider_name,
@@ -1027,6 +1067,7 @@ pub async fn save_order_on_select_items(
     let mut qty_list = vec![];
     let mut mrp_list = vec![];
     let mut unit_price_list = vec![];
+    let mut expected_unit_price_list = vec![];
     let mut tax_rate_list = vec![];
     let mut tax_amount_list = vec![];
     let mut discount_amount_list = vec![];
@@ -1132,12 +1173,18 @@ pub async fn save_order_on_select_items(
             );
             mrp_list.push(seller_item_obj.mrp.clone());
             // unit_price_list.push(seller_item_obj.unit_price.clone());
+            expected_unit_price_list.push(
+                seller_item_obj
+                    .get_price_slab(&BigDecimal::from(item.quantity.selected.count))
+                    .map(|slab| slab.price_without_tax.clone()),
+            );
             tax_rate_list.push(seller_item_obj.tax_rate.clone());
         } else {
             item_code_list.push(None);
             item_name_list.push("");
             item_image_list.push("");
             mrp_list.push(BigDecimal::from(0));
+            expected_unit_price_list.push(None);
 
             tax_rate_list.push(BigDecimal::from(0));
         }
@@ -1148,18 +1195,35 @@ pub async fn save_order_on_select_items(
 
         qty_list.push(BigDecimal::from(item.quantity.selected.count));
     }
+    for ((item_id, unit_price), expected_unit_price) in item_id_list
+        .iter()
+        .zip(&unit_price_list)
+        .zip(&expected_unit_price_list)
+    {
+        if let Some(expected_unit_price) = expected_unit_price {
+            if is_slab_price_ignored(expected_unit_price, unit_price) {
+                tracing::warn!(
+                    "Seller quote for item {} ignores the price slab: quoted {}, expected {}",
+                    item_id,
+                    unit_price,
+                    expected_unit_price
+                );
+            }
+        }
+    }
     let query = sqlx::query!(
         r#"
         INSERT INTO commerce_data_line (id, commerce_data_id, item_id, item_name, item_code, item_image, 
             qty, location_ids, fulfillment_ids, tax_rate, mrp, unit_price, discount_amount, tax_value, gross_total,
-            available_qty,item_req, packaging_req)
+            available_qty,item_req, packaging_req, expected_unit_price)
             SELECT * FROM UNNEST($1::uuid[], $2::uuid[], $3::text[], $4::text[], $5::text[], $6::text[],
              $7::decimal[], $8::jsonb[], $9::jsonb[], $10::decimal[], $11::decimal[], $12::decimal[], $13::decimal[],
-            $14::decimal[], $15::decimal[], $16::decimal[], $17::text[], $18::text[])
+            $14::decimal[], $15::decimal[], $16::decimal[], $17::text[], $18::text[], $19::decimal[])
         ON CONFLICT (commerce_data_id, item_code) 
         DO UPDATE SET 
         fulfillment_ids = EXCLUDED.fulfillment_ids,
         unit_price = EXCLUDED.unit_price,
+        expected_unit_price = EXCLUDED.expected_unit_price,
         discount_amount = EXCLUDED.discount_amount,
         tax_value = EXCLUDED.tax_value,
         gross_total = EXCLUDED.gross_total,
@@ -1183,6 +1247,7 @@ pub async fn save_order_on_select_items(
         &available_qty_list[..] as &[BigDecimal],
         &item_req_list[..] as &[Option<&str>],
         &packaging_req_list[..] as &[Option<&str>],
+        &expected_unit_price_list[..] as &[Option<BigDecimal>],
     );
 
     transaction.execute(query).await.map_err(|e| {
@@ -1250,6 +1315,7 @@ pub async fn get_commerce_data_line(
             tax_rate, 
             tax_value, 
             unit_price, 
+            expected_unit_price,
             gross_total, 
             available_qty, 
             discount_amount, 
@@ -1280,22 +1346,27 @@ pub async fn get_commerce_payments(
         CommercePaymentModel,
         r#"
         SELECT 
-            id, 
-            collected_by as "collected_by?: PaymentCollectedBy",
-            payment_type as "payment_type!: PaymentType", 
-            commerce_data_id,
-            seller_payment_detail as "seller_payment_detail?: Json<SellerPaymentDetailModel>",
-            buyer_fee_type  as "buyer_fee_type?: FeeType",
-            buyer_fee_amount,
-            settlement_window,
-            settlement_basis as "settlement_basis?: SettlementBasis",
-            withholding_amount,
-            settlement_details as "settlement_details?: Json<Vec<PaymentSettlementDetailModel>>",
-            payment_status as "payment_status: PaymentStatus",
-            payment_id,
-            payment_order_id
-        FROM commerce_payment_data 
-        WHERE commerce_data_id = $1
+            p.id, 
+            p.collected_by as "collected_by?: PaymentCollectedBy",
+            p.payment_type as "payment_type!: PaymentType", 
+            p.commerce_data_id,
+            p.seller_payment_detail as "seller_payment_detail?: Json<SellerPaymentDetailModel>",
+            p.buyer_fee_type  as "buyer_fee_type?: FeeType",
+            p.buyer_fee_amount,
+            p.settlement_window,
+            p.settlement_basis as "settlement_basis?: SettlementBasis",
+            p.withholding_amount,
+            p.settlement_details as "settlement_details?: Json<Vec<PaymentSettlementDetailModel>>",
+            p.payment_status as "payment_status: PaymentStatus",
+            p.payment_id,
+            p.payment_order_id,
+            p.payment_amount,
+            o.payment_link as "payment_link?",
+            o.expires_on as "payment_order_expires_on?"
+        FROM commerce_payment_data AS p
+        LEFT JOIN commerce_payment_order AS o ON o.payment_order_id = p.payment_order_id
+        WHERE p.commerce_data_id = $1
+        ORDER BY p.payment_type
         "#,
         order_id
     )
@@ -1404,6 +1475,9 @@ fn get_order_payment_from_model(payments: Vec<CommercePaymentModel>) -> Vec<Comm
             payment_id: payment.payment_id,
             payment_order_id: payment.payment_order_id,
             payment_status: payment.payment_status,
+            payment_amount: payment.payment_amount,
+            payment_link: payment.payment_link,
+            payment_order_expires_on: payment.payment_order_expires_on,
         })
     }
     payment_obj
@@ -1438,6 +1512,11 @@ fn get_order_items_from_model(items: Vec<CommerceItemModel>) -> Vec<CommerceItem
             buyer_terms: buyer_term,
             tax_rate: item.tax_rate,
             tax_value: item.tax_value,
+            is_slab_price_ignored: item
+                .expected_unit_price
+                .as_ref()
+                .is_some_and(|expected| is_slab_price_ignored(expected, &item.unit_price)),
+            expected_unit_price: item.expected_unit_price,
             unit_price: item.unit_price,
             gross_total: item.gross_total,
             available_qty: item.available_qty,
@@ -1585,6 +1664,23 @@ fn get_document_from_document_model(
     document_list
 }
 
+/// Computes the buyer finder fee from the terms the seller echoed on the order payments.
+pub fn get_order_buyer_finder_fee(
+    grand_total: Option<&BigDecimal>,
+    payments: &[CommercePayment],
+) -> Option<BigDecimal> {
+    let grand_total = grand_total?;
+    let payment = payments
+        .iter()
+        .find(|payment| payment.buyer_fee_type.is_some())?;
+    get_buyer_finder_fee(
+        grand_total,
+        payment.buyer_fee_type.as_ref(),
+        payment.buyer_fee_amount.as_deref(),
+    )
+    .ok()
+}
+
 #[tracing::instrument(name = "model to struct", skip())]
 fn get_order_from_model(
     order: CommerceDataModel,
@@ -1595,6 +1691,8 @@ fn get_order_from_model(
     let cancelletion_model_obj = order
         .cancellation_terms
         .map(|e| get_cancelletion_term_from_model(e.0));
+    let payments = get_order_payment_from_model(payments);
+    let buyer_finder_fee = get_order_buyer_finder_fee(order.grand_total.as_ref(), &payments);
     Commerce {
         id: order.id,
         urn: order.urn,
@@ -1612,6 +1710,7 @@ fn get_order_from_model(
         updated_by: order.updated_by,
         created_by: order.created_by,
         grand_total: order.grand_total,
+        buyer_finder_fee,
         bap: BasicNetworkData {
             id: order.bap_id,
             uri: order.bap_uri,
@@ -1623,7 +1722,7 @@ fn get_order_from_model(
         quote_ttl: order.quote_ttl,
         city_code: order.city_code,
         country_code: order.country_code,
-        payments: get_order_payment_from_model(payments),
+        payments,
         items: get_order_items_from_model(lines),
         fulfillments: get_order_fulfillment_from_model(fulfillments),
         billing: order
@@ -1666,6 +1765,41 @@ pub async fn fetch_order_by_id(
     }
 }
 
+#[tracing::instrument(name = "fetch last ordered item", skip(pool))]
+pub async fn fetch_last_ordered_item(
+    pool: &PgPool,
+    business_id: Uuid,
+    bpp_id: &str,
+    provider_id: &str,
+    item_id: &str,
+) -> Result<Option<LastOrderedItemModel>, anyhow::Error> {
+    let row = sqlx::query_as!(
+        LastOrderedItemModel,
+        r#"
+        SELECT cd.created_on, cdl.unit_price, cdl.qty
+        FROM commerce_data_line AS cdl
+        JOIN commerce_data AS cd ON cdl.commerce_data_id = cd.id
+        WHERE cd.buyer_id = $1 AND cd.bpp_id = $2 AND cd.seller_id = $3 AND cdl.item_id = $4
+        AND NOT cd.is_deleted
+        AND cd.record_status IN ('created', 'accepted', 'in_progress', 'completed')
+        ORDER BY cd.created_on DESC
+        LIMIT 1
+        "#,
+        business_id,
+        bpp_id,
+        provider_id,
+        item_id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e)
+            .context("A database failure occurred while fetching last ordered item")
+    })?;
+    Ok(row)
+}
+
 #[tracing::instrument(name = "delete payment", skip(transaction))]
 async fn delete_payment_in_commerce(
     transaction: &mut Transaction<'_, Postgres>,
@@ -1709,36 +1843,28 @@ pub async fn initialize_payment_on_init(
     let mut seller_payment_detail_list = vec![];
     let mut payment_id_list = vec![];
     let mut payment_order_id_list = vec![];
-    let payment_order_id = order
-        .payments
-        .iter()
-        .find(|a| a.payment_order_id.is_some())
-        .and_then(|f| f.payment_order_id.as_deref());
-
-    let payment_id = order
-        .payments
-        .iter()
-        .find(|a| a.payment_id.is_some())
-        .and_then(|f| f.payment_id.as_deref());
-    let payment_status = order
-        .payments
-        .iter()
-        .find(|f| f.payment_order_id.is_some())
-        .map(|f| &f.payment_status)
-        .unwrap_or(&PaymentStatus::NotPaid);
-    // .unwrap_or(PaymentStatus::NotPaid);
+    let mut payment_amount_list = vec![];
     for payment in payments {
+        let payment_type = payment.r#type.get_payment();
+        let stored_payment = get_matching_commerce_payment(order, &payment_type);
+        let payment_order_id = stored_payment.and_then(|f| f.payment_order_id.as_deref());
         id_list.push(Uuid::new_v4());
         commerce_data_id_list.push(order.id);
         collected_by_list.push(payment.collected_by.get_type());
-        payment_type_list.push(payment.r#type.get_payment());
+        payment_type_list.push(payment_type);
         buyer_fee_type_list.push(&payment.buyer_app_finder_fee_type);
         buyer_fee_amount_list
             .push(BigDecimal::from_str(&payment.buyer_app_finder_fee_amount).unwrap());
         settlement_window_list.push(payment.settlement_window.as_deref());
-        payment_status_list.push(payment_status);
-        payment_id_list.push(payment_id);
+        payment_status_list.push(
+            stored_payment
+                .filter(|f| f.payment_order_id.is_some())
+                .map(|f| &f.payment_status)
+                .unwrap_or(&PaymentStatus::NotPaid),
+        );
+        payment_id_list.push(stored_payment.and_then(|f| f.payment_id.as_deref()));
         payment_order_id_list.push(payment_order_id);
+        payment_amount_list.push(stored_payment.and_then(|f| f.payment_amount.clone()));
         withholding_amount_list.push(
             payment
                 .withholding_amount
@@ -1806,10 +1932,11 @@ pub async fn initialize_payment_on_init(
         r#"
         INSERT INTO commerce_payment_data(id, commerce_data_id, collected_by, payment_type, buyer_fee_type,
             buyer_fee_amount, settlement_window, withholding_amount, settlement_basis, settlement_details, seller_payment_detail,
-            payment_status, payment_id, payment_order_id)
+            payment_status, payment_id, payment_order_id, payment_amount)
             SELECT * FROM UNNEST($1::uuid[], $2::uuid[], $3::payment_collected_by_type[],
             $4::payment_type[], $5::ondc_np_fee_type[], $6::decimal[], $7::text[], $8::decimal[],
-            $9::settlement_basis_type[],$10::jsonb[], $11::jsonb[], $12::payment_status[], $13::text[],  $14::text[])
+            $9::settlement_basis_type[],$10::jsonb[], $11::jsonb[], $12::payment_status[], $13::text[],  $14::text[],
+            $15::decimal[])
         "#,
         &id_list[..] as &[Uuid],
         &commerce_data_id_list[..] as &[Uuid],
@@ -1824,7 +1951,8 @@ pub async fn initialize_payment_on_init(
         &seller_payment_detail_list[..] as &[Option<Value>],
         &payment_status_list[..] as &[&PaymentStatus],
         &payment_id_list[..] as &[Option<&str>],
-        &payment_order_id_list[..] as &[Option<&str>]
+        &payment_order_id_list[..] as &[Option<&str>],
+        &payment_amount_list[..] as &[Option<BigDecimal>]
     );
 
     transaction.execute(query).await.map_err(|e| {
@@ -2142,17 +2270,15 @@ pub async fn initialize_payment_on_confirm(
     let mut seller_payment_detail_list = vec![];
     let mut payment_id_list = vec![];
     let mut payment_order_id_list = vec![];
-    let payment_order_id = order
-        .payments
-        .iter()
-        .find(|a| a.payment_order_id.is_some())
-        .and_then(|f| f.payment_order_id.as_ref());
 
     for payment in payments {
+        let payment_type = payment.r#type.get_payment();
+        let payment_order_id = get_matching_commerce_payment(order, &payment_type)
+            .and_then(|f| f.payment_order_id.as_ref());
         id_list.push(Uuid::new_v4());
         commerce_data_id_list.push(order.id);
         collected_by_list.push(payment.collected_by.get_type());
-        payment_type_list.push(payment.r#type.get_payment());
+        payment_type_list.push(payment_type);
         buyer_fee_type_list.push(&payment.buyer_app_finder_fee_type);
         buyer_fee_amount_list
             .push(BigDecimal::from_str(&payment.buyer_app_finder_fee_amount).unwrap());
@@ -2817,6 +2943,42 @@ pub async fn initialize_order_on_update(
     Ok(())
 }
 
+pub fn get_order_select_cart(
+    select_request: &OrderSelectRequest,
+    product_map: &HashMap<String, ONDCSellerProductInfo>,
+) -> OrderSelectCart {
+    let mut currency_code = None;
+    let mut items = vec![];
+    for item in &select_request.items {
+        let key = get_ondc_seller_product_mapping_key(
+            &select_request.bpp_id,
+            &select_request.provider_id,
+            &item.item_id,
+        );
+        if let Some(seller_item_obj) = product_map.get(&key) {
+            let qty = BigDecimal::from(item.qty);
+            let unit_price_with_tax = seller_item_obj.get_price_with_tax(&qty).clone();
+            let unit_price_without_tax = seller_item_obj.get_price(&qty).clone();
+            currency_code.get_or_insert_with(|| seller_item_obj.currency_code.clone());
+            items.push(OrderSelectCartItem {
+                item_id: item.item_id.to_owned(),
+                qty: item.qty,
+                is_price_slab_applied: seller_item_obj.get_price_slab(&qty).is_some(),
+                total_with_tax: &unit_price_with_tax * &qty,
+                total_without_tax: &unit_price_without_tax * &qty,
+                unit_price_with_tax,
+                unit_price_without_tax,
+            });
+        }
+    }
+    OrderSelectCart {
+        currency_code,
+        total_with_tax: items.iter().map(|item| &item.total_with_tax).sum(),
+        total_without_tax: items.iter().map(|item| &item.total_without_tax).sum(),
+        items,
+    }
+}
+
 pub fn validate_select_request(
     body: &OrderSelectRequest,
     business_account: &BusinessAccount,
@@ -2857,6 +3019,165 @@ pub fn validate_select_request(
     Ok(())
 }
 
+/// Rejects delivery fulfillments that a cached provider location cannot service.
+/// Locations without cached servicability are left for the seller to decide.
+#[tracing::instrument(name = "validate select servicability", skip(pool))]
+pub async fn validate_select_servicability(
+    pool: &PgPool,
+    body: &OrderSelectRequest,
+) -> Result<(), OrderError> {
+    let location_ids: Vec<String> = body
+        .items
+        .iter()
+        .flat_map(|item| item.location_ids.clone())
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    for fulfillment in body
+        .fulfillments
+        .iter()
+        .filter(|fulfillment| fulfillment.r#type == FulfillmentType::Delivery)
+    {
+        let fulfillment_location = get_fulfillment_location_from_gps(
+            &fulfillment.location.gps,
+            &fulfillment.location.area_code,
+        )
+        .ok_or_else(|| {
+            OrderError::ValidationError(format!("Invalid gps for fulfillment {}", fulfillment.id))
+        })?;
+        let servicability_request = ServicabilityCheckRequest {
+            bpp_id: body.bpp_id.to_owned(),
+            provider_id: body.provider_id.to_owned(),
+            location_ids: Some(location_ids.clone()),
+            domain_category_code: body.domain_category_code.clone(),
+            category_code: None,
+            country_code: fulfillment.location.country.code.clone(),
+            fulfillment_location,
+        };
+        let non_servicable_location_ids: Vec<String> =
+            get_location_servicability(pool, &servicability_request)
+                .await
+                .map_err(|e| OrderError::DatabaseError(e.to_string(), e))?
+                .into_iter()
+                .filter(|location| location.is_servicable == Some(false))
+                .map(|location| location.location_id)
+                .collect();
+        if !non_servicable_location_ids.is_empty() {
+            return Err(OrderError::ValidationError(format!(
+                "Provider location {} cannot service area code {}",
+                non_servicable_location_ids.join(", "),
+                fulfillment.location.area_code
+            )));
+        }
+    }
+    Ok(())
+}
+
+pub async fn validate_select_supplier(
+    pool: &PgPool,
+    business_id: Uuid,
+    body: &OrderSelectRequest,
+) -> Result<(), OrderError> {
+    let preference = fetch_supplier_preference(pool, business_id, &body.bpp_id, &body.provider_id)
+        .await
+        .map_err(|e| OrderError::DatabaseError(e.to_string(), e))?;
+    if preference == Some(SupplierPreferenceType::Blocked) {
+        return Err(OrderError::ValidationError(format!(
+            "Provider {} is a blocked supplier",
+            body.provider_id
+        )));
+    }
+    Ok(())
+}
+
+/// Splits the order total into an advance and a balance instalment as per the payment schedule.
+pub fn get_payment_schedule_amounts(
+    order: &Commerce,
+    schedule: &OrderPaymentSchedule,
+) -> Result<Vec<(PaymentType, BigDecimal)>, OrderError> {
+    if schedule.advance_percentage <= BigDecimal::from(0)
+        || schedule.advance_percentage >= BigDecimal::from(100)
+    {
+        return Err(OrderError::ValidationError(
+            "Advance percentage should be between 0 and 100".to_owned(),
+        ));
+    }
+    if schedule.balance_payment_type == PaymentType::PrePaid {
+        return Err(OrderError::ValidationError(
+            "Balance payment type cannot be pre paid".to_owned(),
+        ));
+    }
+    for payment_type in [&PaymentType::PrePaid, &schedule.balance_payment_type] {
+        if !order
+            .payments
+            .iter()
+            .any(|payment| &payment.payment_type == payment_type)
+        {
+            return Err(OrderError::ValidationError(format!(
+                "{:?} payment is not offered for the order",
+                payment_type
+            )));
+        }
+    }
+    let grand_total = order.grand_total.as_ref().ok_or_else(|| {
+        OrderError::ValidationError("Order total is not available yet".to_owned())
+    })?;
+    let advance_amount =
+        (grand_total * &schedule.advance_percentage / BigDecimal::from(100)).round(2);
+    let balance_amount = grand_total - &advance_amount;
+    Ok(vec![
+        (PaymentType::PrePaid, advance_amount),
+        (schedule.balance_payment_type.clone(), balance_amount),
+    ])
+}
+
+#[tracing::instrument(name = "save commerce payment amounts", skip(pool))]
+pub async fn save_commerce_payment_amounts(
+    pool: &PgPool,
+    order_id: Uuid,
+    amounts: &[(PaymentType, BigDecimal)],
+) -> Result<(), anyhow::Error> {
+    let (payment_types, payment_amounts): (Vec<PaymentType>, Vec<BigDecimal>) =
+        amounts.iter().cloned().unzip();
+    sqlx::query!(
+        r#"
+        UPDATE commerce_payment_data AS p SET payment_amount = s.amount
+        FROM commerce_payment_data AS c
+        LEFT JOIN UNNEST($2::payment_type[], $3::decimal[]) AS s(payment_type, amount)
+            ON s.payment_type = c.payment_type
+        WHERE p.id = c.id AND c.commerce_data_id = $1
+        "#,
+        order_id,
+        &payment_types[..] as &[PaymentType],
+        &payment_amounts[..] as &[BigDecimal]
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e)
+            .context("A database failure occurred while saving commerce payment amounts")
+    })?;
+    Ok(())
+}
+
+/// Finds the stored instalment a seller payment object refers to, so its gateway state survives re-inserts.
+fn get_matching_commerce_payment<'a>(
+    order: &'a Commerce,
+    payment_type: &PaymentType,
+) -> Option<&'a CommercePayment> {
+    order
+        .payments
+        .iter()
+        .find(|payment| &payment.payment_type == payment_type)
+        .or_else(|| {
+            order
+                .payments
+                .iter()
+                .find(|payment| payment.payment_order_id.is_some())
+        })
+}
+
 pub fn validate_init_request(order_data: &Commerce) -> Result<(), OrderError> {
     if !matches!(
         order_data.record_status,
@@ -3123,12 +3444,27 @@ pub fn send_rfq_accept_chat_description(
     descriptions
 }
 
+fn get_quote_version_chat_description(quote_version: Option<i32>) -> SendMessageDataDescription {
+    SendMessageDataDescription {
+        text: format!(
+            "Quote Version: {}",
+            quote_version.map_or_else(|| "NA".to_owned(), |v| v.to_string())
+        ),
+        r#type: ChatMessageType::Text,
+    }
+}
+
 pub async fn send_rfq_accept_chat(
     chat_client: &ChatClient,
     select_request: &ONDCOnSelectRequest,
     seller_product_mapping: &HashMap<String, ONDCSellerProductInfo>,
+    quote_version: i32,
 ) -> Result<(), anyhow::Error> {
-    let description = send_rfq_accept_chat_description(select_request, seller_product_mapping);
+    let mut description = vec![get_quote_version_chat_description(Some(quote_version))];
+    description.extend(send_rfq_accept_chat_description(
+        select_request,
+        seller_product_mapping,
+    ));
     let data = chat_client.get_send_message_data("Quotation Accepted", description);
     let sender = ChatParticipant {
         id: select_request.message.order.provider.id.to_string(),
@@ -3144,12 +3480,16 @@ pub async fn send_rfq_reject_chat(
     error: &str,
     transaction_id: Uuid,
     provider_id: &str,
+    quote_version: Option<i32>,
 ) -> Result<(), anyhow::Error> {
-    let description = SendMessageDataDescription {
-        text: error.to_owned(),
-        r#type: ChatMessageType::Text,
-    };
-    let data = chat_client.get_send_message_data("Quotation Rejected", vec![description]);
+    let description = vec![
+        get_quote_version_chat_description(quote_version),
+        SendMessageDataDescription {
+            text: error.to_owned(),
+            r#type: ChatMessageType::Text,
+        },
+    ];
+    let data = chat_client.get_send_message_data("Quotation Rejected", description);
     let sender = ChatParticipant {
         id: provider_id.to_owned(),
         name: "NA".to_owned(),
@@ -3159,6 +3499,72 @@ pub async fn send_rfq_reject_chat(
         .await
 }
 
+pub fn send_rfq_negotiate_chat_description(
+    order: &Commerce,
+    negotiate_request: &OrderNegotiateRequest,
+    quote_version: i32,
+) -> Vec<SendMessageDataDescription> {
+    let mut descriptions = vec![get_quote_version_chat_description(Some(quote_version))];
+    for item in negotiate_request.items.iter() {
+        let item_name = order
+            .items
+            .iter()
+            .find(|line| line.item_id == item.item_id)
+            .map_or("", |line| line.item_name.as_str());
+        let vals = vec![
+            SendMessageDataDescription {
+                text: "".to_owned(),
+                r#type: ChatMessageType::Divider,
+            },
+            SendMessageDataDescription {
+                text: "Product".to_owned(),
+                r#type: ChatMessageType::Header,
+            },
+            SendMessageDataDescription {
+                text: item_name.to_owned(),
+                r#type: ChatMessageType::Text,
+            },
+            SendMessageDataDescription {
+                text: format!(
+                    "Counter Qty: {}",
+                    item.qty
+                        .map_or_else(|| "NA".to_owned(), |qty| qty.to_string())
+                ),
+                r#type: ChatMessageType::Text,
+            },
+            SendMessageDataDescription {
+                text: format!(
+                    "Counter Unit Price: {}",
+                    item.unit_price
+                        .as_ref()
+                        .map_or_else(|| "NA".to_owned(), |price| price.to_string())
+                ),
+                r#type: ChatMessageType::Text,
+            },
+        ];
+        descriptions.extend(vals)
+    }
+    descriptions
+}
+
+pub async fn send_rfq_negotiate_chat(
+    chat_client: &ChatClient,
+    order: &Commerce,
+    negotiate_request: &OrderNegotiateRequest,
+    business_account: &BusinessAccount,
+    quote_version: i32,
+) -> Result<(), anyhow::Error> {
+    let description = send_rfq_negotiate_chat_description(order, negotiate_request, quote_version);
+    let data = chat_client.get_send_message_data("Quotation Counter Offer", description);
+    let sender = ChatParticipant {
+        id: business_account.id.to_string(),
+        name: business_account.company_name.to_owned(),
+    };
+    chat_client
+        .send_chat_data(negotiate_request.transaction_id, sender, data)
+        .await
+}
+
 pub async fn send_rfq_init_chat(
     chat_client: &ChatClient,
     transaction_id: Uuid,
@@ -3494,3 +3900,478 @@ pub async fn fetch_minimal_commerce_data_model(
 
     Ok(rows.schema())
 }
+
+pub fn get_quote_version_items_from_select_request(
+    select_request: &OrderSelectRequest,
+) -> Vec<QuoteVersionItem> {
+    select_request
+        .items
+        .iter()
+        .map(|item| QuoteVersionItem {
+            item_id: item.item_id.to_owned(),
+            qty: item.qty,
+            unit_price: None,
+            gross_total: None,
+        })
+        .collect()
+}
+
+pub fn get_quote_version_items_from_ondc_select(
+    select_request: &ONDCSelectRequest,
+) -> Vec<QuoteVersionItem> {
+    select_request
+        .message
+        .order
+        .items
+        .iter()
+        .map(|item| QuoteVersionItem {
+            item_id: item.id.to_owned(),
+            qty: item.quantity.selected.count,
+            unit_price: item.tags.as_ref().and_then(|tags| {
+                get_tag_value_from_list(
+                    tags,
+                    ONDCTagType::Negotiation,
+                    &ONDCTagItemCode::NegotiatedPrice.to_string(),
+                )
+                .and_then(|price| BigDecimal::from_str(price).ok())
+            }),
+            gross_total: None,
+        })
+        .collect()
+}
+
+pub fn get_quote_version_items_from_on_select(
+    on_select_request: &ONDCOnSelectRequest,
+) -> Vec<QuoteVersionItem> {
+    let item_breakup_mapping = get_quote_item_breakup_mapping(
+        &on_select_request.message.order.quote.breakup,
+        &BreakupTitleType::Item,
+    );
+    on_select_request
+        .message
+        .order
+        .items
+        .iter()
+        .map(|item| {
+            let break_up = item_breakup_mapping.get(&item.id);
+            QuoteVersionItem {
+                item_id: item.id.to_owned(),
+                qty: item.quantity.selected.count,
+                unit_price: break_up.and_then(|b| {
+                    b.item
+                        .as_ref()
+                        .and_then(|a| BigDecimal::from_str(&a.price.value).ok())
+                }),
+                gross_total: break_up.and_then(|b| BigDecimal::from_str(&b.price.value).ok()),
+            }
+        })
+        .collect()
+}
+
+#[allow(clippy::too_many_arguments)]
+#[tracing::instrument(name = "save commerce quote version", skip(transaction))]
+pub async fn save_commerce_quote_version(
+    transaction: &mut Transaction<'_, Postgres>,
+    transaction_id: Uuid,
+    message_id: Uuid,
+    version: i32,
+    source: QuoteVersionSource,
+    items: &Vec<QuoteVersionItem>,
+    currency_code: Option<&CurrencyType>,
+    grand_total: Option<&BigDecimal>,
+    created_by: Option<Uuid>,
+) -> Result<(), anyhow::Error> {
+    let query = sqlx::query!(
+        r#"
+        INSERT INTO commerce_quote_version (id, transaction_id, message_id, version, source, items,
+        currency_code, grand_total, created_on, created_by)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+        "#,
+        Uuid::new_v4(),
+        transaction_id,
+        message_id,
+        version,
+        source as QuoteVersionSource,
+        serde_json::to_value(items)?,
+        currency_code as Option<&CurrencyType>,
+        grand_total,
+        Utc::now(),
+        created_by
+    );
+
+    transaction.execute(query).await.map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e)
+            .context("A database failure occurred while saving commerce quote version")
+    })?;
+    Ok(())
+}
+
+#[tracing::instrument(name = "get latest quote version", skip(pool))]
+pub async fn get_latest_quote_version(
+    pool: &PgPool,
+    transaction_id: Uuid,
+) -> Result<Option<i32>, anyhow::Error> {
+    let row = sqlx::query!(
+        r#"SELECT MAX(version) as version FROM commerce_quote_version WHERE transaction_id = $1"#,
+        transaction_id
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e)
+            .context("A database failure occurred while fetching latest quote version")
+    })?;
+    Ok(row.version)
+}
+
+/// Locks the order rows of `transaction_id` until the transaction ends so concurrent quote versions of
+/// the same order are numbered one after another.
+#[tracing::instrument(name = "get next quote version", skip(transaction))]
+pub async fn get_next_quote_version(
+    transaction: &mut Transaction<'_, Postgres>,
+    transaction_id: Uuid,
+) -> Result<i32, anyhow::Error> {
+    sqlx::query!(
+        r#"SELECT id FROM commerce_data WHERE external_urn = $1 FOR UPDATE"#,
+        transaction_id
+    )
+    .fetch_all(&mut **transaction)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e).context("A database failure occurred while locking commerce data")
+    })?;
+    let version = sqlx::query_scalar!(
+        r#"SELECT COALESCE(MAX(version), 0) + 1 AS "version!" FROM commerce_quote_version
+        WHERE transaction_id = $1"#,
+        transaction_id
+    )
+    .fetch_one(&mut **transaction)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e)
+            .context("A database failure occurred while fetching next quote version")
+    })?;
+    Ok(version)
+}
+
+#[tracing::instrument(name = "fetch commerce quote versions", skip(pool))]
+pub async fn fetch_quote_versions(
+    pool: &PgPool,
+    transaction_id: Uuid,
+) -> Result<Vec<CommerceQuoteVersion>, anyhow::Error> {
+    let rows = sqlx::query_as!(
+        CommerceQuoteVersionModel,
+        r#"SELECT version, message_id, source as "source: QuoteVersionSource",
+        items as "items: Json<Vec<QuoteVersionItem>>",
+        currency_code as "currency_code?: CurrencyType", grand_total, created_on
+        FROM commerce_quote_version WHERE transaction_id = $1 ORDER BY version"#,
+        transaction_id
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e).context("A database failure occurred while fetching quote versions")
+    })?;
+    Ok(rows.into_iter().map(|row| row.schema()).collect())
+}
+
+#[tracing::instrument(name = "update order on negotiate", skip(transaction))]
+pub async fn update_order_on_negotiate(
+    transaction: &mut Transaction<'_, Postgres>,
+    transaction_id: Uuid,
+    user_id: Uuid,
+) -> Result<(), anyhow::Error> {
+    let query = sqlx::query!(
+        r#"
+        UPDATE commerce_data SET record_status=$1, updated_on=$2, updated_by=$3 WHERE external_urn=$4
+        "#,
+        CommerceStatusType::QuoteRequested as CommerceStatusType,
+        Utc::now(),
+        user_id.to_string(),
+        transaction_id,
+    );
+
+    transaction.execute(query).await.map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e)
+            .context("A database failure occurred while updating order on negotiation")
+    })?;
+    Ok(())
+}
+
+pub fn validate_negotiate_request(
+    order: &Commerce,
+    negotiate_request: &OrderNegotiateRequest,
+) -> Result<(), OrderError> {
+    if order.record_type != OrderType::PurchaseOrder {
+        return Err(OrderError::ValidationError(
+            "Negotiation is only allowed for purchase orders".to_owned(),
+        ));
+    }
+    if order.record_status != CommerceStatusType::QuoteAccepted {
+        return Err(OrderError::ValidationError(format!(
+            "Order is in {} status, a seller quote is required to negotiate",
+            order.record_status
+        )));
+    }
+    if negotiate_request.items.is_empty() {
+        return Err(OrderError::ValidationError(
+            "At least one counter offer line is required".to_owned(),
+        ));
+    }
+    let mut item_ids = HashSet::new();
+    for item in negotiate_request.items.iter() {
+        if !item_ids.insert(&item.item_id) {
+            return Err(OrderError::ValidationError(format!(
+                "{} has more than one counter offer line",
+                item.item_id
+            )));
+        }
+        if !order.items.iter().any(|line| line.item_id == item.item_id) {
+            return Err(OrderError::ValidationError(format!(
+                "{} is not part of the quote",
+                item.item_id
+            )));
+        }
+        if item.qty.is_none() && item.unit_price.is_none() {
+            return Err(OrderError::ValidationError(format!(
+                "Counter quantity or price is required for {}",
+                item.item_id
+            )));
+        }
+        if item.qty.is_some_and(|qty| qty <= 0) {
+            return Err(OrderError::ValidationError(format!(
+                "Counter quantity for {} must be greater than zero",
+                item.item_id
+            )));
+        }
+        if item
+            .unit_price
+            .as_ref()
+            .is_some_and(|price| price <= &BigDecimal::from(0))
+        {
+            return Err(OrderError::ValidationError(format!(
+                "Counter price for {} must be greater than zero",
+                item.item_id
+            )));
+        }
+    }
+    Ok(())
+}
+
+pub fn get_reorder_fulfillment_location(order: &Commerce) -> Option<ProductFulFillmentLocation> {
+    let fulfillment = order.fulfillments.first()?;
+    let (gps, area_code) = match &fulfillment.drop_off {
+        Some(drop_off) => (&drop_off.location.gps, &drop_off.location.area_code),
+        None => (
+            &fulfillment.pickup.location.gps,
+            &fulfillment.pickup.location.area_code,
+        ),
+    };
+    get_fulfillment_location_from_gps(gps, area_code)
+}
+
+fn get_fulfillment_location_from_gps(
+    gps: &str,
+    area_code: &str,
+) -> Option<ProductFulFillmentLocation> {
+    let mut gps_data = gps.split(',').map(|s| s.trim().parse::<f64>());
+    let latitude = gps_data.next()?.ok()?;
+    let longitude = gps_data.next()?.ok()?;
+    Some(ProductFulFillmentLocation {
+        latitude,
+        longitude,
+        area_code: area_code.to_owned(),
+    })
+}
+
+#[tracing::instrument(name = "get reorder item mapping", skip(es_client, order))]
+pub async fn get_reorder_item_mapping(
+    es_client: &ElasticSearchClient,
+    order: &Commerce,
+    fulfillment_location: ProductFulFillmentLocation,
+) -> Result<HashMap<String, WSSearchItem>, anyhow::Error> {
+    let tasks = order.items.iter().map(|item| {
+        let fetch_request = ItemFetchRequest {
+            id: None,
+            bpp_id: Some(order.bpp.id.to_owned()),
+            provider_id: Some(order.seller.id.to_owned()),
+            item_id: Some(item.item_id.to_owned()),
+            fulfillment_location: Some(fulfillment_location.clone()),
+            qty: Some(item.qty.to_owned()),
+        };
+        async move { get_item_detail_from_es(es_client, &fetch_request).await }
+    });
+    let item_mapping = try_join_all(tasks)
+        .await?
+        .into_iter()
+        .flatten()
+        .filter(|data| {
+            data.servicable_location_ids
+                .as_ref()
+                .is_none_or(|location_ids| !location_ids.is_empty())
+        })
+        .map(|data| (data.item.id.to_owned(), data.item))
+        .collect();
+    Ok(item_mapping)
+}
+
+pub fn get_reorder_items(
+    order: &Commerce,
+    item_mapping: &HashMap<String, WSSearchItem>,
+) -> Vec<ReorderItem> {
+    order
+        .items
+        .iter()
+        .map(|item| {
+            let previous_qty = item.qty.to_i32().unwrap_or(0);
+            let (qty, status, current_unit_price) = match item_mapping.get(&item.item_id) {
+                None => (0, ReorderItemStatus::Unavailable, None),
+                Some(current_item) => {
+                    let available_qty =
+                        i32::try_from(current_item.quantity.available.count).unwrap_or(i32::MAX);
+                    let current_price = current_item.get_price_without_tax(&item.qty);
+                    if available_qty <= 0 {
+                        (0, ReorderItemStatus::Unavailable, Some(current_price))
+                    } else if available_qty < previous_qty {
+                        (
+                            available_qty,
+                            ReorderItemStatus::QuantityReduced,
+                            Some(current_price),
+                        )
+                    } else if current_price != item.unit_price {
+                        (
+                            previous_qty,
+                            ReorderItemStatus::PriceChanged,
+                            Some(current_price),
+                        )
+                    } else {
+                        (
+                            previous_qty,
+                            ReorderItemStatus::Unchanged,
+                            Some(current_price),
+                        )
+                    }
+                }
+            };
+            let price_change = current_unit_price.as_ref().map(|current_unit_price| {
+                ItemPriceChange::new(
+                    order.created_on,
+                    item.unit_price.to_owned(),
+                    current_unit_price.to_owned(),
+                )
+            });
+            ReorderItem {
+                item_id: item.item_id.to_owned(),
+                item_name: item.item_name.to_owned(),
+                previous_qty,
+                qty,
+                status,
+                previous_unit_price: item.unit_price.to_owned(),
+                current_unit_price,
+                price_change,
+            }
+        })
+        .collect()
+}
+
+pub fn get_reorder_select_request(
+    order: Commerce,
+    body: &OrderReorderRequest,
+    reorder_items: &[ReorderItem],
+) -> OrderSelectRequest {
+    let item_qty_mapping: HashMap<&str, i32> = reorder_items
+        .iter()
+        .filter(|item| item.qty > 0)
+        .map(|item| (item.item_id.as_str(), item.qty))
+        .collect();
+    let mut payment_types: Vec<PaymentType> = vec![];
+    for payment in order.payments.iter() {
+        if !payment_types.contains(&payment.payment_type) {
+            payment_types.push(payment.payment_type.clone());
+        }
+    }
+    let items = order
+        .items
+        .into_iter()
+        .filter_map(|item| {
+            item_qty_mapping
+                .get(item.item_id.as_str())
+                .map(|&qty| OrderSelectItem {
+                    item_id: item.item_id,
+                    location_ids: item.location_ids,
+                    qty,
+                    buyer_term: item.buyer_terms.map(|term| BuyerTerms {
+                        item_req: term.item_req,
+                        packaging_req: term.packaging_req,
+                    }),
+                    fulfillment_ids: item.fulfillment_ids,
+                })
+        })
+        .collect();
+    let city_code = order.city_code;
+    let fulfillments = order
+        .fulfillments
+        .into_iter()
+        .map(|fulfillment| {
+            let location = match fulfillment.drop_off {
+                Some(drop_off) => SelectFulfillmentLocation {
+                    gps: drop_off.location.gps,
+                    area_code: drop_off.location.area_code,
+                    address: drop_off.location.address.unwrap_or_default(),
+                    city: City {
+                        code: city_code.clone(),
+                        name: drop_off.location.city,
+                    },
+                    country: Country {
+                        name: format!("{:?}", drop_off.location.country),
+                        code: drop_off.location.country,
+                    },
+                    state: drop_off.location.state,
+                    contact_mobile_no: drop_off.contact.mobile_no,
+                },
+                None => SelectFulfillmentLocation {
+                    gps: fulfillment.pickup.location.gps,
+                    area_code: fulfillment.pickup.location.area_code,
+                    address: fulfillment.pickup.location.address,
+                    city: City {
+                        code: city_code.clone(),
+                        name: fulfillment.pickup.location.city,
+                    },
+                    country: Country {
+                        name: format!("{:?}", fulfillment.pickup.location.country),
+                        code: fulfillment.pickup.location.country,
+                    },
+                    state: fulfillment.pickup.location.state,
+                    contact_mobile_no: fulfillment.pickup.contact.mobile_no,
+                },
+            };
+            OrderSelectFulfillment {
+                id: fulfillment.fulfillment_id,
+                r#type: fulfillment.fulfillment_type,
+                location,
+                delivery_terms: fulfillment.delivery_term.map(|term| OrderDeliveyTerm {
+                    inco_terms: term.inco_terms,
+                    place_of_delivery: term.place_of_delivery,
+                }),
+            }
+        })
+        .collect();
+    OrderSelectRequest {
+        transaction_id: body.new_transaction_id,
+        message_id: body.message_id,
+        domain_category_code: order.domain_category_code,
+        payment_types,
+        provider_id: order.seller.id,
+        items,
+        ttl: order.quote_ttl,
+        fulfillments,
+        order_type: order.record_type,
+        bpp_id: order.bpp.id,
+    }
+}
@@ -0,0 +1,108 @@
+use actix_http::StatusCode;
+use actix_web::web;
+use anyhow::Context;
+use sqlx::PgPool;
+use utoipa::TupleUnit;
+
+use super::schemas::{CommercePayable, PayableListRequest, PayableMarkPaidRequest};
+use super::utils::{fetch_commerce_payables, mark_commerce_payables_paid};
+use crate::errors::GenericError;
+use crate::schemas::GenericResponse;
+use crate::user_client::{BusinessAccount, UserAccount};
+
+#[utoipa::path(
+    post,
+    path = "/payable/list",
+    tag = "Payable",
+    description="This API returns the open credit payables of the business grouped by due status.",
+    summary= "Payable List Request",
+    request_body(content = PayableListRequest, description = "Request Body"),
+    responses(
+        (status=200, description= "Payable List Response", body= GenericResponse<Vec<CommercePayable>>),
+        (status=400, description= "Invalid Request body", body= GenericResponse<TupleUnit>),
+        (status=401, description= "Invalid Token", body= GenericResponse<TupleUnit>),
+	    (status=403, description= "Insufficient Previlege", body= GenericResponse<TupleUnit>),
+	    (status=410, description= "Data not found", body= GenericResponse<TupleUnit>),
+        (status=500, description= "Internal Server Error", body= GenericResponse<TupleUnit>),
+	    (status=501, description= "Not Implemented", body= GenericResponse<TupleUnit>),
+    )
+)]
+#[tracing::instrument(name = "payable list", skip(pool))]
+pub async fn payable_list(
+    body: PayableListRequest,
+    pool: web::Data<PgPool>,
+    business_account: BusinessAccount,
+) -> Result<web::Json<GenericResponse<Vec<CommercePayable>>>, GenericError> {
+    let payables = fetch_commerce_payables(&pool, business_account.id, &body)
+        .await
+        .map_err(|e| GenericError::DatabaseError(e.to_string(), e))?
+        .into_iter()
+        .map(|payable| payable.schema())
+        .collect();
+    Ok(web::Json(GenericResponse::success(
+        "Successfully fetched payables",
+        StatusCode::OK,
+        Some(payables),
+    )))
+}
+
+#[utoipa::path(
+    post,
+    path = "/payable/mark_paid",
+    tag = "Payable",
+    description="This API marks the open credit payables of the given orders as paid.",
+    summary= "Payable Mark Paid Request",
+    request_body(content = PayableMarkPaidRequest, description = "Request Body"),
+    responses(
+        (status=200, description= "Payable Mark Paid Response", body= GenericResponse<TupleUnit>),
+        (status=400, description= "Invalid Request body", body= GenericResponse<TupleUnit>),
+        (status=401, description= "Invalid Token", body= GenericResponse<TupleUnit>),
+	    (status=403, description= "Insufficient Previlege", body= GenericResponse<TupleUnit>),
+	    (status=410, description= "Data not found", body= GenericResponse<TupleUnit>),
+        (status=500, description= "Internal Server Error", body= GenericResponse<TupleUnit>),
+	    (status=501, description= "Not Implemented", body= GenericResponse<TupleUnit>),
+    )
+)]
+#[tracing::instrument(name = "payable mark paid", skip(pool))]
+pub async fn payable_mark_paid(
+    body: PayableMarkPaidRequest,
+    pool: web::Data<PgPool>,
+    user_account: UserAccount,
+    business_account: BusinessAccount,
+) -> Result<web::Json<GenericResponse<()>>, GenericError> {
+    if body.transaction_ids.is_empty() {
+        return Err(GenericError::ValidationError(
+            "Atleast one order is required".to_string(),
+        ));
+    }
+    let mut transaction = pool
+        .begin()
+        .await
+        .context("Failed to acquire a Postgres connection from the pool")
+        .map_err(|e| GenericError::DatabaseError(e.to_string(), e))?;
+    let updated_count = mark_commerce_payables_paid(
+        &mut transaction,
+        business_account.id,
+        user_account.id,
+        &body,
+    )
+    .await
+    .map_err(|e| GenericError::DatabaseError(e.to_string(), e))?;
+    if updated_count != body.transaction_ids.len() as u64 {
+        return Err(GenericError::ValidationError(format!(
+            "Only {} of {} orders had an open payable",
+            updated_count,
+            body.transaction_ids.len()
+        )));
+    }
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit SQL transaction to mark payables paid")
+        .map_err(|e| GenericError::DatabaseError(e.to_string(), e))?;
+    Ok(web::Json(GenericResponse::success(
+        "Successfully marked payables as paid",
+        StatusCode::OK,
+        Some(()),
+    )))
+}
@@ -0,0 +1,80 @@
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use super::schemas::{CommercePayable, CommercePayableStatus};
+use super::utils::get_payable_bucket;
+use crate::routes::order::schemas::{OrderType, SettlementBasis};
+use crate::schemas::CurrencyType;
+
+#[derive(Deserialize, Debug, FromRow)]
+pub struct CommercePayableModel {
+    pub id: Uuid,
+    pub transaction_id: Uuid,
+    pub order_no: String,
+    pub seller_id: String,
+    pub seller_name: Option<String>,
+    pub currency_code: CurrencyType,
+    pub amount: BigDecimal,
+    pub settlement_basis: Option<SettlementBasis>,
+    pub settlement_window: Option<String>,
+    pub basis_on: Option<DateTime<Utc>>,
+    pub due_on: Option<DateTime<Utc>>,
+    pub status: CommercePayableStatus,
+    pub paid_on: Option<DateTime<Utc>>,
+    pub payment_reference: Option<String>,
+    pub reminder_count: i32,
+    pub last_reminder_on: Option<DateTime<Utc>>,
+    pub created_on: DateTime<Utc>,
+}
+
+impl CommercePayableModel {
+    pub fn schema(self) -> CommercePayable {
+        let bucket = (self.status == CommercePayableStatus::Open)
+            .then(|| get_payable_bucket(self.due_on, Utc::now()));
+        CommercePayable {
+            id: self.id,
+            transaction_id: self.transaction_id,
+            order_no: self.order_no,
+            seller_id: self.seller_id,
+            seller_name: self.seller_name,
+            currency_code: self.currency_code,
+            amount: self.amount,
+            settlement_basis: self.settlement_basis,
+            settlement_window: self.settlement_window,
+            basis_on: self.basis_on,
+            due_on: self.due_on,
+            status: self.status,
+            bucket,
+            paid_on: self.paid_on,
+            payment_reference: self.payment_reference,
+            reminder_count: self.reminder_count,
+            last_reminder_on: self.last_reminder_on,
+            created_on: self.created_on,
+        }
+    }
+}
+
+#[derive(Debug, FromRow)]
+pub struct PayableReminderModel {
+    pub id: Uuid,
+    pub transaction_id: Uuid,
+    pub order_no: String,
+    pub record_type: OrderType,
+    pub buyer_id: Uuid,
+    pub created_by: Uuid,
+    pub seller_id: String,
+    pub currency_code: CurrencyType,
+    pub amount: BigDecimal,
+    pub due_on: DateTime<Utc>,
+    pub previous_reminder_on: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, FromRow)]
+pub struct OpenPayableModel {
+    pub id: Uuid,
+    pub settlement_basis: Option<SettlementBasis>,
+    pub settlement_window: Option<String>,
+}
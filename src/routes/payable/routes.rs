@@ -0,0 +1,29 @@
+use crate::middleware::{BusinessAccountValidation, BusinessPermissionValidation, RequireAuth};
+use crate::user_client::{CustomerType, PermissionType};
+use actix_web::web;
+
+use super::handlers::{payable_list, payable_mark_paid};
+pub fn payable_route(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::resource("/list")
+            .route(web::post().to(payable_list))
+            .wrap(BusinessPermissionValidation {
+                permission_list: vec![PermissionType::ReadOrder],
+            })
+            .wrap(BusinessAccountValidation {
+                business_type_list: vec![CustomerType::RetailB2bBuyer],
+            })
+            .wrap(RequireAuth),
+    );
+    cfg.service(
+        web::resource("/mark_paid")
+            .route(web::post().to(payable_mark_paid))
+            .wrap(BusinessPermissionValidation {
+                permission_list: vec![PermissionType::UpdateOrder],
+            })
+            .wrap(BusinessAccountValidation {
+                business_type_list: vec![CustomerType::RetailB2bBuyer],
+            })
+            .wrap(RequireAuth),
+    );
+}
@@ -0,0 +1,103 @@
+use crate::errors::GenericError;
+use crate::routes::order::schemas::SettlementBasis;
+use crate::schemas::CurrencyType;
+use actix_http::Payload;
+use actix_web::{web, FromRequest, HttpRequest};
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Utc};
+use futures_util::future::LocalBoxFuture;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Deserialize, Debug, ToSchema, Serialize, sqlx::Type, Clone, PartialEq)]
+#[sqlx(type_name = "commerce_payable_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum CommercePayableStatus {
+    Open,
+    Paid,
+    Cancelled,
+}
+
+#[derive(Deserialize, Debug, ToSchema, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PayableBucket {
+    Open,
+    Due,
+    Overdue,
+}
+
+#[derive(Deserialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PayableListRequest {
+    pub bucket: Option<PayableBucket>,
+    pub offset: i32,
+    pub limit: i32,
+}
+
+impl FromRequest for PayableListRequest {
+    type Error = GenericError;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let fut = web::Json::<Self>::from_request(req, payload);
+
+        Box::pin(async move {
+            match fut.await {
+                Ok(json) => Ok(json.into_inner()),
+                Err(e) => Err(GenericError::ValidationError(e.to_string())),
+            }
+        })
+    }
+}
+
+#[derive(Deserialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PayableMarkPaidRequest {
+    #[schema(value_type = Vec<String>)]
+    pub transaction_ids: Vec<Uuid>,
+    pub payment_reference: String,
+    pub paid_on: DateTime<Utc>,
+}
+
+impl FromRequest for PayableMarkPaidRequest {
+    type Error = GenericError;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let fut = web::Json::<Self>::from_request(req, payload);
+
+        Box::pin(async move {
+            match fut.await {
+                Ok(json) => Ok(json.into_inner()),
+                Err(e) => Err(GenericError::ValidationError(e.to_string())),
+            }
+        })
+    }
+}
+
+#[derive(Debug, ToSchema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommercePayable {
+    #[schema(value_type = String)]
+    pub id: Uuid,
+    #[schema(value_type = String)]
+    pub transaction_id: Uuid,
+    pub order_no: String,
+    pub seller_id: String,
+    pub seller_name: Option<String>,
+    pub currency_code: CurrencyType,
+    #[schema(value_type = f64)]
+    pub amount: BigDecimal,
+    pub settlement_basis: Option<SettlementBasis>,
+    pub settlement_window: Option<String>,
+    pub basis_on: Option<DateTime<Utc>>,
+    pub due_on: Option<DateTime<Utc>>,
+    pub status: CommercePayableStatus,
+    pub bucket: Option<PayableBucket>,
+    pub paid_on: Option<DateTime<Utc>>,
+    pub payment_reference: Option<String>,
+    pub reminder_count: i32,
+    pub last_reminder_on: Option<DateTime<Utc>>,
+    pub created_on: DateTime<Utc>,
+}
@@ -0,0 +1,72 @@
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Utc};
+    use uuid::Uuid;
+
+    use crate::{
+        routes::payable::{
+            schemas::{PayableBucket, PayableListRequest},
+            utils::{
+                claim_payable_reminders, fetch_commerce_payables, get_payable_bucket,
+                get_payable_due_on,
+            },
+        },
+        tests::tests::get_test_pool,
+    };
+
+    #[test]
+    fn test_payable_due_on() {
+        let now = Utc::now();
+        assert_eq!(get_payable_due_on(now, None), Some(now));
+        assert_eq!(
+            get_payable_due_on(now, Some("P30D")),
+            Some(now + Duration::days(30))
+        );
+        assert_eq!(
+            get_payable_due_on(now, Some("P1DT12H")),
+            Some(now + Duration::days(1) + Duration::hours(12))
+        );
+        assert_eq!(get_payable_due_on(now, Some("30D")), None);
+        assert_eq!(get_payable_due_on(now, Some("P100000000D")), None);
+    }
+
+    #[test]
+    fn test_payable_bucket() {
+        let now = Utc::now();
+        assert_eq!(get_payable_bucket(None, now), PayableBucket::Open);
+        assert_eq!(
+            get_payable_bucket(Some(now - Duration::hours(1)), now),
+            PayableBucket::Overdue
+        );
+        assert_eq!(
+            get_payable_bucket(Some(now + Duration::days(1)), now),
+            PayableBucket::Due
+        );
+        assert_eq!(
+            get_payable_bucket(Some(now + Duration::days(30)), now),
+            PayableBucket::Open
+        );
+    }
+
+    #[tokio::test]
+    async fn test_payable_sql() {
+        let pool = get_test_pool().await;
+        for bucket in [
+            None,
+            Some(PayableBucket::Open),
+            Some(PayableBucket::Due),
+            Some(PayableBucket::Overdue),
+        ] {
+            let body = PayableListRequest {
+                bucket,
+                offset: 0,
+                limit: 10,
+            };
+            let payables = fetch_commerce_payables(&pool, Uuid::new_v4(), &body).await;
+            assert!(payables.is_ok());
+        }
+        let mut transaction = pool.begin().await.unwrap();
+        let reminders = claim_payable_reminders(&mut transaction, Utc::now()).await;
+        assert!(reminders.is_ok());
+    }
+}
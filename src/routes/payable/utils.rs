@@ -0,0 +1,478 @@
+use super::models::{CommercePayableModel, OpenPayableModel, PayableReminderModel};
+use super::schemas::{
+    CommercePayableStatus, PayableBucket, PayableListRequest, PayableMarkPaidRequest,
+};
+use crate::chat_client::{
+    ChatClient, ChatMessageType, ChatParticipant, SendMessageDataDescription,
+};
+use crate::constants::{
+    PAYABLE_DUE_SOON_DAYS, PAYABLE_REMINDER_BATCH_SIZE, PAYABLE_REMINDER_GAP_HOURS,
+    PAYABLE_REMINDER_INTERVAL,
+};
+use crate::email_client::{GenericEmailService, SmtpEmailClient};
+use crate::routes::ondc::{ONDCOnConfirmRequest, ONDCPaymentType};
+use crate::routes::order::schemas::{Commerce, OrderType, SettlementBasis};
+use crate::schemas::CurrencyType;
+use crate::user_client::UserClient;
+use crate::utils::get_duration_from_iso8601;
+use actix_web::web;
+use anyhow::{anyhow, Context};
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Duration, Utc};
+use sqlx::{Executor, PgPool, Postgres, Transaction};
+use std::str::FromStr;
+use uuid::Uuid;
+
+pub struct PayableReminderContext {
+    pub pool: web::Data<PgPool>,
+    pub user_client: web::Data<UserClient>,
+    pub chat_client: web::Data<ChatClient>,
+    pub email_client: web::Data<SmtpEmailClient>,
+}
+
+impl PayableReminderContext {
+    pub fn new(
+        pool: web::Data<PgPool>,
+        user_client: web::Data<UserClient>,
+        chat_client: web::Data<ChatClient>,
+        email_client: web::Data<SmtpEmailClient>,
+    ) -> Self {
+        Self {
+            pool,
+            user_client,
+            chat_client,
+            email_client,
+        }
+    }
+}
+
+/// Adds the seller's settlement window to the basis time, `None` when the window is invalid or overflows.
+pub fn get_payable_due_on(
+    basis_on: DateTime<Utc>,
+    settlement_window: Option<&str>,
+) -> Option<DateTime<Utc>> {
+    match settlement_window {
+        Some(settlement_window) => {
+            basis_on.checked_add_signed(get_duration_from_iso8601(settlement_window)?)
+        }
+        None => Some(basis_on),
+    }
+}
+
+pub fn get_payable_bucket(due_on: Option<DateTime<Utc>>, now: DateTime<Utc>) -> PayableBucket {
+    match due_on {
+        Some(due_on) if due_on < now => PayableBucket::Overdue,
+        Some(due_on) if due_on < now + Duration::days(PAYABLE_DUE_SOON_DAYS) => PayableBucket::Due,
+        _ => PayableBucket::Open,
+    }
+}
+
+/// Returns whether undated payables are included and the `[from, to)` due window for a bucket.
+pub fn get_payable_due_filter(
+    bucket: Option<&PayableBucket>,
+    now: DateTime<Utc>,
+) -> (bool, Option<DateTime<Utc>>, Option<DateTime<Utc>>) {
+    let due_soon = now + Duration::days(PAYABLE_DUE_SOON_DAYS);
+    match bucket {
+        None => (true, None, None),
+        Some(PayableBucket::Open) => (true, Some(due_soon), None),
+        Some(PayableBucket::Due) => (false, Some(now), Some(due_soon)),
+        Some(PayableBucket::Overdue) => (false, None, Some(now)),
+    }
+}
+
+/// Shipment based windows start at confirmation, the rest start once the order is delivered.
+fn is_basis_at_confirm(settlement_basis: &SettlementBasis) -> bool {
+    matches!(settlement_basis, SettlementBasis::Shipment)
+}
+
+#[tracing::instrument(name = "save commerce payable on confirm", skip(transaction, body))]
+pub async fn save_commerce_payable_on_confirm(
+    transaction: &mut Transaction<'_, Postgres>,
+    order: &Commerce,
+    body: &ONDCOnConfirmRequest,
+    created_by: Uuid,
+) -> Result<(), anyhow::Error> {
+    let payment = match body
+        .message
+        .order
+        .payments
+        .iter()
+        .find(|payment| payment.r#type == ONDCPaymentType::PostFulfillment)
+    {
+        Some(payment) => payment,
+        None => return Ok(()),
+    };
+    let settlement_basis = payment
+        .settlement_basis
+        .get_settlement_basis_from_ondc_type();
+    let basis_on = is_basis_at_confirm(&settlement_basis).then(Utc::now);
+    let due_on = basis_on
+        .map(|basis_on| {
+            get_payable_due_on(basis_on, Some(payment.settlement_window.as_str()))
+                .ok_or_else(|| anyhow!("Invalid settlement window {}", payment.settlement_window))
+        })
+        .transpose()?;
+    let amount = match BigDecimal::from_str(&payment.params.amount) {
+        Ok(amount) => amount,
+        Err(_) => BigDecimal::from_str(&body.message.order.quote.price.value)
+            .map_err(|e| anyhow!("Invalid quote price: {}", e))?,
+    };
+    let query = sqlx::query!(
+        r#"
+        INSERT INTO commerce_payable (id, commerce_data_id, buyer_id, currency_code, amount,
+            settlement_basis, settlement_window, basis_on, due_on, status, created_by)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+        ON CONFLICT (commerce_data_id) DO NOTHING
+        "#,
+        Uuid::new_v4(),
+        order.id,
+        order.buyer_id,
+        &body.message.order.quote.price.currency as &CurrencyType,
+        amount,
+        settlement_basis as SettlementBasis,
+        payment.settlement_window,
+        basis_on,
+        due_on,
+        CommercePayableStatus::Open as CommercePayableStatus,
+        created_by
+    );
+    transaction.execute(query).await.map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e).context("A database failure occurred while saving commerce payable")
+    })?;
+    Ok(())
+}
+
+#[tracing::instrument(name = "fetch undated commerce payable", skip(transaction))]
+async fn fetch_undated_commerce_payable(
+    transaction: &mut Transaction<'_, Postgres>,
+    commerce_data_id: Uuid,
+) -> Result<Option<OpenPayableModel>, anyhow::Error> {
+    let row = sqlx::query_as!(
+        OpenPayableModel,
+        r#"SELECT id, settlement_basis as "settlement_basis?: SettlementBasis", settlement_window
+        FROM commerce_payable
+        WHERE commerce_data_id = $1 AND status = $2 AND due_on IS NULL"#,
+        commerce_data_id,
+        CommercePayableStatus::Open as CommercePayableStatus
+    )
+    .fetch_optional(&mut **transaction)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e).context("A database failure occurred while fetching commerce payable")
+    })?;
+    Ok(row)
+}
+
+#[tracing::instrument(name = "update commerce payable on delivery", skip(transaction))]
+pub async fn update_commerce_payable_on_delivery(
+    transaction: &mut Transaction<'_, Postgres>,
+    commerce_data_id: Uuid,
+    delivered_on: DateTime<Utc>,
+) -> Result<(), anyhow::Error> {
+    let payable = match fetch_undated_commerce_payable(transaction, commerce_data_id).await? {
+        Some(payable) => payable,
+        None => return Ok(()),
+    };
+    let due_on = get_payable_due_on(delivered_on, payable.settlement_window.as_deref())
+        .ok_or_else(|| anyhow!("Invalid settlement window {:?}", payable.settlement_window))?;
+    let query = sqlx::query!(
+        r#"UPDATE commerce_payable SET basis_on = $1, due_on = $2, updated_on = $3 WHERE id = $4"#,
+        delivered_on,
+        due_on,
+        Utc::now(),
+        payable.id
+    );
+    transaction.execute(query).await.map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e).context("A database failure occurred while updating commerce payable")
+    })?;
+    Ok(())
+}
+
+#[tracing::instrument(name = "close commerce payable", skip(transaction))]
+pub async fn close_commerce_payable(
+    transaction: &mut Transaction<'_, Postgres>,
+    commerce_data_id: Uuid,
+    status: CommercePayableStatus,
+    payment_reference: Option<&str>,
+) -> Result<(), anyhow::Error> {
+    let now = Utc::now();
+    let query = sqlx::query!(
+        r#"
+        UPDATE commerce_payable SET status = $1, payment_reference = $2,
+            paid_on = CASE WHEN $1 = 'paid'::commerce_payable_status THEN $3::timestamptz ELSE NULL END,
+            updated_on = $3
+        WHERE commerce_data_id = $4 AND status = $5
+        "#,
+        status as CommercePayableStatus,
+        payment_reference,
+        now,
+        commerce_data_id,
+        CommercePayableStatus::Open as CommercePayableStatus
+    );
+    transaction.execute(query).await.map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e).context("A database failure occurred while closing commerce payable")
+    })?;
+    Ok(())
+}
+
+#[tracing::instrument(name = "fetch commerce payables", skip(pool))]
+pub async fn fetch_commerce_payables(
+    pool: &PgPool,
+    buyer_id: Uuid,
+    body: &PayableListRequest,
+) -> Result<Vec<CommercePayableModel>, anyhow::Error> {
+    let (include_undated, due_from, due_to) =
+        get_payable_due_filter(body.bucket.as_ref(), Utc::now());
+    let rows = sqlx::query_as!(
+        CommercePayableModel,
+        r#"SELECT p.id, c.external_urn as transaction_id, c.urn as order_no, c.seller_id,
+        c.seller_name, p.currency_code as "currency_code: CurrencyType", p.amount,
+        p.settlement_basis as "settlement_basis?: SettlementBasis", p.settlement_window,
+        p.basis_on, p.due_on, p.status as "status: CommercePayableStatus", p.paid_on,
+        p.payment_reference, p.reminder_count, p.last_reminder_on, p.created_on
+        FROM commerce_payable AS p
+        INNER JOIN commerce_data AS c ON c.id = p.commerce_data_id
+        WHERE p.buyer_id = $1 AND p.status = $2
+        AND ((p.due_on IS NULL AND $3)
+            OR (p.due_on IS NOT NULL AND ($4::timestamptz IS NULL OR p.due_on >= $4)
+                AND ($5::timestamptz IS NULL OR p.due_on < $5)))
+        ORDER BY p.due_on ASC NULLS LAST, p.created_on DESC LIMIT $6 OFFSET $7"#,
+        buyer_id,
+        CommercePayableStatus::Open as CommercePayableStatus,
+        include_undated,
+        due_from,
+        due_to,
+        body.limit as i64,
+        body.offset as i64
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e)
+            .context("A database failure occurred while fetching commerce payables")
+    })?;
+    Ok(rows)
+}
+
+#[tracing::instrument(name = "mark commerce payables paid", skip(transaction))]
+pub async fn mark_commerce_payables_paid(
+    transaction: &mut Transaction<'_, Postgres>,
+    buyer_id: Uuid,
+    user_id: Uuid,
+    body: &PayableMarkPaidRequest,
+) -> Result<u64, anyhow::Error> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE commerce_payable AS p SET status = $1, payment_reference = $2, paid_on = $3,
+            updated_on = $4, updated_by = $5
+        FROM commerce_data AS c
+        WHERE c.id = p.commerce_data_id AND c.external_urn = ANY($6)
+            AND p.buyer_id = $7 AND p.status = $8
+        "#,
+        CommercePayableStatus::Paid as CommercePayableStatus,
+        body.payment_reference,
+        body.paid_on,
+        Utc::now(),
+        user_id,
+        &body.transaction_ids,
+        buyer_id,
+        CommercePayableStatus::Open as CommercePayableStatus
+    )
+    .execute(&mut **transaction)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e)
+            .context("A database failure occurred while marking commerce payables paid")
+    })?;
+    Ok(result.rows_affected())
+}
+
+#[tracing::instrument(name = "fetch payable reminders", skip(transaction))]
+/// Claims the payables due for a reminder by stamping `last_reminder_on`, so other workers skip them
+/// once the transaction commits, and returns them with the stamp they had before.
+pub async fn claim_payable_reminders(
+    transaction: &mut Transaction<'_, Postgres>,
+    now: DateTime<Utc>,
+) -> Result<Vec<PayableReminderModel>, anyhow::Error> {
+    let rows = sqlx::query_as!(
+        PayableReminderModel,
+        r#"WITH due AS (
+            SELECT id, last_reminder_on FROM commerce_payable
+            WHERE status = $1 AND due_on IS NOT NULL AND due_on < $2
+            AND (last_reminder_on IS NULL OR last_reminder_on < $3)
+            ORDER BY due_on ASC LIMIT $4
+            FOR UPDATE SKIP LOCKED
+        )
+        UPDATE commerce_payable AS p SET last_reminder_on = $5
+        FROM due, commerce_data AS c
+        WHERE p.id = due.id AND c.id = p.commerce_data_id
+        RETURNING p.id, c.external_urn as transaction_id, c.urn as order_no,
+        c.record_type as "record_type: OrderType", p.buyer_id, c.created_by, c.seller_id,
+        p.currency_code as "currency_code: CurrencyType", p.amount, p.due_on as "due_on!",
+        due.last_reminder_on as "previous_reminder_on?""#,
+        CommercePayableStatus::Open as CommercePayableStatus,
+        now + Duration::days(PAYABLE_DUE_SOON_DAYS),
+        now - Duration::hours(PAYABLE_REMINDER_GAP_HOURS),
+        PAYABLE_REMINDER_BATCH_SIZE,
+        now
+    )
+    .fetch_all(&mut **transaction)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e)
+            .context("A database failure occurred while claiming payable reminders")
+    })?;
+    Ok(rows)
+}
+
+#[tracing::instrument(name = "update payable reminder", skip(pool))]
+async fn update_payable_reminder(pool: &PgPool, payable_id: Uuid) -> Result<(), anyhow::Error> {
+    sqlx::query!(
+        r#"UPDATE commerce_payable SET reminder_count = reminder_count + 1, last_reminder_on = $1
+        WHERE id = $2"#,
+        Utc::now(),
+        payable_id
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e).context("A database failure occurred while updating payable reminder")
+    })?;
+    Ok(())
+}
+
+/// Restores the reminder stamp of a payable whose reminder could not be sent, so the next run retries it.
+#[tracing::instrument(name = "release payable reminder", skip(pool))]
+async fn release_payable_reminder(
+    pool: &PgPool,
+    payable_id: Uuid,
+    previous_reminder_on: Option<DateTime<Utc>>,
+) -> Result<(), anyhow::Error> {
+    sqlx::query!(
+        r#"UPDATE commerce_payable SET last_reminder_on = $1 WHERE id = $2"#,
+        previous_reminder_on,
+        payable_id
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e)
+            .context("A database failure occurred while releasing payable reminder")
+    })?;
+    Ok(())
+}
+
+fn get_payable_reminder_text(reminder: &PayableReminderModel, now: DateTime<Utc>) -> String {
+    let due_text = if reminder.due_on < now {
+        "was due"
+    } else {
+        "is due"
+    };
+    format!(
+        "Payment of {} {} for order {} {} on {}.",
+        reminder.currency_code.to_string().to_uppercase(),
+        reminder.amount,
+        reminder.order_no,
+        due_text,
+        reminder.due_on.format("%Y-%m-%d")
+    )
+}
+
+#[tracing::instrument(name = "send payable reminder", skip(context))]
+async fn send_payable_reminder(
+    context: &PayableReminderContext,
+    reminder: &PayableReminderModel,
+) -> Result<(), anyhow::Error> {
+    let text = get_payable_reminder_text(reminder, Utc::now());
+    let user_account = context
+        .user_client
+        .get_user_account(None, Some(reminder.created_by))
+        .await?;
+    if let Err(e) = context
+        .email_client
+        .send_text_email(
+            &user_account.email,
+            &format!("Payment reminder for order {}", reminder.order_no),
+            text.clone(),
+        )
+        .await
+    {
+        tracing::error!("Failed to send payable reminder email: {:?}", e);
+    }
+    if reminder.record_type.is_purchase_order() {
+        let data = context.chat_client.get_send_message_data(
+            "Payment Reminder",
+            vec![SendMessageDataDescription {
+                text,
+                r#type: ChatMessageType::Text,
+            }],
+        );
+        let sender = ChatParticipant {
+            id: reminder.seller_id.to_string(),
+            name: "NA".to_owned(),
+        };
+        context
+            .chat_client
+            .send_chat_data(reminder.transaction_id, sender, data)
+            .await?;
+    }
+    Ok(())
+}
+
+pub async fn process_payable_reminders(
+    context: &PayableReminderContext,
+) -> Result<(), anyhow::Error> {
+    let mut transaction = context
+        .pool
+        .begin()
+        .await
+        .context("Failed to acquire a Postgres connection from the pool")?;
+    let reminders = claim_payable_reminders(&mut transaction, Utc::now()).await?;
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit SQL transaction to claim payable reminders")?;
+    for reminder in reminders {
+        if let Err(e) = send_payable_reminder(context, &reminder).await {
+            tracing::error!("Failed to send payable reminder {}: {:?}", reminder.id, e);
+            if let Err(e) =
+                release_payable_reminder(&context.pool, reminder.id, reminder.previous_reminder_on)
+                    .await
+            {
+                tracing::error!(
+                    "Failed to release payable reminder {}: {:?}",
+                    reminder.id,
+                    e
+                );
+            }
+            continue;
+        }
+        if let Err(e) = update_payable_reminder(&context.pool, reminder.id).await {
+            tracing::error!("Failed to update payable reminder {}: {:?}", reminder.id, e);
+        }
+    }
+    Ok(())
+}
+
+pub fn start_payable_reminder_worker(context: PayableReminderContext) {
+    tokio::spawn(async move {
+        let mut interval =
+            tokio::time::interval(std::time::Duration::from_secs(PAYABLE_REMINDER_INTERVAL));
+        loop {
+            interval.tick().await;
+            if let Err(e) = process_payable_reminders(&context).await {
+                tracing::error!("Failed to process payable reminders: {:?}", e);
+            }
+        }
+    });
+}
@@ -1,15 +1,21 @@
 use super::schemas::{
-    CreatePaymentOrderRequest, PaymentNotificationRequest, PaymentOrderData, WSPayment,
+    CommerceRefund, CommerceRefundStatus, CreatePaymentOrderRequest, PaymentNotificationRequest,
+    PaymentOrderData, RefundAmountType, RefundInitiateRequest, RefundListRequest, WSPayment,
 };
 use super::utils::{
-    get_commerce_payments_with_lock, get_payment_order_id, get_payment_ws_params,
-    update_payment_status, validate_order_for_payment,
+    apply_payment_status, fetch_commerce_refunds, fetch_processed_refund_amount,
+    get_commerce_payments_with_lock, get_payment_amount_mismatch, get_payment_order_id,
+    get_payment_ws_params, initiate_refund, notify_payment_status,
+    save_payment_notification_quarantine, update_commerce_refund_status, update_payment_status,
+    validate_order_for_payment, validate_payment_notification_signature,
 };
 use crate::routes::order::schemas::PaymentStatus;
-use crate::routes::order::utils::fetch_minimal_commerce_data_model;
+use crate::routes::order::utils::{fetch_minimal_commerce_data_model, fetch_order_by_id};
+use crate::routes::webhook::schemas::WebhookEventType;
+use crate::routes::webhook::utils::emit_webhook_event;
 use crate::schemas::RequestMetaData;
 // use crate::routes::order::utils::update_order_update_field;
-use crate::user_client::UserClient;
+use crate::user_client::{SettingKey, UserClient};
 use crate::websocket_client::{WebSocketActionType, WebSocketClient};
 use crate::{
     errors::GenericError,
@@ -103,59 +109,260 @@ pub async fn payment_notification(
     body: PaymentNotificationRequest,
     pool: web::Data<PgPool>,
     websocket_srv: web::Data<WebSocketClient>,
+    payment_client: web::Data<PaymentClient>,
+    user_client: web::Data<UserClient>,
     meta_data: RequestMetaData,
 ) -> Result<web::Json<GenericResponse<()>>, GenericError> {
     let order = fetch_minimal_commerce_data_model(&pool, body.transaction_id)
         .await
         .map_err(|e| GenericError::DatabaseError("Failed to fetch order".to_string(), e))?;
+    let setting_data = user_client
+        .fetch_setting(
+            order.created_by,
+            order.buyer_id,
+            vec![SettingKey::PaymentServiceId],
+        )
+        .await
+        .map_err(|e| GenericError::UnexpectedCustomError(e.to_string()))?;
+    let payment_service_id = setting_data
+        .get_setting(SettingKey::PaymentServiceId)
+        .ok_or_else(|| {
+            GenericError::ValidationError("Payment Service id  is not configured".to_string())
+        })?;
+    validate_payment_notification_signature(&payment_client, &payment_service_id, &body.signature)?;
     let mut transaction = pool
         .begin()
         .await
         .context("Failed to acquire a Postgres connection from the pool")
         .map_err(|e| GenericError::UnexpectedCustomError(e.to_string()))?;
     let payment_data = get_commerce_payments_with_lock(&mut transaction, order.id)
+        .await
+        .map_err(|e| GenericError::UnexpectedCustomError(e.to_string()))?
+        .into_iter()
+        .find(|payment| payment.payment_order_id.as_deref() == Some(&body.payment_order_id))
+        .ok_or_else(|| {
+            GenericError::ValidationError(format!(
+                "Payment order {} is not found",
+                body.payment_order_id
+            ))
+        })?;
+    let mut refund_notification = None;
+    let mut payment_notification_status = None;
+    if let Some(refund_id) = &body.refund_id {
+        let refund_status = body
+            .refund_status
+            .as_ref()
+            .ok_or_else(|| GenericError::ValidationError("Refund status is missing".to_string()))?
+            .refund_status();
+        let refund =
+            update_commerce_refund_status(&mut transaction, order.id, refund_id, &refund_status)
+                .await
+                .map_err(|e| GenericError::UnexpectedCustomError(e.to_string()))?
+                .ok_or_else(|| {
+                    GenericError::ValidationError(format!("Refund {} is not found", refund_id))
+                })?;
+        if refund.status == CommerceRefundStatus::Processed {
+            let refunded_amount = fetch_processed_refund_amount(&mut transaction, order.id)
+                .await
+                .map_err(|e| GenericError::UnexpectedCustomError(e.to_string()))?;
+            if refunded_amount >= order.grand_total {
+                update_payment_status(
+                    &mut transaction,
+                    body.transaction_id,
+                    &meta_data.device_id,
+                    PaymentStatus::Refunded,
+                    &body.payment_id,
+                    &body.payment_order_id,
+                )
+                .await
+                .map_err(|e| GenericError::UnexpectedCustomError(e.to_string()))?;
+            }
+        }
+        let ws_obj = WSPayment {
+            transaction_id: body.transaction_id,
+            message: format!(
+                "Refund of {} for Order {} is: {}",
+                refund.amount, order.urn, refund.status
+            ),
+        };
+        refund_notification = Some(serde_json::to_value(ws_obj).unwrap());
+    } else if let Some(reason) =
+        get_payment_amount_mismatch(&order, &payment_data, &body.amount, &body.currency)
+    {
+        tracing::error!(
+            "Quarantining payment notification for order {}: {}",
+            order.urn,
+            reason
+        );
+        let payload = serde_json::from_slice(&body.signature.payload)?;
+        save_payment_notification_quarantine(
+            &mut transaction,
+            order.id,
+            &body.payment_order_id,
+            &body.payment_id,
+            &reason,
+            &payload,
+        )
         .await
         .map_err(|e| GenericError::UnexpectedCustomError(e.to_string()))?;
-    if payment_data.payment_status != Some(PaymentStatus::Paid) {
-        update_payment_status(
+    } else if payment_data.payment_status != Some(PaymentStatus::Paid) {
+        let payment_status = body.status.payment_status();
+        apply_payment_status(
             &mut transaction,
-            body.transaction_id,
+            &order,
+            &payment_data.payment_type,
             &meta_data.device_id,
-            body.status.payment_status(),
+            &payment_status,
             &body.payment_id,
             &body.payment_order_id,
         )
         .await
         .map_err(|e| GenericError::UnexpectedCustomError(e.to_string()))?;
-        let ws_obj = WSPayment {
-            transaction_id: body.transaction_id,
-            message: format!(
-                "Payment for Order {} is: {}",
-                order.urn,
-                body.status.payment_status()
-            ),
-        };
-        let ws_json = serde_json::to_value(ws_obj).unwrap();
-        websocket_srv
+        payment_notification_status = Some(payment_status);
+    }
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit SQL transaction to store an order")
+        .map_err(|e| GenericError::UnexpectedCustomError(e.to_string()))?;
+    if let Some(ws_json) = refund_notification {
+        emit_webhook_event(
+            &pool,
+            order.buyer_id,
+            WebhookEventType::RefundUpdated,
+            &ws_json,
+        )
+        .await;
+        let _ = websocket_srv
             .send_msg(
                 get_payment_ws_params(&order),
                 WebSocketActionType::OrderPayment,
                 ws_json,
                 None,
             )
+            .await;
+    }
+    if let Some(payment_status) = payment_notification_status {
+        notify_payment_status(&pool, &websocket_srv, &order, &payment_status)
             .await
             .map_err(|e| GenericError::UnexpectedCustomError(e.to_string()))?;
     }
 
-    transaction
-        .commit()
-        .await
-        .context("Failed to commit SQL transaction to store an order")
-        .map_err(|e| GenericError::UnexpectedCustomError(e.to_string()))?;
-
     Ok(web::Json(GenericResponse::success(
         "Successfully recieved notification",
         StatusCode::OK,
         Some(()),
     )))
 }
+
+#[utoipa::path(
+    post,
+    path = "/payment/refund/initiate",
+    tag = "Payment",
+    description="This API initiates a refund against the buyer app collected payment of an order for a cancellation or return.",
+    summary= "Payment Refund Initiate Request",
+    request_body(content = RefundInitiateRequest, description = "Request Body"),
+    responses(
+        (status=200, description= "Payment Refund Initiate Response", body= GenericResponse<CommerceRefund>),
+        (status=400, description= "Invalid Request body", body= GenericResponse<TupleUnit>),
+        (status=401, description= "Invalid Token", body= GenericResponse<TupleUnit>),
+	    (status=403, description= "Insufficient Previlege", body= GenericResponse<TupleUnit>),
+	    (status=410, description= "Data not found", body= GenericResponse<TupleUnit>),
+        (status=500, description= "Internal Server Error", body= GenericResponse<TupleUnit>),
+	    (status=501, description= "Not Implemented", body= GenericResponse<TupleUnit>),
+    )
+)]
+#[tracing::instrument(name = "payment refund initiate", skip(pool, payment_client, user_client), fields(transaction_id=body.transaction_id.to_string()))]
+pub async fn payment_refund_initiate(
+    body: RefundInitiateRequest,
+    pool: web::Data<PgPool>,
+    user_account: UserAccount,
+    allowed_permission: AllowedPermission,
+    payment_client: web::Data<PaymentClient>,
+    user_client: web::Data<UserClient>,
+) -> Result<web::Json<GenericResponse<CommerceRefund>>, GenericError> {
+    let order = fetch_order_by_id(&pool, body.transaction_id)
+        .await
+        .map_err(|e| GenericError::DatabaseError("Failed to fetch order".to_string(), e))?
+        .ok_or_else(|| {
+            GenericError::ValidationError(format!(
+                "{} is not found in datbase",
+                &body.transaction_id
+            ))
+        })?;
+    if !allowed_permission.validate_commerce_self(
+        order.created_by,
+        order.buyer_id,
+        PermissionType::UpdateOrderSelf,
+    ) {
+        return Err(GenericError::InsufficientPrevilegeError(
+            "You do not have sufficent preveliege to refund the order".to_owned(),
+        ));
+    }
+    let refund = initiate_refund(
+        &pool,
+        &payment_client,
+        &user_client,
+        &order,
+        &body.source,
+        &body.reference_id,
+        &RefundAmountType::Exact(body.amount.clone()),
+        body.reason.as_deref(),
+        user_account.id,
+    )
+    .await?;
+    Ok(web::Json(GenericResponse::success(
+        "Successfully initiated refund",
+        StatusCode::OK,
+        Some(refund.into_schema()),
+    )))
+}
+
+#[utoipa::path(
+    post,
+    path = "/payment/refund/list",
+    tag = "Payment",
+    description="This API returns the refunds of an order along with their current status.",
+    summary= "Payment Refund List Request",
+    request_body(content = RefundListRequest, description = "Request Body"),
+    responses(
+        (status=200, description= "Payment Refund List Response", body= GenericResponse<Vec<CommerceRefund>>),
+        (status=400, description= "Invalid Request body", body= GenericResponse<TupleUnit>),
+        (status=401, description= "Invalid Token", body= GenericResponse<TupleUnit>),
+	    (status=403, description= "Insufficient Previlege", body= GenericResponse<TupleUnit>),
+	    (status=410, description= "Data not found", body= GenericResponse<TupleUnit>),
+        (status=500, description= "Internal Server Error", body= GenericResponse<TupleUnit>),
+	    (status=501, description= "Not Implemented", body= GenericResponse<TupleUnit>),
+    )
+)]
+#[tracing::instrument(name = "payment refund list", skip(pool), fields(transaction_id=body.transaction_id.to_string()))]
+pub async fn payment_refund_list(
+    body: RefundListRequest,
+    pool: web::Data<PgPool>,
+    allowed_permission: AllowedPermission,
+) -> Result<web::Json<GenericResponse<Vec<CommerceRefund>>>, GenericError> {
+    let order = fetch_minimal_commerce_data_model(&pool, body.transaction_id)
+        .await
+        .map_err(|e| GenericError::DatabaseError("Failed to fetch order".to_string(), e))?;
+    if !allowed_permission.validate_commerce_self(
+        order.created_by,
+        order.buyer_id,
+        PermissionType::ReadOrderSelf,
+    ) {
+        return Err(GenericError::InsufficientPrevilegeError(
+            "You do not have sufficent preveliege to read the order".to_owned(),
+        ));
+    }
+    let refunds = fetch_commerce_refunds(&pool, order.id)
+        .await
+        .map_err(|e| GenericError::DatabaseError("Failed to fetch refunds".to_string(), e))?
+        .into_iter()
+        .map(|refund| refund.into_schema())
+        .collect();
+    Ok(web::Json(GenericResponse::success(
+        "Successfully fetched refunds",
+        StatusCode::OK,
+        Some(refunds),
+    )))
+}
@@ -2,6 +2,7 @@ mod errors;
 pub mod handlers;
 mod routes;
 pub(crate) mod schemas;
-mod utils;
+mod tests;
+pub mod utils;
 pub use routes::payment_route;
-mod models;
+pub mod models;
@@ -1,8 +1,14 @@
-use super::schemas::CommercePaymentMetaData;
+use super::schemas::{
+    CommercePaymentMetaData, CommercePaymentOrderStatus, CommerceRefund, CommerceRefundSource,
+    CommerceRefundStatus,
+};
 use crate::routes::{
     order::schemas::{PaymentCollectedBy, PaymentStatus},
     product::schemas::PaymentType,
 };
+use crate::schemas::CurrencyType;
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Utc};
 use serde::Deserialize;
 use sqlx::FromRow;
 use uuid::Uuid;
@@ -13,6 +19,7 @@ pub struct CommercePaymentMetaModel {
     pub payment_status: Option<PaymentStatus>,
     pub payment_order_id: Option<String>,
     pub collected_by: Option<PaymentCollectedBy>,
+    pub payment_amount: Option<BigDecimal>,
     pub id: Uuid,
 }
 
@@ -23,7 +30,63 @@ impl CommercePaymentMetaModel {
             payment_status: self.payment_status,
             payment_order_id: self.payment_order_id,
             collected_by: self.collected_by,
+            payment_amount: self.payment_amount,
             id: self.id,
         }
     }
 }
+
+#[derive(Deserialize, Debug, FromRow)]
+pub struct CommercePaymentOrderModel {
+    pub payment_order_id: String,
+    pub payment_link: Option<String>,
+    pub status: CommercePaymentOrderStatus,
+    pub expires_on: DateTime<Utc>,
+}
+
+#[derive(Deserialize, Debug, FromRow)]
+pub struct CommerceRefundModel {
+    pub id: Uuid,
+    pub commerce_data_id: Uuid,
+    pub payment_id: String,
+    pub source: CommerceRefundSource,
+    pub reference_id: String,
+    pub reason: Option<String>,
+    pub currency_code: CurrencyType,
+    pub amount: BigDecimal,
+    pub gateway_refund_id: Option<String>,
+    pub status: CommerceRefundStatus,
+    pub remark: Option<String>,
+    pub processed_on: Option<DateTime<Utc>>,
+    pub created_on: DateTime<Utc>,
+    pub updated_on: Option<DateTime<Utc>>,
+}
+
+impl CommerceRefundModel {
+    pub fn into_schema(self) -> CommerceRefund {
+        CommerceRefund {
+            id: self.id,
+            payment_id: self.payment_id,
+            source: self.source,
+            reference_id: self.reference_id,
+            reason: self.reason,
+            currency_code: self.currency_code,
+            amount: self.amount,
+            gateway_refund_id: self.gateway_refund_id,
+            status: self.status,
+            remark: self.remark,
+            processed_on: self.processed_on,
+            created_on: self.created_on,
+            updated_on: self.updated_on,
+        }
+    }
+}
+
+#[derive(Debug, FromRow)]
+pub struct PendingPaymentModel {
+    pub transaction_id: Uuid,
+    pub buyer_id: Uuid,
+    pub created_by: Uuid,
+    pub payment_id: Option<String>,
+    pub payment_order_id: String,
+}
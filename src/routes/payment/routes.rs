@@ -3,7 +3,9 @@ use crate::{
     user_client::CustomerType,
 };
 
-use super::handlers::{payment_notification, payment_order_creation};
+use super::handlers::{
+    payment_notification, payment_order_creation, payment_refund_initiate, payment_refund_list,
+};
 use crate::middleware::BusinessPermissionValidation;
 use crate::user_client::PermissionType;
 use actix_web::web;
@@ -28,4 +30,30 @@ pub fn payment_route(cfg: &mut web::ServiceConfig) {
                 .wrap(RequireServiceAuth),
         ),
     );
+    cfg.service(
+        web::resource("/refund/initiate").route(
+            web::post()
+                .to(payment_refund_initiate)
+                .wrap(BusinessPermissionValidation {
+                    permission_list: vec![PermissionType::UpdateOrder],
+                })
+                .wrap(BusinessAccountValidation {
+                    business_type_list: vec![CustomerType::RetailB2bBuyer],
+                })
+                .wrap(RequireAuth),
+        ),
+    );
+    cfg.service(
+        web::resource("/refund/list").route(
+            web::post()
+                .to(payment_refund_list)
+                .wrap(BusinessPermissionValidation {
+                    permission_list: vec![PermissionType::ReadOrder],
+                })
+                .wrap(BusinessAccountValidation {
+                    business_type_list: vec![CustomerType::RetailB2bBuyer],
+                })
+                .wrap(RequireAuth),
+        ),
+    );
 }
@@ -1,13 +1,16 @@
 use crate::errors::GenericError;
 
-use crate::payment_client::PaymentServiceStatusType;
+use crate::payment_client::{PaymentServiceRefundStatus, PaymentServiceStatusType};
 use crate::routes::order::schemas::PaymentCollectedBy;
 use crate::routes::order::schemas::PaymentStatus;
 use crate::routes::product::schemas::PaymentType;
+use crate::schemas::CurrencyType;
 use actix_http::Payload;
-use actix_web::web::Json;
+use actix_web::web::{Bytes, Json};
 use actix_web::FromRequest;
 use actix_web::HttpRequest;
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Utc};
 use futures_util::future::LocalBoxFuture;
 use serde::Deserialize;
 use serde::Serialize;
@@ -42,6 +45,7 @@ pub struct CommercePaymentMetaData {
     pub payment_status: Option<PaymentStatus>,
     pub payment_order_id: Option<String>,
     pub collected_by: Option<PaymentCollectedBy>,
+    pub payment_amount: Option<BigDecimal>,
 }
 
 #[derive(Serialize, Debug, ToSchema)]
@@ -49,6 +53,26 @@ pub struct CommercePaymentMetaData {
 pub struct PaymentOrderData {
     pub order_id: String,
     pub status: PaymentStatus,
+    pub payment_type: PaymentType,
+    #[schema(value_type = f64)]
+    pub amount: BigDecimal,
+    pub payment_link: Option<String>,
+    pub expires_on: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize, Debug, ToSchema, Serialize, sqlx::Type, Clone, PartialEq)]
+#[sqlx(type_name = "commerce_payment_order_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum CommercePaymentOrderStatus {
+    Active,
+    Cancelled,
+}
+
+#[derive(Debug, Default)]
+pub struct PaymentNotificationSignature {
+    pub signature: Option<String>,
+    pub timestamp: Option<i64>,
+    pub payload: Bytes,
 }
 
 #[derive(Deserialize, Debug, ToSchema)]
@@ -59,11 +83,105 @@ pub struct PaymentNotificationRequest {
     pub payment_order_id: String,
     pub payment_id: String,
     pub status: PaymentServiceStatusType,
+    #[schema(value_type = f64)]
+    pub amount: BigDecimal,
+    pub currency: CurrencyType,
+    pub refund_id: Option<String>,
+    pub refund_status: Option<PaymentServiceRefundStatus>,
+    #[serde(skip)]
+    pub signature: PaymentNotificationSignature,
 }
 impl FromRequest for PaymentNotificationRequest {
     type Error = GenericError;
     type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
 
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let get_header = |name: &str| {
+            req.headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(|value| value.to_owned())
+        };
+        let signature = get_header("X-Payment-Signature");
+        let timestamp = get_header("X-Payment-Timestamp").and_then(|value| value.parse().ok());
+        let fut = Bytes::from_request(req, payload);
+
+        Box::pin(async move {
+            let payload = fut
+                .await
+                .map_err(|e| GenericError::ValidationError(e.to_string()))?;
+            let mut body = serde_json::from_slice::<Self>(&payload)
+                .map_err(|e| GenericError::ValidationError(e.to_string()))?;
+            body.signature = PaymentNotificationSignature {
+                signature,
+                timestamp,
+                payload,
+            };
+            Ok(body)
+        })
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WSPayment {
+    #[schema(value_type = String)]
+    pub transaction_id: Uuid,
+    pub message: String,
+}
+
+#[derive(Deserialize, Debug, ToSchema, Serialize, sqlx::Type, Clone, PartialEq)]
+#[sqlx(type_name = "commerce_refund_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum CommerceRefundStatus {
+    Initiated,
+    Processed,
+    Failed,
+}
+
+impl std::fmt::Display for CommerceRefundStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            CommerceRefundStatus::Initiated => "initiated",
+            CommerceRefundStatus::Processed => "processed",
+            CommerceRefundStatus::Failed => "failed",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+#[derive(Deserialize, Debug, ToSchema, Serialize, sqlx::Type, Clone, PartialEq)]
+#[sqlx(type_name = "commerce_refund_source", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum CommerceRefundSource {
+    Cancel,
+    Return,
+}
+
+/// Amount of a refund: either an exact amount or the total that refunds of the same source should
+/// add up to, the difference being worked out while the order is locked.
+#[derive(Debug)]
+pub enum RefundAmountType {
+    Exact(BigDecimal),
+    SourceTotal(BigDecimal),
+}
+
+#[derive(Deserialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RefundInitiateRequest {
+    #[schema(value_type = String)]
+    pub transaction_id: Uuid,
+    pub source: CommerceRefundSource,
+    pub reference_id: String,
+    #[schema(value_type = f64)]
+    pub amount: BigDecimal,
+    pub reason: Option<String>,
+}
+
+impl FromRequest for RefundInitiateRequest {
+    type Error = GenericError;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
     fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
         let fut = Json::<Self>::from_request(req, payload);
 
@@ -76,10 +194,45 @@ impl FromRequest for PaymentNotificationRequest {
     }
 }
 
-#[derive(Debug, Serialize, ToSchema)]
+#[derive(Deserialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
-pub struct WSPayment {
+pub struct RefundListRequest {
     #[schema(value_type = String)]
     pub transaction_id: Uuid,
-    pub message: String,
+}
+
+impl FromRequest for RefundListRequest {
+    type Error = GenericError;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let fut = Json::<Self>::from_request(req, payload);
+
+        Box::pin(async move {
+            match fut.await {
+                Ok(json) => Ok(json.into_inner()),
+                Err(e) => Err(GenericError::ValidationError(e.to_string())),
+            }
+        })
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CommerceRefund {
+    #[schema(value_type = String)]
+    pub id: Uuid,
+    pub payment_id: String,
+    pub source: CommerceRefundSource,
+    pub reference_id: String,
+    pub reason: Option<String>,
+    pub currency_code: CurrencyType,
+    #[schema(value_type = f64)]
+    pub amount: BigDecimal,
+    pub gateway_refund_id: Option<String>,
+    pub status: CommerceRefundStatus,
+    pub remark: Option<String>,
+    pub processed_on: Option<DateTime<Utc>>,
+    pub created_on: DateTime<Utc>,
+    pub updated_on: Option<DateTime<Utc>>,
 }
@@ -0,0 +1,206 @@
+#[cfg(test)]
+mod tests {
+    use bigdecimal::BigDecimal;
+    use chrono::{Duration, Utc};
+    use std::collections::HashMap;
+    use uuid::Uuid;
+
+    use crate::{
+        routes::{
+            order::schemas::{CommercePayment, PaymentCollectedBy, PaymentStatus},
+            payment::{
+                schemas::{CommercePaymentMetaData, CommerceRefundSource},
+                utils::{
+                    fetch_commerce_refunds, fetch_payment_refunded_amounts, fetch_pending_payments,
+                    fetch_refunded_amount, get_next_payment_instalment, get_refund_payment,
+                    is_payment_order_stale, validate_refund_amount, verify_payment_signature,
+                },
+            },
+            product::schemas::PaymentType,
+            webhook::utils::get_webhook_signature,
+        },
+        tests::tests::get_test_pool,
+    };
+
+    fn get_payment_instalment(
+        payment_type: PaymentType,
+        payment_status: PaymentStatus,
+    ) -> CommercePaymentMetaData {
+        CommercePaymentMetaData {
+            id: Uuid::new_v4(),
+            payment_type,
+            payment_status: Some(payment_status),
+            payment_order_id: None,
+            collected_by: Some(PaymentCollectedBy::Bap),
+            payment_amount: Some(BigDecimal::from(500)),
+        }
+    }
+
+    #[test]
+    fn test_refund_amount_validation() {
+        let order_amount = BigDecimal::from(1000);
+        assert!(validate_refund_amount(
+            &order_amount,
+            &BigDecimal::from(0),
+            &BigDecimal::from(1000)
+        )
+        .is_ok());
+        assert!(validate_refund_amount(
+            &order_amount,
+            &BigDecimal::from(400),
+            &BigDecimal::from(700)
+        )
+        .is_err());
+        assert!(
+            validate_refund_amount(&order_amount, &BigDecimal::from(0), &BigDecimal::from(0))
+                .is_err()
+        );
+    }
+
+    fn get_paid_payment(payment_id: &str, payment_amount: i32) -> CommercePayment {
+        CommercePayment {
+            id: Uuid::new_v4(),
+            collected_by: Some(PaymentCollectedBy::Bap),
+            payment_type: PaymentType::PrePaid,
+            buyer_fee_type: None,
+            buyer_fee_amount: None,
+            settlement_basis: None,
+            settlement_window: None,
+            withholding_amount: None,
+            seller_payment_detail: None,
+            settlement_details: None,
+            payment_id: Some(payment_id.to_owned()),
+            payment_order_id: None,
+            payment_status: PaymentStatus::Paid,
+            payment_amount: Some(BigDecimal::from(payment_amount)),
+            payment_link: None,
+            payment_order_expires_on: None,
+        }
+    }
+
+    #[test]
+    fn test_refund_payment() {
+        let payments = vec![
+            get_paid_payment("pay_advance", 300),
+            get_paid_payment("pay_balance", 700),
+        ];
+        let mut refunded_amounts = HashMap::new();
+        let payment =
+            get_refund_payment(&payments, None, &refunded_amounts, &BigDecimal::from(700)).unwrap();
+        assert_eq!(payment.payment_id.as_deref(), Some("pay_balance"));
+        assert!(
+            get_refund_payment(&payments, None, &refunded_amounts, &BigDecimal::from(800)).is_err()
+        );
+        refunded_amounts.insert("pay_balance".to_owned(), BigDecimal::from(500));
+        let payment =
+            get_refund_payment(&payments, None, &refunded_amounts, &BigDecimal::from(300)).unwrap();
+        assert_eq!(payment.payment_id.as_deref(), Some("pay_advance"));
+        assert!(
+            get_refund_payment(&payments, None, &refunded_amounts, &BigDecimal::from(301)).is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_refund_sql() {
+        let pool = get_test_pool().await;
+        let refunds = fetch_commerce_refunds(&pool, Uuid::new_v4()).await;
+        assert!(refunds.is_ok());
+        assert!(refunds.unwrap().is_empty());
+        let mut transaction = pool.begin().await.unwrap();
+        let refunded_amount = fetch_refunded_amount(&mut transaction, Uuid::new_v4(), None).await;
+        assert!(refunded_amount.is_ok());
+        assert_eq!(refunded_amount.unwrap(), BigDecimal::from(0));
+        let returned_amount = fetch_refunded_amount(
+            &mut transaction,
+            Uuid::new_v4(),
+            Some(&CommerceRefundSource::Return),
+        )
+        .await;
+        assert!(returned_amount.is_ok());
+        assert_eq!(returned_amount.unwrap(), BigDecimal::from(0));
+        let payment_refunded_amounts =
+            fetch_payment_refunded_amounts(&mut transaction, Uuid::new_v4()).await;
+        assert!(payment_refunded_amounts.is_ok());
+        assert!(payment_refunded_amounts.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_pending_payment_sql() {
+        let pool = get_test_pool().await;
+        let pending_payments = fetch_pending_payments(&pool, Utc::now()).await;
+        assert!(pending_payments.is_ok());
+    }
+
+    #[test]
+    fn test_next_payment_instalment() {
+        let instalment = get_next_payment_instalment(vec![
+            get_payment_instalment(PaymentType::PrePaid, PaymentStatus::NotPaid),
+            get_payment_instalment(PaymentType::Credit, PaymentStatus::NotPaid),
+        ]);
+        assert_eq!(instalment.unwrap().payment_type, PaymentType::PrePaid);
+
+        let instalment = get_next_payment_instalment(vec![
+            get_payment_instalment(PaymentType::PrePaid, PaymentStatus::Paid),
+            get_payment_instalment(PaymentType::Credit, PaymentStatus::NotPaid),
+        ]);
+        assert_eq!(instalment.unwrap().payment_type, PaymentType::Credit);
+
+        assert!(get_next_payment_instalment(vec![get_payment_instalment(
+            PaymentType::PrePaid,
+            PaymentStatus::Paid,
+        )])
+        .is_err());
+        assert!(get_next_payment_instalment(vec![]).is_err());
+    }
+
+    #[test]
+    fn test_payment_signature_verification() {
+        let secret = "payment-webhook-secret";
+        let payload = br#"{"transactionId":"abc"}"#;
+        let timestamp = Utc::now().timestamp();
+        let signature = get_webhook_signature(secret, timestamp, payload);
+        assert!(verify_payment_signature(
+            secret, timestamp, payload, &signature
+        ));
+        assert!(!verify_payment_signature(
+            "other-secret",
+            timestamp,
+            payload,
+            &signature
+        ));
+        assert!(!verify_payment_signature(
+            secret,
+            timestamp + 1,
+            payload,
+            &signature
+        ));
+        assert!(!verify_payment_signature(
+            secret,
+            timestamp,
+            br#"{"transactionId":"xyz"}"#,
+            &signature
+        ));
+        assert!(!verify_payment_signature(
+            secret,
+            timestamp,
+            payload,
+            "sha256=invalid"
+        ));
+    }
+
+    #[test]
+    fn test_payment_order_staleness() {
+        let now = Utc::now();
+        assert!(!is_payment_order_stale(None, None, now));
+        assert!(!is_payment_order_stale(
+            Some(now + Duration::minutes(5)),
+            Some(&vec![]),
+            now
+        ));
+        assert!(is_payment_order_stale(
+            Some(now - Duration::minutes(5)),
+            None,
+            now
+        ));
+    }
+}
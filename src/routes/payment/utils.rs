@@ -1,18 +1,44 @@
 use super::errors::PaymentOrderError;
-use super::models::CommercePaymentMetaModel;
-use super::schemas::{CommercePaymentMetaData, PaymentOrderData};
+use super::models::{
+    CommercePaymentMetaModel, CommercePaymentOrderModel, CommerceRefundModel, PendingPaymentModel,
+};
+use super::schemas::{
+    CommercePaymentMetaData, CommercePaymentOrderStatus, CommerceRefundSource,
+    CommerceRefundStatus, PaymentNotificationSignature, PaymentOrderData, RefundAmountType,
+    WSPayment,
+};
 
-use crate::payment_client::PaymentClient;
+use crate::configuration::get_configuration;
+use crate::constants::{
+    PAYMENT_ORDER_EXPIRY_MINUTES, PAYMENT_RECONCILE_BATCH_SIZE, PAYMENT_RECONCILE_GRACE_MINUTES,
+    PAYMENT_RECONCILE_INTERVAL, PAYMENT_WEBHOOK_TOLERANCE_SECONDS,
+};
+use crate::errors::GenericError;
+use crate::payment_client::{PaymentClient, PaymentServiceOrderData, PaymentServicePaymentData};
 use crate::routes::order::schemas::{
-    CommerceStatusType, MinimalCommerceData, OrderType, PaymentCollectedBy, PaymentStatus,
+    Commerce, CommercePayment, CommerceStatusType, MinimalCommerceData, OrderType,
+    PaymentCollectedBy, PaymentStatus,
 };
-use crate::routes::order::utils::update_order_update_field;
+use crate::routes::order::utils::{fetch_minimal_commerce_data_model, update_order_update_field};
+use crate::routes::payable::schemas::CommercePayableStatus;
+use crate::routes::payable::utils::close_commerce_payable;
 use crate::routes::product::schemas::PaymentType;
-use crate::schemas::WebSocketParam;
+use crate::routes::webhook::schemas::WebhookEventType;
+use crate::routes::webhook::utils::emit_webhook_event;
+use crate::schemas::{CurrencyType, WebSocketParam};
+use crate::telemetry::{get_subscriber, init_subscriber};
 use crate::user_client::{BusinessAccount, SettingKey, UserAccount, UserClient};
+use crate::websocket_client::{WebSocketActionType, WebSocketClient};
+use actix_web::web;
 use anyhow::{anyhow, Context};
-use chrono::Utc;
+use bigdecimal::{BigDecimal, Zero};
+use chrono::{DateTime, Duration, Utc};
+use hmac::{Hmac, Mac};
+use serde_json::Value;
+use sha2::Sha256;
 use sqlx::{Executor, PgPool, Postgres, Transaction};
+use std::collections::HashMap;
+use std::str::FromStr;
 use uuid::Uuid;
 pub fn validate_order_for_payment(order: &MinimalCommerceData) -> Result<(), anyhow::Error> {
     match order.record_type {
@@ -30,8 +56,8 @@ pub fn validate_order_for_payment(order: &MinimalCommerceData) -> Result<(), any
 pub async fn get_commerce_payments_with_lock(
     transaction: &mut Transaction<'_, Postgres>,
     order_id: Uuid,
-) -> Result<CommercePaymentMetaModel, anyhow::Error> {
-    let record = sqlx::query_as!(
+) -> Result<Vec<CommercePaymentMetaModel>, anyhow::Error> {
+    let records = sqlx::query_as!(
         CommercePaymentMetaModel,
         r#"
         SELECT 
@@ -39,15 +65,18 @@ pub async fn get_commerce_payments_with_lock(
             collected_by as "collected_by?: PaymentCollectedBy",
             payment_type as "payment_type!: PaymentType", 
             payment_status as "payment_status!: PaymentStatus",
-            payment_order_id
+            payment_order_id,
+            payment_amount
         FROM commerce_payment_data 
         WHERE commerce_data_id = $1
         AND collected_by != $2
+        ORDER BY payment_type
+        FOR UPDATE
         "#,
         order_id,
         PaymentCollectedBy::Buyer as PaymentCollectedBy
     )
-    .fetch_one(&mut **transaction)
+    .fetch_all(&mut **transaction)
     .await
     .map_err(|e| {
         tracing::error!(
@@ -59,7 +88,20 @@ pub async fn get_commerce_payments_with_lock(
         )
     })?;
 
-    Ok(record)
+    Ok(records)
+}
+
+/// Picks the instalment to be paid next: the first buyer app collected one that is not yet paid.
+pub fn get_next_payment_instalment(
+    payments: Vec<CommercePaymentMetaData>,
+) -> Result<CommercePaymentMetaData, anyhow::Error> {
+    payments
+        .into_iter()
+        .find(|payment| {
+            payment.collected_by == Some(PaymentCollectedBy::Bap)
+                && payment.payment_status != Some(PaymentStatus::Paid)
+        })
+        .ok_or_else(|| anyhow!("No pending buyer app payment instalment for the order"))
 }
 
 fn validate_payment_order_creation(payment: &CommercePaymentMetaData) -> Result<(), anyhow::Error> {
@@ -98,12 +140,35 @@ pub async fn get_payment_order_id(
         .context("Failed to acquire a Postgres connection from the pool")
         .map_err(|e| PaymentOrderError::UnexpectedCustomError(e.to_string()))?;
 
-    let payment = get_commerce_payments_with_lock(&mut transaction, order.id)
+    let payments = get_commerce_payments_with_lock(&mut transaction, order.id)
         .await
-        .map(|op| op.into_schema())
         .map_err(|e| {
             PaymentOrderError::DatabaseError("Failed to fetch order list".to_string(), e)
         })?;
+    let payment = get_next_payment_instalment(
+        payments
+            .into_iter()
+            .map(|payment| payment.into_schema())
+            .collect(),
+    )
+    .map_err(|e| PaymentOrderError::ValidationError(e.to_string()))?;
+    let amount = payment
+        .payment_amount
+        .clone()
+        .unwrap_or_else(|| order.grand_total.clone());
+    let commerce_payment_order = match &payment.payment_order_id {
+        Some(payment_order_id) => fetch_commerce_payment_order(&mut transaction, payment_order_id)
+            .await
+            .map_err(|e| {
+                PaymentOrderError::DatabaseError("Failed to fetch payment order".to_string(), e)
+            })?,
+        None => None,
+    };
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit SQL transaction to fetch payments")
+        .map_err(|e| PaymentOrderError::UnexpectedCustomError(e.to_string()))?;
 
     let setting_data = user_client
         .fetch_setting(
@@ -117,23 +182,28 @@ pub async fn get_payment_order_id(
     let payment_service_id = setting_data
         .get_setting(SettingKey::PaymentServiceId)
         .ok_or_else(|| anyhow!("Payment Service id  is not configured"))?;
-    if let Some(payment_order_id) = payment.payment_order_id {
+    if let Some(payment_order_id) = &payment.payment_order_id {
         let payment_order = payment_client
-            .fetch_payments_by_order_id(&payment_order_id, &payment_service_id)
+            .fetch_payments_by_order_id(payment_order_id, &payment_service_id)
             .await
             .map_err(|e| PaymentOrderError::UnexpectedCustomError(e.to_string()))?;
         let payment_status = payment_client.determine_final_payment_status(payment_order.as_ref());
+        let expires_on = commerce_payment_order
+            .as_ref()
+            .map(|payment_order| payment_order.expires_on);
 
-        transaction
-            .commit()
-            .await
-            .context("Failed to commit SQL transaction to store an order")
-            .map_err(|e| PaymentOrderError::UnexpectedCustomError(e.to_string()))?;
-
-        return Ok(PaymentOrderData {
-            order_id: payment_order_id,
-            status: payment_status,
-        });
+        if payment_status != PaymentStatus::NotPaid
+            || !is_payment_order_stale(expires_on, payment_order.as_ref(), Utc::now())
+        {
+            return Ok(PaymentOrderData {
+                order_id: payment_order_id.to_owned(),
+                status: payment_status,
+                payment_type: payment.payment_type,
+                amount,
+                payment_link: commerce_payment_order.and_then(|e| e.payment_link),
+                expires_on,
+            });
+        }
     }
 
     validate_payment_order_creation(&payment)
@@ -142,17 +212,54 @@ pub async fn get_payment_order_id(
         "https://{}/payment/notification",
         &business_account.subscriber_id
     );
+    let expires_on = Utc::now() + Duration::minutes(PAYMENT_ORDER_EXPIRY_MINUTES);
     let data = payment_client.generate_order_create_request(
         order.external_urn,
-        &order.grand_total,
+        &amount,
         &payment_service_id,
         &order.currency_code,
         &webhook,
+        expires_on.timestamp(),
     );
     let payment_order = payment_client
         .create_order(data)
         .await
         .map_err(|e| PaymentOrderError::UnexpectedCustomError(e.to_string()))?;
+    let expires_on = payment_order
+        .expire_by
+        .and_then(|expire_by| DateTime::from_timestamp(expire_by, 0))
+        .unwrap_or(expires_on);
+
+    let mut transaction = pool
+        .begin()
+        .await
+        .context("Failed to acquire a Postgres connection from the pool")
+        .map_err(|e| PaymentOrderError::UnexpectedCustomError(e.to_string()))?;
+    let is_unchanged = get_commerce_payments_with_lock(&mut transaction, order.id)
+        .await
+        .map_err(|e| PaymentOrderError::DatabaseError("Failed to fetch order list".to_string(), e))?
+        .iter()
+        .any(|locked_payment| {
+            locked_payment.id == payment.id
+                && locked_payment.payment_order_id == payment.payment_order_id
+                && locked_payment.payment_status == payment.payment_status
+        });
+    if !is_unchanged {
+        if let Err(e) = payment_client
+            .cancel_order(&payment_order.id, &payment_service_id)
+            .await
+        {
+            tracing::error!("Failed to cancel superseded payment order: {:?}", e);
+        }
+        return Err(PaymentOrderError::ValidationError(
+            "Payment order is already being updated, please retry".to_string(),
+        ));
+    }
+    if let Some(payment_order_id) = &payment.payment_order_id {
+        cancel_commerce_payment_order(&mut transaction, payment_order_id)
+            .await
+            .map_err(|e| PaymentOrderError::UnexpectedCustomError(e.to_string()))?;
+    }
     update_order_update_field(
         &mut transaction,
         order.external_urn,
@@ -163,18 +270,129 @@ pub async fn get_payment_order_id(
     update_payment_order_id(&mut transaction, payment.id, &payment_order.id)
         .await
         .map_err(|e| PaymentOrderError::UnexpectedCustomError(e.to_string()))?;
+    save_commerce_payment_order(
+        &mut transaction,
+        order,
+        &payment,
+        &payment_order,
+        &amount,
+        expires_on,
+        user_account.id,
+    )
+    .await
+    .map_err(|e| PaymentOrderError::UnexpectedCustomError(e.to_string()))?;
     transaction
         .commit()
         .await
         .context("Failed to commit SQL transaction to store an order")
         .map_err(|e| PaymentOrderError::UnexpectedCustomError(e.to_string()))?;
+    if let Some(payment_order_id) = &payment.payment_order_id {
+        if let Err(e) = payment_client
+            .cancel_order(payment_order_id, &payment_service_id)
+            .await
+        {
+            tracing::error!("Failed to cancel stale payment order: {:?}", e);
+        }
+    }
 
     Ok(PaymentOrderData {
         order_id: payment_order.id,
         status: PaymentStatus::NotPaid,
+        payment_type: payment.payment_type,
+        amount,
+        payment_link: payment_order.payment_link,
+        expires_on: Some(expires_on),
     })
 }
 
+/// A payment order that is still unpaid is stale once it has expired or all its attempts failed.
+pub fn is_payment_order_stale(
+    expires_on: Option<DateTime<Utc>>,
+    payments: Option<&Vec<PaymentServicePaymentData>>,
+    now: DateTime<Utc>,
+) -> bool {
+    expires_on.is_some_and(|expires_on| expires_on <= now)
+        || payments.is_some_and(|payments| !payments.is_empty())
+}
+
+#[tracing::instrument(name = "fetch commerce payment order", skip(transaction))]
+async fn fetch_commerce_payment_order(
+    transaction: &mut Transaction<'_, Postgres>,
+    payment_order_id: &str,
+) -> Result<Option<CommercePaymentOrderModel>, anyhow::Error> {
+    let record = sqlx::query_as!(
+        CommercePaymentOrderModel,
+        r#"SELECT payment_order_id, payment_link, status as "status: CommercePaymentOrderStatus",
+        expires_on FROM commerce_payment_order WHERE payment_order_id = $1"#,
+        payment_order_id
+    )
+    .fetch_optional(&mut **transaction)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e)
+            .context("A database failure occurred while fetching commerce payment order")
+    })?;
+    Ok(record)
+}
+
+#[tracing::instrument(name = "save commerce payment order", skip(transaction))]
+async fn save_commerce_payment_order(
+    transaction: &mut Transaction<'_, Postgres>,
+    order: &MinimalCommerceData,
+    payment: &CommercePaymentMetaData,
+    payment_order: &PaymentServiceOrderData,
+    amount: &BigDecimal,
+    expires_on: DateTime<Utc>,
+    created_by: Uuid,
+) -> Result<(), anyhow::Error> {
+    let query = sqlx::query!(
+        r#"
+        INSERT INTO commerce_payment_order (id, commerce_data_id, payment_order_id, payment_type,
+            currency_code, amount, payment_link, status, expires_on, created_by)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+        "#,
+        Uuid::new_v4(),
+        order.id,
+        payment_order.id,
+        &payment.payment_type as &PaymentType,
+        &order.currency_code as &CurrencyType,
+        amount,
+        payment_order.payment_link,
+        CommercePaymentOrderStatus::Active as CommercePaymentOrderStatus,
+        expires_on,
+        created_by
+    );
+    transaction.execute(query).await.map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e)
+            .context("A database failure occurred while saving commerce payment order")
+    })?;
+    Ok(())
+}
+
+#[tracing::instrument(name = "cancel commerce payment order", skip(transaction))]
+async fn cancel_commerce_payment_order(
+    transaction: &mut Transaction<'_, Postgres>,
+    payment_order_id: &str,
+) -> Result<(), anyhow::Error> {
+    let query = sqlx::query!(
+        r#"
+        UPDATE commerce_payment_order SET status = $1, cancelled_on = $2
+        WHERE payment_order_id = $3
+        "#,
+        CommercePaymentOrderStatus::Cancelled as CommercePaymentOrderStatus,
+        Utc::now(),
+        payment_order_id
+    );
+    transaction.execute(query).await.map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e)
+            .context("A database failure occurred while cancelling commerce payment order")
+    })?;
+    Ok(())
+}
+
 #[tracing::instrument(name = "update_payment_order_id", skip(transaction))]
 async fn update_payment_order_id(
     transaction: &mut Transaction<'_, Postgres>,
@@ -277,3 +495,891 @@ pub fn get_payment_ws_params(order: &MinimalCommerceData) -> WebSocketParam {
         device_id: None,
     }
 }
+
+fn is_paid_bap_payment(payment: &CommercePayment) -> bool {
+    payment.collected_by == Some(PaymentCollectedBy::Bap)
+        && payment.payment_status == PaymentStatus::Paid
+}
+
+/// Returns the paid buyer app instalment with the largest amount, erroring when the order has
+/// nothing to refund against.
+pub fn get_refundable_payment(order: &Commerce) -> Result<&CommercePayment, anyhow::Error> {
+    if !order
+        .payments
+        .iter()
+        .any(|payment| payment.collected_by == Some(PaymentCollectedBy::Bap))
+    {
+        return Err(anyhow!("Order is not paid through buyer app"));
+    }
+    let payment = order
+        .payments
+        .iter()
+        .filter(|payment| is_paid_bap_payment(payment))
+        .max_by_key(|payment| payment.payment_amount.clone())
+        .ok_or_else(|| anyhow!("Payment is not completed for the order"))?;
+    if payment.payment_id.is_none() {
+        return Err(anyhow!("Payment id is missing for the order"));
+    }
+    Ok(payment)
+}
+
+/// A payment without an instalment amount captured the full order amount.
+fn get_captured_amount(payment: &CommercePayment, order_amount: Option<&BigDecimal>) -> BigDecimal {
+    payment
+        .payment_amount
+        .clone()
+        .or_else(|| order_amount.cloned())
+        .unwrap_or_default()
+}
+
+/// Sum of the buyer app instalments paid so far.
+pub fn get_paid_amount(order: &Commerce) -> BigDecimal {
+    order
+        .payments
+        .iter()
+        .filter(|payment| is_paid_bap_payment(payment))
+        .map(|payment| get_captured_amount(payment, order.grand_total.as_ref()))
+        .sum()
+}
+
+/// Picks the paid buyer app instalment with the largest unrefunded balance. A refund is issued
+/// against a single payment, so it is capped by what that payment captured less its prior refunds.
+pub fn get_refund_payment<'a>(
+    payments: &'a [CommercePayment],
+    order_amount: Option<&BigDecimal>,
+    refunded_amounts: &HashMap<String, BigDecimal>,
+    amount: &BigDecimal,
+) -> Result<&'a CommercePayment, anyhow::Error> {
+    let (payment, captured_amount, refunded_amount) = payments
+        .iter()
+        .filter(|payment| is_paid_bap_payment(payment))
+        .filter_map(|payment| {
+            let refunded_amount = refunded_amounts
+                .get(payment.payment_id.as_ref()?)
+                .cloned()
+                .unwrap_or_default();
+            Some((
+                payment,
+                get_captured_amount(payment, order_amount),
+                refunded_amount,
+            ))
+        })
+        .max_by_key(|(_, captured_amount, refunded_amount)| captured_amount - refunded_amount)
+        .ok_or_else(|| anyhow!("Payment is not completed for the order"))?;
+    validate_refund_amount(&captured_amount, &refunded_amount, amount)?;
+    Ok(payment)
+}
+
+pub fn validate_refund_amount(
+    order_amount: &BigDecimal,
+    refunded_amount: &BigDecimal,
+    amount: &BigDecimal,
+) -> Result<(), anyhow::Error> {
+    if amount <= &BigDecimal::zero() {
+        return Err(anyhow!("Refund amount must be greater than zero"));
+    }
+    if refunded_amount + amount > *order_amount {
+        return Err(anyhow!(
+            "Refund amount exceeds the refundable balance of {}",
+            order_amount - refunded_amount
+        ));
+    }
+    Ok(())
+}
+
+/// Locks the order row so that concurrent refunds of the same order check and save one at a time.
+#[tracing::instrument(name = "lock commerce for refund", skip(transaction))]
+async fn lock_commerce_for_refund(
+    transaction: &mut Transaction<'_, Postgres>,
+    commerce_data_id: Uuid,
+) -> Result<(), anyhow::Error> {
+    sqlx::query!(
+        r#"SELECT id FROM commerce_data WHERE id = $1 FOR UPDATE"#,
+        commerce_data_id
+    )
+    .fetch_one(&mut **transaction)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e).context("A database failure occurred while locking order for refund")
+    })?;
+    Ok(())
+}
+
+#[tracing::instrument(name = "fetch refunded amount", skip(transaction))]
+pub async fn fetch_refunded_amount(
+    transaction: &mut Transaction<'_, Postgres>,
+    commerce_data_id: Uuid,
+    source: Option<&CommerceRefundSource>,
+) -> Result<BigDecimal, anyhow::Error> {
+    let row = sqlx::query!(
+        r#"SELECT COALESCE(SUM(amount), 0) as "amount!" FROM commerce_refund
+        WHERE commerce_data_id = $1 AND status <> $2
+        AND ($3::commerce_refund_source IS NULL OR source = $3)"#,
+        commerce_data_id,
+        CommerceRefundStatus::Failed as CommerceRefundStatus,
+        source as Option<&CommerceRefundSource>
+    )
+    .fetch_one(&mut **transaction)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e).context("A database failure occurred while fetching refunded amount")
+    })?;
+    Ok(row.amount)
+}
+
+#[tracing::instrument(name = "fetch payment refunded amounts", skip(transaction))]
+pub async fn fetch_payment_refunded_amounts(
+    transaction: &mut Transaction<'_, Postgres>,
+    commerce_data_id: Uuid,
+) -> Result<HashMap<String, BigDecimal>, anyhow::Error> {
+    let rows = sqlx::query!(
+        r#"SELECT payment_id, SUM(amount) as "amount!" FROM commerce_refund
+        WHERE commerce_data_id = $1 AND status <> $2
+        GROUP BY payment_id"#,
+        commerce_data_id,
+        CommerceRefundStatus::Failed as CommerceRefundStatus
+    )
+    .fetch_all(&mut **transaction)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e)
+            .context("A database failure occurred while fetching payment refunded amounts")
+    })?;
+    Ok(rows
+        .into_iter()
+        .map(|row| (row.payment_id, row.amount))
+        .collect())
+}
+
+#[allow(clippy::too_many_arguments)]
+#[tracing::instrument(name = "save commerce refund", skip(transaction))]
+pub async fn save_commerce_refund(
+    transaction: &mut Transaction<'_, Postgres>,
+    commerce_data_id: Uuid,
+    payment_id: &str,
+    payment_order_id: Option<&str>,
+    source: &CommerceRefundSource,
+    reference_id: &str,
+    reason: Option<&str>,
+    currency_code: &CurrencyType,
+    amount: &BigDecimal,
+    created_by: Uuid,
+) -> Result<Option<Uuid>, anyhow::Error> {
+    let row = sqlx::query!(
+        r#"
+        INSERT INTO commerce_refund (id, commerce_data_id, payment_id, payment_order_id, source,
+            reference_id, reason, currency_code, amount, status, created_by)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+        ON CONFLICT (commerce_data_id, source, reference_id) DO UPDATE SET
+            amount = EXCLUDED.amount, reason = EXCLUDED.reason, status = EXCLUDED.status,
+            gateway_refund_id = NULL, remark = NULL, updated_on = $12
+        WHERE commerce_refund.status = $13
+        RETURNING id
+        "#,
+        Uuid::new_v4(),
+        commerce_data_id,
+        payment_id,
+        payment_order_id,
+        source as &CommerceRefundSource,
+        reference_id,
+        reason,
+        currency_code as &CurrencyType,
+        amount,
+        CommerceRefundStatus::Initiated as CommerceRefundStatus,
+        created_by,
+        Utc::now(),
+        CommerceRefundStatus::Failed as CommerceRefundStatus
+    )
+    .fetch_optional(&mut **transaction)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e).context("A database failure occurred while saving commerce refund")
+    })?;
+    Ok(row.map(|row| row.id))
+}
+
+#[tracing::instrument(name = "update commerce refund on initiate", skip(pool))]
+pub async fn update_commerce_refund_on_initiate(
+    pool: &PgPool,
+    refund_id: Uuid,
+    gateway_refund_id: Option<&str>,
+    status: &CommerceRefundStatus,
+    remark: Option<&str>,
+) -> Result<CommerceRefundModel, anyhow::Error> {
+    let row = sqlx::query_as!(
+        CommerceRefundModel,
+        r#"
+        UPDATE commerce_refund SET gateway_refund_id = $1, status = $2, remark = $3,
+            processed_on = CASE WHEN $2 = 'processed'::commerce_refund_status THEN $4::timestamptz ELSE NULL END,
+            updated_on = $4
+        WHERE id = $5
+        RETURNING id, commerce_data_id, payment_id, source as "source: CommerceRefundSource",
+            reference_id, reason, currency_code as "currency_code: CurrencyType", amount,
+            gateway_refund_id, status as "status: CommerceRefundStatus", remark, processed_on,
+            created_on, updated_on
+        "#,
+        gateway_refund_id,
+        status as &CommerceRefundStatus,
+        remark,
+        Utc::now(),
+        refund_id
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e).context("A database failure occurred while updating commerce refund")
+    })?;
+    Ok(row)
+}
+
+#[tracing::instrument(name = "update commerce refund status", skip(transaction))]
+pub async fn update_commerce_refund_status(
+    transaction: &mut Transaction<'_, Postgres>,
+    commerce_data_id: Uuid,
+    gateway_refund_id: &str,
+    status: &CommerceRefundStatus,
+) -> Result<Option<CommerceRefundModel>, anyhow::Error> {
+    let row = sqlx::query_as!(
+        CommerceRefundModel,
+        r#"
+        UPDATE commerce_refund SET status = $1,
+            processed_on = CASE WHEN $1 = 'processed'::commerce_refund_status THEN $2::timestamptz ELSE NULL END,
+            updated_on = $2
+        WHERE commerce_data_id = $3 AND gateway_refund_id = $4
+        RETURNING id, commerce_data_id, payment_id, source as "source: CommerceRefundSource",
+            reference_id, reason, currency_code as "currency_code: CurrencyType", amount,
+            gateway_refund_id, status as "status: CommerceRefundStatus", remark, processed_on,
+            created_on, updated_on
+        "#,
+        status as &CommerceRefundStatus,
+        Utc::now(),
+        commerce_data_id,
+        gateway_refund_id
+    )
+    .fetch_optional(&mut **transaction)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e)
+            .context("A database failure occurred while updating commerce refund status")
+    })?;
+    Ok(row)
+}
+
+#[tracing::instrument(name = "fetch processed refund amount", skip(transaction))]
+pub async fn fetch_processed_refund_amount(
+    transaction: &mut Transaction<'_, Postgres>,
+    commerce_data_id: Uuid,
+) -> Result<BigDecimal, anyhow::Error> {
+    let row = sqlx::query!(
+        r#"SELECT COALESCE(SUM(amount), 0) as "amount!" FROM commerce_refund
+        WHERE commerce_data_id = $1 AND status = $2"#,
+        commerce_data_id,
+        CommerceRefundStatus::Processed as CommerceRefundStatus
+    )
+    .fetch_one(&mut **transaction)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e)
+            .context("A database failure occurred while fetching processed refund amount")
+    })?;
+    Ok(row.amount)
+}
+
+#[tracing::instrument(name = "fetch commerce refunds", skip(pool))]
+pub async fn fetch_commerce_refunds(
+    pool: &PgPool,
+    commerce_data_id: Uuid,
+) -> Result<Vec<CommerceRefundModel>, anyhow::Error> {
+    let rows = sqlx::query_as!(
+        CommerceRefundModel,
+        r#"SELECT id, commerce_data_id, payment_id, source as "source: CommerceRefundSource",
+        reference_id, reason, currency_code as "currency_code: CurrencyType", amount,
+        gateway_refund_id, status as "status: CommerceRefundStatus", remark, processed_on,
+        created_on, updated_on
+        FROM commerce_refund WHERE commerce_data_id = $1 ORDER BY created_on DESC"#,
+        commerce_data_id
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e).context("A database failure occurred while fetching commerce refunds")
+    })?;
+    Ok(rows)
+}
+
+#[allow(clippy::too_many_arguments)]
+#[tracing::instrument(name = "initiate refund", skip(pool, payment_client, user_client, order), fields(order_no = order.urn))]
+pub async fn initiate_refund(
+    pool: &PgPool,
+    payment_client: &PaymentClient,
+    user_client: &UserClient,
+    order: &Commerce,
+    source: &CommerceRefundSource,
+    reference_id: &str,
+    amount: &RefundAmountType,
+    reason: Option<&str>,
+    created_by: Uuid,
+) -> Result<CommerceRefundModel, PaymentOrderError> {
+    get_refundable_payment(order).map_err(|e| PaymentOrderError::ValidationError(e.to_string()))?;
+    let paid_amount = get_paid_amount(order);
+    let mut transaction = pool
+        .begin()
+        .await
+        .context("Failed to acquire a Postgres connection from the pool")
+        .map_err(|e| PaymentOrderError::UnexpectedCustomError(e.to_string()))?;
+    lock_commerce_for_refund(&mut transaction, order.id)
+        .await
+        .map_err(|e| PaymentOrderError::DatabaseError("Failed to lock order".to_string(), e))?;
+    let refunded_amount = fetch_refunded_amount(&mut transaction, order.id, None)
+        .await
+        .map_err(|e| {
+            PaymentOrderError::DatabaseError("Failed to fetch refunded amount".to_string(), e)
+        })?;
+    let amount = match amount {
+        RefundAmountType::Exact(amount) => amount.clone(),
+        RefundAmountType::SourceTotal(total_amount) => {
+            let source_refunded_amount =
+                fetch_refunded_amount(&mut transaction, order.id, Some(source))
+                    .await
+                    .map_err(|e| {
+                        PaymentOrderError::DatabaseError(
+                            "Failed to fetch refunded amount".to_string(),
+                            e,
+                        )
+                    })?;
+            total_amount - source_refunded_amount
+        }
+    };
+    let amount = &amount;
+    validate_refund_amount(&paid_amount, &refunded_amount, amount)
+        .map_err(|e| PaymentOrderError::ValidationError(e.to_string()))?;
+    let payment_refunded_amounts = fetch_payment_refunded_amounts(&mut transaction, order.id)
+        .await
+        .map_err(|e| {
+            PaymentOrderError::DatabaseError("Failed to fetch refunded amount".to_string(), e)
+        })?;
+    let payment = get_refund_payment(
+        &order.payments,
+        order.grand_total.as_ref(),
+        &payment_refunded_amounts,
+        amount,
+    )
+    .map_err(|e| PaymentOrderError::ValidationError(e.to_string()))?;
+    let payment_id = payment.payment_id.as_deref().unwrap_or_default();
+    let currency_code = order.currency_type.clone().unwrap_or(CurrencyType::Inr);
+    let refund_id = save_commerce_refund(
+        &mut transaction,
+        order.id,
+        payment_id,
+        payment.payment_order_id.as_deref(),
+        source,
+        reference_id,
+        reason,
+        &currency_code,
+        amount,
+        created_by,
+    )
+    .await
+    .map_err(|e| PaymentOrderError::DatabaseError("Failed to save refund".to_string(), e))?
+    .ok_or_else(|| {
+        PaymentOrderError::ValidationError(format!(
+            "Refund is already initiated for {}",
+            reference_id
+        ))
+    })?;
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit SQL transaction to save a refund")
+        .map_err(|e| PaymentOrderError::UnexpectedCustomError(e.to_string()))?;
+
+    let setting_data = user_client
+        .fetch_setting(
+            order.created_by,
+            order.buyer_id,
+            vec![SettingKey::PaymentServiceId],
+        )
+        .await
+        .map_err(|e| PaymentOrderError::UnexpectedCustomError(e.to_string()))?;
+    let payment_service_id = setting_data
+        .get_setting(SettingKey::PaymentServiceId)
+        .ok_or_else(|| anyhow!("Payment Service id  is not configured"))?;
+    let webhook = format!("https://{}/payment/notification", &order.bap.id);
+    let data = payment_client.generate_refund_create_request(
+        refund_id,
+        payment_id,
+        amount,
+        &payment_service_id,
+        &currency_code,
+        reason,
+        &webhook,
+    );
+    let (gateway_refund_id, status, remark) = match payment_client.create_refund(data).await {
+        Ok(refund) => (Some(refund.id), refund.status.refund_status(), None),
+        Err(e) => {
+            tracing::error!("Failed to initiate refund: {:?}", e);
+            (None, CommerceRefundStatus::Failed, Some(e.to_string()))
+        }
+    };
+    let refund = update_commerce_refund_on_initiate(
+        pool,
+        refund_id,
+        gateway_refund_id.as_deref(),
+        &status,
+        remark.as_deref(),
+    )
+    .await
+    .map_err(|e| PaymentOrderError::DatabaseError("Failed to update refund".to_string(), e))?;
+    Ok(refund)
+}
+
+pub fn get_cancel_refund_amount(order: &Commerce, cancelled_quote_value: &str) -> BigDecimal {
+    let paid_amount = get_paid_amount(order);
+    let retained_amount = BigDecimal::from_str(cancelled_quote_value).unwrap_or_default();
+    if retained_amount >= paid_amount {
+        return BigDecimal::zero();
+    }
+    paid_amount - retained_amount
+}
+
+/// Total to be refunded for returned items: whatever has been paid above the seller's updated
+/// quote, capped by the drop of that quote below the order total.
+pub fn get_return_refund_amount(order: &Commerce, updated_quote_value: &str) -> BigDecimal {
+    let order_amount = order.grand_total.clone().unwrap_or_default();
+    let updated_amount = BigDecimal::from_str(updated_quote_value).unwrap_or(order_amount.clone());
+    let paid_amount = get_paid_amount(order);
+    if updated_amount >= order_amount || updated_amount >= paid_amount {
+        return BigDecimal::zero();
+    }
+    (paid_amount - &updated_amount).min(order_amount - updated_amount)
+}
+
+/// Applies a final payment status to an order instalment and closes the credit payable once the
+/// credit instalment is paid.
+#[allow(clippy::too_many_arguments)]
+#[tracing::instrument(name = "apply payment status", skip(transaction))]
+pub async fn apply_payment_status(
+    transaction: &mut Transaction<'_, Postgres>,
+    order: &MinimalCommerceData,
+    payment_type: &PaymentType,
+    updated_by: &str,
+    payment_status: &PaymentStatus,
+    payment_id: &str,
+    payment_order_id: &str,
+) -> Result<(), anyhow::Error> {
+    update_payment_status(
+        transaction,
+        order.external_urn,
+        updated_by,
+        payment_status.clone(),
+        payment_id,
+        payment_order_id,
+    )
+    .await?;
+    if payment_status == &PaymentStatus::Paid && payment_type == &PaymentType::Credit {
+        close_commerce_payable(
+            transaction,
+            order.id,
+            CommercePayableStatus::Paid,
+            Some(payment_id),
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+pub fn verify_payment_signature(
+    secret: &str,
+    timestamp: i64,
+    payload: &[u8],
+    signature: &str,
+) -> bool {
+    let signature = match signature
+        .strip_prefix("sha256=")
+        .and_then(|signature| hex::decode(signature).ok())
+    {
+        Some(signature) => signature,
+        None => return false,
+    };
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(payload);
+    mac.verify_slice(&signature).is_ok()
+}
+
+/// Verifies the payment gateway signature of a notification with the secret of the buyer's
+/// payment service.
+pub fn validate_payment_notification_signature(
+    payment_client: &PaymentClient,
+    payment_service_id: &str,
+    notification: &PaymentNotificationSignature,
+) -> Result<(), GenericError> {
+    let secret = payment_client
+        .get_webhook_secret(payment_service_id)
+        .ok_or_else(|| {
+            tracing::error!(
+                "Webhook secret is not configured for payment service {}",
+                payment_service_id
+            );
+            GenericError::InvalidSignature("Payment signature cannot be verified".to_string())
+        })?;
+    let (signature, timestamp) = match (&notification.signature, notification.timestamp) {
+        (Some(signature), Some(timestamp)) => (signature, timestamp),
+        _ => {
+            return Err(GenericError::InvalidSignature(
+                "Payment signature is missing".to_string(),
+            ))
+        }
+    };
+    if (Utc::now().timestamp() - timestamp).abs() > PAYMENT_WEBHOOK_TOLERANCE_SECONDS {
+        return Err(GenericError::InvalidSignature(
+            "Payment signature has expired".to_string(),
+        ));
+    }
+    if !verify_payment_signature(secret, timestamp, &notification.payload, signature) {
+        return Err(GenericError::InvalidSignature(
+            "Invalid payment signature".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Returns the reason when the notified amount or currency differs from the instalment due.
+pub fn get_payment_amount_mismatch(
+    order: &MinimalCommerceData,
+    payment: &CommercePaymentMetaModel,
+    amount: &BigDecimal,
+    currency: &CurrencyType,
+) -> Option<String> {
+    let expected_amount = payment
+        .payment_amount
+        .as_ref()
+        .unwrap_or(&order.grand_total);
+    if currency != &order.currency_code {
+        return Some(format!(
+            "Currency mismatch: expected {}, received {}",
+            order.currency_code, currency
+        ));
+    }
+    if amount != expected_amount {
+        return Some(format!(
+            "Amount mismatch: expected {}, received {}",
+            expected_amount, amount
+        ));
+    }
+    None
+}
+
+#[tracing::instrument(
+    name = "save payment notification quarantine",
+    skip(transaction, payload)
+)]
+pub async fn save_payment_notification_quarantine(
+    transaction: &mut Transaction<'_, Postgres>,
+    commerce_data_id: Uuid,
+    payment_order_id: &str,
+    payment_id: &str,
+    reason: &str,
+    payload: &Value,
+) -> Result<(), anyhow::Error> {
+    let query = sqlx::query!(
+        r#"
+        INSERT INTO payment_notification_quarantine (id, commerce_data_id, payment_order_id,
+            payment_id, reason, payload)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        "#,
+        Uuid::new_v4(),
+        commerce_data_id,
+        payment_order_id,
+        payment_id,
+        reason,
+        payload
+    );
+    transaction.execute(query).await.map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e)
+            .context("A database failure occurred while quarantining payment notification")
+    })?;
+    Ok(())
+}
+
+#[tracing::instrument(name = "notify payment status", skip(pool, websocket_srv))]
+pub async fn notify_payment_status(
+    pool: &PgPool,
+    websocket_srv: &WebSocketClient,
+    order: &MinimalCommerceData,
+    payment_status: &PaymentStatus,
+) -> Result<(), anyhow::Error> {
+    let ws_obj = WSPayment {
+        transaction_id: order.external_urn,
+        message: format!("Payment for Order {} is: {}", order.urn, payment_status),
+    };
+    let ws_json = serde_json::to_value(ws_obj)?;
+    emit_webhook_event(
+        pool,
+        order.buyer_id,
+        WebhookEventType::PaymentUpdated,
+        &ws_json,
+    )
+    .await;
+    websocket_srv
+        .send_msg(
+            get_payment_ws_params(order),
+            WebSocketActionType::OrderPayment,
+            ws_json,
+            None,
+        )
+        .await?;
+    Ok(())
+}
+
+pub struct PaymentReconcileContext {
+    pub pool: web::Data<PgPool>,
+    pub payment_client: web::Data<PaymentClient>,
+    pub user_client: web::Data<UserClient>,
+    pub websocket_client: web::Data<WebSocketClient>,
+}
+
+impl PaymentReconcileContext {
+    pub fn new(
+        pool: web::Data<PgPool>,
+        payment_client: web::Data<PaymentClient>,
+        user_client: web::Data<UserClient>,
+        websocket_client: web::Data<WebSocketClient>,
+    ) -> Self {
+        Self {
+            pool,
+            payment_client,
+            user_client,
+            websocket_client,
+        }
+    }
+}
+
+/// Payments quarantined for an amount or currency mismatch are left out until they are reviewed.
+#[tracing::instrument(name = "fetch pending payments", skip(pool))]
+pub async fn fetch_pending_payments(
+    pool: &PgPool,
+    updated_before: DateTime<Utc>,
+) -> Result<Vec<PendingPaymentModel>, anyhow::Error> {
+    let rows = sqlx::query_as!(
+        PendingPaymentModel,
+        r#"SELECT c.external_urn as transaction_id, c.buyer_id, c.created_by, p.payment_id,
+        p.payment_order_id as "payment_order_id!"
+        FROM commerce_payment_data AS p
+        INNER JOIN commerce_data AS c ON c.id = p.commerce_data_id
+        WHERE p.payment_status = $1 AND p.payment_order_id IS NOT NULL AND c.is_deleted = false
+        AND COALESCE(c.updated_on, c.created_on) < $2
+        AND NOT EXISTS (
+            SELECT 1 FROM payment_notification_quarantine AS q
+            WHERE q.commerce_data_id = p.commerce_data_id AND q.payment_order_id = p.payment_order_id
+        )
+        ORDER BY p.last_reconciled_on ASC NULLS FIRST, COALESCE(c.updated_on, c.created_on) ASC
+        LIMIT $3"#,
+        PaymentStatus::Pending as PaymentStatus,
+        updated_before,
+        PAYMENT_RECONCILE_BATCH_SIZE
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e).context("A database failure occurred while fetching pending payments")
+    })?;
+    Ok(rows)
+}
+
+#[tracing::instrument(name = "update payment reconcile attempt", skip(pool))]
+async fn update_payment_reconcile_attempt(
+    pool: &PgPool,
+    payment_order_id: &str,
+) -> Result<(), anyhow::Error> {
+    sqlx::query!(
+        r#"UPDATE commerce_payment_data SET last_reconciled_on = $1 WHERE payment_order_id = $2"#,
+        Utc::now(),
+        payment_order_id
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e)
+            .context("A database failure occurred while updating payment reconcile attempt")
+    })?;
+    Ok(())
+}
+
+#[tracing::instrument(name = "reconcile pending payment", skip(context))]
+async fn reconcile_pending_payment(
+    context: &PaymentReconcileContext,
+    pending_payment: &PendingPaymentModel,
+) -> Result<Option<PaymentStatus>, anyhow::Error> {
+    let setting_data = context
+        .user_client
+        .fetch_setting(
+            pending_payment.created_by,
+            pending_payment.buyer_id,
+            vec![SettingKey::PaymentServiceId],
+        )
+        .await?;
+    let payment_service_id = setting_data
+        .get_setting(SettingKey::PaymentServiceId)
+        .ok_or_else(|| anyhow!("Payment Service id  is not configured"))?;
+    let payment_order = context
+        .payment_client
+        .fetch_payments_by_order_id(&pending_payment.payment_order_id, &payment_service_id)
+        .await?;
+    let payment_status = context
+        .payment_client
+        .determine_final_payment_status(payment_order.as_ref());
+    if payment_status == PaymentStatus::Pending {
+        return Ok(None);
+    }
+    let payment_obj = context
+        .payment_client
+        .get_payment_obj(payment_order.as_ref(), payment_status.clone());
+    let payment_id = payment_obj
+        .map(|payment| payment.id.clone())
+        .or_else(|| pending_payment.payment_id.clone())
+        .unwrap_or_default();
+
+    let order =
+        fetch_minimal_commerce_data_model(&context.pool, pending_payment.transaction_id).await?;
+    let mut transaction = context
+        .pool
+        .begin()
+        .await
+        .context("Failed to acquire a Postgres connection from the pool")?;
+    let payment_data = get_commerce_payments_with_lock(&mut transaction, order.id)
+        .await?
+        .into_iter()
+        .find(|payment| {
+            payment.payment_order_id.as_deref() == Some(&pending_payment.payment_order_id)
+        });
+    let payment_data = match payment_data {
+        Some(payment_data) if payment_data.payment_status == Some(PaymentStatus::Pending) => {
+            payment_data
+        }
+        _ => return Ok(None),
+    };
+    if let Some((payment, reason)) = payment_obj.and_then(|payment| {
+        get_payment_amount_mismatch(&order, &payment_data, &payment.amount, &payment.currency)
+            .map(|reason| (payment, reason))
+    }) {
+        tracing::error!(
+            "Quarantining payment reconciliation for order {}: {}",
+            order.urn,
+            reason
+        );
+        save_payment_notification_quarantine(
+            &mut transaction,
+            order.id,
+            &pending_payment.payment_order_id,
+            &payment_id,
+            &reason,
+            &serde_json::to_value(payment)?,
+        )
+        .await?;
+        transaction
+            .commit()
+            .await
+            .context("Failed to commit SQL transaction to quarantine a payment")?;
+        return Ok(None);
+    }
+    apply_payment_status(
+        &mut transaction,
+        &order,
+        &payment_data.payment_type,
+        "payment_reconciler",
+        &payment_status,
+        &payment_id,
+        &pending_payment.payment_order_id,
+    )
+    .await?;
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit SQL transaction to reconcile a payment")?;
+    if let Err(e) = notify_payment_status(
+        &context.pool,
+        &context.websocket_client,
+        &order,
+        &payment_status,
+    )
+    .await
+    {
+        tracing::error!("Failed to notify reconciled payment status: {:?}", e);
+    }
+    Ok(Some(payment_status))
+}
+
+/// Settles payments left in `pending` when the payment service notification never arrived.
+pub async fn process_pending_payments(
+    context: &PaymentReconcileContext,
+) -> Result<usize, anyhow::Error> {
+    let updated_before = Utc::now() - Duration::minutes(PAYMENT_RECONCILE_GRACE_MINUTES);
+    let pending_payments = fetch_pending_payments(&context.pool, updated_before).await?;
+    let mut reconciled_count = 0;
+    for pending_payment in pending_payments {
+        if let Err(e) =
+            update_payment_reconcile_attempt(&context.pool, &pending_payment.payment_order_id).await
+        {
+            tracing::error!("Failed to record payment reconcile attempt: {:?}", e);
+        }
+        match reconcile_pending_payment(context, &pending_payment).await {
+            Ok(Some(_)) => reconciled_count += 1,
+            Ok(None) => {}
+            Err(e) => tracing::error!(
+                "Failed to reconcile payment for order {}: {:?}",
+                pending_payment.transaction_id,
+                e
+            ),
+        }
+    }
+    Ok(reconciled_count)
+}
+
+pub fn start_payment_reconciler(context: PaymentReconcileContext) {
+    tokio::spawn(async move {
+        let mut interval =
+            tokio::time::interval(std::time::Duration::from_secs(PAYMENT_RECONCILE_INTERVAL));
+        loop {
+            interval.tick().await;
+            if let Err(e) = process_pending_payments(&context).await {
+                tracing::error!("Failed to reconcile pending payments: {:?}", e);
+            }
+        }
+    });
+}
+
+pub async fn reconcile_pending_payments_command() -> Result<(), anyhow::Error> {
+    let subscriber = get_subscriber(
+        "ondc-retail-b2b-buyer".into(),
+        "info".into(),
+        std::io::stdout,
+    );
+    init_subscriber(subscriber);
+    let configuration = get_configuration().expect("Failed to read configuration.");
+    let connection_pool = PgPool::connect_with(configuration.database.with_db())
+        .await
+        .expect("Failed to connect to Postgres.");
+    let context = PaymentReconcileContext::new(
+        web::Data::new(connection_pool),
+        web::Data::new(configuration.payment.client()),
+        web::Data::new(configuration.user_obj.client()),
+        web::Data::new(configuration.websocket.client()),
+    );
+    let reconciled_count = process_pending_payments(&context).await?;
+    tracing::info!("Reconciled {} pending payments", reconciled_count);
+    Ok(())
+}
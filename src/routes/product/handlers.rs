@@ -3,18 +3,22 @@ use actix_web::{web, HttpResponse};
 use utoipa::TupleUnit;
 // use anyhow::Context;
 use super::schemas::{
-    AutoCompleteItemRequest, AutoCompleteItemResponseData, ItemCacheResponseData,
+    AutoCompleteItemRequest, AutoCompleteItemResponseData, ItemCacheResponseData, ItemFetchRequest,
+    ItemFetchResponseData, ItemPriceHistory, ItemPriceHistoryRequest, LocationServicability,
     NetworkParticipantListReq, NetworkParticipantListResponse, ProductCacheSearchRequest,
-    ProductSearchRequest, ProviderFetchReq, ProviderListResponse,
+    ProductSearchRequest, ProviderFetchReq, ProviderListResponse, ServicabilityCheckRequest,
 };
 use super::utils::{
-    get_auto_complete_product_data, get_full_item_data_from_es, get_network_participant_from_es,
-    get_provider_from_es, insert_subscribed_search_location, save_search_request,
+    get_auto_complete_product_data, get_full_item_data, get_item_detail_from_es,
+    get_item_price_change, get_item_price_history, get_location_servicability,
+    get_network_participant_from_es, get_provider_from_es, insert_subscribed_search_location,
+    save_search_request,
 };
-use crate::configuration::ONDCConfig;
-use crate::elastic_search_client::ElasticSearchClient;
+use crate::configuration::{ONDCConfig, SearchConfig};
+use crate::elastic_search_client::{ElasticSearchClient, SearchRankingProfile};
 use crate::errors::GenericError;
 use crate::kafka_client::KafkaClient;
+use crate::routes::favourite::utils::fetch_search_supplier_preference;
 use crate::routes::ondc::utils::{get_ondc_search_payload, send_ondc_payload};
 use crate::routes::ondc::ONDCActionType;
 use crate::user_client::{BusinessAccount, UserAccount};
@@ -192,10 +196,14 @@ pub async fn cached_provider_list(
 pub async fn product_autocomplete(
     body: AutoCompleteItemRequest,
     es_client: web::Data<ElasticSearchClient>,
+    pool: web::Data<PgPool>,
+    search_config: web::Data<SearchConfig>,
+    ranking_profile: web::Data<SearchRankingProfile>,
 ) -> Result<web::Json<GenericResponse<AutoCompleteItemResponseData>>, GenericError> {
-    let data = get_auto_complete_product_data(&es_client, &body)
-        .await
-        .map_err(GenericError::UnexpectedError)?;
+    let data =
+        get_auto_complete_product_data(&es_client, &pool, &search_config, &ranking_profile, &body)
+            .await
+            .map_err(GenericError::UnexpectedError)?;
 
     Ok(web::Json(GenericResponse::success(
         "Successfully Fetched autocomplete data",
@@ -225,13 +233,140 @@ pub async fn product_autocomplete(
 pub async fn cached_product_req(
     body: ProductCacheSearchRequest,
     es_client: web::Data<ElasticSearchClient>,
+    pool: web::Data<PgPool>,
+    search_config: web::Data<SearchConfig>,
+    ranking_profile: web::Data<SearchRankingProfile>,
+    business_account: BusinessAccount,
 ) -> Result<web::Json<GenericResponse<ItemCacheResponseData>>, GenericError> {
-    let data = get_full_item_data_from_es(&es_client, &body)
-        .await
-        .map_err(GenericError::UnexpectedError)?;
+    let supplier_preference =
+        fetch_search_supplier_preference(&pool, business_account.id, body.supplier_filter.as_ref())
+            .await
+            .map_err(|e| {
+                GenericError::DatabaseError("Failed to fetch supplier preferences".to_string(), e)
+            })?;
+    let data = get_full_item_data(
+        &es_client,
+        &pool,
+        &search_config,
+        &ranking_profile,
+        &body,
+        &supplier_preference,
+    )
+    .await
+    .map_err(GenericError::UnexpectedError)?;
     Ok(web::Json(GenericResponse::success(
         "Successfully Fetched Product Detail",
         StatusCode::OK,
         data,
     )))
 }
+
+#[utoipa::path(
+    post,
+    path = "/product/item/fetch",
+    tag = "Product",
+    description="This API fetches a single cached item with its provider, locations, variant and servicability.",
+    summary= "Item Fetch API",
+    request_body(content = ItemFetchRequest, description = "Request Body"),
+    responses(
+        (status=200, description= "Item Fetch Response", body= GenericResponse<ItemFetchResponseData>),
+        (status=400, description= "Invalid Request body", body= GenericResponse<TupleUnit>),
+        (status=401, description= "Invalid Token", body= GenericResponse<TupleUnit>),
+	    (status=403, description= "Insufficient Previlege", body= GenericResponse<TupleUnit>),
+	    (status=410, description= "Data not found", body= GenericResponse<TupleUnit>),
+        (status=500, description= "Internal Server Error", body= GenericResponse<TupleUnit>),
+	    (status=501, description= "Not Implemented", body= GenericResponse<TupleUnit>)
+    )
+)]
+#[tracing::instrument(name = "Item Fetch API", skip(es_client, pool), fields())]
+pub async fn item_fetch(
+    body: ItemFetchRequest,
+    es_client: web::Data<ElasticSearchClient>,
+    pool: web::Data<PgPool>,
+    business_account: BusinessAccount,
+) -> Result<web::Json<GenericResponse<ItemFetchResponseData>>, GenericError> {
+    if body.id.is_none()
+        && (body.bpp_id.is_none() || body.provider_id.is_none() || body.item_id.is_none())
+    {
+        return Err(GenericError::ValidationError(
+            "Either id or bppId, providerId and itemId are required".to_string(),
+        ));
+    }
+    let mut data = get_item_detail_from_es(&es_client, &body)
+        .await
+        .map_err(GenericError::UnexpectedError)?
+        .ok_or_else(|| GenericError::DataNotFound("Item not found".to_string()))?;
+    data.price_change = get_item_price_change(&pool, business_account.id, &data)
+        .await
+        .map_err(|e| GenericError::DatabaseError(e.to_string(), e))?;
+    Ok(web::Json(GenericResponse::success(
+        "Successfully Fetched Item Detail",
+        StatusCode::OK,
+        Some(data),
+    )))
+}
+
+#[utoipa::path(
+    post,
+    path = "/product/servicability/check",
+    tag = "Product",
+    description="This API checks which cached provider locations can service the drop-off location and by which rule.",
+    summary= "Servicability Check API",
+    request_body(content = ServicabilityCheckRequest, description = "Request Body"),
+    responses(
+        (status=200, description= "Servicability Check Response", body= GenericResponse<Vec<LocationServicability>>),
+        (status=400, description= "Invalid Request body", body= GenericResponse<TupleUnit>),
+        (status=401, description= "Invalid Token", body= GenericResponse<TupleUnit>),
+	    (status=403, description= "Insufficient Previlege", body= GenericResponse<TupleUnit>),
+	    (status=410, description= "Data not found", body= GenericResponse<TupleUnit>),
+        (status=500, description= "Internal Server Error", body= GenericResponse<TupleUnit>),
+	    (status=501, description= "Not Implemented", body= GenericResponse<TupleUnit>)
+    )
+)]
+#[tracing::instrument(name = "Servicability Check API", skip(pool), fields())]
+pub async fn servicability_check(
+    body: ServicabilityCheckRequest,
+    pool: web::Data<PgPool>,
+) -> Result<web::Json<GenericResponse<Vec<LocationServicability>>>, GenericError> {
+    let data = get_location_servicability(&pool, &body)
+        .await
+        .map_err(|e| GenericError::DatabaseError(e.to_string(), e))?;
+    Ok(web::Json(GenericResponse::success(
+        "Successfully checked servicability",
+        StatusCode::OK,
+        Some(data),
+    )))
+}
+
+#[utoipa::path(
+    post,
+    path = "/product/item/price/history",
+    tag = "Product",
+    description="This API lists the recorded price and availability changes of a cached item, latest first.",
+    summary= "Item Price History API",
+    request_body(content = ItemPriceHistoryRequest, description = "Request Body"),
+    responses(
+        (status=200, description= "Item Price History Response", body= GenericResponse<Vec<ItemPriceHistory>>),
+        (status=400, description= "Invalid Request body", body= GenericResponse<TupleUnit>),
+        (status=401, description= "Invalid Token", body= GenericResponse<TupleUnit>),
+	    (status=403, description= "Insufficient Previlege", body= GenericResponse<TupleUnit>),
+	    (status=410, description= "Data not found", body= GenericResponse<TupleUnit>),
+        (status=500, description= "Internal Server Error", body= GenericResponse<TupleUnit>),
+	    (status=501, description= "Not Implemented", body= GenericResponse<TupleUnit>)
+    )
+)]
+#[tracing::instrument(name = "Item Price History API", skip(pool), fields())]
+pub async fn item_price_history(
+    body: ItemPriceHistoryRequest,
+    pool: web::Data<PgPool>,
+) -> Result<web::Json<GenericResponse<Vec<ItemPriceHistory>>>, GenericError> {
+    body.validate()?;
+    let data = get_item_price_history(&pool, &body)
+        .await
+        .map_err(|e| GenericError::DatabaseError(e.to_string(), e))?;
+    Ok(web::Json(GenericResponse::success(
+        "Successfully fetched item price history",
+        StatusCode::OK,
+        Some(data),
+    )))
+}
@@ -1,11 +1,13 @@
 use super::schemas::{
-    AutoCompleteItem, CategoryDomain, CredentialType, PaymentType, WSCreatorContactData,
+    AutoCompleteItem, CategoryDomain, CredentialType, ItemPriceHistory, ItemSearchFacets,
+    PaymentType, ServicabilityRule, ServicabilityRuleType, WSCreatorContactData,
     WSItemCancellation, WSItemCancellationFee, WSItemCancellationTerm, WSItemReplacementTerm,
     WSItemReturnLocation, WSItemReturnTerm, WSItemReturnTime, WSItemValidity, WSProductCategory,
     WSProductCreator, WSSearchBPP, WSSearchCity, WSSearchCountry, WSSearchItemAttribute,
     WSSearchItemQty, WSSearchItemQtyMeasure, WSSearchItemQuantity, WSSearchProviderContact,
     WSSearchProviderCredential, WSSearchProviderDescription, WSSearchProviderID,
     WSSearchProviderLocation, WSSearchProviderTerms, WSSearchState, WSSearchVariant,
+    WSServicabilityData,
 };
 use crate::{
     routes::{ondc::ONDCItemUOM, order::schemas::FulfillmentStatusType},
@@ -367,6 +369,15 @@ pub struct ESHyperlocalServicabilityModel {
     pub network_participant_cache_id: Uuid,
 }
 
+impl ESHyperlocalServicabilityModel {
+    pub fn get_schema(self) -> WSServicabilityData<f64> {
+        WSServicabilityData {
+            category_code: self.category_code,
+            value: self.radius,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, FromRow)]
 #[serde(rename_all = "snake_case")]
 pub struct ESCountryServicabilityModel {
@@ -380,6 +391,15 @@ pub struct ESCountryServicabilityModel {
     pub network_participant_cache_id: Uuid,
 }
 
+impl ESCountryServicabilityModel {
+    pub fn get_schema(self) -> WSServicabilityData<CountryCode> {
+        WSServicabilityData {
+            category_code: self.category_code,
+            value: self.country_code,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, FromRow)]
 #[serde(rename_all = "snake_case")]
 pub struct ESInterCityServicabilityModel {
@@ -406,6 +426,15 @@ pub struct ESGeoJsonServicabilityModel {
     pub network_participant_cache_id: Uuid,
 }
 
+impl ESGeoJsonServicabilityModel {
+    pub fn get_schema(self) -> WSServicabilityData<Value> {
+        WSServicabilityData {
+            category_code: self.category_code,
+            value: self.coordinates,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub struct ESNetworkParticipantModel {
@@ -492,6 +521,7 @@ pub struct ESProviderModel {
     pub identifications: Value,
     pub created_on: DateTime<Utc>,
     pub updated_on: Option<DateTime<Utc>>,
+    pub expires_on: Option<DateTime<Utc>>,
 }
 
 impl ESProviderModel {
@@ -519,6 +549,7 @@ impl ESProviderModel {
                 .collect(),
             identifications,
             terms: terms.get_schema(),
+            refreshed_on: Some(self.updated_on.unwrap_or(self.created_on)),
         }
     }
 }
@@ -541,6 +572,7 @@ impl ESProviderItemVariantModel {
             id: self.variant_id,
             name: self.variant_name,
             attributes: serde_json::from_value(self.attributes).unwrap(),
+            combinations: vec![],
         }
     }
 }
@@ -584,9 +616,14 @@ pub struct ESProviderItemModel {
     pub cancellation_terms: Value,
     pub created_on: DateTime<Utc>,
     pub location_ids: Option<Vec<Uuid>>,
+    #[serde(default)]
+    pub provider_rating: Option<f32>,
+    /// "lat,lon" of the item locations, indexed as geo points for distance ranking.
+    #[serde(default)]
+    pub location_points: Option<Vec<String>>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, FromRow)]
 #[serde(rename_all = "snake_case")]
 pub struct ESAutoCompleteProviderItemModel {
     pub provider_cache_id: Uuid,
@@ -609,7 +646,67 @@ impl ESAutoCompleteProviderItemModel {
 
 #[derive(Debug, Serialize, FromRow)]
 pub struct SearchLocationModel {
+    pub id: i32,
     pub country_code: CountryCode,
     pub city_code: String,
     pub domain_category_code: CategoryDomain,
+    pub last_refreshed_on: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, FromRow)]
+pub struct ServicabilityMatchModel {
+    pub location_id: String,
+    pub servicability_type: ServicabilityRuleType,
+    pub category_code: Option<String>,
+    pub is_match: bool,
+}
+
+impl ServicabilityMatchModel {
+    pub fn get_rule(self) -> ServicabilityRule {
+        ServicabilityRule {
+            r#type: self.servicability_type,
+            category_code: self.category_code,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ItemSearchPageModel {
+    pub search_after: Vec<String>,
+    pub items: Vec<ESProviderItemModel>,
+    pub facets: Option<ItemSearchFacets>,
+}
+
+#[derive(Debug)]
+pub struct ItemSupportModels {
+    pub network_participants: Vec<ESNetworkParticipantModel>,
+    pub providers: Vec<ESProviderModel>,
+    pub locations: Vec<ESProviderLocationModel>,
+    pub variants: Vec<ESProviderItemVariantModel>,
+}
+
+#[derive(Debug, FromRow)]
+pub struct ItemPriceHistoryModel {
+    pub currency: CurrencyType,
+    pub price_with_tax: BigDecimal,
+    pub price_without_tax: BigDecimal,
+    pub offered_price: Option<BigDecimal>,
+    pub maximum_price: BigDecimal,
+    pub available_qty: i32,
+    pub created_on: DateTime<Utc>,
+}
+
+impl ItemPriceHistoryModel {
+    pub fn get_schema(self) -> ItemPriceHistory {
+        ItemPriceHistory {
+            currency: self.currency,
+            price_with_tax: self.price_with_tax,
+            price_without_tax: self.price_without_tax,
+            offered_price: self.offered_price,
+            maximum_price: self.maximum_price,
+            is_available: self.available_qty > 0,
+            available_qty: self.available_qty,
+            recorded_on: self.created_on,
+        }
+    }
 }
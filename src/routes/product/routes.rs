@@ -1,6 +1,6 @@
 use super::handlers::{
-    cached_network_participant_list, cached_product_req, cached_provider_list,
-    product_autocomplete, realtime_product_search,
+    cached_network_participant_list, cached_product_req, cached_provider_list, item_fetch,
+    item_price_history, product_autocomplete, realtime_product_search, servicability_check,
 };
 use crate::middleware::{BusinessAccountValidation, RequireAuth};
 use crate::user_client::CustomerType;
@@ -58,4 +58,34 @@ pub fn product_route(cfg: &mut web::ServiceConfig) {
                 .wrap(RequireAuth),
         ),
     );
+    cfg.service(
+        web::resource("/item/fetch").route(
+            web::post()
+                .to(item_fetch)
+                .wrap(BusinessAccountValidation {
+                    business_type_list: vec![CustomerType::RetailB2bBuyer],
+                })
+                .wrap(RequireAuth),
+        ),
+    );
+    cfg.service(
+        web::resource("/servicability/check").route(
+            web::post()
+                .to(servicability_check)
+                .wrap(BusinessAccountValidation {
+                    business_type_list: vec![CustomerType::RetailB2bBuyer],
+                })
+                .wrap(RequireAuth),
+        ),
+    );
+    cfg.service(
+        web::resource("/item/price/history").route(
+            web::post()
+                .to(item_price_history)
+                .wrap(BusinessAccountValidation {
+                    business_type_list: vec![CustomerType::RetailB2bBuyer],
+                })
+                .wrap(RequireAuth),
+        ),
+    );
 }
@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
 
+use crate::domain::pricing::{get_applicable_price_slab, PriceSlab};
 use crate::routes::ondc::schemas::{ONDCFulfillmentType, ONDCPaymentType};
 use crate::routes::ondc::ONDCItemUOM;
 use crate::routes::order::schemas::FulfillmentStatusType;
@@ -82,7 +83,7 @@ pub enum ProductSearchType {
     City,
 }
 
-#[derive(Deserialize, Debug, ToSchema)]
+#[derive(Deserialize, Serialize, Debug, ToSchema, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct ProductFulFillmentLocation {
     pub latitude: f64,
@@ -90,6 +91,23 @@ pub struct ProductFulFillmentLocation {
     pub area_code: String,
 }
 
+#[derive(Debug, Deserialize, Serialize, PartialEq, ToSchema, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "catalog_inc_mode_type", rename_all = "snake_case")]
+pub enum CatalogIncMode {
+    Pull,
+    PushStart,
+    PushStop,
+}
+
+#[derive(Deserialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ProductCatalogInc {
+    pub mode: CatalogIncMode,
+    pub start_time: Option<DateTime<Utc>>,
+    pub end_time: Option<DateTime<Utc>>,
+}
+
 #[derive(Deserialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ProductSearchRequest {
@@ -106,6 +124,7 @@ pub struct ProductSearchRequest {
     pub fulfillment_locations: Option<Vec<ProductFulFillmentLocation>>,
     pub city_code: String,
     pub update_cache: bool,
+    pub catalog_inc: Option<ProductCatalogInc>,
 }
 
 impl FromRequest for ProductSearchRequest {
@@ -124,7 +143,7 @@ impl FromRequest for ProductSearchRequest {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, sqlx::Type, ToSchema)]
+#[derive(Debug, Serialize, Deserialize, sqlx::Type, ToSchema, Clone)]
 #[sqlx(type_name = "domain_category_type")]
 pub enum CategoryDomain {
     #[serde(rename = "RET10")]
@@ -341,6 +360,7 @@ pub struct WSSearchProviderDescription {
     pub credentials: Vec<WSSearchProviderCredential>,
     pub identifications: Vec<WSSearchProviderID>,
     pub terms: WSSearchProviderTerms,
+    pub refreshed_on: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -375,7 +395,7 @@ pub struct WSPaymentTypes {
     pub collected_by: ONDCNetworkType,
 }
 
-#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[derive(Debug, Serialize, Deserialize, ToSchema, Clone)]
 #[skip_serializing_none]
 #[serde(rename_all = "camelCase")]
 pub struct WSPriceSlab {
@@ -389,6 +409,15 @@ pub struct WSPriceSlab {
     pub price_without_tax: BigDecimal,
 }
 
+impl PriceSlab for WSPriceSlab {
+    fn min_qty(&self) -> &BigDecimal {
+        &self.min
+    }
+    fn max_qty(&self) -> Option<&BigDecimal> {
+        self.max.as_ref()
+    }
+}
+
 impl WSPriceSlab {
     pub fn get_model(&self) -> WSPriceSlabModel {
         WSPriceSlabModel {
@@ -530,6 +559,19 @@ pub struct WSItemCancellation {
     pub terms: Vec<WSItemCancellationTerm>,
 }
 
+impl WSSearchItem {
+    /// Tax exclusive unit price for the quantity, the applicable price slab taking precedence.
+    pub fn get_price_without_tax(&self, qty: &BigDecimal) -> BigDecimal {
+        self.price_slabs
+            .as_ref()
+            .and_then(|slabs| get_applicable_price_slab(slabs, qty))
+            .map_or(&self.price.price_without_tax, |slab| {
+                &slab.price_without_tax
+            })
+            .clone()
+    }
+}
+
 impl WSItemCancellation {
     pub fn get_model(&self) -> WSItemCancellationModel {
         let terms = self.terms.iter().map(|f| f.get_model()).collect();
@@ -564,6 +606,7 @@ pub struct WSSearchItem {
     pub images: Vec<String>,
     pub videos: Vec<String>,
     pub price_slabs: Option<Vec<WSPriceSlab>>,
+    pub applicable_price_slab: Option<WSPriceSlab>,
     pub attributes: Vec<WSSearchItemAttribute>,
     pub matched: bool,
     pub time_to_ship: String,
@@ -618,6 +661,14 @@ pub struct WSSearchVariant {
     pub id: String,
     pub name: String,
     pub attributes: Vec<WSSearchVariantAttribute>,
+    pub combinations: Vec<WSSearchVariantCombination>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WSSearchVariantCombination {
+    pub item_id: String,
+    pub attributes: HashMap<String, String>,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -637,7 +688,7 @@ pub struct WSServicabilityData<D> {
     pub value: D,
 }
 
-#[derive(Debug, Serialize, ToSchema)]
+#[derive(Debug, Default, Serialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct WSSearchServicability {
     pub geo_json: Vec<WSServicabilityData<Value>>,
@@ -690,6 +741,7 @@ pub struct BulkProviderCache<'a> {
     pub identifications: Vec<Value>,
     pub ids: Vec<Uuid>,
     pub created_ons: Vec<DateTime<Utc>>,
+    pub expires_ons: Vec<Option<DateTime<Utc>>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
@@ -799,6 +851,59 @@ pub struct BulkItemCache<'a> {
     pub created_ons: Vec<DateTime<Utc>>,
 }
 
+#[derive(Debug, Default)]
+pub struct CatalogRemovalData<'a> {
+    pub provider_ids: Vec<&'a str>,
+    pub location_provider_ids: Vec<&'a str>,
+    pub location_ids: Vec<&'a str>,
+    pub item_provider_ids: Vec<&'a str>,
+    pub item_ids: Vec<&'a str>,
+}
+
+impl CatalogRemovalData<'_> {
+    pub fn is_empty(&self) -> bool {
+        self.provider_ids.is_empty() && self.location_ids.is_empty() && self.item_ids.is_empty()
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct DBCacheRemovalIds {
+    pub provider_ids: Vec<Uuid>,
+    pub location_ids: Vec<Uuid>,
+    pub item_ids: Vec<Uuid>,
+    pub variant_ids: Vec<Uuid>,
+}
+
+pub struct BulkItemPriceHistory<'a> {
+    pub ids: Vec<Uuid>,
+    pub subscriber_ids: Vec<&'a str>,
+    pub provider_ids: Vec<&'a str>,
+    pub item_ids: Vec<&'a str>,
+    pub currencies: Vec<&'a CurrencyType>,
+    pub price_with_taxes: Vec<&'a BigDecimal>,
+    pub price_without_taxes: Vec<&'a BigDecimal>,
+    pub offered_prices: Vec<&'a Option<BigDecimal>>,
+    pub maximum_prices: Vec<&'a BigDecimal>,
+    pub available_qtys: Vec<i32>,
+    pub created_ons: Vec<DateTime<Utc>>,
+}
+
+/// Provider cache ids of the buyer's supplier lists, preferred providers are ranked first.
+#[derive(Debug, Default)]
+pub struct SearchSupplierPreference {
+    pub preferred_provider_ids: Vec<Uuid>,
+    pub included_provider_ids: Option<Vec<Uuid>>,
+    pub excluded_provider_ids: Vec<Uuid>,
+}
+
+#[derive(Debug, Default)]
+pub struct ItemSupportIds {
+    pub network_participant_ids: HashSet<Uuid>,
+    pub provider_ids: HashSet<Uuid>,
+    pub location_ids: HashSet<Uuid>,
+    pub variant_ids: HashSet<Uuid>,
+}
+
 pub struct BulkItemLocationCache<'a> {
     pub item_cache_ids: Vec<&'a Uuid>,
     pub location_cache_ids: Vec<&'a Uuid>,
@@ -813,6 +918,7 @@ pub struct AutoCompleteItemRequest {
     pub domain_category_code: Option<CategoryDomain>,
     pub offset: Option<Vec<String>>,
     pub limit: i16,
+    pub attributes: Option<Vec<SearchAttributeFilter>>,
 }
 
 impl FromRequest for AutoCompleteItemRequest {
@@ -944,6 +1050,193 @@ impl FromRequest for ProviderFetchReq {
     }
 }
 
+#[derive(Deserialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemFetchRequest {
+    #[schema(value_type = Option<String>)]
+    pub id: Option<Uuid>,
+    pub bpp_id: Option<String>,
+    pub provider_id: Option<String>,
+    pub item_id: Option<String>,
+    pub fulfillment_location: Option<ProductFulFillmentLocation>,
+    #[schema(value_type = Option<f64>)]
+    pub qty: Option<BigDecimal>,
+}
+
+impl FromRequest for ItemFetchRequest {
+    type Error = GenericError;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let fut = web::Json::<Self>::from_request(req, payload);
+
+        Box::pin(async move {
+            match fut.await {
+                Ok(json) => Ok(json.into_inner()),
+                Err(e) => Err(GenericError::ValidationError(e.to_string())),
+            }
+        })
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemFetchResponseData {
+    pub bpp: WSSearchBPP,
+    pub provider: WSSearchProviderDescription,
+    pub item: WSSearchItem,
+    pub variant: Option<WSSearchVariant>,
+    pub locations: HashMap<String, WSSearchProviderLocation>,
+    pub servicability: HashMap<String, WSSearchServicability>,
+    pub servicable_location_ids: Option<Vec<String>>,
+    pub price_change: Option<ItemPriceChange>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemPriceChange {
+    pub last_ordered_on: DateTime<Utc>,
+    #[schema(value_type = f64)]
+    pub last_ordered_unit_price: BigDecimal,
+    #[schema(value_type = f64)]
+    pub current_unit_price: BigDecimal,
+    #[schema(value_type = f64)]
+    pub change: BigDecimal,
+    pub is_changed: bool,
+}
+
+impl ItemPriceChange {
+    pub fn new(
+        last_ordered_on: DateTime<Utc>,
+        last_ordered_unit_price: BigDecimal,
+        current_unit_price: BigDecimal,
+    ) -> Self {
+        let change = &current_unit_price - &last_ordered_unit_price;
+        Self {
+            last_ordered_on,
+            is_changed: change != BigDecimal::from(0),
+            last_ordered_unit_price,
+            current_unit_price,
+            change,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemPriceHistoryRequest {
+    pub bpp_id: String,
+    pub provider_id: String,
+    pub item_id: String,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub limit: i64,
+}
+
+impl ItemPriceHistoryRequest {
+    pub fn validate(&self) -> Result<(), GenericError> {
+        if self.limit < 1 {
+            return Err(GenericError::ValidationError(
+                "Limit must be atleast 1".to_string(),
+            ));
+        }
+        if let (Some(from), Some(to)) = (self.from, self.to) {
+            if from > to {
+                return Err(GenericError::ValidationError(
+                    "From date must be before to date".to_string(),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromRequest for ItemPriceHistoryRequest {
+    type Error = GenericError;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let fut = web::Json::<Self>::from_request(req, payload);
+
+        Box::pin(async move {
+            match fut.await {
+                Ok(json) => Ok(json.into_inner()),
+                Err(e) => Err(GenericError::ValidationError(e.to_string())),
+            }
+        })
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemPriceHistory {
+    pub currency: CurrencyType,
+    #[schema(value_type = f64)]
+    pub price_with_tax: BigDecimal,
+    #[schema(value_type = f64)]
+    pub price_without_tax: BigDecimal,
+    #[schema(value_type = Option<f64>)]
+    pub offered_price: Option<BigDecimal>,
+    #[schema(value_type = f64)]
+    pub maximum_price: BigDecimal,
+    pub available_qty: i32,
+    pub is_available: bool,
+    pub recorded_on: DateTime<Utc>,
+}
+
+#[derive(Deserialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ServicabilityCheckRequest {
+    pub bpp_id: String,
+    pub provider_id: String,
+    pub location_ids: Option<Vec<String>>,
+    pub domain_category_code: CategoryDomain,
+    pub category_code: Option<String>,
+    pub country_code: CountryCode,
+    pub fulfillment_location: ProductFulFillmentLocation,
+}
+
+impl FromRequest for ServicabilityCheckRequest {
+    type Error = GenericError;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let fut = web::Json::<Self>::from_request(req, payload);
+
+        Box::pin(async move {
+            match fut.await {
+                Ok(json) => Ok(json.into_inner()),
+                Err(e) => Err(GenericError::ValidationError(e.to_string())),
+            }
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, ToSchema, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "text", rename_all = "snake_case")]
+pub enum ServicabilityRuleType {
+    Hyperlocal,
+    GeoJson,
+    Intercity,
+    Country,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ServicabilityRule {
+    pub r#type: ServicabilityRuleType,
+    pub category_code: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LocationServicability {
+    pub location_id: String,
+    pub is_servicable: Option<bool>,
+    pub matched_rules: Vec<ServicabilityRule>,
+}
+
 #[derive(Debug, Serialize, ToSchema)]
 pub struct NetworkParticipantListResponse {
     pub network_participants: Vec<WSSearchBPP>,
@@ -987,7 +1280,7 @@ impl FromRequest for CachedProductSearchRequest {
     }
 }
 
-#[derive(Deserialize, Debug, ToSchema)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ProductCacheSearchRequest {
     pub query: String,
@@ -1001,6 +1294,50 @@ pub struct ProductCacheSearchRequest {
     pub city_code: String,
     pub limit: i32,
     pub offset: Option<Vec<String>>,
+    #[schema(value_type = Option<f64>)]
+    pub qty: Option<BigDecimal>,
+    pub category_codes: Option<Vec<String>>,
+    #[schema(value_type = Option<Vec<String>>)]
+    pub provider_ids: Option<Vec<Uuid>>,
+    #[schema(value_type = Option<Vec<String>>)]
+    pub network_participant_ids: Option<Vec<Uuid>>,
+    pub price_range: Option<SearchPriceRange>,
+    pub attributes: Option<Vec<SearchAttributeFilter>>,
+    pub supplier_filter: Option<SupplierFilterType>,
+    pub sort: Option<SearchSortType>,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SupplierFilterType {
+    PreferredOnly,
+    ExcludeBlocked,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchSortType {
+    #[default]
+    Relevance,
+    Nearest,
+    PriceLowToHigh,
+    PriceHighToLow,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchPriceRange {
+    #[schema(value_type = Option<f64>)]
+    pub min: Option<BigDecimal>,
+    #[schema(value_type = Option<f64>)]
+    pub max: Option<BigDecimal>,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchAttributeFilter {
+    pub key: String,
+    pub values: Vec<String>,
 }
 
 impl FromRequest for ProductCacheSearchRequest {
@@ -1023,4 +1360,40 @@ impl FromRequest for ProductCacheSearchRequest {
 pub struct ItemCacheResponseData {
     pub network_participants: Vec<WSSearchData>,
     pub search_after: Vec<String>,
+    pub facets: Option<ItemSearchFacets>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchFacetBucket {
+    pub value: String,
+    pub label: Option<String>,
+    pub count: i64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchPriceFacetBucket {
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub count: i64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchAttributeFacet {
+    pub key: String,
+    pub values: Vec<SearchFacetBucket>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemSearchFacets {
+    pub category_codes: Vec<SearchFacetBucket>,
+    pub providers: Vec<SearchFacetBucket>,
+    pub network_participants: Vec<SearchFacetBucket>,
+    pub price_ranges: Vec<SearchPriceFacetBucket>,
+    pub payment_types: Vec<SearchFacetBucket>,
+    pub fulfillment_types: Vec<SearchFacetBucket>,
+    pub attributes: Vec<SearchAttributeFacet>,
 }
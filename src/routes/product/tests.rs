@@ -1 +1,824 @@
+#[cfg(test)]
+mod tests {
+    use bigdecimal::BigDecimal;
+    use serde_json::json;
+    use sqlx::PgPool;
+    use std::collections::{HashMap, HashSet};
+    use uuid::Uuid;
 
+    use crate::domain::pricing::{get_applicable_price_slab, is_slab_price_ignored};
+    use crate::elastic_search_client::{ElasticSearchIndex, SearchRankingProfile};
+    use crate::routes::ondc::ONDCItemUOM;
+    use crate::routes::order::schemas::OrderSelectRequest;
+    use crate::routes::order::utils::validate_select_servicability;
+    use crate::routes::product::schemas::{
+        AutoCompleteItemRequest, CategoryDomain, DBCacheRemovalIds, ItemPriceChange,
+        ItemPriceHistoryRequest, ProductCacheSearchRequest, SearchPriceRange,
+        SearchSupplierPreference, ServicabilityCheckRequest, ServicabilityRuleType,
+        WSCreatorContactData, WSItemCancellation, WSPriceSlab, WSProductCreator, WSSearchItem,
+        WSSearchItemAttribute, WSSearchItemPrice, WSSearchItemQty, WSSearchItemQtyMeasure,
+        WSSearchItemQuantity, WSSearchVariant, WSSearchVariantAttribute,
+    };
+    use crate::routes::product::utils::{
+        fetch_cached_ids, get_cache_removal_queries, get_item_facet_aggregations,
+        get_item_facet_filters, get_item_search_facets, get_location_servicability,
+        get_minimal_item_query, get_search_like_patterns, get_servicable_location_ids_from_db,
+        get_variant_combinations, search_item_ids_from_db, set_item_search_ranking,
+        set_variant_combinations,
+    };
+    use crate::schemas::CurrencyType;
+    use crate::tests::tests::get_test_pool;
+
+    #[test]
+    fn test_item_search_facets() {
+        let aggregations = json!({
+            "category_codes": {"doc_count": 4, "nested": {"doc_count": 6, "values": {"buckets": [
+                {"key": "Cement", "doc_count": 3, "items": {"doc_count": 2}}
+            ]}}},
+            "providers": {"doc_count": 4, "values": {"buckets": [
+                {"key": "5f0b8e4e-6f8a-4c3e-9a51-1f6f1e2b7c10", "doc_count": 4}
+            ]}},
+            "network_participants": {"doc_count": 4, "values": {"buckets": []}},
+            "payment_types": {"doc_count": 4, "values": {"buckets": [
+                {"key": "pre_paid", "doc_count": 4},
+                {"key": "credit", "doc_count": 1}
+            ]}},
+            "fulfillment_types": {"doc_count": 4, "values": {"buckets": [
+                {"key": "delivery", "doc_count": 4}
+            ]}},
+            "price_ranges": {"doc_count": 4, "values": {"buckets": [
+                {"key": "*-100.0", "to": 100.0, "doc_count": 1},
+                {"key": "100.0-500.0", "from": 100.0, "to": 500.0, "doc_count": 3}
+            ]}},
+            "attributes": {"doc_count": 4, "nested": {"doc_count": 8, "keys": {"buckets": [
+                {"key": "grade", "doc_count": 4, "values": {"buckets": [
+                    {"key": "OPC 53", "doc_count": 3, "items": {"doc_count": 3}}
+                ]}},
+                {"key": "brand", "doc_count": 2, "values": {"buckets": [
+                    {"key": "Ultratech", "doc_count": 2, "items": {"doc_count": 2}}
+                ]}}
+            ]}}},
+            "attributes:brand": {"doc_count": 6, "nested": {"doc_count": 12, "key": {"doc_count": 6, "values": {"buckets": [
+                {"key": "Ultratech", "doc_count": 2, "items": {"doc_count": 2}},
+                {"key": "ACC", "doc_count": 4, "items": {"doc_count": 4}}
+            ]}}}}
+        });
+        let facets = get_item_search_facets(&aggregations);
+        assert_eq!(facets.category_codes[0].value, "Cement");
+        assert_eq!(facets.category_codes[0].count, 2);
+        assert_eq!(facets.providers[0].count, 4);
+        assert!(facets.network_participants.is_empty());
+        assert_eq!(facets.payment_types.len(), 2);
+        assert_eq!(facets.fulfillment_types[0].value, "delivery");
+        assert_eq!(facets.price_ranges[0].min, None);
+        assert_eq!(facets.price_ranges[1].max, Some(500.0));
+        assert_eq!(facets.attributes.len(), 2);
+        assert_eq!(facets.attributes[0].key, "grade");
+        assert_eq!(facets.attributes[0].values[0].value, "OPC 53");
+        assert_eq!(facets.attributes[1].key, "brand");
+        assert_eq!(facets.attributes[1].values[1].value, "ACC");
+        assert_eq!(facets.attributes[1].values[1].count, 4);
+    }
+
+    #[test]
+    fn test_item_attribute_facet_aggregations() {
+        let mut body = get_cache_search_request("relevance");
+        body.attributes = serde_json::from_value(json!([
+            {"key": "grade", "values": ["OPC 53"]},
+            {"key": "brand", "values": ["ACC"]}
+        ]))
+        .unwrap();
+        let filters = get_item_facet_filters(&body);
+        assert_eq!(filters[0].0, "attributes:grade");
+        assert_eq!(filters[1].0, "attributes:brand");
+        let aggregations = get_item_facet_aggregations(&filters);
+        assert_eq!(
+            aggregations["attributes"]["filter"]["bool"]["filter"],
+            json!([filters[0].1, filters[1].1])
+        );
+        assert_eq!(
+            aggregations["attributes:grade"]["filter"]["bool"]["filter"],
+            json!([filters[1].1])
+        );
+        assert_eq!(
+            aggregations["attributes:brand"]["filter"]["bool"]["filter"],
+            json!([filters[0].1])
+        );
+        assert_eq!(
+            aggregations["attributes:brand"]["aggs"]["nested"]["aggs"]["key"]["filter"],
+            json!({"term": {"attributes.key": "brand"}})
+        );
+    }
+
+    fn get_item_qty(count: u32) -> WSSearchItemQty {
+        WSSearchItemQty {
+            measure: WSSearchItemQtyMeasure {
+                unit: ONDCItemUOM::Unit,
+                value: BigDecimal::from(1),
+            },
+            count,
+        }
+    }
+
+    fn get_search_item(
+        id: &str,
+        parent_item_id: Option<&str>,
+        attributes: &[(&str, &str)],
+    ) -> WSSearchItem {
+        WSSearchItem {
+            id: id.to_owned(),
+            name: id.to_owned(),
+            long_desc: String::new(),
+            short_desc: String::new(),
+            code: None,
+            domain_category: CategoryDomain::Grocery,
+            price: WSSearchItemPrice {
+                currency: CurrencyType::Inr,
+                price_with_tax: BigDecimal::from(118),
+                price_without_tax: BigDecimal::from(100),
+                offered_price: None,
+                maximum_price: BigDecimal::from(118),
+            },
+            parent_item_id: parent_item_id.map(|id| id.to_owned()),
+            recommended: false,
+            fulfillment_options: vec![],
+            location_ids: vec![],
+            payment_options: vec![],
+            creator: WSProductCreator {
+                name: String::new(),
+                contact: WSCreatorContactData {
+                    name: String::new(),
+                    address: String::new(),
+                    phone: String::new(),
+                    email: String::new(),
+                },
+            },
+            quantity: WSSearchItemQuantity {
+                unitized: WSSearchItemQtyMeasure {
+                    unit: ONDCItemUOM::Unit,
+                    value: BigDecimal::from(1),
+                },
+                available: get_item_qty(10),
+                maximum: get_item_qty(10),
+                minimum: None,
+            },
+            categories: vec![],
+            tax_rate: BigDecimal::from(18),
+            images: vec![],
+            videos: vec![],
+            price_slabs: None,
+            applicable_price_slab: None,
+            attributes: attributes
+                .iter()
+                .map(|(key, value)| WSSearchItemAttribute {
+                    label: key.to_string(),
+                    key: key.to_string(),
+                    value: value.to_string(),
+                })
+                .collect(),
+            matched: true,
+            time_to_ship: "P1D".to_owned(),
+            country_of_origin: None,
+            validity: None,
+            replacement_terms: vec![],
+            return_terms: vec![],
+            cancellation_terms: WSItemCancellation {
+                is_cancellable: false,
+                terms: vec![],
+            },
+        }
+    }
+
+    fn get_search_variant(id: &str, attribute_codes: &[&str]) -> WSSearchVariant {
+        WSSearchVariant {
+            id: id.to_owned(),
+            name: id.to_owned(),
+            attributes: attribute_codes
+                .iter()
+                .enumerate()
+                .map(|(sequence, attribute_code)| WSSearchVariantAttribute {
+                    attribute_code: attribute_code.to_string(),
+                    sequence: sequence.to_string(),
+                })
+                .collect(),
+            combinations: vec![],
+        }
+    }
+
+    #[test]
+    fn test_variant_combinations() {
+        let variant = get_search_variant(
+            "V1",
+            &["item.tags.attribute.grade", "item.tags.attribute.size"],
+        );
+        let items = vec![
+            get_search_item(
+                "I1",
+                Some("V1"),
+                &[("grade", "OPC 43"), ("size", "50kg"), ("brand", "A")],
+            ),
+            get_search_item("I2", Some("V1"), &[("grade", "OPC 53")]),
+            get_search_item("I3", Some("V1"), &[("brand", "A")]),
+            get_search_item("I4", Some("V2"), &[("grade", "PPC")]),
+            get_search_item("I5", None, &[("grade", "PPC")]),
+        ];
+        let combinations = get_variant_combinations(&variant, &items);
+        assert_eq!(combinations.len(), 2);
+        assert_eq!(combinations[0].item_id, "I1");
+        assert_eq!(
+            combinations[0].attributes,
+            HashMap::from([
+                ("grade".to_owned(), "OPC 43".to_owned()),
+                ("size".to_owned(), "50kg".to_owned())
+            ])
+        );
+        assert_eq!(combinations[1].item_id, "I2");
+        assert_eq!(
+            combinations[1].attributes,
+            HashMap::from([("grade".to_owned(), "OPC 53".to_owned())])
+        );
+
+        let mut variants = HashMap::from([
+            ("V1".to_owned(), get_search_variant("V1", &["grade"])),
+            ("V2".to_owned(), get_search_variant("V2", &["grade"])),
+            ("V3".to_owned(), get_search_variant("V3", &["grade"])),
+        ]);
+        set_variant_combinations(&mut variants, &items);
+        assert_eq!(variants["V1"].combinations.len(), 2);
+        assert_eq!(variants["V2"].combinations[0].item_id, "I4");
+        assert!(variants["V3"].combinations.is_empty());
+    }
+
+    #[test]
+    fn test_auto_complete_attribute_filter() {
+        let body: AutoCompleteItemRequest = serde_json::from_value(json!({
+            "query": "cement",
+            "limit": 10,
+            "attributes": [
+                {"key": "grade", "values": ["OPC 43", "OPC 53"]},
+                {"key": "brand", "values": []}
+            ]
+        }))
+        .unwrap();
+        let query = get_minimal_item_query(&body);
+        let must = query["query"]["bool"]["must"].as_array().unwrap();
+        assert_eq!(must.len(), 2);
+        assert_eq!(must[0]["multi_match"]["query"], "cement");
+        assert_eq!(
+            must[1],
+            json!({"nested": {"path": "attributes", "query": {"bool": {"filter": [
+                {"term": {"attributes.key": "grade"}},
+                {"terms": {"attributes.value": ["OPC 43", "OPC 53"]}}
+            ]}}}})
+        );
+        assert!(query.get("search_after").is_none());
+
+        let body: AutoCompleteItemRequest = serde_json::from_value(json!({
+            "query": " ",
+            "limit": 10,
+            "offset": ["5f0b8e4e-6f8a-4c3e-9a51-1f6f1e2b7c10"]
+        }))
+        .unwrap();
+        let query = get_minimal_item_query(&body);
+        assert!(query["query"]["bool"]["must"]
+            .as_array()
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            query["search_after"],
+            json!(["5f0b8e4e-6f8a-4c3e-9a51-1f6f1e2b7c10"])
+        );
+    }
+
+    fn get_price_slab(min: i32, max: Option<i32>, price: i32) -> WSPriceSlab {
+        WSPriceSlab {
+            min: BigDecimal::from(min),
+            max: max.map(BigDecimal::from),
+            price_with_tax: BigDecimal::from(price),
+            price_without_tax: BigDecimal::from(price),
+        }
+    }
+
+    #[test]
+    fn test_applicable_price_slab() {
+        let slabs = vec![
+            get_price_slab(1, Some(99), 400),
+            get_price_slab(100, Some(499), 380),
+            get_price_slab(500, None, 360),
+        ];
+        let price_for = |qty: i32| {
+            get_applicable_price_slab(&slabs, &BigDecimal::from(qty))
+                .map(|slab| slab.price_with_tax.clone())
+        };
+        assert_eq!(price_for(0), None);
+        assert_eq!(price_for(99), Some(BigDecimal::from(400)));
+        assert_eq!(price_for(100), Some(BigDecimal::from(380)));
+        assert_eq!(price_for(10000), Some(BigDecimal::from(360)));
+
+        let overlapping = vec![get_price_slab(1, None, 400), get_price_slab(50, None, 390)];
+        let slab = get_applicable_price_slab(&overlapping, &BigDecimal::from(60));
+        assert_eq!(
+            slab.map(|slab| slab.price_with_tax.clone()),
+            Some(BigDecimal::from(390))
+        );
+
+        assert!(is_slab_price_ignored(
+            &BigDecimal::from(380),
+            &BigDecimal::from(400)
+        ));
+        assert!(!is_slab_price_ignored(
+            &BigDecimal::from(380),
+            &"380.004".parse::<BigDecimal>().unwrap()
+        ));
+    }
+
+    #[test]
+    fn test_search_like_patterns() {
+        let terms = vec!["cement".to_owned(), "50%_off".to_owned()];
+        assert_eq!(
+            get_search_like_patterns(&terms),
+            vec!["%cement%".to_owned(), "%50\\%\\_off%".to_owned()]
+        );
+    }
+
+    #[test]
+    fn test_item_price_change() {
+        let ordered_on = chrono::Utc::now();
+        let price_change =
+            ItemPriceChange::new(ordered_on, BigDecimal::from(100), BigDecimal::from(120));
+        assert!(price_change.is_changed);
+        assert_eq!(price_change.change, BigDecimal::from(20));
+
+        let price_change =
+            ItemPriceChange::new(ordered_on, BigDecimal::from(100), BigDecimal::from(100));
+        assert!(!price_change.is_changed);
+    }
+
+    #[test]
+    fn test_item_price_history_request_validation() {
+        let get_request = |limit: i64, from: &str, to: &str| -> ItemPriceHistoryRequest {
+            serde_json::from_value(json!({
+                "bppId": "seller.example.com",
+                "providerId": "P1",
+                "itemId": "I1",
+                "from": from,
+                "to": to,
+                "limit": limit
+            }))
+            .unwrap()
+        };
+        assert!(
+            get_request(10, "2024-01-01T00:00:00Z", "2024-02-01T00:00:00Z")
+                .validate()
+                .is_ok()
+        );
+        assert!(
+            get_request(0, "2024-01-01T00:00:00Z", "2024-02-01T00:00:00Z")
+                .validate()
+                .is_err()
+        );
+        assert!(
+            get_request(10, "2024-02-01T00:00:00Z", "2024-01-01T00:00:00Z")
+                .validate()
+                .is_err()
+        );
+    }
+
+    fn get_cache_search_request(sort: &str) -> ProductCacheSearchRequest {
+        serde_json::from_value(json!({
+            "query": "cement",
+            "domainCategoryCode": "RET10",
+            "countryCode": "IND",
+            "fulfillmentLocation": {"latitude": 12.97, "longitude": 77.59, "areaCode": "560001"},
+            "cityCode": "std:080",
+            "limit": 10,
+            "sort": sort
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_item_search_ranking() {
+        let profile = SearchRankingProfile::default();
+        let preferred_provider_ids = vec![Uuid::new_v4()];
+
+        let mut query = json!({"query": {"bool": {"must": []}}});
+        let body = get_cache_search_request("relevance");
+        set_item_search_ranking(&mut query, &body, &profile, &preferred_provider_ids);
+        let function_score = &query["query"]["function_score"];
+        assert_eq!(function_score["query"], json!({"bool": {"must": []}}));
+        assert_eq!(function_score["functions"].as_array().unwrap().len(), 4);
+        assert_eq!(query["sort"], json!([{"_score": "desc"}, {"id": "asc"}]));
+
+        let mut query = json!({"query": {"bool": {"must": []}}});
+        let body = get_cache_search_request("nearest");
+        set_item_search_ranking(&mut query, &body, &profile, &[]);
+        assert!(query["query"].get("function_score").is_none());
+        assert_eq!(
+            query["sort"][0]["_geo_distance"]["location_points"],
+            json!({"lat": 12.97, "lon": 77.59})
+        );
+
+        let mut query = json!({"query": {"bool": {"must": []}}});
+        let body = get_cache_search_request("price_high_to_low");
+        set_item_search_ranking(&mut query, &body, &profile, &[]);
+        assert_eq!(
+            query["sort"],
+            json!([{"price_with_tax": "desc"}, {"id": "asc"}])
+        );
+    }
+
+    #[test]
+    fn test_cache_removal_queries() {
+        let provider_id = Uuid::new_v4();
+        let item_id = Uuid::new_v4();
+        let variant_id = Uuid::new_v4();
+        let queries = get_cache_removal_queries(&DBCacheRemovalIds {
+            provider_ids: vec![provider_id],
+            location_ids: vec![],
+            item_ids: vec![item_id],
+            variant_ids: vec![variant_id],
+        });
+        let get_queries = |index: ElasticSearchIndex| {
+            queries
+                .iter()
+                .filter(|(query_index, _)| query_index == &index)
+                .map(|(_, query)| query.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            get_queries(ElasticSearchIndex::ProviderItem),
+            vec![
+                json!({"terms": {"provider_cache_id.keyword": [provider_id]}}),
+                json!({"terms": {"id": [item_id]}})
+            ]
+        );
+        assert_eq!(
+            get_queries(ElasticSearchIndex::ProviderItemVariant),
+            vec![
+                json!({"terms": {"provider_cache_id": [provider_id]}}),
+                json!({"terms": {"id": [variant_id]}})
+            ]
+        );
+        assert_eq!(
+            get_queries(ElasticSearchIndex::Provider),
+            vec![json!({"terms": {"id": [provider_id]}})]
+        );
+        assert!(get_cache_removal_queries(&DBCacheRemovalIds {
+            provider_ids: vec![],
+            location_ids: vec![],
+            item_ids: vec![],
+            variant_ids: vec![],
+        })
+        .is_empty());
+    }
+
+    async fn save_test_provider(pool: &PgPool, subscriber_id: &str) -> (Uuid, Uuid) {
+        let network_participant_cache_id = Uuid::new_v4();
+        let provider_cache_id = Uuid::new_v4();
+        sqlx::query(
+            r#"
+            INSERT INTO network_participant_cache (id, subscriber_id, name, short_desc, long_desc, images)
+            VALUES ($1, $2, 'Test Seller', '', '', '[]')
+            "#,
+        )
+        .bind(network_participant_cache_id)
+        .bind(subscriber_id)
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            r#"
+            INSERT INTO provider_cache (id, provider_id, network_participant_cache_id, name, code, short_desc,
+                long_desc, images, ttl, credentials, contact)
+            VALUES ($1, 'P1', $2, 'Test Provider', 'P1', '', '', '[]', 'P1D', '[]', '{}')
+            "#,
+        )
+        .bind(provider_cache_id)
+        .bind(network_participant_cache_id)
+        .execute(pool)
+        .await
+        .unwrap();
+        (network_participant_cache_id, provider_cache_id)
+    }
+
+    async fn save_test_location(
+        pool: &PgPool,
+        provider_cache_id: Uuid,
+        location_id: &str,
+        latitude: f64,
+        longitude: f64,
+    ) -> Uuid {
+        let id = Uuid::new_v4();
+        sqlx::query(
+            r#"
+            INSERT INTO provider_location_cache (id, provider_cache_id, location_id, latitude, longitude, location,
+                address, city_code, city_name, state_code, country_code, area_code)
+            VALUES ($1, $2, $3, $4, $5, ST_Transform(ST_SetSRID(ST_MakePoint($5, $4), 4326), 3857),
+                '', 'std:080', 'Bengaluru', 'KA', 'IND', '560001')
+            "#,
+        )
+        .bind(id)
+        .bind(provider_cache_id)
+        .bind(location_id)
+        .bind(latitude)
+        .bind(longitude)
+        .execute(pool)
+        .await
+        .unwrap();
+        id
+    }
+
+    async fn save_test_hyperlocal_servicability(
+        pool: &PgPool,
+        location_cache_id: Uuid,
+        radius: f64,
+    ) {
+        sqlx::query(
+            r#"
+            INSERT INTO provider_servicability_hyperlocal_cache (id, provider_location_cache_id, domain_code, radius)
+            VALUES ($1, $2, 'RET10', $3)
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(location_cache_id)
+        .bind(radius)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    async fn save_test_item(
+        pool: &PgPool,
+        provider_cache_id: Uuid,
+        location_cache_id: Uuid,
+        item_name: &str,
+        price: i32,
+    ) -> Uuid {
+        let id = Uuid::new_v4();
+        sqlx::query(
+            r#"
+            INSERT INTO provider_item_cache (id, country_code, provider_cache_id, long_desc, short_desc, category_code,
+                domain_code, item_code, item_id, item_name, currency, price_with_tax, price_without_tax, maximum_price,
+                tax_rate, recommended, payment_ids, images, fulfillment_options, payment_options, qty, creator,
+                matched, time_to_ship, replacement_terms, return_terms, cancellation_terms)
+            VALUES ($1, 'IND', $2, '', '', 'Cement', 'RET10', $3, $3, $4, 'INR', $5, $5, $5, 0, false, '[]', '[]',
+                '[]', '[]', '{}', '{}', false, 'P1D', '[]', '[]', '[]')
+            "#,
+        )
+        .bind(id)
+        .bind(provider_cache_id)
+        .bind(id.to_string())
+        .bind(item_name)
+        .bind(BigDecimal::from(price))
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            r#"
+            INSERT INTO item_location_cache_relationship (id, item_cache_id, location_cache_id)
+            VALUES ($1, $2, $3)
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(id)
+        .bind(location_cache_id)
+        .execute(pool)
+        .await
+        .unwrap();
+        id
+    }
+
+    async fn delete_test_network_participant(pool: &PgPool, network_participant_cache_id: Uuid) {
+        sqlx::query("DELETE FROM network_participant_cache WHERE id = $1")
+            .bind(network_participant_cache_id)
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_fetch_cached_ids() {
+        let pool = get_test_pool().await;
+        let (network_participant_cache_id, provider_cache_id) =
+            save_test_provider(&pool, &format!("{}.test", Uuid::new_v4())).await;
+        let location_id = save_test_location(&pool, provider_cache_id, "L1", 12.97, 77.59).await;
+        let missing_id = Uuid::new_v4();
+        for index in ElasticSearchIndex::all() {
+            assert!(fetch_cached_ids(&pool, index, &[missing_id])
+                .await
+                .unwrap()
+                .is_empty());
+        }
+        let cached_ids = fetch_cached_ids(
+            &pool,
+            ElasticSearchIndex::ProviderLocation,
+            &[location_id, missing_id],
+        )
+        .await
+        .unwrap();
+        assert_eq!(cached_ids, HashSet::from([location_id]));
+
+        delete_test_network_participant(&pool, network_participant_cache_id).await;
+    }
+
+    #[tokio::test]
+    async fn test_postgres_item_search() {
+        let pool = get_test_pool().await;
+        let (network_participant_cache_id, provider_cache_id) =
+            save_test_provider(&pool, &format!("{}.test", Uuid::new_v4())).await;
+        let near_location_id =
+            save_test_location(&pool, provider_cache_id, "L1", 12.97, 77.59).await;
+        let far_location_id = save_test_location(&pool, provider_cache_id, "L2", 13.5, 78.5).await;
+        save_test_hyperlocal_servicability(&pool, near_location_id, 5000.0).await;
+        save_test_hyperlocal_servicability(&pool, far_location_id, 1000.0).await;
+        let cheap_item_id = save_test_item(
+            &pool,
+            provider_cache_id,
+            near_location_id,
+            "OPC cement",
+            100,
+        )
+        .await;
+        let costly_item_id = save_test_item(
+            &pool,
+            provider_cache_id,
+            near_location_id,
+            "PPC cement",
+            300,
+        )
+        .await;
+        save_test_item(&pool, provider_cache_id, near_location_id, "TMT bar", 200).await;
+        save_test_item(
+            &pool,
+            provider_cache_id,
+            far_location_id,
+            "White cement",
+            50,
+        )
+        .await;
+
+        let mut body = get_cache_search_request("price_low_to_high");
+        body.provider_ids = Some(vec![provider_cache_id]);
+        let location_ids = get_servicable_location_ids_from_db(&pool, &body)
+            .await
+            .unwrap();
+        assert!(location_ids.contains(&near_location_id));
+        assert!(!location_ids.contains(&far_location_id));
+
+        let profile = SearchRankingProfile::default();
+        let supplier_preference = SearchSupplierPreference::default();
+        let items =
+            search_item_ids_from_db(&pool, &body, &location_ids, &supplier_preference, &profile)
+                .await
+                .unwrap();
+        assert_eq!(
+            items.iter().map(|(id, _)| *id).collect::<Vec<_>>(),
+            vec![cheap_item_id, costly_item_id]
+        );
+
+        body.limit = 1;
+        body.offset = Some(vec![items[0].1.to_string(), items[0].0.to_string()]);
+        let items =
+            search_item_ids_from_db(&pool, &body, &location_ids, &supplier_preference, &profile)
+                .await
+                .unwrap();
+        assert_eq!(items, vec![(costly_item_id, 300.0)]);
+
+        body.limit = 10;
+        body.offset = None;
+        body.price_range = Some(SearchPriceRange {
+            min: None,
+            max: Some(BigDecimal::from(200)),
+        });
+        let items =
+            search_item_ids_from_db(&pool, &body, &location_ids, &supplier_preference, &profile)
+                .await
+                .unwrap();
+        assert_eq!(items, vec![(cheap_item_id, 100.0)]);
+
+        let supplier_preference = SearchSupplierPreference {
+            excluded_provider_ids: vec![provider_cache_id],
+            ..Default::default()
+        };
+        let items =
+            search_item_ids_from_db(&pool, &body, &location_ids, &supplier_preference, &profile)
+                .await
+                .unwrap();
+        assert!(items.is_empty());
+
+        delete_test_network_participant(&pool, network_participant_cache_id).await;
+    }
+
+    #[tokio::test]
+    async fn test_location_servicability() {
+        let pool = get_test_pool().await;
+        let bpp_id = format!("{}.test", Uuid::new_v4());
+        let (network_participant_cache_id, provider_cache_id) =
+            save_test_provider(&pool, &bpp_id).await;
+        let hyperlocal_location_id =
+            save_test_location(&pool, provider_cache_id, "L1", 12.97, 77.59).await;
+        let intercity_location_id =
+            save_test_location(&pool, provider_cache_id, "L2", 28.61, 77.2).await;
+        let country_location_id =
+            save_test_location(&pool, provider_cache_id, "L3", 19.07, 72.87).await;
+        let unservicable_location_id =
+            save_test_location(&pool, provider_cache_id, "L4", 22.57, 88.36).await;
+        save_test_hyperlocal_servicability(&pool, hyperlocal_location_id, 5000.0).await;
+        save_test_hyperlocal_servicability(&pool, unservicable_location_id, 5000.0).await;
+        for (location_cache_id, pincode) in [
+            (intercity_location_id, "560001"),
+            (unservicable_location_id, "700001"),
+        ] {
+            sqlx::query(
+                r#"
+                INSERT INTO provider_servicability_intercity_cache (id, provider_location_cache_id, domain_code, pincode)
+                VALUES ($1, $2, 'RET10', $3)
+                "#,
+            )
+            .bind(Uuid::new_v4())
+            .bind(location_cache_id)
+            .bind(pincode)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+        sqlx::query(
+            r#"
+            INSERT INTO provider_servicability_country_cache (id, provider_location_cache_id, domain_code, country_code)
+            VALUES ($1, $2, 'RET10', 'IND')
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(country_location_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let body: ServicabilityCheckRequest = serde_json::from_value(json!({
+            "bppId": bpp_id,
+            "providerId": "P1",
+            "domainCategoryCode": "RET10",
+            "countryCode": "IND",
+            "fulfillmentLocation": {"latitude": 12.97, "longitude": 77.6, "areaCode": "560001"}
+        }))
+        .unwrap();
+        let locations = get_location_servicability(&pool, &body).await.unwrap();
+        assert_eq!(
+            locations
+                .iter()
+                .map(|location| (
+                    location.location_id.as_str(),
+                    location.is_servicable,
+                    location
+                        .matched_rules
+                        .iter()
+                        .map(|rule| rule.r#type)
+                        .collect::<Vec<_>>()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                ("L1", Some(true), vec![ServicabilityRuleType::Hyperlocal]),
+                ("L2", Some(true), vec![ServicabilityRuleType::Intercity]),
+                ("L3", Some(true), vec![ServicabilityRuleType::Country]),
+                ("L4", Some(false), vec![]),
+            ]
+        );
+
+        let get_select_request = |location_ids: &[&str]| -> OrderSelectRequest {
+            serde_json::from_value(json!({
+                "transactionId": Uuid::new_v4(),
+                "messageId": Uuid::new_v4(),
+                "domainCategoryCode": "RET10",
+                "paymentTypes": ["pre_paid"],
+                "providerId": "P1",
+                "items": [{"itemId": "I1", "locationIds": location_ids, "qty": 1, "fulfillmentIds": ["F1"]}],
+                "ttl": "PT30S",
+                "fulfillments": [{"id": "F1", "type": "delivery", "location": {
+                    "gps": "12.97,77.6",
+                    "areaCode": "560001",
+                    "address": "MG Road",
+                    "city": {"code": "std:080", "name": "Bengaluru"},
+                    "country": {"code": "IND", "name": "India"},
+                    "state": "Karnataka",
+                    "contactMobileNo": "9999999999"
+                }}],
+                "orderType": "purchase_order",
+                "bppId": bpp_id
+            }))
+            .unwrap()
+        };
+        assert!(
+            validate_select_servicability(&pool, &get_select_request(&["L1", "L2", "L3"]))
+                .await
+                .is_ok()
+        );
+        assert!(
+            validate_select_servicability(&pool, &get_select_request(&["L1", "L4"]))
+                .await
+                .is_err()
+        );
+
+        delete_test_network_participant(&pool, network_participant_cache_id).await;
+    }
+}
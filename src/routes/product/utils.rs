@@ -1,24 +1,29 @@
 use std::collections::{HashMap, HashSet};
 use std::str::FromStr;
 
+use actix_web::web;
+use async_trait::async_trait;
 use bigdecimal::{BigDecimal, ToPrimitive};
-use futures::future::join_all;
-use serde::de::DeserializeOwned;
+use futures::future::{join_all, try_join_all};
+use serde::{de::DeserializeOwned, Serialize};
 use serde_json::{json, Value};
 use sqlx::{PgPool, Postgres, Transaction, Executor, types::Json, Row, QueryBuilder};
 use tokio::try_join;
 
 use uuid::Uuid;
-use crate::configuration::get_configuration;
-use crate::elastic_search_client::{ElasticSearchClient, ElasticSearchIndex};
+use crate::configuration::{get_configuration, SearchConfig};
+use crate::constants::{ATTRIBUTE_FACET_PREFIX, CATALOG_CACHE_EXPIRY_INTERVAL, ITEM_PRICE_HISTORY_MAX_LIMIT, SEARCH_FACET_SIZE, SEARCH_PRICE_FACET_BOUNDS};
+use crate::domain::pricing::get_applicable_price_slab;
+use crate::elastic_search_client::{ElasticSearchClient, ElasticSearchIndex, SearchBackendType, SearchRankingProfile};
 use crate::routes::ondc::utils::{get_ondc_search_payload, send_ondc_payload};
 use crate::routes::ondc::ONDCActionType;
+use crate::routes::order::utils::fetch_last_ordered_item;
 use crate::routes::product::models::{ESAutoCompleteProviderItemModel, ESHyperlocalServicabilityModel, ESProviderLocationModel, ESProviderModel};
 use crate::routes::product::schemas::{AutoCompleteItem, FulfillmentType, PaymentType, ProductSearchType, ProviderListResponse};
 use crate::schemas::{CurrencyType, ONDCNetworkType, RequestMetaData, StartUpMap};
-use crate::utils::{create_authorization_header, get_np_detail};
-use super::models::{ESCountryServicabilityModel, ESGeoJsonServicabilityModel, ESInterCityServicabilityModel, ESLocationModel, ESNetworkParticipantModel, ESProviderItemModel, ESProviderItemVariantModel, ProductVariantAttributeModel, SearchLocationModel, SearchProviderCredentialModel, WSItemCancellationModel, WSItemReplacementTermModel, WSItemReturnTermModel, WSItemValidityModel, WSPriceSlabModel, WSProductCategoryModel, WSProductCreatorModel, WSSearchItemAttributeModel, WSSearchItemQuantityModel, WSSearchProviderContactModel, WSSearchProviderTermsModel};
-use super::schemas::{AutoCompleteItemRequest, AutoCompleteItemResponseData, BulkCountryServicabilityCache, BulkGeoServicabilityCache, BulkHyperlocalServicabilityCache, BulkInterCityServicabilityCache, BulkItemCache, BulkItemLocationCache, BulkItemVariantCache, BulkProviderCache, BulkProviderLocationCache, CategoryDomain, DBItemCacheData, ItemCacheResponseData, NetworkParticipantListReq, NetworkParticipantListResponse, ProductCacheSearchRequest, ProductFulFillmentLocation, ProductSearchRequest, ProviderFetchReq, ServicabilityIds, WSItemValidity, WSPriceSlab, WSSearchBPP, WSSearchData, WSSearchItem, WSSearchItemPrice, WSSearchProvider};
+use crate::utils::{create_authorization_header, get_duration_from_iso8601, get_np_detail};
+use super::models::{ESCountryServicabilityModel, ItemPriceHistoryModel, ItemSearchPageModel, ItemSupportModels, ESGeoJsonServicabilityModel, ESInterCityServicabilityModel, ESLocationModel, ESNetworkParticipantModel, ESProviderItemModel, ESProviderItemVariantModel, ProductVariantAttributeModel, SearchLocationModel, SearchProviderCredentialModel, ServicabilityMatchModel, WSItemCancellationModel, WSItemReplacementTermModel, WSItemReturnTermModel, WSItemValidityModel, WSPriceSlabModel, WSProductCategoryModel, WSProductCreatorModel, WSSearchItemAttributeModel, WSSearchItemQuantityModel, WSSearchProviderContactModel, WSSearchProviderTermsModel};
+use super::schemas::{AutoCompleteItemRequest, AutoCompleteItemResponseData, BulkCountryServicabilityCache, BulkGeoServicabilityCache, BulkHyperlocalServicabilityCache, BulkInterCityServicabilityCache, BulkItemCache, BulkItemLocationCache, BulkItemPriceHistory, BulkItemVariantCache, BulkProviderCache, BulkProviderLocationCache, CatalogIncMode, CatalogRemovalData, CategoryDomain, DBCacheRemovalIds, DBItemCacheData, ItemCacheResponseData, ItemFetchRequest, ItemFetchResponseData, ItemPriceChange, ItemPriceHistory, ItemPriceHistoryRequest, ItemSearchFacets, ItemSupportIds, NetworkParticipantListReq, NetworkParticipantListResponse, ProductCacheSearchRequest, ProductCatalogInc, ProductFulFillmentLocation, ProductSearchRequest, ProviderFetchReq, LocationServicability, SearchAttributeFacet, SearchAttributeFilter, SearchFacetBucket, SearchPriceFacetBucket, SearchSortType, SearchSupplierPreference, ServicabilityCheckRequest, ServicabilityIds, ServicabilityRuleType, WSItemValidity, WSPriceSlab, WSSearchBPP, WSSearchData, WSSearchItem, WSSearchItemPrice, WSSearchProvider, WSSearchServicability, WSSearchVariant, WSSearchVariantCombination, WSServicabilityData};
 use chrono::{DateTime, Utc};
 use crate::user_client::{BusinessAccount, CustomerType, UserAccount};
 use crate::schemas::CountryCode;
@@ -33,8 +38,8 @@ pub async fn save_search_request(
 ) -> Result<(), anyhow::Error> {
     sqlx::query!(
         r#"
-        INSERT INTO search_request (message_id, transaction_id, device_id, business_id,  user_id, created_on, update_cache, query, payment_type, domain_category_code, search_type, fulfillment_type)
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+        INSERT INTO search_request (message_id, transaction_id, device_id, business_id,  user_id, created_on, update_cache, query, payment_type, domain_category_code, search_type, fulfillment_type, catalog_inc_mode)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
         "#,
         &search_request.message_id,
         &search_request.transaction_id,
@@ -47,7 +52,8 @@ pub async fn save_search_request(
         &search_request.payment_type as &Option<PaymentType>, 
         &search_request.domain_category_code.to_string(),
         &search_request.search_type as &ProductSearchType,
-        &search_request.fulfillment_type as &Option<FulfillmentType>
+        &search_request.fulfillment_type as &Option<FulfillmentType>,
+        search_request.catalog_inc.as_ref().map(|catalog_inc| &catalog_inc.mode) as Option<&CatalogIncMode>
 
     )
     .execute(pool).await
@@ -75,6 +81,7 @@ fn create_bulk_provider_cache_objs<'a>(body: &'a Vec<WSSearchProvider>, network_
     let mut credentials= vec![];
     let mut ids = vec![];
     let mut created_ons =vec![];
+    let mut expires_ons = vec![];
     for provider in body.iter() {
         ids.push(Uuid::new_v4());
         provider_ids.push(provider.description.id.as_ref());
@@ -87,6 +94,7 @@ fn create_bulk_provider_cache_objs<'a>(body: &'a Vec<WSSearchProvider>, network_
         ratings.push(provider.description.rating);
         images.push(serde_json::to_value(&provider.description.images).unwrap());
         created_ons.push(created_on);
+        expires_ons.push(get_duration_from_iso8601(&provider.description.ttl).and_then(|ttl| created_on.checked_add_signed(ttl)));
         contacts.push(
             serde_json::to_value(&WSSearchProviderContactModel{ 
                 mobile_no: provider.description.contact.mobile_no.to_owned(),
@@ -140,7 +148,7 @@ fn create_bulk_provider_cache_objs<'a>(body: &'a Vec<WSSearchProvider>, network_
          identifications,
          ids,
          created_ons,
-         
+         expires_ons,
     };
 }
 
@@ -157,7 +165,7 @@ async fn save_provider_cache(
     let query = sqlx::query!(
         r#"
         INSERT INTO provider_cache  (provider_id, network_participant_cache_id, name, code, short_desc, long_desc, images, rating,
-        ttl, credentials, contact, terms, identifications, created_on, updated_on, id)
+        ttl, credentials, contact, terms, identifications, created_on, updated_on, id, expires_on)
         SELECT *
         FROM UNNEST(
             $1::text[], 
@@ -175,11 +183,14 @@ async fn save_provider_cache(
             $13::jsonb[],
             $14::timestamptz[],
             $15::timestamptz[],
-            $16::uuid[]
+            $16::uuid[],
+            $17::timestamptz[]
         )
         ON CONFLICT (network_participant_cache_id, provider_id) 
         DO UPDATE SET 
         updated_on = EXCLUDED.updated_on,
+        ttl = EXCLUDED.ttl,
+        expires_on = EXCLUDED.expires_on,
         credentials = COALESCE((
             SELECT jsonb_agg(DISTINCT c) 
             FROM (
@@ -206,6 +217,7 @@ async fn save_provider_cache(
         &data.created_ons[..] as &[DateTime<Utc>],
         &data.created_ons[..] as &[DateTime<Utc>],
         &data.ids,
+        &data.expires_ons[..] as &[Option<DateTime<Utc>>],
     );
 
     let result = query
@@ -1350,6 +1362,94 @@ async fn save_item_cache(transaction: &mut Transaction<'_, Postgres>, country_co
     Ok(map)
 }
 
+fn create_bulk_item_price_history<'a>(subscriber_id: &'a str, providers: &'a [WSSearchProvider], created_on: DateTime<Utc>) -> BulkItemPriceHistory<'a> {
+    let mut data = BulkItemPriceHistory {
+        ids: vec![],
+        subscriber_ids: vec![],
+        provider_ids: vec![],
+        item_ids: vec![],
+        currencies: vec![],
+        price_with_taxes: vec![],
+        price_without_taxes: vec![],
+        offered_prices: vec![],
+        maximum_prices: vec![],
+        available_qtys: vec![],
+        created_ons: vec![],
+    };
+    for provider in providers {
+        for item in &provider.items {
+            data.ids.push(Uuid::new_v4());
+            data.subscriber_ids.push(subscriber_id);
+            data.provider_ids.push(&provider.description.id);
+            data.item_ids.push(&item.id);
+            data.currencies.push(&item.price.currency);
+            data.price_with_taxes.push(&item.price.price_with_tax);
+            data.price_without_taxes.push(&item.price.price_without_tax);
+            data.offered_prices.push(&item.price.offered_price);
+            data.maximum_prices.push(&item.price.maximum_price);
+            data.available_qtys.push(i32::try_from(item.quantity.available.count).unwrap_or(i32::MAX));
+            data.created_ons.push(created_on);
+        }
+    }
+    data
+}
+
+/// Records an item's price only when it differs from the latest recorded price or the item's availability flips.
+#[tracing::instrument(name = "save item price history", skip(transaction, providers))]
+async fn save_item_price_history(transaction: &mut Transaction<'_, Postgres>, subscriber_id: &str, providers: &[WSSearchProvider], created_on: DateTime<Utc>) -> Result<(), anyhow::Error> {
+    let data = create_bulk_item_price_history(subscriber_id, providers, created_on);
+    if data.ids.is_empty() {
+        return Ok(());
+    }
+    sqlx::query!(
+        r#"
+        INSERT INTO provider_item_price_history (
+            id, subscriber_id, provider_id, item_id, currency, price_with_tax, price_without_tax,
+            offered_price, maximum_price, available_qty, created_on
+        )
+        SELECT d.id, d.subscriber_id, d.provider_id, d.item_id, d.currency, d.price_with_tax, d.price_without_tax,
+            d.offered_price, d.maximum_price, d.available_qty, d.created_on
+        FROM unnest(
+            $1::uuid[], $2::text[], $3::text[], $4::text[], $5::currency_code_type[], $6::decimal[],
+            $7::decimal[], $8::decimal[], $9::decimal[], $10::integer[], $11::timestamptz[]
+        ) AS d(id, subscriber_id, provider_id, item_id, currency, price_with_tax, price_without_tax,
+            offered_price, maximum_price, available_qty, created_on)
+        LEFT JOIN LATERAL (
+            SELECT h.id, h.currency, h.price_with_tax, h.price_without_tax, h.offered_price, h.maximum_price, h.available_qty
+            FROM provider_item_price_history AS h
+            WHERE h.subscriber_id = d.subscriber_id AND h.provider_id = d.provider_id AND h.item_id = d.item_id
+            ORDER BY h.created_on DESC
+            LIMIT 1
+        ) AS latest ON true
+        WHERE latest.id IS NULL
+            OR latest.currency <> d.currency
+            OR latest.price_with_tax <> d.price_with_tax
+            OR latest.price_without_tax <> d.price_without_tax
+            OR latest.offered_price IS DISTINCT FROM d.offered_price
+            OR latest.maximum_price <> d.maximum_price
+            OR (latest.available_qty > 0) <> (d.available_qty > 0)
+        "#,
+        &data.ids,
+        &data.subscriber_ids[..] as &[&str],
+        &data.provider_ids[..] as &[&str],
+        &data.item_ids[..] as &[&str],
+        &data.currencies[..] as &[&CurrencyType],
+        &data.price_with_taxes[..] as &[&BigDecimal],
+        &data.price_without_taxes[..] as &[&BigDecimal],
+        &data.offered_prices[..] as &[&Option<BigDecimal>],
+        &data.maximum_prices[..] as &[&BigDecimal],
+        &data.available_qtys,
+        &data.created_ons,
+    )
+    .execute(&mut **transaction)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e).context("A database failure occurred while saving item price history")
+    })?;
+    Ok(())
+}
+
 pub async fn save_cache_to_db(transaction: &mut Transaction<'_, Postgres>, country_code: &CountryCode, domain: &CategoryDomain, product_objs: &WSSearchData,  created_on: DateTime<Utc>) -> Result<DBItemCacheData,anyhow::Error>{
     
     let id = save_np_cache(transaction, &product_objs.bpp, created_on)
@@ -1379,6 +1479,7 @@ pub async fn save_cache_to_db(transaction: &mut Transaction<'_, Postgres>, count
     let variant_ids = variant_map.values().copied().collect();
     let item_map = save_item_cache(transaction, country_code, &product_objs.providers, &provider_map, &variant_map, created_on).await?;
     let item_ids = item_map.values().copied().collect();
+    save_item_price_history(transaction, &product_objs.bpp.subscriber_id, &product_objs.providers, created_on).await?;
     save_item_location_relationship_cache(transaction, &product_objs.providers, &provider_map, &location_map, &item_map, created_on).await?;
 
     Ok(DBItemCacheData{ servicability_ids: sericability_data, network_participant_ids: vec![id], location_ids, provider_ids, variant_ids, item_ids})
@@ -1720,7 +1821,7 @@ async fn get_provider_cache_data_from_db(
             id, provider_id, network_participant_cache_id, name, code, 
             short_desc, long_desc, images as "images: Json<Vec<String>>", rating, ttl, 
             credentials, contact, terms, identifications, 
-            created_on, updated_on
+            created_on, updated_on, expires_on
         FROM provider_cache
         WHERE id = ANY($1)
         "#,
@@ -1736,19 +1837,203 @@ async fn get_provider_cache_data_from_db(
     Ok(data)
 }
 
-async fn delete_cache_from_db(pool: &PgPool) -> Result<(), anyhow::Error> {
-    let query = "DELETE FROM network_participant_cache";
+#[tracing::instrument(name = "delete disabled catalog entries from cache", skip(transaction))]
+pub async fn delete_cache_removals_from_db(transaction: &mut Transaction<'_, Postgres>, subscriber_id: &str, country_code: &CountryCode, domain: &CategoryDomain, data: &CatalogRemovalData<'_>) -> Result<DBCacheRemovalIds, anyhow::Error> {
+    let provider_ids = sqlx::query_scalar!(
+        r#"
+        DELETE FROM provider_cache AS pc
+        USING network_participant_cache AS npc
+        WHERE pc.network_participant_cache_id = npc.id
+        AND npc.subscriber_id = $1 AND pc.provider_id = ANY($2)
+        RETURNING pc.id
+        "#,
+        subscriber_id,
+        &data.provider_ids[..] as &[&str]
+    )
+    .fetch_all(&mut **transaction)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e).context("A database failure occurred while deleting disabled providers from cache")
+    })?;
 
-    sqlx::query(query)
-        .execute(pool)
-        .await
-        .map_err(|e| anyhow!(e))?; 
+    let location_ids = sqlx::query_scalar!(
+        r#"
+        DELETE FROM provider_location_cache AS plc
+        USING provider_cache AS pc, network_participant_cache AS npc,
+            UNNEST($2::text[], $3::text[]) AS removed(provider_id, location_id)
+        WHERE plc.provider_cache_id = pc.id AND pc.network_participant_cache_id = npc.id
+        AND npc.subscriber_id = $1 AND pc.provider_id = removed.provider_id
+        AND plc.location_id = removed.location_id
+        RETURNING plc.id
+        "#,
+        subscriber_id,
+        &data.location_provider_ids[..] as &[&str],
+        &data.location_ids[..] as &[&str]
+    )
+    .fetch_all(&mut **transaction)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e).context("A database failure occurred while deleting disabled locations from cache")
+    })?;
+
+    let items = sqlx::query!(
+        r#"
+        DELETE FROM provider_item_cache AS pic
+        USING provider_cache AS pc, network_participant_cache AS npc,
+            UNNEST($4::text[], $5::text[]) AS removed(provider_id, item_id)
+        WHERE pic.provider_cache_id = pc.id AND pc.network_participant_cache_id = npc.id
+        AND npc.subscriber_id = $1 AND pic.country_code = $2 AND pic.domain_code = $3
+        AND pc.provider_id = removed.provider_id AND pic.item_id = removed.item_id
+        RETURNING pic.id, pic.variant_cache_id
+        "#,
+        subscriber_id,
+        country_code as &CountryCode,
+        domain as &CategoryDomain,
+        &data.item_provider_ids[..] as &[&str],
+        &data.item_ids[..] as &[&str]
+    )
+    .fetch_all(&mut **transaction)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e).context("A database failure occurred while deleting disabled items from cache")
+    })?;
+    let item_ids = items.iter().map(|item| item.id).collect();
+    let variant_ids = delete_orphan_item_variants_from_db(transaction, items.iter().filter_map(|item| item.variant_cache_id).collect()).await?;
+
+    Ok(DBCacheRemovalIds { provider_ids, location_ids, item_ids, variant_ids })
+}
+
+#[tracing::instrument(name = "delete orphan item variants from cache", skip(transaction))]
+async fn delete_orphan_item_variants_from_db(transaction: &mut Transaction<'_, Postgres>, variant_ids: Vec<Uuid>) -> Result<Vec<Uuid>, anyhow::Error> {
+    if variant_ids.is_empty() {
+        return Ok(vec![]);
+    }
+    let variant_ids = sqlx::query_scalar!(
+        r#"
+        DELETE FROM provider_item_variant_cache AS pivc
+        WHERE pivc.id = ANY($1)
+        AND NOT EXISTS (SELECT 1 FROM provider_item_cache AS pic WHERE pic.variant_cache_id = pivc.id)
+        RETURNING pivc.id
+        "#,
+        &variant_ids
+    )
+    .fetch_all(&mut **transaction)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e).context("A database failure occurred while deleting orphan item variants from cache")
+    })?;
+    Ok(variant_ids)
+}
+
+#[tracing::instrument(name = "delete expired catalog entries from cache", skip(transaction))]
+async fn delete_expired_cache_from_db(transaction: &mut Transaction<'_, Postgres>, now: DateTime<Utc>) -> Result<DBCacheRemovalIds, anyhow::Error> {
+    let provider_ids = sqlx::query_scalar!(
+        r#"
+        DELETE FROM provider_cache
+        WHERE expires_on < $1
+        RETURNING id
+        "#,
+        now
+    )
+    .fetch_all(&mut **transaction)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e).context("A database failure occurred while deleting expired providers from cache")
+    })?;
+
+    let items = sqlx::query!(
+        r#"
+        DELETE FROM provider_item_cache
+        WHERE jsonb_typeof(validity) = 'object' AND (validity->>'end')::timestamptz < $1
+        RETURNING id, variant_cache_id
+        "#,
+        now
+    )
+    .fetch_all(&mut **transaction)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e).context("A database failure occurred while deleting expired items from cache")
+    })?;
+    let item_ids = items.iter().map(|item| item.id).collect();
+    let variant_ids = delete_orphan_item_variants_from_db(transaction, items.iter().filter_map(|item| item.variant_cache_id).collect()).await?;
+
+    Ok(DBCacheRemovalIds { provider_ids, location_ids: vec![], item_ids, variant_ids })
+}
+
+/// Removes providers past their catalog TTL and items past their validity from Postgres and Elasticsearch.
+pub async fn expire_catalog_cache(pool: &PgPool, es_client: &ElasticSearchClient, search_config: &SearchConfig) -> Result<(), anyhow::Error> {
+    let mut transaction = pool.begin().await.map_err(|e| anyhow!(e))?;
+    let removed_ids = delete_expired_cache_from_db(&mut transaction, Utc::now()).await?;
+    transaction.commit().await.map_err(|e| anyhow!(e))?;
+    if !removed_ids.provider_ids.is_empty() || !removed_ids.item_ids.is_empty() {
+        tracing::info!("Expired {} providers and {} items from cache", removed_ids.provider_ids.len(), removed_ids.item_ids.len());
+        delete_cache_removals_from_elastic_search(es_client, search_config, removed_ids).await?;
+    }
     Ok(())
 }
-pub async fn clear_network_participant_cache(pool: &PgPool, es_client: &ElasticSearchClient) -> Result<(), anyhow::Error> {
-    let task_1 = delete_cache_from_db(pool);
-    let task_2 = es_client.delete_all_indices();
-    try_join!(task_1, task_2)?;
+
+pub fn start_catalog_cache_expiry_worker(pool: web::Data<PgPool>, es_client: web::Data<ElasticSearchClient>, search_config: web::Data<SearchConfig>) {
+    tokio::spawn(async move {
+        let mut interval =
+            tokio::time::interval(std::time::Duration::from_secs(CATALOG_CACHE_EXPIRY_INTERVAL));
+        loop {
+            interval.tick().await;
+            if let Err(e) = expire_catalog_cache(&pool, &es_client, &search_config).await {
+                tracing::error!("Failed to expire catalog cache: {:?}", e);
+            }
+        }
+    });
+}
+
+/// Delete by query requests for removed cache entries, `provider_cache_id` being a text field with a keyword sub field in the item index.
+pub fn get_cache_removal_queries(data: &DBCacheRemovalIds) -> Vec<(ElasticSearchIndex, Value)> {
+    let mut queries = vec![];
+    if !data.provider_ids.is_empty() {
+        queries.push((ElasticSearchIndex::Provider, json!({"terms": {"id": data.provider_ids}})));
+        queries.push((ElasticSearchIndex::ProviderItem, json!({"terms": {"provider_cache_id.keyword": data.provider_ids}})));
+        for index in [
+            ElasticSearchIndex::ProviderLocation,
+            ElasticSearchIndex::ProviderItemVariant,
+            ElasticSearchIndex::ProviderServicabilityHyperLocal,
+            ElasticSearchIndex::ProviderServicabilityCountry,
+            ElasticSearchIndex::ProviderServicabilityInterCity,
+            ElasticSearchIndex::ProviderServicabilityGeoJson,
+        ] {
+            queries.push((index, json!({"terms": {"provider_cache_id": data.provider_ids}})));
+        }
+    }
+    if !data.location_ids.is_empty() {
+        queries.push((ElasticSearchIndex::ProviderLocation, json!({"terms": {"id": data.location_ids}})));
+        for index in [
+            ElasticSearchIndex::ProviderServicabilityHyperLocal,
+            ElasticSearchIndex::ProviderServicabilityCountry,
+            ElasticSearchIndex::ProviderServicabilityInterCity,
+            ElasticSearchIndex::ProviderServicabilityGeoJson,
+        ] {
+            queries.push((index, json!({"terms": {"location_cache_id": data.location_ids}})));
+        }
+    }
+    if !data.item_ids.is_empty() {
+        queries.push((ElasticSearchIndex::ProviderItem, json!({"terms": {"id": data.item_ids}})));
+    }
+    if !data.variant_ids.is_empty() {
+        queries.push((ElasticSearchIndex::ProviderItemVariant, json!({"terms": {"id": data.variant_ids}})));
+    }
+    queries
+}
+
+pub async fn delete_cache_removals_from_elastic_search(es_client: &ElasticSearchClient, search_config: &SearchConfig, data: DBCacheRemovalIds) -> Result<(), anyhow::Error> {
+    if search_config.backend == SearchBackendType::Postgres {
+        return Ok(());
+    }
+    let tasks = get_cache_removal_queries(&data).into_iter().map(|(index, query)| es_client.delete_by_query(index, query));
+    try_join_all(tasks).await?;
     Ok(())
 }
 
@@ -1843,14 +2128,19 @@ async fn get_provider_item_cache_data_from_db(
             ic.created_on,
             pc.network_participant_cache_id,  
             COALESCE(array_agg(DISTINCT ilcr.location_cache_id) 
-                FILTER (WHERE ilcr.location_cache_id IS NOT NULL), '{}') AS location_ids
+                FILTER (WHERE ilcr.location_cache_id IS NOT NULL), '{}') AS location_ids,
+            pc.rating AS provider_rating,
+            COALESCE(array_agg(DISTINCT plc.latitude::text || ',' || plc.longitude::text)
+                FILTER (WHERE plc.id IS NOT NULL), '{}') AS location_points
         FROM provider_item_cache ic
         LEFT JOIN item_location_cache_relationship ilcr 
             ON ic.id = ilcr.item_cache_id
+        LEFT JOIN provider_location_cache plc
+            ON ilcr.location_cache_id = plc.id
         LEFT JOIN provider_cache pc 
             ON ic.provider_cache_id = pc.id
         WHERE ic.id = ANY($1)
-        GROUP BY ic.id, pc.network_participant_cache_id  -- Updated GROUP BY
+        GROUP BY ic.id, pc.network_participant_cache_id, pc.rating  -- Updated GROUP BY
         "#,
         &id_list
     );
@@ -1868,7 +2158,10 @@ async fn get_provider_item_cache_data_from_db(
 
 
 
- pub async fn save_cache_to_elastic_search(pool: &PgPool, es_client: &ElasticSearchClient, data: DBItemCacheData)-> Result<(), anyhow::Error>{
+ pub async fn save_cache_to_elastic_search(pool: &PgPool, es_client: &ElasticSearchClient, search_config: &SearchConfig, data: DBItemCacheData)-> Result<(), anyhow::Error>{
+    if search_config.backend == SearchBackendType::Postgres {
+        return Ok(());
+    }
     let (network_participant_models, provider_models, location_models, item_models,variant_models) =  try_join!(
         get_network_participant_cache_data_from_db(pool, data.network_participant_ids),
         get_provider_cache_data_from_db(pool, &data.provider_ids),
@@ -1889,7 +2182,7 @@ async fn get_provider_item_cache_data_from_db(
 }
 
 
-async fn get_servicable_uq_ids_from_es(es_client: &ElasticSearchClient, distinct_key: &str, domain_category_code: Option<&CategoryDomain>, country_code: Option<&CountryCode>,category_code: Option<&String>, fulfillment_location: Option<&ProductFulFillmentLocation>)-> Result<Vec<Uuid>, anyhow::Error>{
+async fn get_servicable_uq_ids_from_es(es_client: &ElasticSearchClient, distinct_key: &str, domain_category_code: Option<&CategoryDomain>, country_code: Option<&CountryCode>,category_code: Option<&String>, fulfillment_location: Option<&ProductFulFillmentLocation>, location_cache_ids: Option<&[Uuid]>)-> Result<Vec<Uuid>, anyhow::Error>{
     let mut tasks =vec![];
     let mut base_query = json!({
         "size": 0, 
@@ -1913,6 +2206,11 @@ async fn get_servicable_uq_ids_from_es(es_client: &ElasticSearchClient, distinct
             must_clause.push(json!({ "term": { "category_code": category_code } }));
         }
     }
+    if let Some(location_cache_ids) = location_cache_ids {
+        if let Some(must_clause) = base_query["query"]["bool"]["must"].as_array_mut() {
+            must_clause.push(json!({ "terms": { "location_cache_id": location_cache_ids } }));
+        }
+    }
     if let Some(fulfillment_location) = fulfillment_location{
         let mut intecity_query = base_query.clone();
         if let Some(must_clause) = intecity_query["query"]["bool"]["must"].as_array_mut() {
@@ -2018,7 +2316,7 @@ pub async fn get_network_participant_from_es(es_client: &ElasticSearchClient, bo
             .push(multi_match_query);
     }
     if body.domain_category_code.is_some() || body.country_code.is_some() || body.category_code.is_some() || body.fulfillment_location.is_some(){
-        let network_participant_ids = get_servicable_uq_ids_from_es(es_client, "network_participant_cache_id", body.domain_category_code.as_ref(), body.country_code.as_ref(), body.category_code.as_ref(), body.fulfillment_location.as_ref()).await?;
+        let network_participant_ids = get_servicable_uq_ids_from_es(es_client, "network_participant_cache_id", body.domain_category_code.as_ref(), body.country_code.as_ref(), body.category_code.as_ref(), body.fulfillment_location.as_ref(), None).await?;
         if network_participant_ids.is_empty(){
             return  Ok(None)
         }
@@ -2096,7 +2394,7 @@ pub async fn get_provider_from_es(es_client: &ElasticSearchClient, body: Provide
             .push(multi_match_query);
     }
     if body.domain_category_code.is_some() || body.country_code.is_some() || body.category_code.is_some() || body.fulfillment_location.is_some(){
-        let provider_ids = get_servicable_uq_ids_from_es(es_client,"provider_cache_id", body.domain_category_code.as_ref(), body.country_code.as_ref(),body.category_code.as_ref(), body.fulfillment_location.as_ref()).await?;
+        let provider_ids = get_servicable_uq_ids_from_es(es_client,"provider_cache_id", body.domain_category_code.as_ref(), body.country_code.as_ref(),body.category_code.as_ref(), body.fulfillment_location.as_ref(), None).await?;
         if provider_ids.is_empty(){
             return  Ok(None)
         }
@@ -2149,7 +2447,7 @@ pub async fn get_provider_from_es(es_client: &ElasticSearchClient, body: Provide
 
 
 
-pub async fn get_minimal_item_from_es(es_client: &ElasticSearchClient, body: &AutoCompleteItemRequest) ->Result<AutoCompleteItemResponseData, anyhow::Error>{
+pub fn get_minimal_item_query(body: &AutoCompleteItemRequest) -> Value {
      let mut base_query: Value = json!({
         "size": body.limit,
         "_source": ["provider_cache_id", "network_participant_cache_id","id", "item_id", "item_code", "item_name"],
@@ -2174,13 +2472,22 @@ pub async fn get_minimal_item_from_es(es_client: &ElasticSearchClient, body: &Au
             .push(multi_match_query);
     }
 
+    for attribute in body.attributes.iter().flatten().filter(|attribute| !attribute.values.is_empty()) {
+        base_query["query"]["bool"]["must"]
+            .as_array_mut()
+            .unwrap()
+            .push(get_item_attribute_filter(attribute));
+    }
+
     if let Some(search_after) = &body.offset {
         base_query["search_after"] = json!(search_after);
     }
+    base_query
+}
 
-
+pub async fn get_minimal_item_from_es(es_client: &ElasticSearchClient, body: &AutoCompleteItemRequest) ->Result<AutoCompleteItemResponseData, anyhow::Error>{
     let data = es_client
-        .fetch(base_query, ElasticSearchIndex::ProviderItem)
+        .fetch(get_minimal_item_query(body), ElasticSearchIndex::ProviderItem)
         .await
         .map_err(|e| {
             anyhow!(e)
@@ -2211,22 +2518,278 @@ pub async fn get_minimal_item_from_es(es_client: &ElasticSearchClient, body: &Au
 
 
 
-pub async fn get_auto_complete_product_data(es_client: &ElasticSearchClient, body: &AutoCompleteItemRequest) -> Result<AutoCompleteItemResponseData, anyhow::Error>{
-    get_minimal_item_from_es(es_client, body).await
+pub async fn get_auto_complete_product_data(es_client: &ElasticSearchClient, pool: &PgPool, search_config: &SearchConfig, ranking_profile: &SearchRankingProfile, body: &AutoCompleteItemRequest) -> Result<AutoCompleteItemResponseData, anyhow::Error>{
+    if search_config.backend == SearchBackendType::ElasticSearch {
+        match (ElasticSearchBackend { es_client, ranking_profile }).get_auto_complete_items(body).await {
+            Ok(data) => return Ok(data),
+            Err(e) => tracing::warn!("Elastic search autocomplete failed, falling back to postgres: {:?}", e),
+        }
+    }
+    PostgresSearchBackend { pool, ranking_profile }.get_auto_complete_items(body).await
 }
 
 
 
 
-pub async fn get_item_from_es(es_client: &ElasticSearchClient, body: &ProductCacheSearchRequest, location_cache_ids: &Vec<Uuid>) ->Result<Option<(Vec<String>, Vec<ESProviderItemModel>)>, anyhow::Error>{
+pub fn get_item_facet_filters(body: &ProductCacheSearchRequest) -> Vec<(String, Value)> {
+    let mut filters = vec![];
+    if let Some(category_codes) = body.category_codes.as_ref().filter(|codes| !codes.is_empty()) {
+        filters.push((
+            "category_codes".to_owned(),
+            json!({"nested": {"path": "categories", "query": {"terms": {"categories.code.keyword": category_codes}}}}),
+        ));
+    }
+    if let Some(provider_ids) = body.provider_ids.as_ref().filter(|ids| !ids.is_empty()) {
+        filters.push(("providers".to_owned(), json!({"terms": {"provider_cache_id.keyword": provider_ids}})));
+    }
+    if let Some(network_participant_ids) = body.network_participant_ids.as_ref().filter(|ids| !ids.is_empty()) {
+        filters.push((
+            "network_participants".to_owned(),
+            json!({"terms": {"network_participant_cache_id.keyword": network_participant_ids}}),
+        ));
+    }
+    if let Some(price_range) = &body.price_range {
+        let mut range = json!({});
+        if let Some(min) = price_range.min.as_ref().and_then(|min| min.to_f64()) {
+            range["gte"] = json!(min);
+        }
+        if let Some(max) = price_range.max.as_ref().and_then(|max| max.to_f64()) {
+            range["lte"] = json!(max);
+        }
+        filters.push(("price_ranges".to_owned(), json!({"range": {"price_with_tax": range}})));
+    }
+    if let Some(payment_type) = &body.payment_type {
+        filters.push(("payment_types".to_owned(), json!({"term": {"payment_options": payment_type}})));
+    }
+    if let Some(fulfillment_type) = &body.fulfillment_type {
+        filters.push(("fulfillment_types".to_owned(), json!({"term": {"fulfillment_options": fulfillment_type}})));
+    }
+    for attribute in body.attributes.iter().flatten().filter(|attribute| !attribute.values.is_empty()) {
+        filters.push((get_attribute_facet_name(&attribute.key), get_item_attribute_filter(attribute)));
+    }
+    filters
+}
+
+fn get_attribute_facet_name(key: &str) -> String {
+    format!("{}{}", ATTRIBUTE_FACET_PREFIX, key)
+}
+
+fn get_item_attribute_filter(attribute: &SearchAttributeFilter) -> Value {
+    json!({"nested": {"path": "attributes", "query": {"bool": {"filter": [
+        {"term": {"attributes.key": attribute.key}},
+        {"terms": {"attributes.value": attribute.values}}
+    ]}}}})
+}
+
+fn get_price_facet_ranges() -> Vec<Value> {
+    let mut ranges = vec![];
+    let mut from: Option<f64> = None;
+    for bound in SEARCH_PRICE_FACET_BOUNDS {
+        ranges.push(match from {
+            Some(from) => json!({"from": from, "to": bound}),
+            None => json!({"to": bound}),
+        });
+        from = Some(bound);
+    }
+    if let Some(from) = from {
+        ranges.push(json!({"from": from}));
+    }
+    ranges
+}
+
+/// Each facet is counted with every filter except its own, so selecting a value keeps its siblings visible.
+/// Selected attribute keys get a facet of their own; the remaining keys are counted under every filter.
+pub fn get_item_facet_aggregations(filters: &[(String, Value)]) -> Value {
+    let facet_filter = |name: &str| {
+        let other_filters: Vec<&Value> = filters.iter().filter(|(facet, _)| facet != name).map(|(_, filter)| filter).collect();
+        json!({"bool": {"filter": other_filters}})
+    };
+    let terms_facet = |name: &str, field: &str| {
+        json!({
+            "filter": facet_filter(name),
+            "aggs": {"values": {"terms": {"field": field, "size": SEARCH_FACET_SIZE}}}
+        })
+    };
+    let mut aggregations = json!({
+        "category_codes": {
+            "filter": facet_filter("category_codes"),
+            "aggs": {"nested": {"nested": {"path": "categories"}, "aggs": {"values": {
+                "terms": {"field": "categories.code.keyword", "size": SEARCH_FACET_SIZE},
+                "aggs": {"items": {"reverse_nested": {}}}
+            }}}}
+        },
+        "providers": terms_facet("providers", "provider_cache_id.keyword"),
+        "network_participants": terms_facet("network_participants", "network_participant_cache_id.keyword"),
+        "payment_types": terms_facet("payment_types", "payment_options"),
+        "fulfillment_types": terms_facet("fulfillment_types", "fulfillment_options"),
+        "price_ranges": {
+            "filter": facet_filter("price_ranges"),
+            "aggs": {"values": {"range": {"field": "price_with_tax", "ranges": get_price_facet_ranges()}}}
+        },
+        "attributes": {
+            "filter": facet_filter("attributes"),
+            "aggs": {"nested": {"nested": {"path": "attributes"}, "aggs": {"keys": {
+                "terms": {"field": "attributes.key", "size": SEARCH_FACET_SIZE},
+                "aggs": {"values": {
+                    "terms": {"field": "attributes.value", "size": SEARCH_FACET_SIZE},
+                    "aggs": {"items": {"reverse_nested": {}}}
+                }}
+            }}}}
+        }
+    });
+    for (name, _) in filters.iter().filter(|(name, _)| name.starts_with(ATTRIBUTE_FACET_PREFIX)) {
+        aggregations[name] = json!({
+            "filter": facet_filter(name),
+            "aggs": {"nested": {"nested": {"path": "attributes"}, "aggs": {"key": {
+                "filter": {"term": {"attributes.key": &name[ATTRIBUTE_FACET_PREFIX.len()..]}},
+                "aggs": {"values": {
+                    "terms": {"field": "attributes.value", "size": SEARCH_FACET_SIZE},
+                    "aggs": {"items": {"reverse_nested": {}}}
+                }}
+            }}}}
+        });
+    }
+    aggregations
+}
+
+fn get_facet_buckets(aggregation: &Value) -> Vec<SearchFacetBucket> {
+    aggregation["buckets"]
+        .as_array()
+        .map(|buckets| {
+            buckets
+                .iter()
+                .filter_map(|bucket| {
+                    Some(SearchFacetBucket {
+                        value: bucket["key"].as_str()?.to_owned(),
+                        label: None,
+                        count: bucket["items"]["doc_count"].as_i64().or_else(|| bucket["doc_count"].as_i64())?,
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+pub fn get_item_search_facets(aggregations: &Value) -> ItemSearchFacets {
+    let price_ranges = aggregations["price_ranges"]["values"]["buckets"]
+        .as_array()
+        .map(|buckets| {
+            buckets
+                .iter()
+                .map(|bucket| SearchPriceFacetBucket {
+                    min: bucket["from"].as_f64(),
+                    max: bucket["to"].as_f64(),
+                    count: bucket["doc_count"].as_i64().unwrap_or_default(),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let mut attributes: Vec<SearchAttributeFacet> = aggregations["attributes"]["nested"]["keys"]["buckets"]
+        .as_array()
+        .map(|buckets| {
+            buckets
+                .iter()
+                .filter_map(|bucket| {
+                    let key = bucket["key"].as_str()?;
+                    if aggregations.get(get_attribute_facet_name(key)).is_some() {
+                        return None;
+                    }
+                    Some(SearchAttributeFacet {
+                        key: key.to_owned(),
+                        values: get_facet_buckets(&bucket["values"]),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    if let Some(aggregations) = aggregations.as_object() {
+        for (name, aggregation) in aggregations {
+            if let Some(key) = name.strip_prefix(ATTRIBUTE_FACET_PREFIX) {
+                attributes.push(SearchAttributeFacet {
+                    key: key.to_owned(),
+                    values: get_facet_buckets(&aggregation["nested"]["key"]["values"]),
+                });
+            }
+        }
+    }
+    ItemSearchFacets {
+        category_codes: get_facet_buckets(&aggregations["category_codes"]["nested"]["values"]),
+        providers: get_facet_buckets(&aggregations["providers"]["values"]),
+        network_participants: get_facet_buckets(&aggregations["network_participants"]["values"]),
+        price_ranges,
+        payment_types: get_facet_buckets(&aggregations["payment_types"]["values"]),
+        fulfillment_types: get_facet_buckets(&aggregations["fulfillment_types"]["values"]),
+        attributes,
+    }
+}
+
+fn set_facet_labels(buckets: &mut [SearchFacetBucket], response: &Value, label_key: &str) {
+    let mut label_map = HashMap::new();
+    if let Some(hits) = response["hits"]["hits"].as_array() {
+        for hit in hits {
+            if let (Some(id), Some(label)) = (hit["_source"]["id"].as_str(), hit["_source"][label_key].as_str()) {
+                label_map.insert(id.to_owned(), label.to_owned());
+            }
+        }
+    }
+    for bucket in buckets {
+        bucket.label = label_map.remove(&bucket.value);
+    }
+}
+
+async fn set_item_facet_labels(es_client: &ElasticSearchClient, facets: &mut ItemSearchFacets) -> Result<(), anyhow::Error> {
+    let get_ids = |buckets: &[SearchFacetBucket]| -> HashSet<Uuid> {
+        buckets.iter().filter_map(|bucket| Uuid::parse_str(&bucket.value).ok()).collect()
+    };
+    let provider_ids = get_ids(&facets.providers);
+    let network_participant_ids = get_ids(&facets.network_participants);
+    let (provider_res, network_participant_res) = try_join!(
+        get_item_support_data(es_client, &provider_ids, ElasticSearchIndex::Provider),
+        get_item_support_data(es_client, &network_participant_ids, ElasticSearchIndex::NetworkParticipant)
+    )?;
+    set_facet_labels(&mut facets.providers, &provider_res, "name");
+    set_facet_labels(&mut facets.network_participants, &network_participant_res, "name");
+    Ok(())
+}
+
+/// Wraps the item query in the ranking profile function_score for relevance, otherwise sorts by distance or price. Ties are broken by id.
+pub fn set_item_search_ranking(base_query: &mut Value, body: &ProductCacheSearchRequest, profile: &SearchRankingProfile, preferred_provider_ids: &[Uuid]) {
+    let origin = json!({"lat": body.fulfillment_location.latitude, "lon": body.fulfillment_location.longitude});
+    let sort = match body.sort.as_ref().unwrap_or(&SearchSortType::Relevance) {
+        SearchSortType::Relevance => {
+            let mut functions = vec![
+                json!({"gauss": {"location_points": {"origin": origin, "scale": format!("{}km", profile.distance_scale_km)}, "multi_value_mode": "min"}, "weight": profile.distance_weight}),
+                json!({"field_value_factor": {"field": "provider_rating", "factor": profile.rating_factor, "modifier": "log1p", "missing": 0}}),
+                json!({"filter": {"term": {"recommended": true}}, "weight": profile.recommended_boost}),
+            ];
+            if !preferred_provider_ids.is_empty() {
+                functions.push(json!({"filter": {"terms": {"provider_cache_id.keyword": preferred_provider_ids}}, "weight": profile.preferred_supplier_boost}));
+            }
+            base_query["query"] = json!({
+                "function_score": {
+                    "query": base_query["query"].take(),
+                    "functions": functions,
+                    "score_mode": "sum",
+                    "boost_mode": "sum"
+                }
+            });
+            json!({"_score": "desc"})
+        }
+        SearchSortType::Nearest => json!({"_geo_distance": {"location_points": origin, "order": "asc", "unit": "km", "mode": "min"}}),
+        SearchSortType::PriceLowToHigh => json!({"price_with_tax": "asc"}),
+        SearchSortType::PriceHighToLow => json!({"price_with_tax": "desc"}),
+    };
+    base_query["sort"] = json!([sort, {"id": "asc"}]);
+}
+
+pub async fn get_item_from_es(es_client: &ElasticSearchClient, body: &ProductCacheSearchRequest, location_cache_ids: &[Uuid], supplier_preference: &SearchSupplierPreference, ranking_profile: &SearchRankingProfile) ->Result<Option<(Vec<String>, Vec<ESProviderItemModel>, Option<Value>)>, anyhow::Error>{
      let mut base_query: Value = json!({
         "size": body.limit,
         "query": {
             "bool": {
                 "must": []
             }
-        },
-        "sort": [{"id": "asc"}]
+        }
     });   
     if !body.query.trim().is_empty() {
         let multi_match_query = json!({
@@ -2243,17 +2806,28 @@ pub async fn get_item_from_es(es_client: &ElasticSearchClient, body: &ProductCac
     }
 
     if let Some(search_after) = &body.offset {
+        // The score, distance or price part of the sort key has to be sent back as a number.
+        let search_after: Vec<Value> = search_after.iter().map(|value| value.parse::<f64>().map_or_else(|_| json!(value), |score| json!(score))).collect();
         base_query["search_after"] = json!(search_after);
     }
 
     if location_cache_ids.is_empty(){
         return Ok(None)
     }
-    let terms_filter = json!({
+    let mut query_filters = vec![json!({
         "terms": {
             "location_ids": location_cache_ids
         }
-    });
+    })];
+    if let Some(included_provider_ids) = &supplier_preference.included_provider_ids {
+        if included_provider_ids.is_empty() {
+            return Ok(None)
+        }
+        query_filters.push(json!({"terms": {"provider_cache_id.keyword": included_provider_ids}}));
+    }
+    if !supplier_preference.excluded_provider_ids.is_empty() {
+        base_query["query"]["bool"]["must_not"] = json!([{"terms": {"provider_cache_id.keyword": &supplier_preference.excluded_provider_ids}}]);
+    }
     base_query["query"]["bool"]
         .as_object_mut()
         .unwrap()
@@ -2261,7 +2835,16 @@ pub async fn get_item_from_es(es_client: &ElasticSearchClient, body: &ProductCac
         .or_insert_with(|| json!([]))
         .as_array_mut()
         .unwrap()
-        .push(terms_filter);
+        .extend(query_filters);
+    set_item_search_ranking(&mut base_query, body, ranking_profile, &supplier_preference.preferred_provider_ids);
+    let facet_filters = get_item_facet_filters(body);
+    if !facet_filters.is_empty() {
+        let post_filters: Vec<&Value> = facet_filters.iter().map(|(_, filter)| filter).collect();
+        base_query["post_filter"] = json!({"bool": {"filter": post_filters}});
+    }
+    if body.offset.is_none() {
+        base_query["aggs"] = get_item_facet_aggregations(&facet_filters);
+    }
     let data = es_client
         .fetch(base_query, ElasticSearchIndex::ProviderItem)
         .await
@@ -2276,10 +2859,11 @@ pub async fn get_item_from_es(es_client: &ElasticSearchClient, body: &ProductCac
             .collect();
         let search_after: Vec<String> = hits.last().map_or(Vec::new(), |hit| {
             hit["sort"].as_array()
-                .map(|arr| arr.iter().filter_map(|v| v.as_str().map(|s| s.to_string())).collect())
+                .map(|arr| arr.iter().filter_map(|v| v.as_str().map(|s| s.to_string()).or_else(|| v.as_f64().map(|n| n.to_string()))).collect())
                 .unwrap_or_default()
         });
-        return Ok(Some((search_after, items)))
+        let aggregations = data.get("aggregations").cloned();
+        return Ok(Some((search_after, items, aggregations)))
     } 
     Err(anyhow!("Something found while fetching data from elastic search"))
     
@@ -2301,38 +2885,50 @@ async fn get_item_support_data(es_client: &ElasticSearchClient, ids: &HashSet<Uu
     Ok(data)
 }
 
-fn extract_es_model_map<T: DeserializeOwned>(
-    response: &Value,
-    first_key: &str,
-    second_key: &str,
-) -> HashMap<Uuid, HashMap<Uuid, T>> {
+fn group_models_by<T>(models: Vec<T>, get_keys: impl Fn(&T) -> (Uuid, Uuid)) -> HashMap<Uuid, HashMap<Uuid, T>> {
     let mut map: HashMap<Uuid, HashMap<Uuid, T>> = HashMap::new();
+    for model in models {
+        let (first_id, second_id) = get_keys(&model);
+        map.entry(first_id).or_default().insert(second_id, model);
+    }
+    map
+}
+fn get_variant_attribute_key(attribute_code: &str) -> &str {
+    attribute_code.rsplit('.').next().unwrap_or(attribute_code)
+}
 
-    if let Some(hits) = response["hits"]["hits"].as_array() {
-        for hit in hits {
-            if let Some(source) = hit["_source"].as_object() {
-                if let Some(first_id_str) = source.get(first_key).and_then(|id| id.as_str()) {
-                    if let Some(second_id_str) = source.get(second_key).and_then(|id| id.as_str()) {
-                        if let (Ok(first_id), Ok(second_id)) =
-                            (first_id_str.parse::<Uuid>(), second_id_str.parse::<Uuid>())
-                        {
-                            if let Ok(model) =
-                                serde_json::from_value::<T>(Value::Object(source.clone()))
-                            {
-                                map.entry(first_id)
-                                    .or_default()
-                                    .insert(second_id, model);
-                            }
-                        }
-                    }
-                }
+pub fn get_variant_combinations(variant: &WSSearchVariant, items: &[WSSearchItem]) -> Vec<WSSearchVariantCombination> {
+    items
+        .iter()
+        .filter(|item| item.parent_item_id.as_deref() == Some(variant.id.as_str()))
+        .filter_map(|item| {
+            let attributes: HashMap<String, String> = variant
+                .attributes
+                .iter()
+                .filter_map(|variant_attribute| {
+                    let key = get_variant_attribute_key(&variant_attribute.attribute_code);
+                    item.attributes
+                        .iter()
+                        .find(|attribute| attribute.key == key)
+                        .map(|attribute| (key.to_owned(), attribute.value.to_owned()))
+                })
+                .collect();
+            if attributes.is_empty() {
+                None
+            } else {
+                Some(WSSearchVariantCombination { item_id: item.id.to_owned(), attributes })
             }
-        }
-    }
+        })
+        .collect()
+}
 
-    map
+pub fn set_variant_combinations(variants: &mut HashMap<String, WSSearchVariant>, items: &[WSSearchItem]) {
+    for variant in variants.values_mut() {
+        variant.combinations = get_variant_combinations(variant, items);
+    }
 }
-fn get_ws_item_from_es_model(item_model: ESProviderItemModel, provider_location_map: &Option<HashMap<Uuid, ESProviderLocationModel>>, provider_variant_map: &Option<HashMap<Uuid, ESProviderItemVariantModel>>) -> Result<Option<WSSearchItem>, anyhow::Error>{
+
+fn get_ws_item_from_es_model(item_model: ESProviderItemModel, provider_location_map: Option<&HashMap<Uuid, ESProviderLocationModel>>, provider_variant_map: Option<&HashMap<Uuid, ESProviderItemVariantModel>>, qty: Option<&BigDecimal>) -> Result<Option<WSSearchItem>, anyhow::Error>{
     let videos: Vec<String> = serde_json::from_value(item_model.videos)?;
     let images : Vec<String> = serde_json::from_value(item_model.images)?;
     let fulfillment_options: Vec<FulfillmentType> = serde_json::from_value(item_model.fulfillment_options)?;
@@ -2347,6 +2943,10 @@ fn get_ws_item_from_es_model(item_model: ESProviderItemModel, provider_location_
         .price_slabs
         .map(serde_json::from_value)
         .transpose()?;
+    let applicable_price_slab = qty
+        .zip(price_slabs.as_ref())
+        .and_then(|(qty, price_slabs)| get_applicable_price_slab(price_slabs, qty))
+        .cloned();
     let attribute_models: Vec<WSSearchItemAttributeModel>= serde_json::from_value(item_model.attributes)?;
     let validity: Option<WSItemValidity> = item_model
         .validity
@@ -2354,12 +2954,11 @@ fn get_ws_item_from_es_model(item_model: ESProviderItemModel, provider_location_
         .transpose()?
         .map(|v| v.get_schema());
     let parent_item_id = provider_variant_map
-    .as_ref()
     .and_then(|map| item_model.variant_cache_id.and_then(|id| map.get(&id)))
     .map(|variant_model| variant_model.variant_id.clone());
     let location_ids: Vec<String> = item_model.location_ids.as_ref().map(|ids| {
         ids.iter()
-            .filter_map(|id| provider_location_map.as_ref()?.get(id).map(|loc| loc.location_id.to_owned()))
+            .filter_map(|id| provider_location_map?.get(id).map(|loc| loc.location_id.to_owned()))
             .collect()
     }).unwrap_or_default();
     if location_ids.is_empty(){
@@ -2395,6 +2994,7 @@ fn get_ws_item_from_es_model(item_model: ESProviderItemModel, provider_location_
         quantity: quantity_model.get_schema(),
         categories: categories_models.into_iter().map(|f|f.get_schema()).collect(),
         price_slabs,
+        applicable_price_slab,
         attributes: attribute_models.into_iter().map(|f|f.get_schema()).collect(),
         validity,
         parent_item_id,
@@ -2403,36 +3003,26 @@ fn get_ws_item_from_es_model(item_model: ESProviderItemModel, provider_location_
     }))
 }
 
-pub async fn get_full_item_data_from_es(
-    es_client: &ElasticSearchClient,
+async fn get_full_item_data_from_backend(
+    backend: &dyn CatalogSearchBackend,
     body: &ProductCacheSearchRequest,
+    supplier_preference: &SearchSupplierPreference,
 ) -> Result<Option<ItemCacheResponseData>, anyhow::Error> {
-    let location_cache_ids = get_servicable_uq_ids_from_es(
-        es_client,
-        "location_cache_id",
-        Some(&body.domain_category_code),
-        Some(&body.country_code),
-        body.category_code.as_ref(),
-        Some(&body.fulfillment_location),
-    )
-    .await?;
+    let location_cache_ids = backend.get_servicable_location_ids(body).await?;
 
-    if let Some((search_after, item_models)) = get_item_from_es(es_client, body, &location_cache_ids).await? {
-        let mut provider_ids = HashSet::new();
-        let mut network_participant_ids = HashSet::new();
-        let mut location_ids = HashSet::new();
-        let mut variant_ids = HashSet::new();
+    if let Some(ItemSearchPageModel { search_after, items: item_models, facets }) = backend.get_items(body, &location_cache_ids, supplier_preference).await? {
+        let mut support_ids = ItemSupportIds::default();
 
         for item_model in &item_models {
-            provider_ids.insert(item_model.provider_cache_id);
-            network_participant_ids.insert(item_model.network_participant_cache_id);
+            support_ids.provider_ids.insert(item_model.provider_cache_id);
+            support_ids.network_participant_ids.insert(item_model.network_participant_cache_id);
             if let Some(variant_id) = item_model.variant_cache_id {
-                variant_ids.insert(variant_id);
+                support_ids.variant_ids.insert(variant_id);
             }
             if let Some(item_location_ids) = &item_model.location_ids {
                 for &location_id in item_location_ids {
                     if location_cache_ids.contains(&location_id) {
-                        location_ids.insert(location_id);
+                        support_ids.location_ids.insert(location_id);
                     }
                 }
             }
@@ -2440,97 +3030,750 @@ pub async fn get_full_item_data_from_es(
 
         // Efficient way to group items by provider
         let mut item_map: HashMap<Uuid, Vec<ESProviderItemModel>> = HashMap::new();
-        for item in item_models {
+        // Providers keep the rank of their best placed item.
+        let mut provider_rank_map: HashMap<Uuid, usize> = HashMap::new();
+        for (rank, item) in item_models.into_iter().enumerate() {
+            provider_rank_map.entry(item.provider_cache_id).or_insert(rank);
             item_map.entry(item.provider_cache_id).or_default().push(item);
         }
 
-        let (network_participant_res, provider_res, location_res, variant_res ) = try_join!(
-            get_item_support_data(es_client, &network_participant_ids, ElasticSearchIndex::NetworkParticipant),
-            get_item_support_data(es_client, &provider_ids, ElasticSearchIndex::Provider),
-            get_item_support_data(es_client, &location_ids, ElasticSearchIndex::ProviderLocation),
-            get_item_support_data(es_client, &variant_ids, ElasticSearchIndex::ProviderItemVariant)
-        )?;
+        let support_models = backend.get_item_support_models(&support_ids).await?;
 
         let mut provider_model_map: HashMap<Uuid, HashMap<Uuid, ESProviderModel>> =
-            extract_es_model_map(&provider_res, "network_participant_cache_id", "id");
+            group_models_by(support_models.providers, |model| (model.network_participant_cache_id, model.id));
         let mut location_model_map: HashMap<Uuid, HashMap<Uuid, ESProviderLocationModel>> =
-            extract_es_model_map(&location_res, "provider_cache_id", "id");
+            group_models_by(support_models.locations, |model| (model.provider_cache_id, model.id));
         let mut variant_model_map: HashMap<Uuid, HashMap<Uuid, ESProviderItemVariantModel>> =
-            extract_es_model_map(&variant_res, "provider_cache_id", "id");
+            group_models_by(support_models.variants, |model| (model.provider_cache_id, model.id));
 
         let mut final_data = Vec::new();
-        tracing::info!("{:?}", network_participant_res);
-        if let Some(hits) = network_participant_res["hits"]["hits"].as_array() {
-            let network_participants: Vec<ESNetworkParticipantModel> =
-                hits.iter()
-                    .filter_map(|hit| serde_json::from_value(hit["_source"].clone()).ok())
-                    .collect();
-
-            for network_participant in network_participants {
-                let mut provider_data = Vec::new();
-
-                if let Some(providers) = provider_model_map.remove(&network_participant.id) {
-                    for (_, provider) in providers {
-                        let provider_variant_models = variant_model_map
-                            .remove(&provider.id);
-                        let provider_location_models =location_model_map
-                            .remove(&provider.id);
-
-                        let item_models = item_map.remove(&provider.id).unwrap_or_default();
-                        // let mut item_final_data= vec![];
-                        let item_final_data: Vec<WSSearchItem> = item_models
-                            .into_iter()
-                            .filter_map(|item_model| get_ws_item_from_es_model(item_model, &provider_location_models, &provider_variant_models).ok().flatten())
-                            .collect();
-                        if item_final_data.is_empty(){
-                            continue
-                        }
-                        let final_variant = provider_variant_models.map(|variants| {
-                                variants.into_values().map(|v| (v.variant_id.to_owned(), v.get_schema())).collect()
-                            }).
-                            unwrap_or_default();
-
-                        let final_location = 
-                            provider_location_models.map(|locations| {
-                                locations.into_values().map(|l| (l.location_id.to_owned(), l.get_schema())).collect()
-                            }).
-                            unwrap_or_default();
+        for network_participant in support_models.network_participants {
+            let mut provider_data = Vec::new();
+
+            if let Some(providers) = provider_model_map.remove(&network_participant.id) {
+                for (_, provider) in providers {
+                    let provider_variant_models = variant_model_map
+                        .remove(&provider.id);
+                    let provider_location_models =location_model_map
+                        .remove(&provider.id);
+
+                    let item_models = item_map.remove(&provider.id).unwrap_or_default();
+                    // let mut item_final_data= vec![];
+                    let item_final_data: Vec<WSSearchItem> = item_models
+                        .into_iter()
+                        .filter_map(|item_model| get_ws_item_from_es_model(item_model, provider_location_models.as_ref(), provider_variant_models.as_ref(), body.qty.as_ref()).ok().flatten())
+                        .collect();
+                    if item_final_data.is_empty(){
+                        continue
+                    }
+                    let mut final_variant = provider_variant_models.map(|variants| {
+                            variants.into_values().map(|v| (v.variant_id.to_owned(), v.get_schema())).collect()
+                        }).
+                        unwrap_or_default();
+                    set_variant_combinations(&mut final_variant, &item_final_data);
+
+                    let final_location = 
+                        provider_location_models.map(|locations| {
+                            locations.into_values().map(|l| (l.location_id.to_owned(), l.get_schema())).collect()
+                        }).
+                        unwrap_or_default();
 
-       
+   
 
-                        provider_data.push(WSSearchProvider {
-                            description: provider.get_ws_provider(),
-                            servicability: HashMap::new(),
-                            variants: Some(final_variant),
-                            locations: final_location,
-                            items: item_final_data,
-                        });
-                    }
+                    provider_data.push((provider_rank_map.get(&provider.id).copied().unwrap_or(usize::MAX), WSSearchProvider {
+                        description: provider.get_ws_provider(),
+                        servicability: HashMap::new(),
+                        variants: Some(final_variant),
+                        locations: final_location,
+                        items: item_final_data,
+                    }));
                 }
-
-                final_data.push(WSSearchData {
-                    bpp: network_participant.get_schema(),
-                    providers: provider_data,
-                });
             }
+            provider_data.sort_by_key(|(rank, _)| *rank);
+            let network_participant_rank = provider_data.first().map_or(usize::MAX, |(rank, _)| *rank);
+
+            final_data.push((network_participant_rank, WSSearchData {
+                bpp: network_participant.get_schema(),
+                providers: provider_data.into_iter().map(|(_, provider)| provider).collect(),
+            }));
         }
+        final_data.sort_by_key(|(rank, _)| *rank);
 
         return Ok(Some(ItemCacheResponseData {
-            network_participants: final_data,
+            network_participants: final_data.into_iter().map(|(_, data)| data).collect(),
             search_after,
+            facets,
         }));
     }
 
     Ok(None)
 }
 
+/// Searches the cache through the configured backend, falling back to Postgres when Elasticsearch fails.
+pub async fn get_full_item_data(es_client: &ElasticSearchClient, pool: &PgPool, search_config: &SearchConfig, ranking_profile: &SearchRankingProfile, body: &ProductCacheSearchRequest, supplier_preference: &SearchSupplierPreference) -> Result<Option<ItemCacheResponseData>, anyhow::Error> {
+    if search_config.backend == SearchBackendType::ElasticSearch {
+        match get_full_item_data_from_backend(&ElasticSearchBackend { es_client, ranking_profile }, body, supplier_preference).await {
+            Ok(data) => return Ok(data),
+            Err(e) => tracing::warn!("Elastic search failed, falling back to postgres search: {:?}", e),
+        }
+    }
+    get_full_item_data_from_backend(&PostgresSearchBackend { pool, ranking_profile }, body, supplier_preference).await
+}
+
+#[async_trait]
+pub trait CatalogSearchBackend: Send + Sync {
+    async fn get_servicable_location_ids(&self, body: &ProductCacheSearchRequest) -> Result<Vec<Uuid>, anyhow::Error>;
+
+    async fn get_items(&self, body: &ProductCacheSearchRequest, location_cache_ids: &[Uuid], supplier_preference: &SearchSupplierPreference) -> Result<Option<ItemSearchPageModel>, anyhow::Error>;
+
+    async fn get_item_support_models(&self, ids: &ItemSupportIds) -> Result<ItemSupportModels, anyhow::Error>;
+
+    async fn get_auto_complete_items(&self, body: &AutoCompleteItemRequest) -> Result<AutoCompleteItemResponseData, anyhow::Error>;
+}
+
+pub struct ElasticSearchBackend<'a> {
+    es_client: &'a ElasticSearchClient,
+    ranking_profile: &'a SearchRankingProfile,
+}
+
+#[async_trait]
+impl CatalogSearchBackend for ElasticSearchBackend<'_> {
+    async fn get_servicable_location_ids(&self, body: &ProductCacheSearchRequest) -> Result<Vec<Uuid>, anyhow::Error> {
+        get_servicable_uq_ids_from_es(
+            self.es_client,
+            "location_cache_id",
+            Some(&body.domain_category_code),
+            Some(&body.country_code),
+            body.category_code.as_ref(),
+            Some(&body.fulfillment_location),
+            None,
+        )
+        .await
+    }
+
+    async fn get_items(&self, body: &ProductCacheSearchRequest, location_cache_ids: &[Uuid], supplier_preference: &SearchSupplierPreference) -> Result<Option<ItemSearchPageModel>, anyhow::Error> {
+        let Some((search_after, items, aggregations)) = get_item_from_es(self.es_client, body, location_cache_ids, supplier_preference, self.ranking_profile).await? else {
+            return Ok(None);
+        };
+        let facets = match aggregations {
+            Some(aggregations) => {
+                let mut facets = get_item_search_facets(&aggregations);
+                set_item_facet_labels(self.es_client, &mut facets).await?;
+                Some(facets)
+            }
+            None => None,
+        };
+        Ok(Some(ItemSearchPageModel { search_after, items, facets }))
+    }
+
+    async fn get_item_support_models(&self, ids: &ItemSupportIds) -> Result<ItemSupportModels, anyhow::Error> {
+        let (network_participant_res, provider_res, location_res, variant_res) = try_join!(
+            get_item_support_data(self.es_client, &ids.network_participant_ids, ElasticSearchIndex::NetworkParticipant),
+            get_item_support_data(self.es_client, &ids.provider_ids, ElasticSearchIndex::Provider),
+            get_item_support_data(self.es_client, &ids.location_ids, ElasticSearchIndex::ProviderLocation),
+            get_item_support_data(self.es_client, &ids.variant_ids, ElasticSearchIndex::ProviderItemVariant)
+        )?;
+        Ok(ItemSupportModels {
+            network_participants: get_es_hit_models(&network_participant_res),
+            providers: get_es_hit_models(&provider_res),
+            locations: get_es_hit_models(&location_res),
+            variants: get_es_hit_models(&variant_res),
+        })
+    }
+
+    async fn get_auto_complete_items(&self, body: &AutoCompleteItemRequest) -> Result<AutoCompleteItemResponseData, anyhow::Error> {
+        get_minimal_item_from_es(self.es_client, body).await
+    }
+}
+
+pub struct PostgresSearchBackend<'a> {
+    pool: &'a PgPool,
+    ranking_profile: &'a SearchRankingProfile,
+}
+
+#[async_trait]
+impl CatalogSearchBackend for PostgresSearchBackend<'_> {
+    async fn get_servicable_location_ids(&self, body: &ProductCacheSearchRequest) -> Result<Vec<Uuid>, anyhow::Error> {
+        get_servicable_location_ids_from_db(self.pool, body).await
+    }
+
+    /// Facets are only aggregated by Elasticsearch, so the Postgres page carries none.
+    async fn get_items(&self, body: &ProductCacheSearchRequest, location_cache_ids: &[Uuid], supplier_preference: &SearchSupplierPreference) -> Result<Option<ItemSearchPageModel>, anyhow::Error> {
+        if location_cache_ids.is_empty() || supplier_preference.included_provider_ids.as_ref().is_some_and(|ids| ids.is_empty()) {
+            return Ok(None);
+        }
+        let item_ids = search_item_ids_from_db(self.pool, body, location_cache_ids, supplier_preference, self.ranking_profile).await?;
+        let search_after = item_ids.last().map(|(id, sort_value)| vec![sort_value.to_string(), id.to_string()]).unwrap_or_default();
+        let item_ids: Vec<Uuid> = item_ids.into_iter().map(|(id, _)| id).collect();
+        let mut items = get_provider_item_cache_data_from_db(self.pool, item_ids.clone()).await?;
+        items.sort_by_key(|item| item_ids.iter().position(|id| id == &item.id));
+        Ok(Some(ItemSearchPageModel { search_after, items, facets: None }))
+    }
+
+    async fn get_item_support_models(&self, ids: &ItemSupportIds) -> Result<ItemSupportModels, anyhow::Error> {
+        let provider_ids: Vec<Uuid> = ids.provider_ids.iter().cloned().collect();
+        let location_ids: Vec<Uuid> = ids.location_ids.iter().cloned().collect();
+        let (network_participants, providers, locations, variants) = try_join!(
+            get_network_participant_cache_data_from_db(self.pool, ids.network_participant_ids.iter().cloned().collect()),
+            get_provider_cache_data_from_db(self.pool, &provider_ids),
+            get_provider_location_cache_data_from_db(self.pool, Some(&location_ids), None),
+            get_provider_item_variant_cache_data_from_db(self.pool, Some(ids.variant_ids.iter().cloned().collect()), None)
+        )?;
+        Ok(ItemSupportModels { network_participants, providers, locations, variants })
+    }
+
+    async fn get_auto_complete_items(&self, body: &AutoCompleteItemRequest) -> Result<AutoCompleteItemResponseData, anyhow::Error> {
+        get_minimal_item_from_db(self.pool, body).await
+    }
+}
+
+#[tracing::instrument(name = "get servicable location ids from db", skip(pool))]
+pub async fn get_servicable_location_ids_from_db(pool: &PgPool, body: &ProductCacheSearchRequest) -> Result<Vec<Uuid>, anyhow::Error> {
+    let location_ids = sqlx::query_scalar!(
+        r#"
+        SELECT plc.id
+        FROM provider_location_cache AS plc
+        WHERE EXISTS (
+            SELECT 1 FROM provider_servicability_hyperlocal_cache AS shc
+            WHERE shc.provider_location_cache_id = plc.id AND shc.domain_code = $1
+            AND ($2::text IS NULL OR shc.category_code = $2)
+            AND ST_DWithin(
+                ST_SetSRID(ST_MakePoint(plc.longitude::float8, plc.latitude::float8), 4326)::geography,
+                ST_SetSRID(ST_MakePoint($3, $4), 4326)::geography,
+                shc.radius
+            )
+        ) OR EXISTS (
+            SELECT 1 FROM provider_servicability_geo_json_cache AS sgc
+            WHERE sgc.provider_location_cache_id = plc.id AND sgc.domain_code = $1
+            AND ($2::text IS NULL OR sgc.category_code = $2)
+            AND ST_Intersects(sgc.geom, ST_SetSRID(ST_MakePoint($3, $4), 4326))
+        ) OR EXISTS (
+            SELECT 1 FROM provider_servicability_intercity_cache AS sic
+            WHERE sic.provider_location_cache_id = plc.id AND sic.domain_code = $1
+            AND ($2::text IS NULL OR sic.category_code = $2) AND sic.pincode = $5
+        ) OR EXISTS (
+            SELECT 1 FROM provider_servicability_country_cache AS scc
+            WHERE scc.provider_location_cache_id = plc.id AND scc.domain_code = $1
+            AND ($2::text IS NULL OR scc.category_code = $2) AND scc.country_code = $6
+        )
+        "#,
+        &body.domain_category_code as &CategoryDomain,
+        body.category_code.as_deref(),
+        body.fulfillment_location.longitude,
+        body.fulfillment_location.latitude,
+        &body.fulfillment_location.area_code,
+        &body.country_code as &CountryCode,
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e).context("A database failure occurred while fetching servicable locations")
+    })?;
+    Ok(location_ids)
+}
+
+pub fn get_search_like_patterns(terms: &[String]) -> Vec<String> {
+    terms
+        .iter()
+        .map(|term| format!("%{}%", term.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")))
+        .collect()
+}
+
+/// Matches any query term exactly against item ids and codes, or by trigram against names and descriptions.
+fn push_item_text_filter(query_builder: &mut QueryBuilder<'_, Postgres>, query: &str) {
+    let terms: Vec<String> = query.split_whitespace().map(|term| term.to_owned()).collect();
+    if terms.is_empty() {
+        return;
+    }
+    let patterns = get_search_like_patterns(&terms);
+    query_builder.push(" AND (ic.item_name % ");
+    query_builder.push_bind(query.trim().to_owned());
+    query_builder.push(" OR ic.item_id = ANY(");
+    query_builder.push_bind(terms.clone());
+    query_builder.push(") OR ic.item_code = ANY(");
+    query_builder.push_bind(terms);
+    query_builder.push(") OR ic.item_name ILIKE ANY(");
+    query_builder.push_bind(patterns.clone());
+    query_builder.push(") OR ic.short_desc ILIKE ANY(");
+    query_builder.push_bind(patterns.clone());
+    query_builder.push(") OR ic.long_desc ILIKE ANY(");
+    query_builder.push_bind(patterns);
+    query_builder.push("))");
+}
+
+fn push_item_attribute_filters(query_builder: &mut QueryBuilder<'_, Postgres>, attributes: Option<&Vec<SearchAttributeFilter>>) {
+    for attribute in attributes.into_iter().flatten().filter(|attribute| !attribute.values.is_empty()) {
+        query_builder.push(" AND EXISTS (SELECT 1 FROM jsonb_array_elements(COALESCE(ic.attributes, '[]'::jsonb)) AS attribute WHERE attribute->>'key' = ");
+        query_builder.push_bind(attribute.key.to_owned());
+        query_builder.push(" AND attribute->>'value' = ANY(");
+        query_builder.push_bind(attribute.values.clone());
+        query_builder.push("))");
+    }
+}
+
+fn push_item_distance_km(query_builder: &mut QueryBuilder<'_, Postgres>, location: &ProductFulFillmentLocation) {
+    query_builder.push("(SELECT MIN(ST_Distance(ST_SetSRID(ST_MakePoint(plc.longitude::float8, plc.latitude::float8), 4326)::geography, ST_SetSRID(ST_MakePoint(");
+    query_builder.push_bind(location.longitude);
+    query_builder.push(", ");
+    query_builder.push_bind(location.latitude);
+    query_builder.push("), 4326)::geography)) / 1000 FROM item_location_cache_relationship AS ilcr JOIN provider_location_cache AS plc ON ilcr.location_cache_id = plc.id WHERE ilcr.item_cache_id = ic.id)");
+}
+
+/// Text relevance is only scored by Elasticsearch, the Postgres relevance sort applies the ranking profile boosts alone.
+fn push_item_sort_value(query_builder: &mut QueryBuilder<'_, Postgres>, body: &ProductCacheSearchRequest, profile: &SearchRankingProfile, preferred_provider_ids: &[Uuid]) {
+    match body.sort.as_ref().unwrap_or(&SearchSortType::Relevance) {
+        SearchSortType::Relevance => {
+            query_builder.push("((CASE WHEN ic.provider_cache_id = ANY(");
+            query_builder.push_bind(preferred_provider_ids.to_vec());
+            query_builder.push(") THEN ");
+            query_builder.push_bind(profile.preferred_supplier_boost);
+            query_builder.push(" ELSE 0 END) + (CASE WHEN ic.recommended THEN ");
+            query_builder.push_bind(profile.recommended_boost);
+            query_builder.push(" ELSE 0 END) + log(1 + ");
+            query_builder.push_bind(profile.rating_factor);
+            query_builder.push(" * COALESCE(pc.rating, 0)) + ");
+            query_builder.push_bind(profile.distance_weight);
+            // Same gauss decay as Elasticsearch, capped to keep power() from underflowing.
+            query_builder.push(" * power(0.5, LEAST(power(");
+            push_item_distance_km(query_builder, &body.fulfillment_location);
+            query_builder.push(" / ");
+            query_builder.push_bind(profile.distance_scale_km);
+            query_builder.push(", 2), 50)))::float8");
+        }
+        SearchSortType::Nearest => push_item_distance_km(query_builder, &body.fulfillment_location),
+        SearchSortType::PriceLowToHigh | SearchSortType::PriceHighToLow => {
+            query_builder.push("ic.price_with_tax::float8");
+        }
+    }
+}
+
+/// Returns the matching item ids with their sort value, the search_after key is (sort value, id).
+#[tracing::instrument(name = "search item ids from db", skip(pool))]
+pub async fn search_item_ids_from_db(pool: &PgPool, body: &ProductCacheSearchRequest, location_cache_ids: &[Uuid], supplier_preference: &SearchSupplierPreference, ranking_profile: &SearchRankingProfile) -> Result<Vec<(Uuid, f64)>, anyhow::Error> {
+    let preferred_provider_ids = &supplier_preference.preferred_provider_ids;
+    let mut query_builder = QueryBuilder::new("SELECT ic.id, ");
+    push_item_sort_value(&mut query_builder, body, ranking_profile, preferred_provider_ids);
+    query_builder.push(
+        r#" AS sort_value
+        FROM provider_item_cache AS ic
+        JOIN provider_cache AS pc ON ic.provider_cache_id = pc.id
+        WHERE EXISTS (
+            SELECT 1 FROM item_location_cache_relationship AS ilcr
+            WHERE ilcr.item_cache_id = ic.id AND ilcr.location_cache_id = ANY(
+        "#,
+    );
+    query_builder.push_bind(location_cache_ids.to_vec());
+    query_builder.push("))");
+    push_item_text_filter(&mut query_builder, &body.query);
+    if let Some(category_codes) = body.category_codes.as_ref().filter(|codes| !codes.is_empty()) {
+        query_builder.push(" AND EXISTS (SELECT 1 FROM jsonb_array_elements(COALESCE(ic.categories, '[]'::jsonb)) AS category WHERE category->>'code' = ANY(");
+        query_builder.push_bind(category_codes.clone());
+        query_builder.push("))");
+    }
+    if let Some(provider_ids) = body.provider_ids.as_ref().filter(|ids| !ids.is_empty()) {
+        query_builder.push(" AND ic.provider_cache_id = ANY(");
+        query_builder.push_bind(provider_ids.clone());
+        query_builder.push(")");
+    }
+    if let Some(network_participant_ids) = body.network_participant_ids.as_ref().filter(|ids| !ids.is_empty()) {
+        query_builder.push(" AND pc.network_participant_cache_id = ANY(");
+        query_builder.push_bind(network_participant_ids.clone());
+        query_builder.push(")");
+    }
+    if let Some(price_range) = &body.price_range {
+        if let Some(min) = &price_range.min {
+            query_builder.push(" AND ic.price_with_tax >= ");
+            query_builder.push_bind(min.clone());
+        }
+        if let Some(max) = &price_range.max {
+            query_builder.push(" AND ic.price_with_tax <= ");
+            query_builder.push_bind(max.clone());
+        }
+    }
+    if let Some(payment_type) = &body.payment_type {
+        query_builder.push(" AND ic.payment_options @> ");
+        query_builder.push_bind(json!([payment_type]));
+    }
+    if let Some(fulfillment_type) = &body.fulfillment_type {
+        query_builder.push(" AND ic.fulfillment_options @> ");
+        query_builder.push_bind(json!([fulfillment_type]));
+    }
+    push_item_attribute_filters(&mut query_builder, body.attributes.as_ref());
+    if let Some(included_provider_ids) = &supplier_preference.included_provider_ids {
+        query_builder.push(" AND ic.provider_cache_id = ANY(");
+        query_builder.push_bind(included_provider_ids.clone());
+        query_builder.push(")");
+    }
+    if !supplier_preference.excluded_provider_ids.is_empty() {
+        query_builder.push(" AND NOT (ic.provider_cache_id = ANY(");
+        query_builder.push_bind(supplier_preference.excluded_provider_ids.clone());
+        query_builder.push("))");
+    }
+    let (operator, direction) = match body.sort.as_ref().unwrap_or(&SearchSortType::Relevance) {
+        SearchSortType::Relevance | SearchSortType::PriceHighToLow => (" < ", " DESC"),
+        SearchSortType::Nearest | SearchSortType::PriceLowToHigh => (" > ", " ASC"),
+    };
+    let search_after = body.offset.as_deref().and_then(|offset| match offset {
+        [sort_value, id] => Some((sort_value.parse::<f64>().ok()?, Uuid::parse_str(id).ok()?)),
+        _ => None,
+    });
+    if let Some((sort_value, id)) = search_after {
+        query_builder.push(" AND (");
+        push_item_sort_value(&mut query_builder, body, ranking_profile, preferred_provider_ids);
+        query_builder.push(operator);
+        query_builder.push_bind(sort_value);
+        query_builder.push(" OR (");
+        push_item_sort_value(&mut query_builder, body, ranking_profile, preferred_provider_ids);
+        query_builder.push(" = ");
+        query_builder.push_bind(sort_value);
+        query_builder.push(" AND ic.id > ");
+        query_builder.push_bind(id);
+        query_builder.push("))");
+    }
+    query_builder.push(" ORDER BY ");
+    push_item_sort_value(&mut query_builder, body, ranking_profile, preferred_provider_ids);
+    query_builder.push(direction);
+    query_builder.push(", ic.id LIMIT ");
+    query_builder.push_bind(i64::from(body.limit));
+
+    let item_ids = query_builder
+        .build_query_as::<(Uuid, f64)>()
+        .fetch_all(pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to execute query: {:?}", e);
+            anyhow::Error::new(e).context("A database failure occurred while searching provider items")
+        })?;
+    Ok(item_ids)
+}
+
+#[tracing::instrument(name = "get minimal item from db", skip(pool))]
+async fn get_minimal_item_from_db(pool: &PgPool, body: &AutoCompleteItemRequest) -> Result<AutoCompleteItemResponseData, anyhow::Error> {
+    let mut query_builder = QueryBuilder::new(
+        r#"
+        SELECT ic.provider_cache_id, pc.network_participant_cache_id, ic.id, ic.item_id, ic.item_code, ic.item_name
+        FROM provider_item_cache AS ic
+        JOIN provider_cache AS pc ON ic.provider_cache_id = pc.id
+        WHERE 1=1
+        "#,
+    );
+    push_item_text_filter(&mut query_builder, &body.query);
+    push_item_attribute_filters(&mut query_builder, body.attributes.as_ref());
+    if let Some(search_after) = body.offset.as_ref().and_then(|offset| offset.first()).and_then(|id| Uuid::parse_str(id).ok()) {
+        query_builder.push(" AND ic.id > ");
+        query_builder.push_bind(search_after);
+    }
+    query_builder.push(" ORDER BY ic.id LIMIT ");
+    query_builder.push_bind(i64::from(body.limit));
+
+    let results = query_builder
+        .build_query_as::<ESAutoCompleteProviderItemModel>()
+        .fetch_all(pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to execute query: {:?}", e);
+            anyhow::Error::new(e).context("A database failure occurred while fetching autocomplete items")
+        })?;
+    let search_after = results.last().map(|item| vec![item.id.to_string()]).unwrap_or_default();
+    let items = results.into_iter().map(|a| a.into_schema()).collect();
+    Ok(AutoCompleteItemResponseData { items, search_after })
+}
+
+
+
+
+
+
+
+fn get_es_hit_models<T: DeserializeOwned>(response: &Value) -> Vec<T> {
+    response["hits"]["hits"]
+        .as_array()
+        .map(|hits| hits.iter().filter_map(|hit| serde_json::from_value(hit["_source"].clone()).ok()).collect())
+        .unwrap_or_default()
+}
 
+async fn get_es_models_by_term<T: DeserializeOwned>(es_client: &ElasticSearchClient, index: ElasticSearchIndex, filters: Value, size: i64) -> Result<Vec<T>, anyhow::Error> {
+    let data = es_client
+        .fetch(json!({
+            "size": size,
+            "query": {
+                "bool": {
+                    "filter": filters
+                }
+            }
+        }), index)
+        .await?;
+    Ok(get_es_hit_models(&data))
+}
 
+async fn get_item_model_from_es(es_client: &ElasticSearchClient, body: &ItemFetchRequest) -> Result<Option<ESProviderItemModel>, anyhow::Error> {
+    let item_filters = match (body.id, &body.bpp_id, &body.provider_id, &body.item_id) {
+        (Some(id), _, _, _) => json!([{ "term": { "id": id } }]),
+        (None, Some(bpp_id), Some(provider_id), Some(item_id)) => {
+            let network_participants: Vec<ESNetworkParticipantModel> = get_es_models_by_term(es_client, ElasticSearchIndex::NetworkParticipant, json!([{ "term": { "subscriber_id": bpp_id } }]), 1).await?;
+            let Some(network_participant) = network_participants.into_iter().next() else {
+                return Ok(None);
+            };
+            let providers: Vec<ESProviderModel> = get_es_models_by_term(es_client, ElasticSearchIndex::Provider, json!([
+                { "term": { "provider_id": provider_id } },
+                { "term": { "network_participant_cache_id": network_participant.id } }
+            ]), 1).await?;
+            let Some(provider) = providers.into_iter().next() else {
+                return Ok(None);
+            };
+            json!([
+                { "term": { "item_id.keyword": item_id } },
+                { "term": { "provider_cache_id.keyword": provider.id } }
+            ])
+        }
+        _ => return Ok(None),
+    };
+    let items: Vec<ESProviderItemModel> = get_es_models_by_term(es_client, ElasticSearchIndex::ProviderItem, item_filters, 1).await?;
+    Ok(items.into_iter().next())
+}
 
+async fn get_variant_item_models_from_es(es_client: &ElasticSearchClient, variant_cache_id: Option<Uuid>) -> Result<Vec<ESProviderItemModel>, anyhow::Error> {
+    match variant_cache_id {
+        Some(variant_cache_id) => get_es_models_by_term(es_client, ElasticSearchIndex::ProviderItem, json!([{ "term": { "variant_cache_id": variant_cache_id } }]), 100).await,
+        None => Ok(vec![]),
+    }
+}
 
+async fn get_location_servicability_from_es<T: DeserializeOwned>(es_client: &ElasticSearchClient, location_cache_ids: &[Uuid], index: ElasticSearchIndex) -> Result<Vec<T>, anyhow::Error> {
+    get_es_models_by_term(es_client, index, json!([{ "terms": { "location_cache_id": location_cache_ids } }]), 1000).await
+}
 
+fn get_location_servicability_map(location_map: &HashMap<Uuid, ESProviderLocationModel>, hyperlocal_models: Vec<ESHyperlocalServicabilityModel>, country_models: Vec<ESCountryServicabilityModel>, intercity_models: Vec<ESInterCityServicabilityModel>, geo_json_models: Vec<ESGeoJsonServicabilityModel>) -> HashMap<String, WSSearchServicability> {
+    let mut servicability_map: HashMap<String, WSSearchServicability> = HashMap::new();
+    let location_id = |location_cache_id: &Uuid| location_map.get(location_cache_id).map(|location| location.location_id.to_owned());
+    for model in hyperlocal_models {
+        if let Some(location_id) = location_id(&model.location_cache_id) {
+            servicability_map.entry(location_id).or_default().hyperlocal.push(model.get_schema());
+        }
+    }
+    for model in country_models {
+        if let Some(location_id) = location_id(&model.location_cache_id) {
+            servicability_map.entry(location_id).or_default().country.push(model.get_schema());
+        }
+    }
+    for model in geo_json_models {
+        if let Some(location_id) = location_id(&model.location_cache_id) {
+            servicability_map.entry(location_id).or_default().geo_json.push(model.get_schema());
+        }
+    }
+    let mut intercity_map: HashMap<(String, Option<String>), HashSet<String>> = HashMap::new();
+    for model in intercity_models {
+        if let Some(location_id) = location_id(&model.location_cache_id) {
+            intercity_map.entry((location_id, model.category_code)).or_default().insert(model.pincode);
+        }
+    }
+    for ((location_id, category_code), pincodes) in intercity_map {
+        servicability_map.entry(location_id).or_default().intercity.push(WSServicabilityData { category_code, value: pincodes });
+    }
+    servicability_map
+}
 
-pub async fn generate_cache() -> Result<(), anyhow::Error>{
+pub async fn get_item_detail_from_es(es_client: &ElasticSearchClient, body: &ItemFetchRequest) -> Result<Option<ItemFetchResponseData>, anyhow::Error> {
+    let Some(item_model) = get_item_model_from_es(es_client, body).await? else {
+        return Ok(None);
+    };
+    let location_cache_ids = item_model.location_ids.clone().unwrap_or_default();
+    let provider_filter = json!([{ "term": { "provider_cache_id": item_model.provider_cache_id } }]);
+    let (network_participants, providers, location_models, variant_models, variant_item_models) = try_join!(
+        get_es_models_by_term::<ESNetworkParticipantModel>(es_client, ElasticSearchIndex::NetworkParticipant, json!([{ "term": { "id": item_model.network_participant_cache_id } }]), 1),
+        get_es_models_by_term::<ESProviderModel>(es_client, ElasticSearchIndex::Provider, json!([{ "term": { "id": item_model.provider_cache_id } }]), 1),
+        get_es_models_by_term::<ESProviderLocationModel>(es_client, ElasticSearchIndex::ProviderLocation, provider_filter.clone(), 1000),
+        get_es_models_by_term::<ESProviderItemVariantModel>(es_client, ElasticSearchIndex::ProviderItemVariant, provider_filter, 1000),
+        get_variant_item_models_from_es(es_client, item_model.variant_cache_id),
+    )?;
+    let (Some(network_participant), Some(provider)) = (network_participants.into_iter().next(), providers.into_iter().next()) else {
+        return Ok(None);
+    };
+    let (hyperlocal_models, country_models, intercity_models, geo_json_models) = try_join!(
+        get_location_servicability_from_es(es_client, &location_cache_ids, ElasticSearchIndex::ProviderServicabilityHyperLocal),
+        get_location_servicability_from_es(es_client, &location_cache_ids, ElasticSearchIndex::ProviderServicabilityCountry),
+        get_location_servicability_from_es(es_client, &location_cache_ids, ElasticSearchIndex::ProviderServicabilityInterCity),
+        get_location_servicability_from_es(es_client, &location_cache_ids, ElasticSearchIndex::ProviderServicabilityGeoJson),
+    )?;
+
+    let servicable_location_ids = match &body.fulfillment_location {
+        Some(fulfillment_location) => Some(get_servicable_uq_ids_from_es(
+            es_client,
+            "location_cache_id",
+            Some(&item_model.domain_code),
+            Some(&item_model.country_code),
+            None,
+            Some(fulfillment_location),
+            Some(&location_cache_ids),
+        )
+        .await?),
+        None => None,
+    };
+
+    let mut location_map: HashMap<Uuid, ESProviderLocationModel> = location_models.into_iter().map(|location| (location.id, location)).collect();
+    let mut variant_map: HashMap<Uuid, ESProviderItemVariantModel> = variant_models.into_iter().map(|variant| (variant.id, variant)).collect();
+    let variant_cache_id = item_model.variant_cache_id;
+    let Some(item) = get_ws_item_from_es_model(item_model, Some(&location_map), Some(&variant_map), body.qty.as_ref())? else {
+        return Ok(None);
+    };
+    let variant_items: Vec<WSSearchItem> = variant_item_models
+        .into_iter()
+        .filter_map(|variant_item| get_ws_item_from_es_model(variant_item, Some(&location_map), Some(&variant_map), None).ok().flatten())
+        .collect();
+    let variant = variant_cache_id
+        .and_then(|id| variant_map.remove(&id))
+        .map(|variant_model| {
+            let mut variant = variant_model.get_schema();
+            variant.combinations = get_variant_combinations(&variant, &variant_items);
+            variant
+        });
+    let servicable_location_ids = servicable_location_ids.map(|ids| {
+        ids.iter()
+            .filter_map(|id| location_map.get(id).map(|location| location.location_id.to_owned()))
+            .collect()
+    });
+    location_map.retain(|id, _| location_cache_ids.contains(id));
+    let servicability = get_location_servicability_map(&location_map, hyperlocal_models, country_models, intercity_models, geo_json_models);
+    let locations = location_map
+        .into_values()
+        .map(|location| (location.location_id.to_owned(), location.get_schema()))
+        .collect();
+
+    Ok(Some(ItemFetchResponseData {
+        bpp: network_participant.get_schema(),
+        provider: provider.get_ws_provider(),
+        item,
+        variant,
+        locations,
+        servicability,
+        servicable_location_ids,
+        price_change: None,
+    }))
+}
+
+#[tracing::instrument(name = "fetch location servicability matches", skip(pool))]
+async fn fetch_location_servicability_matches(pool: &PgPool, body: &ServicabilityCheckRequest) -> Result<Vec<ServicabilityMatchModel>, anyhow::Error> {
+    let rows = sqlx::query_as!(
+        ServicabilityMatchModel,
+        r#"
+        WITH provider_location AS (
+            SELECT plc.id, plc.location_id, plc.latitude, plc.longitude
+            FROM provider_location_cache AS plc
+            JOIN provider_cache AS pc ON plc.provider_cache_id = pc.id
+            JOIN network_participant_cache AS npc ON pc.network_participant_cache_id = npc.id
+            WHERE npc.subscriber_id = $1 AND pc.provider_id = $2
+            AND ($3::text[] IS NULL OR plc.location_id = ANY($3))
+        )
+        SELECT pl.location_id AS "location_id!", 'hyperlocal' AS "servicability_type!: ServicabilityRuleType", shc.category_code,
+            ST_DWithin(
+                ST_SetSRID(ST_MakePoint(pl.longitude::float8, pl.latitude::float8), 4326)::geography,
+                ST_SetSRID(ST_MakePoint($6, $7), 4326)::geography,
+                shc.radius
+            ) AS "is_match!"
+        FROM provider_servicability_hyperlocal_cache AS shc
+        JOIN provider_location AS pl ON shc.provider_location_cache_id = pl.id
+        WHERE shc.domain_code = $4 AND ($5::text IS NULL OR shc.category_code IS NULL OR shc.category_code = $5)
+        UNION ALL
+        SELECT pl.location_id, 'geo_json', sgc.category_code,
+            ST_Intersects(sgc.geom, ST_SetSRID(ST_MakePoint($6, $7), 4326))
+        FROM provider_servicability_geo_json_cache AS sgc
+        JOIN provider_location AS pl ON sgc.provider_location_cache_id = pl.id
+        WHERE sgc.domain_code = $4 AND ($5::text IS NULL OR sgc.category_code IS NULL OR sgc.category_code = $5)
+        UNION ALL
+        SELECT pl.location_id, 'intercity', sic.category_code, bool_or(sic.pincode = $8)
+        FROM provider_servicability_intercity_cache AS sic
+        JOIN provider_location AS pl ON sic.provider_location_cache_id = pl.id
+        WHERE sic.domain_code = $4 AND ($5::text IS NULL OR sic.category_code IS NULL OR sic.category_code = $5)
+        GROUP BY pl.location_id, sic.category_code
+        UNION ALL
+        SELECT pl.location_id, 'country', scc.category_code, scc.country_code = $9
+        FROM provider_servicability_country_cache AS scc
+        JOIN provider_location AS pl ON scc.provider_location_cache_id = pl.id
+        WHERE scc.domain_code = $4 AND ($5::text IS NULL OR scc.category_code IS NULL OR scc.category_code = $5)
+        "#,
+        &body.bpp_id,
+        &body.provider_id,
+        body.location_ids.as_deref() as Option<&[String]>,
+        &body.domain_category_code as &CategoryDomain,
+        body.category_code.as_deref(),
+        body.fulfillment_location.longitude,
+        body.fulfillment_location.latitude,
+        &body.fulfillment_location.area_code,
+        &body.country_code as &CountryCode,
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e).context("A database failure occurred while checking location servicability")
+    })?;
+    Ok(rows)
+}
+
+/// Checks each cached provider location against the drop-off. `is_servicable` is `None` when no servicability is cached for a location.
+pub async fn get_location_servicability(pool: &PgPool, body: &ServicabilityCheckRequest) -> Result<Vec<LocationServicability>, anyhow::Error> {
+    let matches = fetch_location_servicability_matches(pool, body).await?;
+    let mut location_map: HashMap<String, LocationServicability> = body
+        .location_ids
+        .iter()
+        .flatten()
+        .map(|location_id| (location_id.to_owned(), LocationServicability { location_id: location_id.to_owned(), is_servicable: None, matched_rules: vec![] }))
+        .collect();
+    for servicability_match in matches {
+        let location = location_map
+            .entry(servicability_match.location_id.to_owned())
+            .or_insert_with(|| LocationServicability { location_id: servicability_match.location_id.to_owned(), is_servicable: None, matched_rules: vec![] });
+        let is_match = servicability_match.is_match;
+        location.is_servicable = Some(location.is_servicable.unwrap_or(false) || is_match);
+        if is_match {
+            location.matched_rules.push(servicability_match.get_rule());
+        }
+    }
+    let mut locations: Vec<LocationServicability> = location_map.into_values().collect();
+    locations.sort_by(|a, b| a.location_id.cmp(&b.location_id));
+    Ok(locations)
+}
+
+#[tracing::instrument(name = "get item price history", skip(pool))]
+pub async fn get_item_price_history(pool: &PgPool, body: &ItemPriceHistoryRequest) -> Result<Vec<ItemPriceHistory>, anyhow::Error> {
+    let rows = sqlx::query_as!(
+        ItemPriceHistoryModel,
+        r#"
+        SELECT currency AS "currency: CurrencyType", price_with_tax, price_without_tax, offered_price,
+            maximum_price, available_qty, created_on
+        FROM provider_item_price_history
+        WHERE subscriber_id = $1 AND provider_id = $2 AND item_id = $3
+        AND ($4::timestamptz IS NULL OR created_on >= $4)
+        AND ($5::timestamptz IS NULL OR created_on <= $5)
+        ORDER BY created_on DESC
+        LIMIT $6
+        "#,
+        &body.bpp_id,
+        &body.provider_id,
+        &body.item_id,
+        body.from,
+        body.to,
+        body.limit.min(ITEM_PRICE_HISTORY_MAX_LIMIT),
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e).context("A database failure occurred while fetching item price history")
+    })?;
+    Ok(rows.into_iter().map(|row| row.get_schema()).collect())
+}
+
+/// Compares the item's current tax exclusive price with the unit price of the business's latest order for it.
+pub async fn get_item_price_change(pool: &PgPool, business_id: Uuid, data: &ItemFetchResponseData) -> Result<Option<ItemPriceChange>, anyhow::Error> {
+    let last_ordered_item = fetch_last_ordered_item(pool, business_id, &data.bpp.subscriber_id, &data.provider.id, &data.item.id).await?;
+    Ok(last_ordered_item.map(|last_ordered_item| {
+        ItemPriceChange::new(last_ordered_item.created_on, last_ordered_item.unit_price, data.item.get_price_without_tax(&last_ordered_item.qty))
+    }))
+}
+
+pub async fn generate_cache(incremental: bool) -> Result<(), anyhow::Error>{
     let configuration = get_configuration().expect("Failed to read configuration.");
     let connection_pool = PgPool::connect_with(configuration.database.with_db())
         .await
@@ -2544,9 +3787,16 @@ pub async fn generate_cache() -> Result<(), anyhow::Error>{
         let subscribed_locations = fetch_search_locations(&connection_pool).await?;
         if let Some(np_detail) =  get_np_detail(&connection_pool, &StartUpMap::default(), &business_account.subscriber_id,
                 &ONDCNetworkType::Bap).await?{
-            let es_client = configuration.elastic_search.client();
-            clear_network_participant_cache(&connection_pool, &es_client).await?;
             for subscribed_location in subscribed_locations{
+                let refreshed_on = Utc::now();
+                let catalog_inc = match subscribed_location.last_refreshed_on {
+                    Some(last_refreshed_on) if incremental => Some(ProductCatalogInc{
+                        mode: CatalogIncMode::Pull,
+                        start_time: Some(last_refreshed_on),
+                        end_time: Some(refreshed_on),
+                    }),
+                    _ => None
+                };
                 let req_body = ProductSearchRequest{
                     query: "".to_owned(),
                     transaction_id:Uuid::new_v4(),
@@ -2558,7 +3808,8 @@ pub async fn generate_cache() -> Result<(), anyhow::Error>{
                     search_type: ProductSearchType::City,
                     fulfillment_locations: None,
                     city_code: subscribed_location.city_code,
-                    update_cache: true 
+                    update_cache: true,
+                    catalog_inc
                 };
                 let ondc_search_payload =
                     get_ondc_search_payload(&user_account, &business_account, &req_body, &np_detail)?;
@@ -2577,6 +3828,7 @@ pub async fn generate_cache() -> Result<(), anyhow::Error>{
                     configuration.ondc.observability.is_enabled,
                 );
                 try_join!(task_1, task_2)?;
+                update_search_location_refreshed_on(&connection_pool, subscribed_location.id, refreshed_on).await?;
             }
         }
 
@@ -2588,7 +3840,7 @@ pub async fn generate_cache() -> Result<(), anyhow::Error>{
 
 pub async fn fetch_search_locations(pool: &PgPool) -> Result<Vec<SearchLocationModel>, sqlx::Error> {
     let query = r#"
-        SELECT country_code, city_code, domain_category_code
+        SELECT id, country_code, city_code, domain_category_code, last_refreshed_on
         FROM subscribed_search_location
     "#;
 
@@ -2599,6 +3851,25 @@ pub async fn fetch_search_locations(pool: &PgPool) -> Result<Vec<SearchLocationM
     Ok(results)
 }
 
+#[tracing::instrument(name = "update search location refreshed on", skip(pool))]
+pub async fn update_search_location_refreshed_on(pool: &PgPool, id: i32, refreshed_on: DateTime<Utc>) -> Result<(), anyhow::Error> {
+    sqlx::query!(
+        r#"
+        UPDATE subscribed_search_location SET last_refreshed_on = $2
+        WHERE id = $1
+        "#,
+        id,
+        refreshed_on
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e).context("A database failure occurred while updating search location refresh time")
+    })?;
+    Ok(())
+}
+
 pub async fn insert_subscribed_search_location(
     pool: &PgPool,
     city_code: &str,
@@ -2624,17 +3895,20 @@ pub async fn insert_subscribed_search_location(
 
 pub async fn fetch_provider_item_ids(
     pool: &PgPool,
+    created_before: DateTime<Utc>,
+    after_id: Uuid,
     limit: i64,
-    offset: i64,
 ) -> Result<Vec<uuid::Uuid>, anyhow::Error> {
     let rows = sqlx::query!(
         r#"
         SELECT id FROM provider_item_cache
-        ORDER BY created_on
-        LIMIT $1 OFFSET $2
+        WHERE created_on <= $1 AND id > $2
+        ORDER BY id
+        LIMIT $3
         "#,
-        limit,
-        offset
+        created_before,
+        after_id,
+        limit
     )
     .fetch_all(pool)
     .await?;
@@ -2643,61 +3917,259 @@ pub async fn fetch_provider_item_ids(
     Ok(ids)
 }
 
+#[tracing::instrument(name = "fetch provider ids updated since", skip(pool))]
+async fn fetch_provider_ids_updated_since(pool: &PgPool, updated_since: DateTime<Utc>) -> Result<Vec<Uuid>, anyhow::Error> {
+    let ids = sqlx::query_scalar!(
+        r#"SELECT id FROM provider_cache WHERE COALESCE(updated_on, created_on) >= $1"#,
+        updated_since
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e).context("A database failure occurred while fetching updated provider ids")
+    })?;
+    Ok(ids)
+}
 
-pub async fn regenerate_cache_to_es() -> Result<(), anyhow::Error> {
-    let configuration = get_configuration().expect("Failed to read configuration.");
-    let pool = PgPool::connect_with(configuration.database.with_db())
-        .await
-        .expect("Failed to connect to Postgres.");
-    let es_client = configuration.elastic_search.client();
+#[tracing::instrument(name = "fetch provider item ids by provider", skip(pool))]
+async fn fetch_provider_item_ids_by_provider(pool: &PgPool, provider_ids: &[Uuid]) -> Result<Vec<Uuid>, anyhow::Error> {
+    let ids = sqlx::query_scalar!(
+        r#"SELECT id FROM provider_item_cache WHERE provider_cache_id = ANY($1)"#,
+        provider_ids
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e).context("A database failure occurred while fetching provider item ids")
+    })?;
+    Ok(ids)
+}
+
+
+async fn add_to_target_index<T: Serialize>(es_client: &ElasticSearchClient, targets: &HashMap<ElasticSearchIndex, String>, index: ElasticSearchIndex, records: Vec<T>, get_id: impl Fn(&T) -> Uuid) -> Result<Vec<Uuid>, anyhow::Error> {
+    let Some(index_name) = targets.get(&index) else {
+        return Ok(vec![]);
+    };
+    let ids = records.iter().map(&get_id).collect();
+    es_client.add_to_index(index_name, records, get_id).await?;
+    Ok(ids)
+}
 
+/// Writes the given items along with their providers, network participants, locations, variants and servicability into the physical index of each target, returning the ids written per index.
+async fn index_provider_items_to_elastic_search(pool: &PgPool, es_client: &ElasticSearchClient, targets: &HashMap<ElasticSearchIndex, String>, item_ids: Vec<Uuid>) -> Result<Vec<(ElasticSearchIndex, Vec<Uuid>)>, anyhow::Error> {
+    let item_models = get_provider_item_cache_data_from_db(pool, item_ids).await?;
+    let mut provider_ids = HashSet::new();
+    let mut network_participant_ids =  HashSet::new();
+    for item_model in item_models.iter(){
+        provider_ids.insert(item_model.provider_cache_id);
+        network_participant_ids.insert(item_model.network_participant_cache_id);
+    }
+    let provider_ids: Vec<Uuid> =  provider_ids.into_iter().collect();
+    let (provider_models, network_participant_models, 
+        location_models, variant_models, hyperlocal_models,country_models, inter_city_models, geo_json_models) = try_join!(
+        get_provider_cache_data_from_db(pool, &provider_ids),
+        get_network_participant_cache_data_from_db(pool, network_participant_ids.into_iter().collect()),
+        get_provider_location_cache_data_from_db(pool,  None, Some(&provider_ids)),
+        get_provider_item_variant_cache_data_from_db(pool,None, Some(&provider_ids)),
+        get_hyperlocal_cache_data_from_db(pool, None, Some(&provider_ids)),
+        get_country_cache_data_from_db(pool, None, Some(&provider_ids)),
+        get_intercity_cache_data_from_db(pool, None, Some(&provider_ids)),
+        get_geo_json_cache_data_from_db(pool, None, Some(&provider_ids)),
+    )?;
+
+    let batch_ids = try_join!(
+        add_to_target_index(es_client, targets, ElasticSearchIndex::NetworkParticipant, network_participant_models,|record| record.id),
+        add_to_target_index(es_client, targets, ElasticSearchIndex::Provider, provider_models, |record| record.id),
+        add_to_target_index(es_client, targets, ElasticSearchIndex::ProviderItem, item_models, |record| record.id),
+        add_to_target_index(es_client, targets, ElasticSearchIndex::ProviderLocation, location_models, |record| record.id),
+        add_to_target_index(es_client, targets, ElasticSearchIndex::ProviderItemVariant, variant_models, |record| record.id),
+        add_to_target_index(es_client, targets, ElasticSearchIndex::ProviderServicabilityHyperLocal, hyperlocal_models, |record| record.id),
+        add_to_target_index(es_client, targets, ElasticSearchIndex::ProviderServicabilityGeoJson, geo_json_models, |record| record.id),
+        add_to_target_index(es_client, targets, ElasticSearchIndex::ProviderServicabilityInterCity, inter_city_models, |record| record.id),
+        add_to_target_index(es_client, targets, ElasticSearchIndex::ProviderServicabilityCountry, country_models, |record| record.id),
+    )?;
+    Ok(vec![
+        (ElasticSearchIndex::NetworkParticipant, batch_ids.0),
+        (ElasticSearchIndex::Provider, batch_ids.1),
+        (ElasticSearchIndex::ProviderItem, batch_ids.2),
+        (ElasticSearchIndex::ProviderLocation, batch_ids.3),
+        (ElasticSearchIndex::ProviderItemVariant, batch_ids.4),
+        (ElasticSearchIndex::ProviderServicabilityHyperLocal, batch_ids.5),
+        (ElasticSearchIndex::ProviderServicabilityGeoJson, batch_ids.6),
+        (ElasticSearchIndex::ProviderServicabilityInterCity, batch_ids.7),
+        (ElasticSearchIndex::ProviderServicabilityCountry, batch_ids.8),
+    ])
+}
+
+/// Copies the items created up to `created_before` from the Postgres cache into the given physical index of each target, returning the ids written per index.
+async fn index_cache_to_elastic_search(pool: &PgPool, es_client: &ElasticSearchClient, targets: &HashMap<ElasticSearchIndex, String>, created_before: DateTime<Utc>) -> Result<HashMap<ElasticSearchIndex, HashSet<Uuid>>, anyhow::Error> {
     let batch_size = 1000;
-    let mut offset = 0;
-    es_client.delete_all_indices().await?;
+    let mut after_id = Uuid::nil();
+    let mut indexed_ids: HashMap<ElasticSearchIndex, HashSet<Uuid>> = HashMap::new();
     loop {
-        let item_ids = fetch_provider_item_ids(&pool, batch_size, offset).await?;
-        if item_ids.is_empty() {
+        let item_ids = fetch_provider_item_ids(pool, created_before, after_id, batch_size).await?;
+        let Some(last_id) = item_ids.last() else {
             break;
+        };
+        after_id = *last_id;
+        for (index, ids) in index_provider_items_to_elastic_search(pool, es_client, targets, item_ids).await? {
+            indexed_ids.entry(index).or_default().extend(ids);
         }
-        let item_models = get_provider_item_cache_data_from_db(&pool, item_ids).await?;
-        let mut provider_ids = HashSet::new();
-        let mut network_participant_ids =  HashSet::new();
-        for item_model in item_models.iter(){
-            provider_ids.insert(item_model.provider_cache_id);
-            network_participant_ids.insert(item_model.network_participant_cache_id);
+    }
+
+    Ok(indexed_ids)
+}
+
+/// Re-applies every provider written by on_search since `updated_since` to the new indices, clearing its documents first so entries removed meanwhile do not survive the rebuild.
+async fn apply_cache_changes_to_elastic_search(pool: &PgPool, es_client: &ElasticSearchClient, targets: &HashMap<ElasticSearchIndex, String>, indexed_ids: &mut HashMap<ElasticSearchIndex, HashSet<Uuid>>, updated_since: DateTime<Utc>) -> Result<(), anyhow::Error> {
+    let provider_ids = fetch_provider_ids_updated_since(pool, updated_since).await?;
+    for provider_ids in provider_ids.chunks(100) {
+        let removal_ids = DBCacheRemovalIds { provider_ids: provider_ids.to_vec(), ..Default::default() };
+        let tasks = get_cache_removal_queries(&removal_ids)
+            .into_iter()
+            .filter_map(|(index, query)| targets.get(&index).map(|index_name| es_client.delete_by_query_from_index(index_name, query)));
+        try_join_all(tasks).await?;
+        let item_ids = fetch_provider_item_ids_by_provider(pool, provider_ids).await?;
+        for item_ids in item_ids.chunks(1000) {
+            for (index, ids) in index_provider_items_to_elastic_search(pool, es_client, targets, item_ids.to_vec()).await? {
+                indexed_ids.entry(index).or_default().extend(ids);
+            }
         }
-         let provider_ids: Vec<Uuid> =  provider_ids.into_iter().collect();
-        let (provider_models, network_participant_models, 
-            location_models, variant_models, hyperlocal_models,country_models, inter_city_models, geo_json_models) = try_join!(
-            get_provider_cache_data_from_db(&pool, &provider_ids),
-            get_network_participant_cache_data_from_db(&pool, network_participant_ids.into_iter().collect()),
-            get_provider_location_cache_data_from_db(&pool,  None, Some(&provider_ids)),
-            get_provider_item_variant_cache_data_from_db(&pool,None, Some(&provider_ids)),
-            get_hyperlocal_cache_data_from_db(&pool, None, Some(&provider_ids)),
-            get_country_cache_data_from_db(&pool, None, Some(&provider_ids)),
-            get_intercity_cache_data_from_db(&pool, None, Some(&provider_ids)),
-            get_geo_json_cache_data_from_db(&pool, None, Some(&provider_ids)),
-        )?;
+    }
+    Ok(())
+}
 
-   
+fn get_cache_table_name(index: ElasticSearchIndex) -> &'static str {
+    match index {
+        ElasticSearchIndex::NetworkParticipant => "network_participant_cache",
+        ElasticSearchIndex::Provider => "provider_cache",
+        ElasticSearchIndex::ProviderItem => "provider_item_cache",
+        ElasticSearchIndex::ProviderLocation => "provider_location_cache",
+        ElasticSearchIndex::ProviderItemVariant => "provider_item_variant_cache",
+        ElasticSearchIndex::ProviderServicabilityHyperLocal => "provider_servicability_hyperlocal_cache",
+        ElasticSearchIndex::ProviderServicabilityGeoJson => "provider_servicability_geo_json_cache",
+        ElasticSearchIndex::ProviderServicabilityInterCity => "provider_servicability_intercity_cache",
+        ElasticSearchIndex::ProviderServicabilityCountry => "provider_servicability_country_cache",
+    }
+}
 
-      
-        try_join!(
-        es_client.add(ElasticSearchIndex::NetworkParticipant, network_participant_models,|record| record.id),
-        es_client.add(ElasticSearchIndex::Provider, provider_models, |record| record.id),
-        es_client.add(ElasticSearchIndex::ProviderItem, item_models, |record| record.id),
-        es_client.add(ElasticSearchIndex::ProviderLocation, location_models, |record| record.id),
-        es_client.add(ElasticSearchIndex::ProviderItemVariant, variant_models, |record| record.id),
-        es_client.add(ElasticSearchIndex::ProviderServicabilityHyperLocal, hyperlocal_models, |record| record.id),
-        es_client.add(ElasticSearchIndex::ProviderServicabilityGeoJson, geo_json_models, |record| record.id),
-        es_client.add(ElasticSearchIndex::ProviderServicabilityInterCity, inter_city_models, |record| record.id),
-        es_client.add(ElasticSearchIndex::ProviderServicabilityCountry, country_models, |record| record.id),
+#[tracing::instrument(name = "fetch cached ids", skip(pool, ids))]
+pub async fn fetch_cached_ids(pool: &PgPool, index: ElasticSearchIndex, ids: &[Uuid]) -> Result<HashSet<Uuid>, anyhow::Error> {
+    let mut query_builder = QueryBuilder::new(format!("SELECT id FROM {} WHERE id = ANY(", get_cache_table_name(index)));
+    query_builder.push_bind(ids).push(")");
+    let ids = query_builder
+        .build_query_scalar::<Uuid>()
+        .fetch_all(pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to execute query: {:?}", e);
+            anyhow::Error::new(e).context("A database failure occurred while fetching cached ids")
+        })?;
+    Ok(ids.into_iter().collect())
+}
 
-    )?;
+/// Deletes the documents of the new indices whose cache rows were removed while the build ran, since the expiry worker and catalog removals only reach the live alias.
+async fn remove_deleted_cache_from_elastic_search(pool: &PgPool, es_client: &ElasticSearchClient, targets: &HashMap<ElasticSearchIndex, String>, indexed_ids: &mut HashMap<ElasticSearchIndex, HashSet<Uuid>>) -> Result<(), anyhow::Error> {
+    for (index, index_name) in targets {
+        let Some(ids) = indexed_ids.get_mut(index) else {
+            continue;
+        };
+        let mut deleted_ids = vec![];
+        for chunk in ids.iter().copied().collect::<Vec<Uuid>>().chunks(1000) {
+            let cached_ids = fetch_cached_ids(pool, *index, chunk).await?;
+            deleted_ids.extend(chunk.iter().filter(|id| !cached_ids.contains(id)).copied());
+        }
+        for chunk in deleted_ids.chunks(1000) {
+            es_client.delete_by_query_from_index(index_name, json!({"ids": {"values": chunk}})).await?;
+        }
+        for id in deleted_ids {
+            ids.remove(&id);
+        }
+    }
+    Ok(())
+}
+
+pub async fn regenerate_cache_to_es() -> Result<(), anyhow::Error> {
+    let configuration = get_configuration().expect("Failed to read configuration.");
+    let pool = PgPool::connect_with(configuration.database.with_db())
+        .await
+        .expect("Failed to connect to Postgres.");
+    let es_client = configuration.elastic_search.client();
+    let targets = ElasticSearchIndex::all().into_iter().map(|index| (index, es_client.get_index(&index.to_string()))).collect();
+    index_cache_to_elastic_search(&pool, &es_client, &targets, Utc::now()).await?;
+    Ok(())
+}
 
+#[tracing::instrument(name = "count provider item cache", skip(pool))]
+async fn count_provider_item_cache(pool: &PgPool, created_before: DateTime<Utc>) -> Result<i64, anyhow::Error> {
+    let count = sqlx::query_scalar!(
+        r#"SELECT COUNT(*) AS "count!" FROM provider_item_cache WHERE created_on <= $1"#,
+        created_before
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e).context("A database failure occurred while counting provider item cache")
+    })?;
+    Ok(count)
+}
 
-        offset += batch_size; 
+/// Checks every new index holds the documents written to it and the item index holds every item created before the build that is still cached.
+async fn validate_reindexed_counts(es_client: &ElasticSearchClient, targets: &HashMap<ElasticSearchIndex, String>, indexed_ids: &HashMap<ElasticSearchIndex, HashSet<Uuid>>, cached_item_count: u64) -> Result<(), anyhow::Error> {
+    for (index, index_name) in targets {
+        let expected_count = indexed_ids.get(index).map(|ids| ids.len()).unwrap_or_default() as u64;
+        let document_count = es_client.count_documents(index_name).await?;
+        if document_count != expected_count {
+            return Err(anyhow!("Index {} has {} documents, expected {}", index_name, document_count, expected_count));
+        }
     }
+    if let Some(index_name) = targets.get(&ElasticSearchIndex::ProviderItem) {
+        let document_count = indexed_ids.get(&ElasticSearchIndex::ProviderItem).map(|ids| ids.len()).unwrap_or_default() as u64;
+        if document_count < cached_item_count {
+            return Err(anyhow!("Index {} has {} documents, provider item cache still holds {} created before the build started", index_name, document_count, cached_item_count));
+        }
+    }
+    Ok(())
+}
 
+/// Rebuilds `indices` into new versioned indices and swaps their aliases once
+/// document counts match, so searches keep hitting the old version meanwhile.
+/// Providers written by on_search during the build are re-applied and documents
+/// whose cache rows were deleted meanwhile are removed before the swap.
+pub async fn reindex_elastic_search(indices: Vec<ElasticSearchIndex>) -> Result<(), anyhow::Error> {
+    if indices.is_empty() {
+        return Ok(());
+    }
+    let configuration = get_configuration().expect("Failed to read configuration.");
+    let pool = PgPool::connect_with(configuration.database.with_db())
+        .await
+        .expect("Failed to connect to Postgres.");
+    let es_client = configuration.elastic_search.client();
+
+    let build_started_on = Utc::now();
+    let mut targets = HashMap::new();
+    for index in indices {
+        targets.insert(index, es_client.create_index_version(&index).await?);
+    }
+    let build_result = async {
+        let mut indexed_ids = index_cache_to_elastic_search(&pool, &es_client, &targets, build_started_on).await?;
+        apply_cache_changes_to_elastic_search(&pool, &es_client, &targets, &mut indexed_ids, build_started_on).await?;
+        remove_deleted_cache_from_elastic_search(&pool, &es_client, &targets, &mut indexed_ids).await?;
+        let cached_item_count = count_provider_item_cache(&pool, build_started_on).await? as u64;
+        validate_reindexed_counts(&es_client, &targets, &indexed_ids, cached_item_count).await?;
+        es_client.swap_aliases(&targets).await
+    }
+    .await;
+    if let Err(e) = build_result {
+        es_client.delete_indices(&targets.into_values().collect::<Vec<_>>()).await?;
+        return Err(e);
+    }
+    for (index, index_name) in targets.iter() {
+        es_client.delete_old_index_versions(index, index_name).await?;
+        tracing::info!("Alias of {} swapped to {}", index, index_name);
+    }
     Ok(())
 }
@@ -0,0 +1,357 @@
+use actix_http::StatusCode;
+use actix_web::{web, HttpResponse};
+use sqlx::PgPool;
+use utoipa::TupleUnit;
+
+use super::schemas::{
+    RecurringOrderCreateRequest, RecurringOrderListRequest, RecurringOrderRun,
+    RecurringOrderRunActionRequest, RecurringOrderRunListRequest, RecurringOrderRunStatus,
+    RecurringOrderStatusUpdateRequest, RecurringOrderTemplate,
+};
+use super::utils::{
+    claim_recurring_order_run, fetch_pending_recurring_order_run, fetch_recurring_order_runs,
+    fetch_recurring_order_template_by_id, fetch_recurring_order_templates,
+    save_recurring_order_template, trigger_recurring_order_init, update_recurring_order_run,
+    update_recurring_order_template_status, validate_recurring_order_create_request,
+    RecurringOrderContext,
+};
+use crate::chat_client::ChatClient;
+use crate::configuration::ONDCConfig;
+use crate::errors::GenericError;
+use crate::kafka_client::KafkaClient;
+use crate::schemas::{GenericResponse, StartUpMap};
+use crate::user_client::{
+    AllowedPermission, BusinessAccount, PermissionType, UserAccount, UserClient,
+};
+
+#[utoipa::path(
+    post,
+    path = "/recurring_order/create",
+    tag = "Recurring Order",
+    description="This API creates a recurring order template which is placed automatically on the given schedule.",
+    summary= "Recurring Order Create Request",
+    request_body(content = RecurringOrderCreateRequest, description = "Request Body"),
+    responses(
+        (status=200, description= "Recurring Order Create Response", body= GenericResponse<TupleUnit>),
+        (status=400, description= "Invalid Request body", body= GenericResponse<TupleUnit>),
+        (status=401, description= "Invalid Token", body= GenericResponse<TupleUnit>),
+	    (status=403, description= "Insufficient Previlege", body= GenericResponse<TupleUnit>),
+	    (status=410, description= "Data not found", body= GenericResponse<TupleUnit>),
+        (status=500, description= "Internal Server Error", body= GenericResponse<TupleUnit>),
+	    (status=501, description= "Not Implemented", body= GenericResponse<TupleUnit>),
+    )
+)]
+#[tracing::instrument(name = "recurring order create", skip(pool), fields())]
+pub async fn recurring_order_create(
+    body: RecurringOrderCreateRequest,
+    pool: web::Data<PgPool>,
+    user_account: UserAccount,
+    business_account: BusinessAccount,
+) -> Result<web::Json<GenericResponse<()>>, GenericError> {
+    validate_recurring_order_create_request(&body)?;
+    save_recurring_order_template(&pool, &body, user_account.id, business_account.id)
+        .await
+        .map_err(|e| {
+            GenericError::DatabaseError("Failed to save recurring order".to_string(), e)
+        })?;
+    Ok(web::Json(GenericResponse::success(
+        "Successfully created recurring order",
+        StatusCode::OK,
+        Some(()),
+    )))
+}
+
+#[utoipa::path(
+    post,
+    path = "/recurring_order/list",
+    tag = "Recurring Order",
+    description="This API lists the recurring order templates of the business.",
+    summary= "Recurring Order List Request",
+    request_body(content = RecurringOrderListRequest, description = "Request Body"),
+    responses(
+        (status=200, description= "Recurring Order List Response", body= GenericResponse<Vec<RecurringOrderTemplate>>),
+        (status=400, description= "Invalid Request body", body= GenericResponse<TupleUnit>),
+        (status=401, description= "Invalid Token", body= GenericResponse<TupleUnit>),
+	    (status=403, description= "Insufficient Previlege", body= GenericResponse<TupleUnit>),
+	    (status=410, description= "Data not found", body= GenericResponse<TupleUnit>),
+        (status=500, description= "Internal Server Error", body= GenericResponse<TupleUnit>),
+	    (status=501, description= "Not Implemented", body= GenericResponse<TupleUnit>),
+    )
+)]
+#[tracing::instrument(name = "recurring order list", skip(pool), fields())]
+pub async fn recurring_order_list(
+    body: RecurringOrderListRequest,
+    pool: web::Data<PgPool>,
+    user_account: UserAccount,
+    business_account: BusinessAccount,
+    allowed_permission: AllowedPermission,
+) -> Result<web::Json<GenericResponse<Vec<RecurringOrderTemplate>>>, GenericError> {
+    let user_id = if allowed_permission
+        .permission_list
+        .contains(&PermissionType::ListOrderSelf)
+    {
+        Some(allowed_permission.user_id)
+    } else {
+        None
+    };
+    let data = fetch_recurring_order_templates(
+        &pool,
+        allowed_permission.business_id,
+        user_id,
+        body.limit,
+        body.offset,
+    )
+    .await
+    .map_err(|e| GenericError::DatabaseError("Failed to fetch recurring orders".to_string(), e))?;
+    Ok(web::Json(GenericResponse::success(
+        "Successfully fetched recurring orders",
+        StatusCode::OK,
+        Some(data.into_iter().map(|template| template.schema()).collect()),
+    )))
+}
+
+#[utoipa::path(
+    post,
+    path = "/recurring_order/status/update",
+    tag = "Recurring Order",
+    description="This API pauses or resumes a recurring order template.",
+    summary= "Recurring Order Status Update Request",
+    request_body(content = RecurringOrderStatusUpdateRequest, description = "Request Body"),
+    responses(
+        (status=200, description= "Recurring Order Status Update Response", body= GenericResponse<TupleUnit>),
+        (status=400, description= "Invalid Request body", body= GenericResponse<TupleUnit>),
+        (status=401, description= "Invalid Token", body= GenericResponse<TupleUnit>),
+	    (status=403, description= "Insufficient Previlege", body= GenericResponse<TupleUnit>),
+	    (status=410, description= "Data not found", body= GenericResponse<TupleUnit>),
+        (status=500, description= "Internal Server Error", body= GenericResponse<TupleUnit>),
+	    (status=501, description= "Not Implemented", body= GenericResponse<TupleUnit>),
+    )
+)]
+#[tracing::instrument(name = "recurring order status update", skip(pool), fields(template_id = %body.template_id))]
+pub async fn recurring_order_status_update(
+    body: RecurringOrderStatusUpdateRequest,
+    pool: web::Data<PgPool>,
+    user_account: UserAccount,
+    business_account: BusinessAccount,
+    allowed_permission: AllowedPermission,
+) -> Result<web::Json<GenericResponse<()>>, GenericError> {
+    let template = fetch_recurring_order_template_by_id(&pool, body.template_id)
+        .await
+        .map_err(|e| GenericError::DatabaseError("Failed to fetch recurring order".to_string(), e))?
+        .filter(|template| template.business_id == business_account.id)
+        .ok_or_else(|| GenericError::ValidationError("Recurring order not found".to_string()))?;
+    if !allowed_permission.validate_commerce_self(
+        template.created_by,
+        template.business_id,
+        PermissionType::UpdateOrderSelf,
+    ) {
+        return Err(GenericError::InsufficientPrevilegeError(
+            "You do not have sufficent preveliege to update the recurring order".to_owned(),
+        ));
+    }
+    update_recurring_order_template_status(&pool, template.id, body.is_active, user_account.id)
+        .await
+        .map_err(|e| {
+            GenericError::DatabaseError("Failed to update recurring order".to_string(), e)
+        })?;
+    Ok(web::Json(GenericResponse::success(
+        "Successfully updated recurring order",
+        StatusCode::OK,
+        Some(()),
+    )))
+}
+
+#[utoipa::path(
+    post,
+    path = "/recurring_order/run/list",
+    tag = "Recurring Order",
+    description="This API lists every run of a recurring order template along with its outcome.",
+    summary= "Recurring Order Run List Request",
+    request_body(content = RecurringOrderRunListRequest, description = "Request Body"),
+    responses(
+        (status=200, description= "Recurring Order Run List Response", body= GenericResponse<Vec<RecurringOrderRun>>),
+        (status=400, description= "Invalid Request body", body= GenericResponse<TupleUnit>),
+        (status=401, description= "Invalid Token", body= GenericResponse<TupleUnit>),
+	    (status=403, description= "Insufficient Previlege", body= GenericResponse<TupleUnit>),
+	    (status=410, description= "Data not found", body= GenericResponse<TupleUnit>),
+        (status=500, description= "Internal Server Error", body= GenericResponse<TupleUnit>),
+	    (status=501, description= "Not Implemented", body= GenericResponse<TupleUnit>),
+    )
+)]
+#[tracing::instrument(name = "recurring order run list", skip(pool), fields(template_id = %body.template_id))]
+pub async fn recurring_order_run_list(
+    body: RecurringOrderRunListRequest,
+    pool: web::Data<PgPool>,
+    user_account: UserAccount,
+    business_account: BusinessAccount,
+    allowed_permission: AllowedPermission,
+) -> Result<web::Json<GenericResponse<Vec<RecurringOrderRun>>>, GenericError> {
+    let task1 = fetch_recurring_order_template_by_id(&pool, body.template_id);
+    let task2 = fetch_recurring_order_runs(&pool, body.template_id);
+    let (template, runs) = tokio::try_join!(task1, task2).map_err(|e| {
+        GenericError::DatabaseError("Failed to fetch recurring order runs".to_string(), e)
+    })?;
+    let template = template
+        .filter(|template| template.business_id == business_account.id)
+        .ok_or_else(|| GenericError::ValidationError("Recurring order not found".to_string()))?;
+    if !allowed_permission.validate_commerce_self(
+        template.created_by,
+        template.business_id,
+        PermissionType::ReadOrderSelf,
+    ) {
+        return Err(GenericError::InsufficientPrevilegeError(
+            "You do not have sufficent preveliege to read the recurring order".to_owned(),
+        ));
+    }
+    Ok(web::Json(GenericResponse::success(
+        "Successfully fetched recurring order runs",
+        StatusCode::OK,
+        Some(runs.into_iter().map(|run| run.schema()).collect()),
+    )))
+}
+
+#[utoipa::path(
+    post,
+    path = "/recurring_order/run/approve",
+    tag = "Recurring Order",
+    description="This API approves a recurring order run which is waiting for approval and continues it with the init request.",
+    summary= "Recurring Order Run Approve Request",
+    request_body(content = RecurringOrderRunActionRequest, description = "Request Body"),
+    responses(
+        (status=202, description= "Recurring Order Run Approve Response", body= GenericResponse<TupleUnit>),
+        (status=400, description= "Invalid Request body", body= GenericResponse<TupleUnit>),
+        (status=401, description= "Invalid Token", body= GenericResponse<TupleUnit>),
+	    (status=403, description= "Insufficient Previlege", body= GenericResponse<TupleUnit>),
+	    (status=410, description= "Data not found", body= GenericResponse<TupleUnit>),
+        (status=500, description= "Internal Server Error", body= GenericResponse<TupleUnit>),
+	    (status=501, description= "Not Implemented", body= GenericResponse<TupleUnit>),
+    )
+)]
+#[allow(clippy::too_many_arguments)]
+#[tracing::instrument(name = "recurring order run approve", skip(pool, kafka_client), fields(run_id = %body.run_id))]
+pub async fn recurring_order_run_approve(
+    body: RecurringOrderRunActionRequest,
+    pool: web::Data<PgPool>,
+    ondc_obj: web::Data<ONDCConfig>,
+    user_account: UserAccount,
+    business_account: BusinessAccount,
+    allowed_permission: AllowedPermission,
+    chat_client: web::Data<ChatClient>,
+    user_client: web::Data<UserClient>,
+    maps: web::Data<StartUpMap>,
+    kafka_client: web::Data<KafkaClient>,
+) -> Result<HttpResponse, GenericError> {
+    let (run, template) =
+        fetch_pending_recurring_order_run(&pool, body.run_id, &business_account).await?;
+    if !allowed_permission.validate_commerce_self(
+        template.created_by,
+        template.business_id,
+        PermissionType::UpdateOrderSelf,
+    ) {
+        return Err(GenericError::InsufficientPrevilegeError(
+            "You do not have sufficent preveliege to update the recurring order".to_owned(),
+        ));
+    }
+    let transaction_id = run.transaction_id;
+    let claimed = claim_recurring_order_run(
+        &pool,
+        transaction_id,
+        RecurringOrderRunStatus::PendingApproval,
+        RecurringOrderRunStatus::InitSent,
+        None,
+    )
+    .await
+    .map_err(|e| {
+        GenericError::DatabaseError("Failed to update recurring order run".to_string(), e)
+    })?;
+    if !claimed {
+        return Err(GenericError::ValidationError(
+            "Recurring order run is already approved or rejected".to_string(),
+        ));
+    }
+    let context =
+        RecurringOrderContext::new(pool, ondc_obj, user_client, chat_client, maps, kafka_client);
+    if let Err(e) = trigger_recurring_order_init(
+        &context,
+        transaction_id,
+        template.billing.0,
+        user_account,
+        business_account,
+        allowed_permission,
+    )
+    .await
+    {
+        update_recurring_order_run(
+            &context.pool,
+            transaction_id,
+            RecurringOrderRunStatus::PendingApproval,
+            None,
+            None,
+        )
+        .await
+        .map_err(|e| {
+            GenericError::DatabaseError("Failed to update recurring order run".to_string(), e)
+        })?;
+        return Err(e);
+    }
+
+    Ok(HttpResponse::Accepted().json(GenericResponse::success(
+        "Successfully approved recurring order run",
+        StatusCode::ACCEPTED,
+        Some(()),
+    )))
+}
+
+#[utoipa::path(
+    post,
+    path = "/recurring_order/run/reject",
+    tag = "Recurring Order",
+    description="This API rejects a recurring order run which is waiting for approval.",
+    summary= "Recurring Order Run Reject Request",
+    request_body(content = RecurringOrderRunActionRequest, description = "Request Body"),
+    responses(
+        (status=200, description= "Recurring Order Run Reject Response", body= GenericResponse<TupleUnit>),
+        (status=400, description= "Invalid Request body", body= GenericResponse<TupleUnit>),
+        (status=401, description= "Invalid Token", body= GenericResponse<TupleUnit>),
+	    (status=403, description= "Insufficient Previlege", body= GenericResponse<TupleUnit>),
+	    (status=410, description= "Data not found", body= GenericResponse<TupleUnit>),
+        (status=500, description= "Internal Server Error", body= GenericResponse<TupleUnit>),
+	    (status=501, description= "Not Implemented", body= GenericResponse<TupleUnit>),
+    )
+)]
+#[tracing::instrument(name = "recurring order run reject", skip(pool), fields(run_id = %body.run_id))]
+pub async fn recurring_order_run_reject(
+    body: RecurringOrderRunActionRequest,
+    pool: web::Data<PgPool>,
+    user_account: UserAccount,
+    business_account: BusinessAccount,
+    allowed_permission: AllowedPermission,
+) -> Result<web::Json<GenericResponse<()>>, GenericError> {
+    let (run, template) =
+        fetch_pending_recurring_order_run(&pool, body.run_id, &business_account).await?;
+    if !allowed_permission.validate_commerce_self(
+        template.created_by,
+        template.business_id,
+        PermissionType::UpdateOrderSelf,
+    ) {
+        return Err(GenericError::InsufficientPrevilegeError(
+            "You do not have sufficent preveliege to update the recurring order".to_owned(),
+        ));
+    }
+    update_recurring_order_run(
+        &pool,
+        run.transaction_id,
+        RecurringOrderRunStatus::Rejected,
+        Some(&format!("Rejected by {}", user_account.username)),
+        None,
+    )
+    .await
+    .map_err(|e| {
+        GenericError::DatabaseError("Failed to update recurring order run".to_string(), e)
+    })?;
+    Ok(web::Json(GenericResponse::success(
+        "Successfully rejected recurring order run",
+        StatusCode::OK,
+        Some(()),
+    )))
+}
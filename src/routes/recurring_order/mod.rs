@@ -0,0 +1,7 @@
+pub mod handlers;
+pub mod models;
+mod routes;
+pub(crate) mod schemas;
+mod tests;
+pub mod utils;
+pub use routes::recurring_order_route;
@@ -0,0 +1,90 @@
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use super::schemas::{
+    RecurringOrderFrequency, RecurringOrderItem, RecurringOrderRun, RecurringOrderRunStatus,
+    RecurringOrderTemplate,
+};
+use crate::routes::order::schemas::{OrderInitBilling, OrderSelectFulfillment, OrderType};
+use crate::routes::product::schemas::{CategoryDomain, PaymentType};
+
+#[derive(Deserialize, Debug, FromRow)]
+pub struct RecurringOrderTemplateModel {
+    pub id: Uuid,
+    pub name: String,
+    pub business_id: Uuid,
+    pub user_id: Uuid,
+    pub domain_category_code: CategoryDomain,
+    pub order_type: OrderType,
+    pub bpp_id: String,
+    pub provider_id: String,
+    pub payment_type: PaymentType,
+    pub ttl: String,
+    pub items: sqlx::types::Json<Vec<RecurringOrderItem>>,
+    pub fulfillments: sqlx::types::Json<Vec<OrderSelectFulfillment>>,
+    pub billing: sqlx::types::Json<OrderInitBilling>,
+    pub frequency: RecurringOrderFrequency,
+    pub frequency_interval: i32,
+    pub next_run_on: DateTime<Utc>,
+    pub price_tolerance: BigDecimal,
+    pub is_active: bool,
+    pub created_by: Uuid,
+    pub created_on: DateTime<Utc>,
+}
+
+impl RecurringOrderTemplateModel {
+    pub fn schema(self) -> RecurringOrderTemplate {
+        RecurringOrderTemplate {
+            id: self.id,
+            name: self.name,
+            domain_category_code: self.domain_category_code,
+            order_type: self.order_type,
+            bpp_id: self.bpp_id,
+            provider_id: self.provider_id,
+            payment_type: self.payment_type,
+            ttl: self.ttl,
+            items: self.items.0,
+            fulfillments: self.fulfillments.0,
+            billing: self.billing.0,
+            frequency: self.frequency,
+            frequency_interval: self.frequency_interval,
+            next_run_on: self.next_run_on,
+            price_tolerance: self.price_tolerance,
+            is_active: self.is_active,
+            created_by: self.created_by,
+            created_on: self.created_on,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, FromRow)]
+pub struct RecurringOrderRunModel {
+    pub id: Uuid,
+    pub template_id: Uuid,
+    pub transaction_id: Uuid,
+    pub status: RecurringOrderRunStatus,
+    pub remark: Option<String>,
+    pub quote_total: Option<BigDecimal>,
+    pub scheduled_on: DateTime<Utc>,
+    pub created_on: DateTime<Utc>,
+    pub updated_on: Option<DateTime<Utc>>,
+}
+
+impl RecurringOrderRunModel {
+    pub fn schema(self) -> RecurringOrderRun {
+        RecurringOrderRun {
+            id: self.id,
+            template_id: self.template_id,
+            transaction_id: self.transaction_id,
+            status: self.status,
+            remark: self.remark,
+            quote_total: self.quote_total,
+            scheduled_on: self.scheduled_on,
+            created_on: self.created_on,
+            updated_on: self.updated_on,
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use crate::middleware::{BusinessAccountValidation, BusinessPermissionValidation, RequireAuth};
+use crate::user_client::{CustomerType, PermissionType};
+use actix_web::web;
+
+use super::handlers::{
+    recurring_order_create, recurring_order_list, recurring_order_run_approve,
+    recurring_order_run_list, recurring_order_run_reject, recurring_order_status_update,
+};
+pub fn recurring_order_route(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::resource("/create")
+            .route(web::post().to(recurring_order_create))
+            .wrap(BusinessPermissionValidation {
+                permission_list: vec![PermissionType::CreateOrder, PermissionType::CreateOrderSelf],
+            })
+            .wrap(BusinessAccountValidation {
+                business_type_list: vec![CustomerType::RetailB2bBuyer],
+            })
+            .wrap(RequireAuth),
+    );
+    cfg.service(
+        web::resource("/list")
+            .route(web::post().to(recurring_order_list))
+            .wrap(BusinessPermissionValidation {
+                permission_list: vec![PermissionType::ListOrder, PermissionType::ListOrderSelf],
+            })
+            .wrap(BusinessAccountValidation {
+                business_type_list: vec![CustomerType::RetailB2bBuyer],
+            })
+            .wrap(RequireAuth),
+    );
+    cfg.service(
+        web::resource("/status/update")
+            .route(web::post().to(recurring_order_status_update))
+            .wrap(BusinessPermissionValidation {
+                permission_list: vec![PermissionType::UpdateOrder, PermissionType::UpdateOrderSelf],
+            })
+            .wrap(BusinessAccountValidation {
+                business_type_list: vec![CustomerType::RetailB2bBuyer],
+            })
+            .wrap(RequireAuth),
+    );
+    cfg.service(
+        web::resource("/run/list")
+            .route(web::post().to(recurring_order_run_list))
+            .wrap(BusinessPermissionValidation {
+                permission_list: vec![PermissionType::ReadOrder, PermissionType::ReadOrderSelf],
+            })
+            .wrap(BusinessAccountValidation {
+                business_type_list: vec![CustomerType::RetailB2bBuyer],
+            })
+            .wrap(RequireAuth),
+    );
+    cfg.service(
+        web::resource("/run/approve")
+            .route(web::post().to(recurring_order_run_approve))
+            .wrap(BusinessPermissionValidation {
+                permission_list: vec![PermissionType::UpdateOrder, PermissionType::UpdateOrderSelf],
+            })
+            .wrap(BusinessAccountValidation {
+                business_type_list: vec![CustomerType::RetailB2bBuyer],
+            })
+            .wrap(RequireAuth),
+    );
+    cfg.service(
+        web::resource("/run/reject")
+            .route(web::post().to(recurring_order_run_reject))
+            .wrap(BusinessPermissionValidation {
+                permission_list: vec![PermissionType::UpdateOrder, PermissionType::UpdateOrderSelf],
+            })
+            .wrap(BusinessAccountValidation {
+                business_type_list: vec![CustomerType::RetailB2bBuyer],
+            })
+            .wrap(RequireAuth),
+    );
+}
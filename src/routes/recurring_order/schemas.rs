@@ -0,0 +1,251 @@
+use crate::errors::GenericError;
+use crate::routes::order::schemas::{
+    BuyerTerms, OrderInitBilling, OrderSelectFulfillment, OrderType,
+};
+use crate::routes::product::schemas::{CategoryDomain, PaymentType};
+use actix_http::Payload;
+use actix_web::{web, FromRequest, HttpRequest};
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Duration, Months, Utc};
+use futures_util::future::LocalBoxFuture;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Deserialize, Debug, ToSchema, Serialize, sqlx::Type, Clone, PartialEq)]
+#[sqlx(type_name = "recurring_order_frequency", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum RecurringOrderFrequency {
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+impl RecurringOrderFrequency {
+    pub fn get_next_run_on(&self, from: DateTime<Utc>, interval: i32) -> Option<DateTime<Utc>> {
+        let interval = interval.max(1) as u32;
+        match self {
+            RecurringOrderFrequency::Daily => {
+                from.checked_add_signed(Duration::days(interval as i64))
+            }
+            RecurringOrderFrequency::Weekly => {
+                from.checked_add_signed(Duration::weeks(interval as i64))
+            }
+            RecurringOrderFrequency::Monthly => from.checked_add_months(Months::new(interval)),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, ToSchema, Serialize, sqlx::Type, Clone, PartialEq)]
+#[sqlx(type_name = "recurring_order_run_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum RecurringOrderRunStatus {
+    SelectSent,
+    PendingApproval,
+    InitSent,
+    ConfirmSent,
+    Completed,
+    Rejected,
+    Failed,
+}
+
+impl fmt::Display for RecurringOrderRunStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            RecurringOrderRunStatus::SelectSent => "select_sent",
+            RecurringOrderRunStatus::PendingApproval => "pending_approval",
+            RecurringOrderRunStatus::InitSent => "init_sent",
+            RecurringOrderRunStatus::ConfirmSent => "confirm_sent",
+            RecurringOrderRunStatus::Completed => "completed",
+            RecurringOrderRunStatus::Rejected => "rejected",
+            RecurringOrderRunStatus::Failed => "failed",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+#[derive(Deserialize, Debug, ToSchema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecurringOrderItem {
+    pub item_id: String,
+    pub location_ids: Vec<String>,
+    pub qty: i32,
+    pub buyer_term: Option<BuyerTerms>,
+    pub fulfillment_ids: Vec<String>,
+    #[schema(value_type = Option<f64>)]
+    pub expected_unit_price: Option<BigDecimal>,
+}
+
+#[derive(Deserialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RecurringOrderCreateRequest {
+    pub name: String,
+    pub domain_category_code: CategoryDomain,
+    pub order_type: OrderType,
+    pub bpp_id: String,
+    pub provider_id: String,
+    pub payment_type: PaymentType,
+    pub ttl: String,
+    pub items: Vec<RecurringOrderItem>,
+    pub fulfillments: Vec<OrderSelectFulfillment>,
+    pub billing: OrderInitBilling,
+    pub frequency: RecurringOrderFrequency,
+    pub frequency_interval: i32,
+    pub start_on: DateTime<Utc>,
+    #[schema(value_type = f64)]
+    pub price_tolerance: BigDecimal,
+}
+
+impl FromRequest for RecurringOrderCreateRequest {
+    type Error = GenericError;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let fut = web::Json::<Self>::from_request(req, payload);
+
+        Box::pin(async move {
+            match fut.await {
+                Ok(json) => Ok(json.into_inner()),
+                Err(e) => Err(GenericError::ValidationError(e.to_string())),
+            }
+        })
+    }
+}
+
+#[derive(Deserialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RecurringOrderStatusUpdateRequest {
+    #[schema(value_type = String)]
+    pub template_id: Uuid,
+    pub is_active: bool,
+}
+
+impl FromRequest for RecurringOrderStatusUpdateRequest {
+    type Error = GenericError;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let fut = web::Json::<Self>::from_request(req, payload);
+
+        Box::pin(async move {
+            match fut.await {
+                Ok(json) => Ok(json.into_inner()),
+                Err(e) => Err(GenericError::ValidationError(e.to_string())),
+            }
+        })
+    }
+}
+
+#[derive(Deserialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RecurringOrderListRequest {
+    pub offset: i32,
+    pub limit: i32,
+}
+
+impl FromRequest for RecurringOrderListRequest {
+    type Error = GenericError;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let fut = web::Json::<Self>::from_request(req, payload);
+
+        Box::pin(async move {
+            match fut.await {
+                Ok(json) => Ok(json.into_inner()),
+                Err(e) => Err(GenericError::ValidationError(e.to_string())),
+            }
+        })
+    }
+}
+
+#[derive(Deserialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RecurringOrderRunListRequest {
+    #[schema(value_type = String)]
+    pub template_id: Uuid,
+}
+
+impl FromRequest for RecurringOrderRunListRequest {
+    type Error = GenericError;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let fut = web::Json::<Self>::from_request(req, payload);
+
+        Box::pin(async move {
+            match fut.await {
+                Ok(json) => Ok(json.into_inner()),
+                Err(e) => Err(GenericError::ValidationError(e.to_string())),
+            }
+        })
+    }
+}
+
+#[derive(Deserialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RecurringOrderRunActionRequest {
+    #[schema(value_type = String)]
+    pub run_id: Uuid,
+}
+
+impl FromRequest for RecurringOrderRunActionRequest {
+    type Error = GenericError;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let fut = web::Json::<Self>::from_request(req, payload);
+
+        Box::pin(async move {
+            match fut.await {
+                Ok(json) => Ok(json.into_inner()),
+                Err(e) => Err(GenericError::ValidationError(e.to_string())),
+            }
+        })
+    }
+}
+
+#[derive(Debug, ToSchema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecurringOrderTemplate {
+    #[schema(value_type = String)]
+    pub id: Uuid,
+    pub name: String,
+    pub domain_category_code: CategoryDomain,
+    pub order_type: OrderType,
+    pub bpp_id: String,
+    pub provider_id: String,
+    pub payment_type: PaymentType,
+    pub ttl: String,
+    pub items: Vec<RecurringOrderItem>,
+    pub fulfillments: Vec<OrderSelectFulfillment>,
+    pub billing: OrderInitBilling,
+    pub frequency: RecurringOrderFrequency,
+    pub frequency_interval: i32,
+    pub next_run_on: DateTime<Utc>,
+    #[schema(value_type = f64)]
+    pub price_tolerance: BigDecimal,
+    pub is_active: bool,
+    #[schema(value_type = String)]
+    pub created_by: Uuid,
+    pub created_on: DateTime<Utc>,
+}
+
+#[derive(Debug, ToSchema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecurringOrderRun {
+    #[schema(value_type = String)]
+    pub id: Uuid,
+    #[schema(value_type = String)]
+    pub template_id: Uuid,
+    #[schema(value_type = String)]
+    pub transaction_id: Uuid,
+    pub status: RecurringOrderRunStatus,
+    pub remark: Option<String>,
+    #[schema(value_type = Option<f64>)]
+    pub quote_total: Option<BigDecimal>,
+    pub scheduled_on: DateTime<Utc>,
+    pub created_on: DateTime<Utc>,
+    pub updated_on: Option<DateTime<Utc>>,
+}
@@ -0,0 +1,118 @@
+#[cfg(test)]
+mod tests {
+    use bigdecimal::BigDecimal;
+    use chrono::{TimeZone, Utc};
+    use std::str::FromStr;
+    use uuid::Uuid;
+
+    use crate::{
+        routes::{
+            order::schemas::QuoteVersionItem,
+            recurring_order::{
+                schemas::{RecurringOrderFrequency, RecurringOrderItem, RecurringOrderRunStatus},
+                utils::{
+                    claim_recurring_order_run, fail_stale_recurring_order_runs,
+                    fetch_recurring_order_run_by_transaction_id, fetch_recurring_order_runs,
+                    fetch_recurring_order_templates, get_recurring_order_quote_deviation,
+                },
+            },
+        },
+        tests::tests::get_test_pool,
+    };
+
+    fn get_template_item(item_id: &str, qty: i32, price: Option<&str>) -> RecurringOrderItem {
+        RecurringOrderItem {
+            item_id: item_id.to_string(),
+            location_ids: vec![],
+            qty,
+            buyer_term: None,
+            fulfillment_ids: vec![],
+            expected_unit_price: price.map(|p| BigDecimal::from_str(p).unwrap()),
+        }
+    }
+
+    fn get_quote_item(item_id: &str, qty: i32, price: Option<&str>) -> QuoteVersionItem {
+        QuoteVersionItem {
+            item_id: item_id.to_string(),
+            qty,
+            unit_price: price.map(|p| BigDecimal::from_str(p).unwrap()),
+            gross_total: None,
+        }
+    }
+
+    #[test]
+    fn test_recurring_order_next_run() {
+        let from = Utc.with_ymd_and_hms(2024, 1, 31, 10, 0, 0).unwrap();
+        assert_eq!(
+            RecurringOrderFrequency::Daily.get_next_run_on(from, 2),
+            Some(Utc.with_ymd_and_hms(2024, 2, 2, 10, 0, 0).unwrap())
+        );
+        assert_eq!(
+            RecurringOrderFrequency::Weekly.get_next_run_on(from, 1),
+            Some(Utc.with_ymd_and_hms(2024, 2, 7, 10, 0, 0).unwrap())
+        );
+        assert_eq!(
+            RecurringOrderFrequency::Monthly.get_next_run_on(from, 1),
+            Some(Utc.with_ymd_and_hms(2024, 2, 29, 10, 0, 0).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_recurring_order_quote_deviation() {
+        let template_items = vec![get_template_item("item-1", 10, Some("100.00"))];
+        let tolerance = BigDecimal::from(5);
+
+        let quote_items = vec![get_quote_item("item-1", 10, Some("104.00"))];
+        assert!(
+            get_recurring_order_quote_deviation(&template_items, &quote_items, &tolerance)
+                .is_none()
+        );
+
+        let quote_items = vec![get_quote_item("item-1", 10, Some("106.00"))];
+        assert!(
+            get_recurring_order_quote_deviation(&template_items, &quote_items, &tolerance)
+                .is_some()
+        );
+
+        let quote_items = vec![get_quote_item("item-1", 8, Some("100.00"))];
+        assert!(
+            get_recurring_order_quote_deviation(&template_items, &quote_items, &tolerance)
+                .is_some()
+        );
+
+        let quote_items = vec![
+            get_quote_item("item-1", 10, Some("100.00")),
+            get_quote_item("item-2", 1, Some("10.00")),
+        ];
+        assert!(
+            get_recurring_order_quote_deviation(&template_items, &quote_items, &tolerance)
+                .is_some()
+        );
+
+        assert!(get_recurring_order_quote_deviation(&template_items, &[], &tolerance).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_recurring_order_sql() {
+        let pool = get_test_pool().await;
+        let templates = fetch_recurring_order_templates(&pool, Uuid::new_v4(), None, 10, 0).await;
+        assert!(templates.is_ok());
+        let runs = fetch_recurring_order_runs(&pool, Uuid::new_v4()).await;
+        assert!(runs.is_ok());
+        let run = fetch_recurring_order_run_by_transaction_id(&pool, Uuid::new_v4()).await;
+        assert!(run.is_ok());
+        assert!(run.unwrap().is_none());
+        let claimed = claim_recurring_order_run(
+            &pool,
+            Uuid::new_v4(),
+            RecurringOrderRunStatus::PendingApproval,
+            RecurringOrderRunStatus::InitSent,
+            None,
+        )
+        .await;
+        assert!(claimed.is_ok());
+        assert!(!claimed.unwrap());
+        let failed = fail_stale_recurring_order_runs(&pool, Utc.timestamp_opt(0, 0).unwrap()).await;
+        assert_eq!(failed.unwrap(), 0);
+    }
+}
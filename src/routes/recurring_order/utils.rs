@@ -0,0 +1,959 @@
+use super::models::{RecurringOrderRunModel, RecurringOrderTemplateModel};
+use super::schemas::{
+    RecurringOrderCreateRequest, RecurringOrderFrequency, RecurringOrderItem,
+    RecurringOrderRunStatus,
+};
+use crate::chat_client::ChatClient;
+use crate::configuration::ONDCConfig;
+use crate::constants::{
+    RECURRING_ORDER_BATCH_SIZE, RECURRING_ORDER_RUN_TIMEOUT_MINUTES,
+    RECURRING_ORDER_SCHEDULER_INTERVAL,
+};
+use crate::errors::GenericError;
+use crate::kafka_client::KafkaClient;
+use crate::routes::order::handlers::{order_confirm, order_init, order_select};
+use crate::routes::order::schemas::{
+    OrderConfirmRequest, OrderInitBilling, OrderInitRequest, OrderSelectFulfillment,
+    OrderSelectItem, OrderSelectRequest, OrderType, QuoteVersionItem,
+};
+use crate::routes::product::schemas::{CategoryDomain, PaymentType};
+use crate::schemas::{RequestMetaData, StartUpMap};
+use crate::user_client::{
+    AllowedPermission, BusinessAccount, CustomerType, PermissionType, UserAccount, UserClient,
+};
+use actix_web::web;
+use anyhow::anyhow;
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Duration, Utc};
+use sqlx::types::Json;
+use sqlx::{Executor, PgPool, Postgres, Transaction};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct RecurringOrderContext {
+    pub pool: web::Data<PgPool>,
+    pub ondc_obj: web::Data<ONDCConfig>,
+    pub user_client: web::Data<UserClient>,
+    pub chat_client: web::Data<ChatClient>,
+    pub maps: web::Data<StartUpMap>,
+    pub kafka_client: web::Data<KafkaClient>,
+}
+
+impl RecurringOrderContext {
+    pub fn new(
+        pool: web::Data<PgPool>,
+        ondc_obj: web::Data<ONDCConfig>,
+        user_client: web::Data<UserClient>,
+        chat_client: web::Data<ChatClient>,
+        maps: web::Data<StartUpMap>,
+        kafka_client: web::Data<KafkaClient>,
+    ) -> Self {
+        Self {
+            pool,
+            ondc_obj,
+            user_client,
+            chat_client,
+            maps,
+            kafka_client,
+        }
+    }
+}
+
+pub fn validate_recurring_order_create_request(
+    body: &RecurringOrderCreateRequest,
+) -> Result<(), GenericError> {
+    if body.items.is_empty() {
+        return Err(GenericError::ValidationError(
+            "Atleast one item is required".to_string(),
+        ));
+    }
+    if body.items.iter().any(|item| item.qty <= 0) {
+        return Err(GenericError::ValidationError(
+            "Item quantity must be greater than zero".to_string(),
+        ));
+    }
+    if body.frequency_interval < 1 {
+        return Err(GenericError::ValidationError(
+            "Frequency interval must be atleast 1".to_string(),
+        ));
+    }
+    if body.price_tolerance < BigDecimal::from(0) || body.price_tolerance > BigDecimal::from(100) {
+        return Err(GenericError::ValidationError(
+            "Price tolerance must be between 0 and 100".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+#[tracing::instrument(name = "save recurring order template", skip(pool, body))]
+pub async fn save_recurring_order_template(
+    pool: &PgPool,
+    body: &RecurringOrderCreateRequest,
+    user_id: Uuid,
+    business_id: Uuid,
+) -> Result<Uuid, anyhow::Error> {
+    let id = Uuid::new_v4();
+    sqlx::query!(
+        r#"
+        INSERT INTO recurring_order_template (id, name, business_id, user_id, domain_category_code,
+        order_type, bpp_id, provider_id, payment_type, ttl, items, fulfillments, billing, frequency,
+        frequency_interval, next_run_on, price_tolerance, is_active, created_on, created_by)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20)
+        "#,
+        id,
+        &body.name,
+        business_id,
+        user_id,
+        &body.domain_category_code as &CategoryDomain,
+        &body.order_type as &OrderType,
+        &body.bpp_id,
+        &body.provider_id,
+        &body.payment_type as &PaymentType,
+        &body.ttl,
+        serde_json::to_value(&body.items)?,
+        serde_json::to_value(&body.fulfillments)?,
+        serde_json::to_value(&body.billing)?,
+        &body.frequency as &RecurringOrderFrequency,
+        body.frequency_interval,
+        body.start_on,
+        body.price_tolerance,
+        true,
+        Utc::now(),
+        user_id
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e)
+            .context("A database failure occurred while saving recurring order template")
+    })?;
+    Ok(id)
+}
+
+#[tracing::instrument(name = "fetch recurring order templates", skip(pool))]
+pub async fn fetch_recurring_order_templates(
+    pool: &PgPool,
+    business_id: Uuid,
+    user_id: Option<Uuid>,
+    limit: i32,
+    offset: i32,
+) -> Result<Vec<RecurringOrderTemplateModel>, anyhow::Error> {
+    let rows = sqlx::query_as!(
+        RecurringOrderTemplateModel,
+        r#"SELECT id, name, business_id, user_id,
+        domain_category_code as "domain_category_code: CategoryDomain",
+        order_type as "order_type: OrderType", bpp_id, provider_id,
+        payment_type as "payment_type: PaymentType", ttl,
+        items as "items: Json<Vec<RecurringOrderItem>>",
+        fulfillments as "fulfillments: Json<Vec<OrderSelectFulfillment>>",
+        billing as "billing: Json<OrderInitBilling>",
+        frequency as "frequency: RecurringOrderFrequency", frequency_interval, next_run_on,
+        price_tolerance, is_active, created_by, created_on
+        FROM recurring_order_template
+        WHERE business_id = $1 AND ($2::uuid IS NULL OR created_by = $2)
+        ORDER BY created_on DESC LIMIT $3 OFFSET $4"#,
+        business_id,
+        user_id,
+        limit as i64,
+        offset as i64
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e)
+            .context("A database failure occurred while fetching recurring order templates")
+    })?;
+    Ok(rows)
+}
+
+#[tracing::instrument(name = "fetch recurring order template by id", skip(pool))]
+pub async fn fetch_recurring_order_template_by_id(
+    pool: &PgPool,
+    template_id: Uuid,
+) -> Result<Option<RecurringOrderTemplateModel>, anyhow::Error> {
+    let row = sqlx::query_as!(
+        RecurringOrderTemplateModel,
+        r#"SELECT id, name, business_id, user_id,
+        domain_category_code as "domain_category_code: CategoryDomain",
+        order_type as "order_type: OrderType", bpp_id, provider_id,
+        payment_type as "payment_type: PaymentType", ttl,
+        items as "items: Json<Vec<RecurringOrderItem>>",
+        fulfillments as "fulfillments: Json<Vec<OrderSelectFulfillment>>",
+        billing as "billing: Json<OrderInitBilling>",
+        frequency as "frequency: RecurringOrderFrequency", frequency_interval, next_run_on,
+        price_tolerance, is_active, created_by, created_on
+        FROM recurring_order_template WHERE id = $1"#,
+        template_id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e)
+            .context("A database failure occurred while fetching recurring order template")
+    })?;
+    Ok(row)
+}
+
+#[tracing::instrument(name = "update recurring order template status", skip(pool))]
+pub async fn update_recurring_order_template_status(
+    pool: &PgPool,
+    template_id: Uuid,
+    is_active: bool,
+    user_id: Uuid,
+) -> Result<(), anyhow::Error> {
+    sqlx::query!(
+        r#"
+        UPDATE recurring_order_template SET is_active = $1, updated_on = $2, updated_by = $3
+        WHERE id = $4
+        "#,
+        is_active,
+        Utc::now(),
+        user_id,
+        template_id
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e)
+            .context("A database failure occurred while updating recurring order template status")
+    })?;
+    Ok(())
+}
+
+#[tracing::instrument(name = "fetch due recurring order templates", skip(transaction))]
+pub async fn fetch_due_recurring_order_templates(
+    transaction: &mut Transaction<'_, Postgres>,
+    run_on: DateTime<Utc>,
+    limit: i64,
+) -> Result<Vec<RecurringOrderTemplateModel>, anyhow::Error> {
+    let rows = sqlx::query_as!(
+        RecurringOrderTemplateModel,
+        r#"SELECT id, name, business_id, user_id,
+        domain_category_code as "domain_category_code: CategoryDomain",
+        order_type as "order_type: OrderType", bpp_id, provider_id,
+        payment_type as "payment_type: PaymentType", ttl,
+        items as "items: Json<Vec<RecurringOrderItem>>",
+        fulfillments as "fulfillments: Json<Vec<OrderSelectFulfillment>>",
+        billing as "billing: Json<OrderInitBilling>",
+        frequency as "frequency: RecurringOrderFrequency", frequency_interval, next_run_on,
+        price_tolerance, is_active, created_by, created_on
+        FROM recurring_order_template
+        WHERE is_active AND next_run_on <= $1
+        ORDER BY next_run_on LIMIT $2
+        FOR UPDATE SKIP LOCKED"#,
+        run_on,
+        limit
+    )
+    .fetch_all(&mut **transaction)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e)
+            .context("A database failure occurred while fetching due recurring order templates")
+    })?;
+    Ok(rows)
+}
+
+#[tracing::instrument(name = "update recurring order template next run", skip(transaction))]
+pub async fn update_recurring_order_template_next_run(
+    transaction: &mut Transaction<'_, Postgres>,
+    template_id: Uuid,
+    next_run_on: Option<DateTime<Utc>>,
+) -> Result<(), anyhow::Error> {
+    let query = sqlx::query!(
+        r#"
+        UPDATE recurring_order_template SET next_run_on = COALESCE($1, next_run_on),
+        is_active = $1 IS NOT NULL AND is_active, updated_on = $2 WHERE id = $3
+        "#,
+        next_run_on,
+        Utc::now(),
+        template_id
+    );
+    transaction.execute(query).await.map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e)
+            .context("A database failure occurred while updating recurring order next run")
+    })?;
+    Ok(())
+}
+
+#[tracing::instrument(name = "save recurring order run", skip(transaction))]
+pub async fn save_recurring_order_run(
+    transaction: &mut Transaction<'_, Postgres>,
+    template_id: Uuid,
+    transaction_id: Uuid,
+    scheduled_on: DateTime<Utc>,
+) -> Result<(), anyhow::Error> {
+    let query = sqlx::query!(
+        r#"
+        INSERT INTO recurring_order_run (id, template_id, transaction_id, status, scheduled_on, created_on)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        "#,
+        Uuid::new_v4(),
+        template_id,
+        transaction_id,
+        RecurringOrderRunStatus::SelectSent as RecurringOrderRunStatus,
+        scheduled_on,
+        Utc::now()
+    );
+    transaction.execute(query).await.map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e)
+            .context("A database failure occurred while saving recurring order run")
+    })?;
+    Ok(())
+}
+
+#[tracing::instrument(name = "update recurring order run", skip(pool))]
+pub async fn update_recurring_order_run(
+    pool: &PgPool,
+    transaction_id: Uuid,
+    status: RecurringOrderRunStatus,
+    remark: Option<&str>,
+    quote_total: Option<&BigDecimal>,
+) -> Result<(), anyhow::Error> {
+    sqlx::query!(
+        r#"
+        UPDATE recurring_order_run SET status = $1, remark = COALESCE($2, remark),
+        quote_total = COALESCE($3, quote_total), updated_on = $4 WHERE transaction_id = $5
+        "#,
+        status as RecurringOrderRunStatus,
+        remark,
+        quote_total,
+        Utc::now(),
+        transaction_id
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e)
+            .context("A database failure occurred while updating recurring order run")
+    })?;
+    Ok(())
+}
+
+/// Moves a run from `current_status` to `status` only if it is still in `current_status`, so that
+/// a run is sent onwards once even when callbacks or approvals race.
+#[tracing::instrument(name = "claim recurring order run", skip(pool))]
+pub async fn claim_recurring_order_run(
+    pool: &PgPool,
+    transaction_id: Uuid,
+    current_status: RecurringOrderRunStatus,
+    status: RecurringOrderRunStatus,
+    quote_total: Option<&BigDecimal>,
+) -> Result<bool, anyhow::Error> {
+    let row = sqlx::query!(
+        r#"
+        UPDATE recurring_order_run SET status = $1, quote_total = COALESCE($2, quote_total),
+        updated_on = $3 WHERE transaction_id = $4 AND status = $5
+        RETURNING id
+        "#,
+        status as RecurringOrderRunStatus,
+        quote_total,
+        Utc::now(),
+        transaction_id,
+        current_status as RecurringOrderRunStatus
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e)
+            .context("A database failure occurred while claiming recurring order run")
+    })?;
+    Ok(row.is_some())
+}
+
+/// Fails the runs still waiting for an on_select or on_init callback at `stale_before`, since a NACK
+/// or a callback that errors before reaching the run would otherwise leave them pending forever.
+#[tracing::instrument(name = "fail stale recurring order runs", skip(pool))]
+pub async fn fail_stale_recurring_order_runs(
+    pool: &PgPool,
+    stale_before: DateTime<Utc>,
+) -> Result<u64, anyhow::Error> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE recurring_order_run SET status = $1, remark = $2, updated_on = $3
+        WHERE status = ANY($4) AND COALESCE(updated_on, created_on) < $5
+        "#,
+        RecurringOrderRunStatus::Failed as RecurringOrderRunStatus,
+        "No response from the seller before the run timed out",
+        Utc::now(),
+        &[
+            RecurringOrderRunStatus::SelectSent,
+            RecurringOrderRunStatus::InitSent
+        ] as &[RecurringOrderRunStatus],
+        stale_before
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e)
+            .context("A database failure occurred while failing stale recurring order runs")
+    })?;
+    Ok(result.rows_affected())
+}
+
+#[tracing::instrument(name = "fetch recurring order run by transaction id", skip(pool))]
+pub async fn fetch_recurring_order_run_by_transaction_id(
+    pool: &PgPool,
+    transaction_id: Uuid,
+) -> Result<Option<RecurringOrderRunModel>, anyhow::Error> {
+    let row = sqlx::query_as!(
+        RecurringOrderRunModel,
+        r#"SELECT id, template_id, transaction_id, status as "status: RecurringOrderRunStatus",
+        remark, quote_total, scheduled_on, created_on, updated_on
+        FROM recurring_order_run WHERE transaction_id = $1"#,
+        transaction_id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e)
+            .context("A database failure occurred while fetching recurring order run")
+    })?;
+    Ok(row)
+}
+
+#[tracing::instrument(name = "fetch recurring order run by id", skip(pool))]
+pub async fn fetch_recurring_order_run_by_id(
+    pool: &PgPool,
+    run_id: Uuid,
+) -> Result<Option<RecurringOrderRunModel>, anyhow::Error> {
+    let row = sqlx::query_as!(
+        RecurringOrderRunModel,
+        r#"SELECT id, template_id, transaction_id, status as "status: RecurringOrderRunStatus",
+        remark, quote_total, scheduled_on, created_on, updated_on
+        FROM recurring_order_run WHERE id = $1"#,
+        run_id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e)
+            .context("A database failure occurred while fetching recurring order run")
+    })?;
+    Ok(row)
+}
+
+#[tracing::instrument(name = "fetch recurring order runs", skip(pool))]
+pub async fn fetch_recurring_order_runs(
+    pool: &PgPool,
+    template_id: Uuid,
+) -> Result<Vec<RecurringOrderRunModel>, anyhow::Error> {
+    let rows = sqlx::query_as!(
+        RecurringOrderRunModel,
+        r#"SELECT id, template_id, transaction_id, status as "status: RecurringOrderRunStatus",
+        remark, quote_total, scheduled_on, created_on, updated_on
+        FROM recurring_order_run WHERE template_id = $1 ORDER BY created_on DESC"#,
+        template_id
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e)
+            .context("A database failure occurred while fetching recurring order runs")
+    })?;
+    Ok(rows)
+}
+
+pub fn get_recurring_order_quote_deviation(
+    template_items: &[RecurringOrderItem],
+    quote_items: &[QuoteVersionItem],
+    price_tolerance: &BigDecimal,
+) -> Option<String> {
+    let quote_item_map: HashMap<&str, &QuoteVersionItem> = quote_items
+        .iter()
+        .map(|item| (item.item_id.as_str(), item))
+        .collect();
+    for item in template_items {
+        let quote_item = match quote_item_map.get(item.item_id.as_str()) {
+            Some(quote_item) => quote_item,
+            None => {
+                return Some(format!(
+                    "Item {} is not present in the seller quote",
+                    item.item_id
+                ))
+            }
+        };
+        if quote_item.qty != item.qty {
+            return Some(format!(
+                "Quantity of item {} changed from {} to {}",
+                item.item_id, item.qty, quote_item.qty
+            ));
+        }
+        if let (Some(expected_price), Some(quoted_price)) =
+            (&item.expected_unit_price, &quote_item.unit_price)
+        {
+            if *expected_price > BigDecimal::from(0) {
+                let deviation =
+                    (quoted_price - expected_price).abs() * BigDecimal::from(100) / expected_price;
+                if deviation > *price_tolerance {
+                    return Some(format!(
+                        "Unit price of item {} changed from {} to {}",
+                        item.item_id, expected_price, quoted_price
+                    ));
+                }
+            }
+        }
+    }
+    if let Some(extra_item) = quote_items.iter().find(|quote_item| {
+        !template_items
+            .iter()
+            .any(|i| i.item_id == quote_item.item_id)
+    }) {
+        return Some(format!(
+            "Item {} is not part of the recurring order",
+            extra_item.item_id
+        ));
+    }
+    None
+}
+
+fn get_recurring_order_meta_data() -> RequestMetaData {
+    RequestMetaData {
+        device_id: "internal".to_string(),
+        request_id: Uuid::new_v4().to_string(),
+    }
+}
+
+/// Resolves the template creator's accounts and current order permissions, runs fail once the creator can no longer create or update orders.
+async fn fetch_recurring_order_accounts(
+    user_client: &UserClient,
+    user_id: Uuid,
+    business_id: Uuid,
+) -> Result<(UserAccount, BusinessAccount, AllowedPermission), GenericError> {
+    let (user_account, business_account, permission_list) = tokio::try_join!(
+        user_client.get_user_account(None, Some(user_id)),
+        user_client.get_business_account(user_id, business_id, vec![CustomerType::RetailB2bBuyer]),
+        user_client.permission_validation(
+            user_id,
+            business_id,
+            vec![
+                PermissionType::CreateOrder,
+                PermissionType::CreateOrderSelf,
+                PermissionType::UpdateOrder,
+                PermissionType::UpdateOrderSelf,
+            ]
+        )
+    )?;
+    let business_account = business_account.ok_or_else(|| {
+        GenericError::ValidationError(format!("Business account {} not found", business_id))
+    })?;
+    let has_permission = |permissions: [PermissionType; 2]| {
+        permissions
+            .iter()
+            .any(|permission| permission_list.contains(permission))
+    };
+    if !has_permission([PermissionType::CreateOrder, PermissionType::CreateOrderSelf])
+        || !has_permission([PermissionType::UpdateOrder, PermissionType::UpdateOrderSelf])
+    {
+        return Err(GenericError::InsufficientPrevilegeError(
+            "Recurring order creator no longer has permission to create and update orders"
+                .to_owned(),
+        ));
+    }
+    Ok((
+        user_account,
+        business_account,
+        AllowedPermission::new(user_id, business_id, permission_list),
+    ))
+}
+
+#[tracing::instrument(name = "trigger recurring order select", skip(context, template))]
+async fn trigger_recurring_order_select(
+    context: &RecurringOrderContext,
+    template: RecurringOrderTemplateModel,
+    transaction_id: Uuid,
+) -> Result<(), GenericError> {
+    let (user_account, business_account, _) = fetch_recurring_order_accounts(
+        &context.user_client,
+        template.user_id,
+        template.business_id,
+    )
+    .await?;
+    let body = OrderSelectRequest {
+        transaction_id,
+        message_id: Uuid::new_v4(),
+        domain_category_code: template.domain_category_code,
+        payment_types: vec![template.payment_type],
+        provider_id: template.provider_id,
+        items: template
+            .items
+            .0
+            .into_iter()
+            .map(|item| OrderSelectItem {
+                item_id: item.item_id,
+                location_ids: item.location_ids,
+                qty: item.qty,
+                buyer_term: item.buyer_term,
+                fulfillment_ids: item.fulfillment_ids,
+            })
+            .collect(),
+        ttl: template.ttl,
+        fulfillments: template.fulfillments.0,
+        order_type: template.order_type,
+        bpp_id: template.bpp_id,
+    };
+    order_select(
+        body,
+        context.pool.clone(),
+        context.ondc_obj.clone(),
+        user_account,
+        business_account,
+        get_recurring_order_meta_data(),
+        context.chat_client.clone(),
+        context.user_client.clone(),
+        context.maps.clone(),
+        context.kafka_client.clone(),
+    )
+    .await?;
+    Ok(())
+}
+
+#[tracing::instrument(name = "trigger recurring order init", skip(context, billing))]
+pub async fn trigger_recurring_order_init(
+    context: &RecurringOrderContext,
+    transaction_id: Uuid,
+    billing: OrderInitBilling,
+    user_account: UserAccount,
+    business_account: BusinessAccount,
+    allowed_permission: AllowedPermission,
+) -> Result<(), GenericError> {
+    let body = OrderInitRequest {
+        transaction_id,
+        message_id: Uuid::new_v4(),
+        billing,
+        payment_schedule: None,
+    };
+    order_init(
+        body,
+        context.pool.clone(),
+        user_account,
+        business_account,
+        get_recurring_order_meta_data(),
+        allowed_permission,
+        context.maps.clone(),
+        context.ondc_obj.clone(),
+        context.kafka_client.clone(),
+    )
+    .await?;
+    Ok(())
+}
+
+#[tracing::instrument(name = "trigger recurring order confirm", skip(context))]
+async fn trigger_recurring_order_confirm(
+    context: &RecurringOrderContext,
+    transaction_id: Uuid,
+    user_account: UserAccount,
+    business_account: BusinessAccount,
+    allowed_permission: AllowedPermission,
+) -> Result<(), GenericError> {
+    let body = OrderConfirmRequest {
+        transaction_id,
+        message_id: Uuid::new_v4(),
+    };
+    order_confirm(
+        body,
+        context.pool.clone(),
+        user_account,
+        business_account,
+        get_recurring_order_meta_data(),
+        allowed_permission,
+        context.maps.clone(),
+        context.ondc_obj.clone(),
+        context.kafka_client.clone(),
+    )
+    .await?;
+    Ok(())
+}
+
+#[tracing::instrument(name = "process due recurring orders", skip(context))]
+pub async fn process_due_recurring_orders(
+    context: &RecurringOrderContext,
+) -> Result<(), anyhow::Error> {
+    let now = Utc::now();
+    fail_stale_recurring_order_runs(
+        &context.pool,
+        now - Duration::minutes(RECURRING_ORDER_RUN_TIMEOUT_MINUTES),
+    )
+    .await?;
+    let mut transaction = context.pool.begin().await?;
+    let templates =
+        fetch_due_recurring_order_templates(&mut transaction, now, RECURRING_ORDER_BATCH_SIZE)
+            .await?;
+    let mut due_runs = vec![];
+    for template in templates {
+        let mut next_run_on = template.next_run_on;
+        while next_run_on <= now {
+            match template
+                .frequency
+                .get_next_run_on(next_run_on, template.frequency_interval)
+            {
+                Some(next) => next_run_on = next,
+                None => break,
+            }
+        }
+        let next_run_on = (next_run_on > now).then_some(next_run_on);
+        let transaction_id = Uuid::new_v4();
+        update_recurring_order_template_next_run(&mut transaction, template.id, next_run_on)
+            .await?;
+        save_recurring_order_run(
+            &mut transaction,
+            template.id,
+            transaction_id,
+            template.next_run_on,
+        )
+        .await?;
+        due_runs.push((template, transaction_id));
+    }
+    transaction.commit().await?;
+
+    for (template, transaction_id) in due_runs {
+        if let Err(e) = trigger_recurring_order_select(context, template, transaction_id).await {
+            tracing::error!("Failed to trigger recurring order select: {:?}", e);
+            if let Err(e) = update_recurring_order_run(
+                &context.pool,
+                transaction_id,
+                RecurringOrderRunStatus::Failed,
+                Some(&e.to_string()),
+                None,
+            )
+            .await
+            {
+                tracing::error!("Failed to mark recurring order run as failed: {:?}", e);
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn start_recurring_order_scheduler(context: RecurringOrderContext) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(
+            RECURRING_ORDER_SCHEDULER_INTERVAL,
+        ));
+        loop {
+            interval.tick().await;
+            if let Err(e) = process_due_recurring_orders(&context).await {
+                tracing::error!("Failed to process recurring orders: {:?}", e);
+            }
+        }
+    });
+}
+
+#[tracing::instrument(name = "process recurring order on select", skip(context, quote_items))]
+pub async fn process_recurring_order_on_select(
+    context: &RecurringOrderContext,
+    transaction_id: Uuid,
+    error: Option<String>,
+    quote_items: Vec<QuoteVersionItem>,
+    quote_total: Option<BigDecimal>,
+) -> Result<(), anyhow::Error> {
+    let run =
+        match fetch_recurring_order_run_by_transaction_id(&context.pool, transaction_id).await? {
+            Some(run) if run.status == RecurringOrderRunStatus::SelectSent => run,
+            _ => return Ok(()),
+        };
+    if let Some(error) = error {
+        update_recurring_order_run(
+            &context.pool,
+            transaction_id,
+            RecurringOrderRunStatus::Failed,
+            Some(&error),
+            quote_total.as_ref(),
+        )
+        .await?;
+        return Ok(());
+    }
+    let template = fetch_recurring_order_template_by_id(&context.pool, run.template_id)
+        .await?
+        .ok_or_else(|| anyhow!("Recurring order template {} not found", run.template_id))?;
+    if let Some(remark) = get_recurring_order_quote_deviation(
+        &template.items,
+        &quote_items,
+        &template.price_tolerance,
+    ) {
+        update_recurring_order_run(
+            &context.pool,
+            transaction_id,
+            RecurringOrderRunStatus::PendingApproval,
+            Some(&remark),
+            quote_total.as_ref(),
+        )
+        .await?;
+        return Ok(());
+    }
+    if !claim_recurring_order_run(
+        &context.pool,
+        transaction_id,
+        RecurringOrderRunStatus::SelectSent,
+        RecurringOrderRunStatus::InitSent,
+        quote_total.as_ref(),
+    )
+    .await?
+    {
+        return Ok(());
+    }
+
+    let result = match fetch_recurring_order_accounts(
+        &context.user_client,
+        template.user_id,
+        template.business_id,
+    )
+    .await
+    {
+        Ok((user_account, business_account, allowed_permission)) => {
+            trigger_recurring_order_init(
+                context,
+                transaction_id,
+                template.billing.0,
+                user_account,
+                business_account,
+                allowed_permission,
+            )
+            .await
+        }
+        Err(e) => Err(e),
+    };
+    match result {
+        Ok(()) => Ok(()),
+        Err(e) => {
+            update_recurring_order_run(
+                &context.pool,
+                transaction_id,
+                RecurringOrderRunStatus::Failed,
+                Some(&e.to_string()),
+                None,
+            )
+            .await
+        }
+    }
+}
+
+#[tracing::instrument(name = "process recurring order on init", skip(context))]
+pub async fn process_recurring_order_on_init(
+    context: &RecurringOrderContext,
+    transaction_id: Uuid,
+    error: Option<String>,
+) -> Result<(), anyhow::Error> {
+    let run =
+        match fetch_recurring_order_run_by_transaction_id(&context.pool, transaction_id).await? {
+            Some(run) if run.status == RecurringOrderRunStatus::InitSent => run,
+            _ => return Ok(()),
+        };
+    if let Some(error) = error {
+        return update_recurring_order_run(
+            &context.pool,
+            transaction_id,
+            RecurringOrderRunStatus::Failed,
+            Some(&error),
+            None,
+        )
+        .await;
+    }
+    let template = fetch_recurring_order_template_by_id(&context.pool, run.template_id)
+        .await?
+        .ok_or_else(|| anyhow!("Recurring order template {} not found", run.template_id))?;
+    if !claim_recurring_order_run(
+        &context.pool,
+        transaction_id,
+        RecurringOrderRunStatus::InitSent,
+        RecurringOrderRunStatus::ConfirmSent,
+        None,
+    )
+    .await?
+    {
+        return Ok(());
+    }
+    let result = match fetch_recurring_order_accounts(
+        &context.user_client,
+        template.user_id,
+        template.business_id,
+    )
+    .await
+    {
+        Ok((user_account, business_account, allowed_permission)) => {
+            trigger_recurring_order_confirm(
+                context,
+                transaction_id,
+                user_account,
+                business_account,
+                allowed_permission,
+            )
+            .await
+        }
+        Err(e) => Err(e),
+    };
+    match result {
+        Ok(()) => Ok(()),
+        Err(e) => {
+            update_recurring_order_run(
+                &context.pool,
+                transaction_id,
+                RecurringOrderRunStatus::Failed,
+                Some(&e.to_string()),
+                None,
+            )
+            .await
+        }
+    }
+}
+
+#[tracing::instrument(name = "process recurring order on confirm", skip(pool))]
+pub async fn process_recurring_order_on_confirm(
+    pool: &PgPool,
+    transaction_id: Uuid,
+    error: Option<&str>,
+) -> Result<(), anyhow::Error> {
+    match fetch_recurring_order_run_by_transaction_id(pool, transaction_id).await? {
+        Some(run) if run.status == RecurringOrderRunStatus::ConfirmSent => (),
+        _ => return Ok(()),
+    };
+    let status = if error.is_some() {
+        RecurringOrderRunStatus::Failed
+    } else {
+        RecurringOrderRunStatus::Completed
+    };
+    update_recurring_order_run(pool, transaction_id, status, error, None).await
+}
+
+#[tracing::instrument(name = "fetch pending recurring order run", skip(pool))]
+pub async fn fetch_pending_recurring_order_run(
+    pool: &PgPool,
+    run_id: Uuid,
+    business_account: &BusinessAccount,
+) -> Result<(RecurringOrderRunModel, RecurringOrderTemplateModel), GenericError> {
+    let run = fetch_recurring_order_run_by_id(pool, run_id)
+        .await
+        .map_err(|e| {
+            GenericError::DatabaseError("Failed to fetch recurring order run".to_string(), e)
+        })?
+        .ok_or_else(|| {
+            GenericError::ValidationError("Recurring order run not found".to_string())
+        })?;
+    let template = fetch_recurring_order_template_by_id(pool, run.template_id)
+        .await
+        .map_err(|e| GenericError::DatabaseError("Failed to fetch recurring order".to_string(), e))?
+        .filter(|template| template.business_id == business_account.id)
+        .ok_or_else(|| {
+            GenericError::ValidationError("Recurring order run not found".to_string())
+        })?;
+    if run.status != RecurringOrderRunStatus::PendingApproval {
+        return Err(GenericError::ValidationError(format!(
+            "Recurring order run is already {}",
+            run.status
+        )));
+    }
+    Ok((run, template))
+}
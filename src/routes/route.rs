@@ -2,7 +2,10 @@ use super::ondc::{ondc_route, ONDCObservability, SellerHeaderVerification};
 use super::payment::payment_route;
 use crate::middleware::HeaderValidation;
 use crate::openapi::ApiDoc;
-use crate::routes::{notification_route, order_route, product_route, util_route};
+use crate::routes::{
+    favourite_route, notification_route, order_route, payable_route, product_route,
+    recurring_order_route, saved_search_route, settlement_route, util_route, webhook_route,
+};
 use actix_web::web;
 use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
@@ -26,6 +29,36 @@ pub fn main_route(cfg: &mut web::ServiceConfig) {
                 .configure(product_route)
                 .wrap(HeaderValidation),
         )
+        .service(
+            web::scope("/recurring_order")
+                .configure(recurring_order_route)
+                .wrap(HeaderValidation),
+        )
+        .service(
+            web::scope("/saved_search")
+                .configure(saved_search_route)
+                .wrap(HeaderValidation),
+        )
+        .service(
+            web::scope("/favourite")
+                .configure(favourite_route)
+                .wrap(HeaderValidation),
+        )
+        .service(
+            web::scope("/payable")
+                .configure(payable_route)
+                .wrap(HeaderValidation),
+        )
+        .service(
+            web::scope("/settlement")
+                .configure(settlement_route)
+                .wrap(HeaderValidation),
+        )
+        .service(
+            web::scope("/webhook")
+                .configure(webhook_route)
+                .wrap(HeaderValidation),
+        )
         .service(
             web::scope("/v1/ondc/buyer")
                 .configure(ondc_route)
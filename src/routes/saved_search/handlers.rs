@@ -0,0 +1,290 @@
+use actix_http::StatusCode;
+use actix_web::web;
+use sqlx::PgPool;
+use utoipa::TupleUnit;
+
+use super::schemas::{
+    CatalogAlert, CatalogAlertListRequest, ItemWatch, ItemWatchCreateRequest,
+    ItemWatchCreateResponse, ItemWatchStatusUpdateRequest, SavedSearch, SavedSearchCreateRequest,
+    SavedSearchCreateResponse, SavedSearchStatusUpdateRequest,
+};
+use super::utils::{
+    fetch_catalog_alerts, fetch_item_watch_by_id, fetch_item_watches, fetch_saved_search_by_id,
+    fetch_saved_searches, save_item_watch, save_saved_search, update_item_watch_status,
+    update_saved_search_status, validate_item_watch_create_request,
+    validate_saved_search_create_request,
+};
+use crate::errors::GenericError;
+use crate::schemas::GenericResponse;
+use crate::user_client::{BusinessAccount, UserAccount};
+
+#[utoipa::path(
+    post,
+    path = "/saved_search/create",
+    tag = "Saved Search",
+    description="This API saves a cached product search whose new matching items are notified after each cache refresh.",
+    summary= "Saved Search Create Request",
+    request_body(content = SavedSearchCreateRequest, description = "Request Body"),
+    responses(
+        (status=200, description= "Saved Search Create Response", body= GenericResponse<SavedSearchCreateResponse>),
+        (status=400, description= "Invalid Request body", body= GenericResponse<TupleUnit>),
+        (status=401, description= "Invalid Token", body= GenericResponse<TupleUnit>),
+	    (status=403, description= "Insufficient Previlege", body= GenericResponse<TupleUnit>),
+	    (status=410, description= "Data not found", body= GenericResponse<TupleUnit>),
+        (status=500, description= "Internal Server Error", body= GenericResponse<TupleUnit>),
+	    (status=501, description= "Not Implemented", body= GenericResponse<TupleUnit>),
+    )
+)]
+#[tracing::instrument(name = "saved search create", skip(pool), fields())]
+pub async fn saved_search_create(
+    body: SavedSearchCreateRequest,
+    pool: web::Data<PgPool>,
+    user_account: UserAccount,
+    business_account: BusinessAccount,
+) -> Result<web::Json<GenericResponse<SavedSearchCreateResponse>>, GenericError> {
+    validate_saved_search_create_request(&body)?;
+    let id = save_saved_search(&pool, &body, user_account.id, business_account.id)
+        .await
+        .map_err(|e| GenericError::DatabaseError("Failed to save saved search".to_string(), e))?;
+    Ok(web::Json(GenericResponse::success(
+        "Successfully created saved search",
+        StatusCode::OK,
+        Some(SavedSearchCreateResponse { id }),
+    )))
+}
+
+#[utoipa::path(
+    post,
+    path = "/saved_search/list",
+    tag = "Saved Search",
+    description="This API lists the saved searches of the user.",
+    summary= "Saved Search List Request",
+    responses(
+        (status=200, description= "Saved Search List Response", body= GenericResponse<Vec<SavedSearch>>),
+        (status=400, description= "Invalid Request body", body= GenericResponse<TupleUnit>),
+        (status=401, description= "Invalid Token", body= GenericResponse<TupleUnit>),
+	    (status=403, description= "Insufficient Previlege", body= GenericResponse<TupleUnit>),
+	    (status=410, description= "Data not found", body= GenericResponse<TupleUnit>),
+        (status=500, description= "Internal Server Error", body= GenericResponse<TupleUnit>),
+	    (status=501, description= "Not Implemented", body= GenericResponse<TupleUnit>),
+    )
+)]
+#[tracing::instrument(name = "saved search list", skip(pool), fields())]
+pub async fn saved_search_list(
+    pool: web::Data<PgPool>,
+    user_account: UserAccount,
+    business_account: BusinessAccount,
+) -> Result<web::Json<GenericResponse<Vec<SavedSearch>>>, GenericError> {
+    let data = fetch_saved_searches(&pool, business_account.id, user_account.id)
+        .await
+        .map_err(|e| {
+            GenericError::DatabaseError("Failed to fetch saved searches".to_string(), e)
+        })?;
+    Ok(web::Json(GenericResponse::success(
+        "Successfully fetched saved searches",
+        StatusCode::OK,
+        Some(
+            data.into_iter()
+                .map(|saved_search| saved_search.schema())
+                .collect(),
+        ),
+    )))
+}
+
+#[utoipa::path(
+    post,
+    path = "/saved_search/status/update",
+    tag = "Saved Search",
+    description="This API pauses or resumes the alerts of a saved search.",
+    summary= "Saved Search Status Update Request",
+    request_body(content = SavedSearchStatusUpdateRequest, description = "Request Body"),
+    responses(
+        (status=200, description= "Saved Search Status Update Response", body= GenericResponse<TupleUnit>),
+        (status=400, description= "Invalid Request body", body= GenericResponse<TupleUnit>),
+        (status=401, description= "Invalid Token", body= GenericResponse<TupleUnit>),
+	    (status=403, description= "Insufficient Previlege", body= GenericResponse<TupleUnit>),
+	    (status=410, description= "Data not found", body= GenericResponse<TupleUnit>),
+        (status=500, description= "Internal Server Error", body= GenericResponse<TupleUnit>),
+	    (status=501, description= "Not Implemented", body= GenericResponse<TupleUnit>),
+    )
+)]
+#[tracing::instrument(name = "saved search status update", skip(pool), fields(saved_search_id = %body.saved_search_id))]
+pub async fn saved_search_status_update(
+    body: SavedSearchStatusUpdateRequest,
+    pool: web::Data<PgPool>,
+    user_account: UserAccount,
+    business_account: BusinessAccount,
+) -> Result<web::Json<GenericResponse<()>>, GenericError> {
+    let saved_search = fetch_saved_search_by_id(&pool, body.saved_search_id)
+        .await
+        .map_err(|e| GenericError::DatabaseError("Failed to fetch saved search".to_string(), e))?
+        .ok_or_else(|| GenericError::ValidationError("Saved search not found".to_string()))?;
+    if saved_search.business_id != business_account.id || saved_search.user_id != user_account.id {
+        return Err(GenericError::InsufficientPrevilegeError(
+            "You do not have sufficent preveliege to update the saved search".to_owned(),
+        ));
+    }
+    update_saved_search_status(&pool, body.saved_search_id, body.is_active)
+        .await
+        .map_err(|e| GenericError::DatabaseError("Failed to update saved search".to_string(), e))?;
+    Ok(web::Json(GenericResponse::success(
+        "Successfully updated saved search",
+        StatusCode::OK,
+        Some(()),
+    )))
+}
+
+#[utoipa::path(
+    post,
+    path = "/saved_search/item_watch/create",
+    tag = "Saved Search",
+    description="This API watches a cached item for price drops below a threshold or for it becoming available again.",
+    summary= "Item Watch Create Request",
+    request_body(content = ItemWatchCreateRequest, description = "Request Body"),
+    responses(
+        (status=200, description= "Item Watch Create Response", body= GenericResponse<ItemWatchCreateResponse>),
+        (status=400, description= "Invalid Request body", body= GenericResponse<TupleUnit>),
+        (status=401, description= "Invalid Token", body= GenericResponse<TupleUnit>),
+	    (status=403, description= "Insufficient Previlege", body= GenericResponse<TupleUnit>),
+	    (status=410, description= "Data not found", body= GenericResponse<TupleUnit>),
+        (status=500, description= "Internal Server Error", body= GenericResponse<TupleUnit>),
+	    (status=501, description= "Not Implemented", body= GenericResponse<TupleUnit>),
+    )
+)]
+#[tracing::instrument(name = "item watch create", skip(pool), fields())]
+pub async fn item_watch_create(
+    body: ItemWatchCreateRequest,
+    pool: web::Data<PgPool>,
+    user_account: UserAccount,
+    business_account: BusinessAccount,
+) -> Result<web::Json<GenericResponse<ItemWatchCreateResponse>>, GenericError> {
+    validate_item_watch_create_request(&body)?;
+    let id = save_item_watch(&pool, &body, user_account.id, business_account.id)
+        .await
+        .map_err(|e| GenericError::DatabaseError("Failed to save item watch".to_string(), e))?;
+    Ok(web::Json(GenericResponse::success(
+        "Successfully created item watch",
+        StatusCode::OK,
+        Some(ItemWatchCreateResponse { id }),
+    )))
+}
+
+#[utoipa::path(
+    post,
+    path = "/saved_search/item_watch/list",
+    tag = "Saved Search",
+    description="This API lists the item watches of the user.",
+    summary= "Item Watch List Request",
+    responses(
+        (status=200, description= "Item Watch List Response", body= GenericResponse<Vec<ItemWatch>>),
+        (status=400, description= "Invalid Request body", body= GenericResponse<TupleUnit>),
+        (status=401, description= "Invalid Token", body= GenericResponse<TupleUnit>),
+	    (status=403, description= "Insufficient Previlege", body= GenericResponse<TupleUnit>),
+	    (status=410, description= "Data not found", body= GenericResponse<TupleUnit>),
+        (status=500, description= "Internal Server Error", body= GenericResponse<TupleUnit>),
+	    (status=501, description= "Not Implemented", body= GenericResponse<TupleUnit>),
+    )
+)]
+#[tracing::instrument(name = "item watch list", skip(pool), fields())]
+pub async fn item_watch_list(
+    pool: web::Data<PgPool>,
+    user_account: UserAccount,
+    business_account: BusinessAccount,
+) -> Result<web::Json<GenericResponse<Vec<ItemWatch>>>, GenericError> {
+    let data = fetch_item_watches(&pool, business_account.id, user_account.id)
+        .await
+        .map_err(|e| GenericError::DatabaseError("Failed to fetch item watches".to_string(), e))?;
+    Ok(web::Json(GenericResponse::success(
+        "Successfully fetched item watches",
+        StatusCode::OK,
+        Some(
+            data.into_iter()
+                .map(|item_watch| item_watch.schema())
+                .collect(),
+        ),
+    )))
+}
+
+#[utoipa::path(
+    post,
+    path = "/saved_search/item_watch/status/update",
+    tag = "Saved Search",
+    description="This API pauses or resumes an item watch.",
+    summary= "Item Watch Status Update Request",
+    request_body(content = ItemWatchStatusUpdateRequest, description = "Request Body"),
+    responses(
+        (status=200, description= "Item Watch Status Update Response", body= GenericResponse<TupleUnit>),
+        (status=400, description= "Invalid Request body", body= GenericResponse<TupleUnit>),
+        (status=401, description= "Invalid Token", body= GenericResponse<TupleUnit>),
+	    (status=403, description= "Insufficient Previlege", body= GenericResponse<TupleUnit>),
+	    (status=410, description= "Data not found", body= GenericResponse<TupleUnit>),
+        (status=500, description= "Internal Server Error", body= GenericResponse<TupleUnit>),
+	    (status=501, description= "Not Implemented", body= GenericResponse<TupleUnit>),
+    )
+)]
+#[tracing::instrument(name = "item watch status update", skip(pool), fields(item_watch_id = %body.item_watch_id))]
+pub async fn item_watch_status_update(
+    body: ItemWatchStatusUpdateRequest,
+    pool: web::Data<PgPool>,
+    user_account: UserAccount,
+    business_account: BusinessAccount,
+) -> Result<web::Json<GenericResponse<()>>, GenericError> {
+    let item_watch = fetch_item_watch_by_id(&pool, body.item_watch_id)
+        .await
+        .map_err(|e| GenericError::DatabaseError("Failed to fetch item watch".to_string(), e))?
+        .ok_or_else(|| GenericError::ValidationError("Item watch not found".to_string()))?;
+    if item_watch.business_id != business_account.id || item_watch.user_id != user_account.id {
+        return Err(GenericError::InsufficientPrevilegeError(
+            "You do not have sufficent preveliege to update the item watch".to_owned(),
+        ));
+    }
+    update_item_watch_status(&pool, body.item_watch_id, body.is_active)
+        .await
+        .map_err(|e| GenericError::DatabaseError("Failed to update item watch".to_string(), e))?;
+    Ok(web::Json(GenericResponse::success(
+        "Successfully updated item watch",
+        StatusCode::OK,
+        Some(()),
+    )))
+}
+
+#[utoipa::path(
+    post,
+    path = "/saved_search/alert/list",
+    tag = "Saved Search",
+    description="This API lists the new item, price drop and back in stock alerts raised for the user.",
+    summary= "Catalog Alert List Request",
+    request_body(content = CatalogAlertListRequest, description = "Request Body"),
+    responses(
+        (status=200, description= "Catalog Alert List Response", body= GenericResponse<Vec<CatalogAlert>>),
+        (status=400, description= "Invalid Request body", body= GenericResponse<TupleUnit>),
+        (status=401, description= "Invalid Token", body= GenericResponse<TupleUnit>),
+	    (status=403, description= "Insufficient Previlege", body= GenericResponse<TupleUnit>),
+	    (status=410, description= "Data not found", body= GenericResponse<TupleUnit>),
+        (status=500, description= "Internal Server Error", body= GenericResponse<TupleUnit>),
+	    (status=501, description= "Not Implemented", body= GenericResponse<TupleUnit>),
+    )
+)]
+#[tracing::instrument(name = "catalog alert list", skip(pool), fields())]
+pub async fn catalog_alert_list(
+    body: CatalogAlertListRequest,
+    pool: web::Data<PgPool>,
+    user_account: UserAccount,
+    business_account: BusinessAccount,
+) -> Result<web::Json<GenericResponse<Vec<CatalogAlert>>>, GenericError> {
+    let data = fetch_catalog_alerts(
+        &pool,
+        business_account.id,
+        user_account.id,
+        body.alert_type.as_ref(),
+        body.limit,
+        body.offset,
+    )
+    .await
+    .map_err(|e| GenericError::DatabaseError("Failed to fetch catalog alerts".to_string(), e))?;
+    Ok(web::Json(GenericResponse::success(
+        "Successfully fetched catalog alerts",
+        StatusCode::OK,
+        Some(data.into_iter().map(|alert| alert.schema()).collect()),
+    )))
+}
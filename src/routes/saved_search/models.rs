@@ -0,0 +1,131 @@
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use sqlx::types::Json;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use super::schemas::{CatalogAlert, CatalogAlertChannel, CatalogAlertType, ItemWatch, SavedSearch};
+use crate::routes::product::schemas::ProductCacheSearchRequest;
+use crate::schemas::CurrencyType;
+
+#[derive(Deserialize, Debug, FromRow)]
+pub struct SavedSearchModel {
+    pub id: Uuid,
+    pub business_id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub search_request: Json<ProductCacheSearchRequest>,
+    pub channels: Vec<CatalogAlertChannel>,
+    pub is_active: bool,
+    pub cache_refreshed_on: Option<DateTime<Utc>>,
+    pub created_on: DateTime<Utc>,
+}
+
+impl SavedSearchModel {
+    pub fn schema(self) -> SavedSearch {
+        SavedSearch {
+            id: self.id,
+            name: self.name,
+            search: self.search_request.0,
+            channels: self.channels,
+            is_active: self.is_active,
+            cache_refreshed_on: self.cache_refreshed_on,
+            created_on: self.created_on,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, FromRow)]
+pub struct ItemWatchModel {
+    pub id: Uuid,
+    pub business_id: Uuid,
+    pub user_id: Uuid,
+    pub subscriber_id: String,
+    pub provider_id: String,
+    pub item_id: String,
+    pub price_threshold: Option<BigDecimal>,
+    pub notify_on_available: bool,
+    pub channels: Vec<CatalogAlertChannel>,
+    pub is_active: bool,
+    pub created_on: DateTime<Utc>,
+}
+
+impl ItemWatchModel {
+    pub fn schema(self) -> ItemWatch {
+        ItemWatch {
+            id: self.id,
+            bpp_id: self.subscriber_id,
+            provider_id: self.provider_id,
+            item_id: self.item_id,
+            price_threshold: self.price_threshold,
+            notify_on_available: self.notify_on_available,
+            channels: self.channels,
+            is_active: self.is_active,
+            created_on: self.created_on,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, FromRow)]
+pub struct CatalogAlertModel {
+    pub id: Uuid,
+    pub alert_type: CatalogAlertType,
+    pub saved_search_id: Option<Uuid>,
+    pub item_watch_id: Option<Uuid>,
+    pub subscriber_id: String,
+    pub provider_id: String,
+    pub item_id: String,
+    pub item_name: Option<String>,
+    pub currency: CurrencyType,
+    pub price: BigDecimal,
+    pub previous_price: Option<BigDecimal>,
+    pub created_on: DateTime<Utc>,
+}
+
+impl CatalogAlertModel {
+    pub fn schema(self) -> CatalogAlert {
+        CatalogAlert {
+            id: self.id,
+            alert_type: self.alert_type,
+            saved_search_id: self.saved_search_id,
+            item_watch_id: self.item_watch_id,
+            bpp_id: self.subscriber_id,
+            provider_id: self.provider_id,
+            item_id: self.item_id,
+            item_name: self.item_name,
+            currency: self.currency,
+            price: self.price,
+            previous_price: self.previous_price,
+            created_on: self.created_on,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, FromRow)]
+pub struct SavedSearchItemModel {
+    pub subscriber_id: String,
+    pub provider_id: String,
+    pub item_id: String,
+}
+
+#[derive(Deserialize, Debug, FromRow)]
+pub struct ItemWatchChangeModel {
+    pub item_watch_id: Uuid,
+    pub business_id: Uuid,
+    pub user_id: Uuid,
+    pub price_threshold: Option<BigDecimal>,
+    pub notify_on_available: bool,
+    pub channels: Vec<CatalogAlertChannel>,
+    pub history_id: Uuid,
+    pub subscriber_id: String,
+    pub provider_id: String,
+    pub item_id: String,
+    pub item_name: Option<String>,
+    pub currency: CurrencyType,
+    pub price_with_tax: BigDecimal,
+    pub available_qty: i32,
+    pub previous_price_with_tax: Option<BigDecimal>,
+    pub previous_available_qty: Option<i32>,
+    pub created_on: DateTime<Utc>,
+}
@@ -0,0 +1,66 @@
+use crate::middleware::{BusinessAccountValidation, RequireAuth};
+use crate::user_client::CustomerType;
+use actix_web::web;
+
+use super::handlers::{
+    catalog_alert_list, item_watch_create, item_watch_list, item_watch_status_update,
+    saved_search_create, saved_search_list, saved_search_status_update,
+};
+pub fn saved_search_route(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::resource("/create")
+            .route(web::post().to(saved_search_create))
+            .wrap(BusinessAccountValidation {
+                business_type_list: vec![CustomerType::RetailB2bBuyer],
+            })
+            .wrap(RequireAuth),
+    );
+    cfg.service(
+        web::resource("/list")
+            .route(web::post().to(saved_search_list))
+            .wrap(BusinessAccountValidation {
+                business_type_list: vec![CustomerType::RetailB2bBuyer],
+            })
+            .wrap(RequireAuth),
+    );
+    cfg.service(
+        web::resource("/status/update")
+            .route(web::post().to(saved_search_status_update))
+            .wrap(BusinessAccountValidation {
+                business_type_list: vec![CustomerType::RetailB2bBuyer],
+            })
+            .wrap(RequireAuth),
+    );
+    cfg.service(
+        web::resource("/item_watch/create")
+            .route(web::post().to(item_watch_create))
+            .wrap(BusinessAccountValidation {
+                business_type_list: vec![CustomerType::RetailB2bBuyer],
+            })
+            .wrap(RequireAuth),
+    );
+    cfg.service(
+        web::resource("/item_watch/list")
+            .route(web::post().to(item_watch_list))
+            .wrap(BusinessAccountValidation {
+                business_type_list: vec![CustomerType::RetailB2bBuyer],
+            })
+            .wrap(RequireAuth),
+    );
+    cfg.service(
+        web::resource("/item_watch/status/update")
+            .route(web::post().to(item_watch_status_update))
+            .wrap(BusinessAccountValidation {
+                business_type_list: vec![CustomerType::RetailB2bBuyer],
+            })
+            .wrap(RequireAuth),
+    );
+    cfg.service(
+        web::resource("/alert/list")
+            .route(web::post().to(catalog_alert_list))
+            .wrap(BusinessAccountValidation {
+                business_type_list: vec![CustomerType::RetailB2bBuyer],
+            })
+            .wrap(RequireAuth),
+    );
+}
@@ -0,0 +1,237 @@
+use crate::errors::GenericError;
+use crate::routes::product::schemas::ProductCacheSearchRequest;
+use crate::schemas::CurrencyType;
+use actix_http::Payload;
+use actix_web::{web, FromRequest, HttpRequest};
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Utc};
+use futures_util::future::LocalBoxFuture;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Deserialize, Debug, ToSchema, Serialize, sqlx::Type, Clone, PartialEq)]
+#[sqlx(type_name = "catalog_alert_channel", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum CatalogAlertChannel {
+    Email,
+    Chat,
+    Websocket,
+}
+
+#[derive(Deserialize, Debug, ToSchema, Serialize, sqlx::Type, Clone, PartialEq)]
+#[sqlx(type_name = "catalog_alert_type", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum CatalogAlertType {
+    NewItem,
+    PriceDrop,
+    BackInStock,
+}
+
+impl fmt::Display for CatalogAlertType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            CatalogAlertType::NewItem => "new_item",
+            CatalogAlertType::PriceDrop => "price_drop",
+            CatalogAlertType::BackInStock => "back_in_stock",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+#[derive(Deserialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedSearchCreateRequest {
+    pub name: String,
+    pub search: ProductCacheSearchRequest,
+    pub channels: Vec<CatalogAlertChannel>,
+}
+
+impl FromRequest for SavedSearchCreateRequest {
+    type Error = GenericError;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let fut = web::Json::<Self>::from_request(req, payload);
+
+        Box::pin(async move {
+            match fut.await {
+                Ok(json) => Ok(json.into_inner()),
+                Err(e) => Err(GenericError::ValidationError(e.to_string())),
+            }
+        })
+    }
+}
+
+#[derive(Deserialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedSearchStatusUpdateRequest {
+    #[schema(value_type = String)]
+    pub saved_search_id: Uuid,
+    pub is_active: bool,
+}
+
+impl FromRequest for SavedSearchStatusUpdateRequest {
+    type Error = GenericError;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let fut = web::Json::<Self>::from_request(req, payload);
+
+        Box::pin(async move {
+            match fut.await {
+                Ok(json) => Ok(json.into_inner()),
+                Err(e) => Err(GenericError::ValidationError(e.to_string())),
+            }
+        })
+    }
+}
+
+#[derive(Deserialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemWatchCreateRequest {
+    pub bpp_id: String,
+    pub provider_id: String,
+    pub item_id: String,
+    #[schema(value_type = Option<f64>)]
+    pub price_threshold: Option<BigDecimal>,
+    pub notify_on_available: bool,
+    pub channels: Vec<CatalogAlertChannel>,
+}
+
+impl FromRequest for ItemWatchCreateRequest {
+    type Error = GenericError;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let fut = web::Json::<Self>::from_request(req, payload);
+
+        Box::pin(async move {
+            match fut.await {
+                Ok(json) => Ok(json.into_inner()),
+                Err(e) => Err(GenericError::ValidationError(e.to_string())),
+            }
+        })
+    }
+}
+
+#[derive(Deserialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemWatchStatusUpdateRequest {
+    #[schema(value_type = String)]
+    pub item_watch_id: Uuid,
+    pub is_active: bool,
+}
+
+impl FromRequest for ItemWatchStatusUpdateRequest {
+    type Error = GenericError;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let fut = web::Json::<Self>::from_request(req, payload);
+
+        Box::pin(async move {
+            match fut.await {
+                Ok(json) => Ok(json.into_inner()),
+                Err(e) => Err(GenericError::ValidationError(e.to_string())),
+            }
+        })
+    }
+}
+
+#[derive(Deserialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CatalogAlertListRequest {
+    pub alert_type: Option<CatalogAlertType>,
+    pub offset: i32,
+    pub limit: i32,
+}
+
+impl FromRequest for CatalogAlertListRequest {
+    type Error = GenericError;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let fut = web::Json::<Self>::from_request(req, payload);
+
+        Box::pin(async move {
+            match fut.await {
+                Ok(json) => Ok(json.into_inner()),
+                Err(e) => Err(GenericError::ValidationError(e.to_string())),
+            }
+        })
+    }
+}
+
+#[derive(Debug, ToSchema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedSearchCreateResponse {
+    #[schema(value_type = String)]
+    pub id: Uuid,
+}
+
+#[derive(Debug, ToSchema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemWatchCreateResponse {
+    #[schema(value_type = String)]
+    pub id: Uuid,
+}
+
+#[derive(Debug, ToSchema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedSearch {
+    #[schema(value_type = String)]
+    pub id: Uuid,
+    pub name: String,
+    pub search: ProductCacheSearchRequest,
+    pub channels: Vec<CatalogAlertChannel>,
+    pub is_active: bool,
+    pub cache_refreshed_on: Option<DateTime<Utc>>,
+    pub created_on: DateTime<Utc>,
+}
+
+#[derive(Debug, ToSchema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemWatch {
+    #[schema(value_type = String)]
+    pub id: Uuid,
+    pub bpp_id: String,
+    pub provider_id: String,
+    pub item_id: String,
+    #[schema(value_type = Option<f64>)]
+    pub price_threshold: Option<BigDecimal>,
+    pub notify_on_available: bool,
+    pub channels: Vec<CatalogAlertChannel>,
+    pub is_active: bool,
+    pub created_on: DateTime<Utc>,
+}
+
+#[derive(Debug, ToSchema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CatalogAlert {
+    #[schema(value_type = String)]
+    pub id: Uuid,
+    pub alert_type: CatalogAlertType,
+    #[schema(value_type = Option<String>)]
+    pub saved_search_id: Option<Uuid>,
+    #[schema(value_type = Option<String>)]
+    pub item_watch_id: Option<Uuid>,
+    pub bpp_id: String,
+    pub provider_id: String,
+    pub item_id: String,
+    pub item_name: Option<String>,
+    pub currency: CurrencyType,
+    #[schema(value_type = f64)]
+    pub price: BigDecimal,
+    #[schema(value_type = Option<f64>)]
+    pub previous_price: Option<BigDecimal>,
+    pub created_on: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CatalogAlertNotification<'a> {
+    pub title: &'a str,
+    pub alerts: &'a [CatalogAlert],
+}
@@ -0,0 +1,159 @@
+#[cfg(test)]
+mod tests {
+    use bigdecimal::BigDecimal;
+    use chrono::Utc;
+    use serde_json::json;
+    use std::str::FromStr;
+    use uuid::Uuid;
+
+    use crate::{
+        routes::saved_search::{
+            models::ItemWatchChangeModel,
+            schemas::{
+                CatalogAlert, CatalogAlertChannel, CatalogAlertType, SavedSearchCreateRequest,
+            },
+            utils::{
+                fetch_catalog_alerts, fetch_item_watches, fetch_saved_search_by_id,
+                fetch_saved_searches, get_item_watch_alert_types, save_saved_search,
+                save_saved_search_alerts,
+            },
+        },
+        schemas::CurrencyType,
+        tests::tests::get_test_pool,
+    };
+
+    fn get_item_watch_change(
+        price_threshold: Option<&str>,
+        notify_on_available: bool,
+        price: &str,
+        available_qty: i32,
+        previous_price: Option<&str>,
+        previous_available_qty: Option<i32>,
+    ) -> ItemWatchChangeModel {
+        ItemWatchChangeModel {
+            item_watch_id: Uuid::new_v4(),
+            business_id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            price_threshold: price_threshold.map(|p| BigDecimal::from_str(p).unwrap()),
+            notify_on_available,
+            channels: vec![CatalogAlertChannel::Email],
+            history_id: Uuid::new_v4(),
+            subscriber_id: "bpp".to_string(),
+            provider_id: "provider".to_string(),
+            item_id: "item".to_string(),
+            item_name: None,
+            currency: CurrencyType::Inr,
+            price_with_tax: BigDecimal::from_str(price).unwrap(),
+            available_qty,
+            previous_price_with_tax: previous_price.map(|p| BigDecimal::from_str(p).unwrap()),
+            previous_available_qty,
+            created_on: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_item_watch_alert_types() {
+        let change = get_item_watch_change(Some("100"), false, "90", 5, Some("120"), Some(5));
+        assert_eq!(
+            get_item_watch_alert_types(&change),
+            vec![CatalogAlertType::PriceDrop]
+        );
+        let change = get_item_watch_change(Some("100"), false, "95", 5, Some("90"), Some(5));
+        assert!(get_item_watch_alert_types(&change).is_empty());
+        let change = get_item_watch_change(Some("100"), false, "110", 5, Some("120"), Some(5));
+        assert!(get_item_watch_alert_types(&change).is_empty());
+        let change = get_item_watch_change(Some("100"), false, "90", 5, None, None);
+        assert!(get_item_watch_alert_types(&change).is_empty());
+        let change = get_item_watch_change(None, true, "90", 5, Some("90"), Some(0));
+        assert_eq!(
+            get_item_watch_alert_types(&change),
+            vec![CatalogAlertType::BackInStock]
+        );
+        let change = get_item_watch_change(None, true, "90", 0, Some("90"), Some(5));
+        assert!(get_item_watch_alert_types(&change).is_empty());
+        let change = get_item_watch_change(Some("100"), true, "90", 5, Some("120"), Some(0));
+        assert_eq!(
+            get_item_watch_alert_types(&change),
+            vec![CatalogAlertType::PriceDrop, CatalogAlertType::BackInStock]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_saved_search_sql() {
+        let pool = get_test_pool().await;
+        let saved_searches = fetch_saved_searches(&pool, Uuid::new_v4(), Uuid::new_v4()).await;
+        assert!(saved_searches.is_ok());
+        let item_watches = fetch_item_watches(&pool, Uuid::new_v4(), Uuid::new_v4()).await;
+        assert!(item_watches.is_ok());
+        let alerts = fetch_catalog_alerts(&pool, Uuid::new_v4(), Uuid::new_v4(), None, 10, 0).await;
+        assert!(alerts.is_ok());
+    }
+
+    fn get_saved_search_alert(saved_search_id: Uuid) -> CatalogAlert {
+        CatalogAlert {
+            id: Uuid::new_v4(),
+            alert_type: CatalogAlertType::NewItem,
+            saved_search_id: Some(saved_search_id),
+            item_watch_id: None,
+            bpp_id: "bpp".to_string(),
+            provider_id: "provider".to_string(),
+            item_id: "item".to_string(),
+            item_name: Some("Cement".to_string()),
+            currency: CurrencyType::Inr,
+            price: BigDecimal::from_str("100").unwrap(),
+            previous_price: None,
+            created_on: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_saved_search_alert_raised_once() {
+        let pool = get_test_pool().await;
+        let body: SavedSearchCreateRequest = serde_json::from_value(json!({
+            "name": "Cement",
+            "search": {
+                "query": "cement",
+                "domainCategoryCode": "RET10",
+                "countryCode": "IND",
+                "fulfillmentLocation": {"latitude": 12.97, "longitude": 77.59, "areaCode": "560001"},
+                "cityCode": "std:080",
+                "limit": 10
+            },
+            "channels": ["email"]
+        }))
+        .unwrap();
+        let saved_search_id = save_saved_search(&pool, &body, Uuid::new_v4(), Uuid::new_v4())
+            .await
+            .unwrap();
+        let mut saved_search = fetch_saved_search_by_id(&pool, saved_search_id)
+            .await
+            .unwrap()
+            .unwrap();
+        saved_search.cache_refreshed_on = Some(Utc::now());
+
+        for expected_count in [1, 0] {
+            let mut transaction = pool.begin().await.unwrap();
+            let alerts = save_saved_search_alerts(
+                &mut transaction,
+                &saved_search,
+                vec![get_saved_search_alert(saved_search_id)],
+                Utc::now(),
+            )
+            .await
+            .unwrap();
+            transaction.commit().await.unwrap();
+            assert_eq!(alerts.len(), expected_count);
+        }
+        let alerts = fetch_catalog_alerts(
+            &pool,
+            saved_search.business_id,
+            saved_search.user_id,
+            None,
+            10,
+            0,
+        )
+        .await
+        .unwrap();
+        assert_eq!(alerts.len(), 1);
+    }
+}
@@ -0,0 +1,1071 @@
+use super::models::{
+    CatalogAlertModel, ItemWatchChangeModel, ItemWatchModel, SavedSearchItemModel, SavedSearchModel,
+};
+use super::schemas::{
+    CatalogAlert, CatalogAlertChannel, CatalogAlertNotification, CatalogAlertType,
+    ItemWatchCreateRequest, SavedSearchCreateRequest,
+};
+use crate::chat_client::{
+    ChatClient, ChatMessageType, ChatParticipant, SendMessageDataDescription,
+};
+use crate::configuration::SearchConfig;
+use crate::constants::{CATALOG_ALERT_BATCH_SIZE, CATALOG_ALERT_INTERVAL, CATALOG_ALERT_MAX_PAGES};
+use crate::elastic_search_client::{ElasticSearchClient, SearchRankingProfile};
+use crate::email_client::{GenericEmailService, SmtpEmailClient};
+use crate::errors::GenericError;
+use crate::routes::favourite::utils::fetch_search_supplier_preference;
+use crate::routes::product::schemas::{ItemCacheResponseData, ProductCacheSearchRequest};
+use crate::routes::product::utils::get_full_item_data;
+use crate::schemas::{CurrencyType, WebSocketParam};
+use crate::user_client::UserClient;
+use crate::websocket_client::{NotificationProcessType, WebSocketActionType, WebSocketClient};
+use actix_web::web;
+use anyhow::Context;
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Utc};
+use sqlx::types::Json;
+use sqlx::{PgPool, Postgres, Transaction};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+pub struct CatalogAlertContext {
+    pub pool: web::Data<PgPool>,
+    pub es_client: web::Data<ElasticSearchClient>,
+    pub search_config: web::Data<SearchConfig>,
+    pub ranking_profile: web::Data<SearchRankingProfile>,
+    pub user_client: web::Data<UserClient>,
+    pub chat_client: web::Data<ChatClient>,
+    pub email_client: web::Data<SmtpEmailClient>,
+    pub websocket_client: web::Data<WebSocketClient>,
+}
+
+impl CatalogAlertContext {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        pool: web::Data<PgPool>,
+        es_client: web::Data<ElasticSearchClient>,
+        search_config: web::Data<SearchConfig>,
+        ranking_profile: web::Data<SearchRankingProfile>,
+        user_client: web::Data<UserClient>,
+        chat_client: web::Data<ChatClient>,
+        email_client: web::Data<SmtpEmailClient>,
+        websocket_client: web::Data<WebSocketClient>,
+    ) -> Self {
+        Self {
+            pool,
+            es_client,
+            search_config,
+            ranking_profile,
+            user_client,
+            chat_client,
+            email_client,
+            websocket_client,
+        }
+    }
+}
+
+pub fn validate_saved_search_create_request(
+    body: &SavedSearchCreateRequest,
+) -> Result<(), GenericError> {
+    if body.name.trim().is_empty() {
+        return Err(GenericError::ValidationError(
+            "Saved search name is required".to_string(),
+        ));
+    }
+    if body.channels.is_empty() {
+        return Err(GenericError::ValidationError(
+            "Atleast one notification channel is required".to_string(),
+        ));
+    }
+    if body.search.limit < 1 {
+        return Err(GenericError::ValidationError(
+            "Search limit must be atleast 1".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+pub fn validate_item_watch_create_request(
+    body: &ItemWatchCreateRequest,
+) -> Result<(), GenericError> {
+    if body.channels.is_empty() {
+        return Err(GenericError::ValidationError(
+            "Atleast one notification channel is required".to_string(),
+        ));
+    }
+    if body.price_threshold.is_none() && !body.notify_on_available {
+        return Err(GenericError::ValidationError(
+            "Either a price threshold or availability notification is required".to_string(),
+        ));
+    }
+    if body
+        .price_threshold
+        .as_ref()
+        .is_some_and(|threshold| threshold <= &BigDecimal::from(0))
+    {
+        return Err(GenericError::ValidationError(
+            "Price threshold must be greater than zero".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+#[tracing::instrument(name = "save saved search", skip(pool, body))]
+pub async fn save_saved_search(
+    pool: &PgPool,
+    body: &SavedSearchCreateRequest,
+    user_id: Uuid,
+    business_id: Uuid,
+) -> Result<Uuid, anyhow::Error> {
+    let id = Uuid::new_v4();
+    sqlx::query!(
+        r#"
+        INSERT INTO saved_search (id, business_id, user_id, name, search_request, channels,
+        is_active, created_on)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        "#,
+        id,
+        business_id,
+        user_id,
+        body.name.trim(),
+        serde_json::to_value(&body.search)?,
+        &body.channels as &[CatalogAlertChannel],
+        true,
+        Utc::now()
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e).context("A database failure occurred while saving saved search")
+    })?;
+    Ok(id)
+}
+
+#[tracing::instrument(name = "fetch saved searches", skip(pool))]
+pub async fn fetch_saved_searches(
+    pool: &PgPool,
+    business_id: Uuid,
+    user_id: Uuid,
+) -> Result<Vec<SavedSearchModel>, anyhow::Error> {
+    let rows = sqlx::query_as!(
+        SavedSearchModel,
+        r#"SELECT id, business_id, user_id, name,
+        search_request as "search_request: Json<ProductCacheSearchRequest>",
+        channels as "channels: Vec<CatalogAlertChannel>", is_active, cache_refreshed_on, created_on
+        FROM saved_search WHERE business_id = $1 AND user_id = $2
+        ORDER BY created_on DESC"#,
+        business_id,
+        user_id
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e).context("A database failure occurred while fetching saved searches")
+    })?;
+    Ok(rows)
+}
+
+#[tracing::instrument(name = "fetch saved search by id", skip(pool))]
+pub async fn fetch_saved_search_by_id(
+    pool: &PgPool,
+    saved_search_id: Uuid,
+) -> Result<Option<SavedSearchModel>, anyhow::Error> {
+    let row = sqlx::query_as!(
+        SavedSearchModel,
+        r#"SELECT id, business_id, user_id, name,
+        search_request as "search_request: Json<ProductCacheSearchRequest>",
+        channels as "channels: Vec<CatalogAlertChannel>", is_active, cache_refreshed_on, created_on
+        FROM saved_search WHERE id = $1"#,
+        saved_search_id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e).context("A database failure occurred while fetching saved search")
+    })?;
+    Ok(row)
+}
+
+#[tracing::instrument(name = "update saved search status", skip(pool))]
+pub async fn update_saved_search_status(
+    pool: &PgPool,
+    saved_search_id: Uuid,
+    is_active: bool,
+) -> Result<(), anyhow::Error> {
+    sqlx::query!(
+        r#"UPDATE saved_search SET is_active = $1, updated_on = $2 WHERE id = $3"#,
+        is_active,
+        Utc::now(),
+        saved_search_id
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e)
+            .context("A database failure occurred while updating saved search status")
+    })?;
+    Ok(())
+}
+
+/// Watching an already watched item replaces its conditions and re-activates the watch.
+#[tracing::instrument(name = "save item watch", skip(pool, body))]
+pub async fn save_item_watch(
+    pool: &PgPool,
+    body: &ItemWatchCreateRequest,
+    user_id: Uuid,
+    business_id: Uuid,
+) -> Result<Uuid, anyhow::Error> {
+    let now = Utc::now();
+    let id = sqlx::query_scalar!(
+        r#"
+        INSERT INTO item_watch (id, business_id, user_id, subscriber_id, provider_id, item_id,
+        price_threshold, notify_on_available, channels, is_active, last_evaluated_on, created_on)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $11)
+        ON CONFLICT (user_id, subscriber_id, provider_id, item_id) DO UPDATE SET
+        price_threshold = EXCLUDED.price_threshold,
+        notify_on_available = EXCLUDED.notify_on_available,
+        channels = EXCLUDED.channels,
+        is_active = EXCLUDED.is_active,
+        updated_on = EXCLUDED.created_on
+        RETURNING id
+        "#,
+        Uuid::new_v4(),
+        business_id,
+        user_id,
+        &body.bpp_id,
+        &body.provider_id,
+        &body.item_id,
+        body.price_threshold.as_ref(),
+        body.notify_on_available,
+        &body.channels as &[CatalogAlertChannel],
+        true,
+        now
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e).context("A database failure occurred while saving item watch")
+    })?;
+    Ok(id)
+}
+
+#[tracing::instrument(name = "fetch item watches", skip(pool))]
+pub async fn fetch_item_watches(
+    pool: &PgPool,
+    business_id: Uuid,
+    user_id: Uuid,
+) -> Result<Vec<ItemWatchModel>, anyhow::Error> {
+    let rows = sqlx::query_as!(
+        ItemWatchModel,
+        r#"SELECT id, business_id, user_id, subscriber_id, provider_id, item_id, price_threshold,
+        notify_on_available, channels as "channels: Vec<CatalogAlertChannel>", is_active, created_on
+        FROM item_watch WHERE business_id = $1 AND user_id = $2
+        ORDER BY created_on DESC"#,
+        business_id,
+        user_id
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e).context("A database failure occurred while fetching item watches")
+    })?;
+    Ok(rows)
+}
+
+#[tracing::instrument(name = "fetch item watch by id", skip(pool))]
+pub async fn fetch_item_watch_by_id(
+    pool: &PgPool,
+    item_watch_id: Uuid,
+) -> Result<Option<ItemWatchModel>, anyhow::Error> {
+    let row = sqlx::query_as!(
+        ItemWatchModel,
+        r#"SELECT id, business_id, user_id, subscriber_id, provider_id, item_id, price_threshold,
+        notify_on_available, channels as "channels: Vec<CatalogAlertChannel>", is_active, created_on
+        FROM item_watch WHERE id = $1"#,
+        item_watch_id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e).context("A database failure occurred while fetching item watch")
+    })?;
+    Ok(row)
+}
+
+/// Changes recorded while a watch was paused are skipped when it is resumed.
+#[tracing::instrument(name = "update item watch status", skip(pool))]
+pub async fn update_item_watch_status(
+    pool: &PgPool,
+    item_watch_id: Uuid,
+    is_active: bool,
+) -> Result<(), anyhow::Error> {
+    let now = Utc::now();
+    sqlx::query!(
+        r#"
+        UPDATE item_watch SET is_active = $1, updated_on = $2,
+        last_evaluated_on = CASE WHEN $1 AND NOT is_active THEN $2 ELSE last_evaluated_on END
+        WHERE id = $3
+        "#,
+        is_active,
+        now,
+        item_watch_id
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e)
+            .context("A database failure occurred while updating item watch status")
+    })?;
+    Ok(())
+}
+
+#[tracing::instrument(name = "fetch catalog alerts", skip(pool))]
+pub async fn fetch_catalog_alerts(
+    pool: &PgPool,
+    business_id: Uuid,
+    user_id: Uuid,
+    alert_type: Option<&CatalogAlertType>,
+    limit: i32,
+    offset: i32,
+) -> Result<Vec<CatalogAlertModel>, anyhow::Error> {
+    let rows = sqlx::query_as!(
+        CatalogAlertModel,
+        r#"SELECT id, alert_type as "alert_type: CatalogAlertType", saved_search_id, item_watch_id,
+        subscriber_id, provider_id, item_id, item_name, currency as "currency: CurrencyType", price,
+        previous_price, created_on
+        FROM catalog_alert
+        WHERE business_id = $1 AND user_id = $2
+        AND ($3::catalog_alert_type IS NULL OR alert_type = $3)
+        ORDER BY created_on DESC LIMIT $4 OFFSET $5"#,
+        business_id,
+        user_id,
+        alert_type as Option<&CatalogAlertType>,
+        limit as i64,
+        offset as i64
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e).context("A database failure occurred while fetching catalog alerts")
+    })?;
+    Ok(rows)
+}
+
+/// Latest time at which any cached item was inserted or refreshed.
+#[tracing::instrument(name = "fetch last item cache refresh", skip(pool))]
+async fn fetch_last_item_cache_refresh(
+    pool: &PgPool,
+) -> Result<Option<DateTime<Utc>>, anyhow::Error> {
+    let refreshed_on = sqlx::query_scalar!(
+        r#"SELECT GREATEST(
+            (SELECT MAX(created_on) FROM provider_item_cache),
+            (SELECT MAX(updated_on) FROM provider_item_cache)
+        ) AS "refreshed_on""#
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e)
+            .context("A database failure occurred while fetching last item cache refresh")
+    })?;
+    Ok(refreshed_on)
+}
+
+#[tracing::instrument(name = "fetch due saved searches", skip(pool))]
+async fn fetch_due_saved_searches(
+    pool: &PgPool,
+    refreshed_on: DateTime<Utc>,
+    limit: i64,
+) -> Result<Vec<SavedSearchModel>, anyhow::Error> {
+    let rows = sqlx::query_as!(
+        SavedSearchModel,
+        r#"SELECT id, business_id, user_id, name,
+        search_request as "search_request: Json<ProductCacheSearchRequest>",
+        channels as "channels: Vec<CatalogAlertChannel>", is_active, cache_refreshed_on, created_on
+        FROM saved_search
+        WHERE is_active AND (cache_refreshed_on IS NULL OR cache_refreshed_on < $1)
+        ORDER BY cache_refreshed_on NULLS FIRST LIMIT $2"#,
+        refreshed_on,
+        limit
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e)
+            .context("A database failure occurred while fetching due saved searches")
+    })?;
+    Ok(rows)
+}
+
+#[tracing::instrument(name = "update saved search cache refresh", skip(transaction))]
+async fn update_saved_search_cache_refresh(
+    transaction: &mut Transaction<'_, Postgres>,
+    saved_search_id: Uuid,
+    refreshed_on: DateTime<Utc>,
+) -> Result<(), anyhow::Error> {
+    sqlx::query!(
+        r#"UPDATE saved_search SET cache_refreshed_on = $1 WHERE id = $2"#,
+        refreshed_on,
+        saved_search_id
+    )
+    .execute(&mut **transaction)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e)
+            .context("A database failure occurred while updating saved search cache refresh")
+    })?;
+    Ok(())
+}
+
+/// Records the items matched by a saved search and returns the ones it had not matched before.
+#[tracing::instrument(name = "save saved search items", skip(transaction, alerts))]
+async fn save_saved_search_items(
+    transaction: &mut Transaction<'_, Postgres>,
+    saved_search_id: Uuid,
+    alerts: &[CatalogAlert],
+) -> Result<Vec<SavedSearchItemModel>, anyhow::Error> {
+    if alerts.is_empty() {
+        return Ok(vec![]);
+    }
+    let subscriber_ids: Vec<&str> = alerts.iter().map(|a| a.bpp_id.as_str()).collect();
+    let provider_ids: Vec<&str> = alerts.iter().map(|a| a.provider_id.as_str()).collect();
+    let item_ids: Vec<&str> = alerts.iter().map(|a| a.item_id.as_str()).collect();
+    let rows = sqlx::query_as!(
+        SavedSearchItemModel,
+        r#"
+        INSERT INTO saved_search_item (saved_search_id, subscriber_id, provider_id, item_id, created_on)
+        SELECT $1, d.subscriber_id, d.provider_id, d.item_id, $5
+        FROM unnest($2::text[], $3::text[], $4::text[]) AS d(subscriber_id, provider_id, item_id)
+        ON CONFLICT DO NOTHING
+        RETURNING subscriber_id, provider_id, item_id
+        "#,
+        saved_search_id,
+        &subscriber_ids[..] as &[&str],
+        &provider_ids[..] as &[&str],
+        &item_ids[..] as &[&str],
+        Utc::now()
+    )
+    .fetch_all(&mut **transaction)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e).context("A database failure occurred while saving saved search items")
+    })?;
+    Ok(rows)
+}
+
+/// Price and availability changes of watched items recorded since each watch was last evaluated.
+#[tracing::instrument(name = "fetch item watch changes", skip(pool))]
+async fn fetch_item_watch_changes(
+    pool: &PgPool,
+    limit: i64,
+) -> Result<Vec<ItemWatchChangeModel>, anyhow::Error> {
+    let rows = sqlx::query_as!(
+        ItemWatchChangeModel,
+        r#"
+        SELECT w.id AS item_watch_id, w.business_id, w.user_id, w.price_threshold,
+        w.notify_on_available, w.channels as "channels: Vec<CatalogAlertChannel>",
+        h.id AS history_id, h.subscriber_id, h.provider_id, h.item_id,
+        item.item_name as "item_name?", h.currency as "currency: CurrencyType", h.price_with_tax,
+        h.available_qty, prev.price_with_tax as "previous_price_with_tax?",
+        prev.available_qty as "previous_available_qty?", h.created_on
+        FROM item_watch AS w
+        JOIN provider_item_price_history AS h ON h.subscriber_id = w.subscriber_id
+            AND h.provider_id = w.provider_id AND h.item_id = w.item_id
+            AND h.created_on > w.last_evaluated_on
+        LEFT JOIN LATERAL (
+            SELECT p.price_with_tax, p.available_qty
+            FROM provider_item_price_history AS p
+            WHERE p.subscriber_id = h.subscriber_id AND p.provider_id = h.provider_id
+            AND p.item_id = h.item_id AND p.created_on < h.created_on
+            ORDER BY p.created_on DESC
+            LIMIT 1
+        ) AS prev ON true
+        LEFT JOIN LATERAL (
+            SELECT pic.item_name
+            FROM provider_item_cache AS pic
+            JOIN provider_cache AS pc ON pic.provider_cache_id = pc.id
+            JOIN network_participant_cache AS npc ON pc.network_participant_cache_id = npc.id
+            WHERE npc.subscriber_id = h.subscriber_id AND pc.provider_id = h.provider_id
+            AND pic.item_id = h.item_id
+            LIMIT 1
+        ) AS item ON true
+        WHERE w.is_active
+        ORDER BY h.created_on
+        LIMIT $1
+        "#,
+        limit
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e)
+            .context("A database failure occurred while fetching item watch changes")
+    })?;
+    Ok(rows)
+}
+
+#[tracing::instrument(name = "update item watch evaluation", skip(pool, evaluated_on))]
+async fn update_item_watch_evaluation(
+    pool: &PgPool,
+    evaluated_on: &HashMap<Uuid, DateTime<Utc>>,
+) -> Result<(), anyhow::Error> {
+    if evaluated_on.is_empty() {
+        return Ok(());
+    }
+    let (ids, evaluated_ons): (Vec<Uuid>, Vec<DateTime<Utc>>) = evaluated_on.iter().unzip();
+    sqlx::query!(
+        r#"
+        UPDATE item_watch AS w SET last_evaluated_on = d.evaluated_on
+        FROM unnest($1::uuid[], $2::timestamptz[]) AS d(id, evaluated_on)
+        WHERE w.id = d.id
+        "#,
+        &ids,
+        &evaluated_ons
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e)
+            .context("A database failure occurred while updating item watch evaluation")
+    })?;
+    Ok(())
+}
+
+/// Stores the alerts and returns the ids of the ones not raised before, the dedup key of an alert identifies the change it reports.
+#[tracing::instrument(name = "save catalog alerts", skip(transaction, alerts, dedup_keys))]
+async fn save_catalog_alerts(
+    transaction: &mut Transaction<'_, Postgres>,
+    business_id: Uuid,
+    user_id: Uuid,
+    alerts: &[CatalogAlert],
+    dedup_keys: &[String],
+) -> Result<HashSet<Uuid>, anyhow::Error> {
+    if alerts.is_empty() {
+        return Ok(HashSet::new());
+    }
+    let ids: Vec<Uuid> = alerts.iter().map(|a| a.id).collect();
+    let alert_types: Vec<&CatalogAlertType> = alerts.iter().map(|a| &a.alert_type).collect();
+    let saved_search_ids: Vec<Option<Uuid>> = alerts.iter().map(|a| a.saved_search_id).collect();
+    let item_watch_ids: Vec<Option<Uuid>> = alerts.iter().map(|a| a.item_watch_id).collect();
+    let subscriber_ids: Vec<&str> = alerts.iter().map(|a| a.bpp_id.as_str()).collect();
+    let provider_ids: Vec<&str> = alerts.iter().map(|a| a.provider_id.as_str()).collect();
+    let item_ids: Vec<&str> = alerts.iter().map(|a| a.item_id.as_str()).collect();
+    let item_names: Vec<&Option<String>> = alerts.iter().map(|a| &a.item_name).collect();
+    let currencies: Vec<&CurrencyType> = alerts.iter().map(|a| &a.currency).collect();
+    let prices: Vec<&BigDecimal> = alerts.iter().map(|a| &a.price).collect();
+    let previous_prices: Vec<&Option<BigDecimal>> =
+        alerts.iter().map(|a| &a.previous_price).collect();
+    let created_ons: Vec<DateTime<Utc>> = alerts.iter().map(|a| a.created_on).collect();
+    let rows = sqlx::query_scalar!(
+        r#"
+        INSERT INTO catalog_alert (id, business_id, user_id, alert_type, saved_search_id,
+        item_watch_id, subscriber_id, provider_id, item_id, item_name, currency, price,
+        previous_price, dedup_key, created_on)
+        SELECT d.id, $1, $2, d.alert_type, d.saved_search_id, d.item_watch_id, d.subscriber_id,
+        d.provider_id, d.item_id, d.item_name, d.currency, d.price, d.previous_price, d.dedup_key,
+        d.created_on
+        FROM unnest(
+            $3::uuid[], $4::catalog_alert_type[], $5::uuid[], $6::uuid[], $7::text[], $8::text[],
+            $9::text[], $10::text[], $11::currency_code_type[], $12::decimal[], $13::decimal[],
+            $14::text[], $15::timestamptz[]
+        ) AS d(id, alert_type, saved_search_id, item_watch_id, subscriber_id, provider_id,
+            item_id, item_name, currency, price, previous_price, dedup_key, created_on)
+        ON CONFLICT (dedup_key) DO NOTHING
+        RETURNING id
+        "#,
+        business_id,
+        user_id,
+        &ids,
+        &alert_types[..] as &[&CatalogAlertType],
+        &saved_search_ids[..] as &[Option<Uuid>],
+        &item_watch_ids[..] as &[Option<Uuid>],
+        &subscriber_ids[..] as &[&str],
+        &provider_ids[..] as &[&str],
+        &item_ids[..] as &[&str],
+        &item_names[..] as &[&Option<String>],
+        &currencies[..] as &[&CurrencyType],
+        &prices[..] as &[&BigDecimal],
+        &previous_prices[..] as &[&Option<BigDecimal>],
+        dedup_keys,
+        &created_ons
+    )
+    .fetch_all(&mut **transaction)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e).context("A database failure occurred while saving catalog alerts")
+    })?;
+    Ok(rows.into_iter().collect())
+}
+
+#[tracing::instrument(name = "fetch last order transaction id", skip(pool))]
+async fn fetch_last_order_transaction_id(
+    pool: &PgPool,
+    business_id: Uuid,
+    bpp_id: &str,
+    provider_id: &str,
+) -> Result<Option<Uuid>, anyhow::Error> {
+    let transaction_id = sqlx::query_scalar!(
+        r#"
+        SELECT external_urn FROM commerce_data
+        WHERE buyer_id = $1 AND bpp_id = $2 AND seller_id = $3 AND NOT is_deleted
+        ORDER BY created_on DESC
+        LIMIT 1
+        "#,
+        business_id,
+        bpp_id,
+        provider_id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e)
+            .context("A database failure occurred while fetching last order transaction id")
+    })?;
+    Ok(transaction_id)
+}
+
+/// Every item returned by a saved search as a new item alert, the caller keeps only the unseen ones.
+pub fn get_saved_search_alerts(
+    saved_search_id: Uuid,
+    data: &ItemCacheResponseData,
+    created_on: DateTime<Utc>,
+) -> Vec<CatalogAlert> {
+    let mut alerts = vec![];
+    for network_participant in &data.network_participants {
+        for provider in &network_participant.providers {
+            for item in &provider.items {
+                alerts.push(CatalogAlert {
+                    id: Uuid::new_v4(),
+                    alert_type: CatalogAlertType::NewItem,
+                    saved_search_id: Some(saved_search_id),
+                    item_watch_id: None,
+                    bpp_id: network_participant.bpp.subscriber_id.clone(),
+                    provider_id: provider.description.id.clone(),
+                    item_id: item.id.clone(),
+                    item_name: Some(item.name.clone()),
+                    currency: item.price.currency.clone(),
+                    price: item.price.price_with_tax.clone(),
+                    previous_price: None,
+                    created_on,
+                });
+            }
+        }
+    }
+    alerts
+}
+
+/// A price drop is raised only when the price falls to or below the threshold from a higher price,
+/// and back in stock only when the previous recorded quantity was zero.
+pub fn get_item_watch_alert_types(change: &ItemWatchChangeModel) -> Vec<CatalogAlertType> {
+    let mut alert_types = vec![];
+    if let (Some(threshold), Some(previous_price)) = (
+        change.price_threshold.as_ref(),
+        change.previous_price_with_tax.as_ref(),
+    ) {
+        if &change.price_with_tax <= threshold && &change.price_with_tax < previous_price {
+            alert_types.push(CatalogAlertType::PriceDrop);
+        }
+    }
+    if change.notify_on_available
+        && change.available_qty > 0
+        && change.previous_available_qty == Some(0)
+    {
+        alert_types.push(CatalogAlertType::BackInStock);
+    }
+    alert_types
+}
+
+pub fn get_catalog_alert_text(alerts: &[CatalogAlert]) -> String {
+    alerts
+        .iter()
+        .map(|alert| {
+            let name = alert.item_name.as_deref().unwrap_or(&alert.item_id);
+            match alert.alert_type {
+                CatalogAlertType::NewItem => format!(
+                    "New item {} from provider {} at {} {}",
+                    name, alert.provider_id, alert.currency, alert.price
+                ),
+                CatalogAlertType::PriceDrop => format!(
+                    "{} dropped from {} {} to {} {}",
+                    name,
+                    alert.currency,
+                    alert
+                        .previous_price
+                        .as_ref()
+                        .map(|price| price.to_string())
+                        .unwrap_or_default(),
+                    alert.currency,
+                    alert.price
+                ),
+                CatalogAlertType::BackInStock => format!(
+                    "{} is back in stock at {} {}",
+                    name, alert.currency, alert.price
+                ),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Chat alerts are posted to the latest order conversation with the item's provider, alerts of providers without an order are skipped.
+async fn send_catalog_alert_chat(
+    context: &CatalogAlertContext,
+    business_id: Uuid,
+    title: &str,
+    alerts: &[CatalogAlert],
+) -> Result<(), anyhow::Error> {
+    let mut provider_alerts: HashMap<(&str, &str), Vec<&CatalogAlert>> = HashMap::new();
+    for alert in alerts {
+        provider_alerts
+            .entry((&alert.bpp_id, &alert.provider_id))
+            .or_default()
+            .push(alert);
+    }
+    for ((bpp_id, provider_id), alerts) in provider_alerts {
+        let Some(transaction_id) =
+            fetch_last_order_transaction_id(&context.pool, business_id, bpp_id, provider_id)
+                .await?
+        else {
+            continue;
+        };
+        let data = context.chat_client.get_send_message_data(
+            title,
+            alerts
+                .into_iter()
+                .map(|alert| SendMessageDataDescription {
+                    text: get_catalog_alert_text(std::slice::from_ref(alert)),
+                    r#type: ChatMessageType::Text,
+                })
+                .collect(),
+        );
+        let sender = ChatParticipant {
+            id: provider_id.to_string(),
+            name: "NA".to_owned(),
+        };
+        context
+            .chat_client
+            .send_chat_data(transaction_id, sender, data)
+            .await?;
+    }
+    Ok(())
+}
+
+async fn send_catalog_alert_notification(
+    context: &CatalogAlertContext,
+    business_id: Uuid,
+    user_id: Uuid,
+    channels: &[CatalogAlertChannel],
+    title: &str,
+    alerts: &[CatalogAlert],
+) -> Result<(), anyhow::Error> {
+    for channel in channels {
+        match channel {
+            CatalogAlertChannel::Email => {
+                let user_account = context
+                    .user_client
+                    .get_user_account(None, Some(user_id))
+                    .await?;
+                if let Err(e) = context
+                    .email_client
+                    .send_text_email(&user_account.email, title, get_catalog_alert_text(alerts))
+                    .await
+                {
+                    tracing::error!("Failed to send catalog alert email: {:?}", e);
+                }
+            }
+            CatalogAlertChannel::Websocket => {
+                let data = serde_json::to_value(CatalogAlertNotification { title, alerts })?;
+                if let Err(e) = context
+                    .websocket_client
+                    .send_msg(
+                        WebSocketParam {
+                            user_id: Some(user_id),
+                            business_id,
+                            device_id: None,
+                        },
+                        WebSocketActionType::CatalogAlert,
+                        data,
+                        Some(NotificationProcessType::Immediate),
+                    )
+                    .await
+                {
+                    tracing::error!("Failed to send catalog alert websocket message: {:?}", e);
+                }
+            }
+            CatalogAlertChannel::Chat => {
+                if let Err(e) = send_catalog_alert_chat(context, business_id, title, alerts).await {
+                    tracing::error!("Failed to send catalog alert chat message: {:?}", e);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Runs a saved search through its result pages, up to `CATALOG_ALERT_MAX_PAGES`, and returns an alert for each matched item.
+/// Paging ends on an empty or repeated cursor since filtered pages can hold fewer items than the limit.
+async fn fetch_saved_search_alerts(
+    context: &CatalogAlertContext,
+    saved_search: &mut SavedSearchModel,
+) -> Result<Vec<CatalogAlert>, anyhow::Error> {
+    let saved_search_id = saved_search.id;
+    let search = &mut saved_search.search_request.0;
+    search.offset = None;
+    let supplier_preference = fetch_search_supplier_preference(
+        &context.pool,
+        saved_search.business_id,
+        search.supplier_filter.as_ref(),
+    )
+    .await?;
+    let mut alerts = vec![];
+    for _ in 0..CATALOG_ALERT_MAX_PAGES {
+        let Some(data) = get_full_item_data(
+            &context.es_client,
+            &context.pool,
+            &context.search_config,
+            &context.ranking_profile,
+            search,
+            &supplier_preference,
+        )
+        .await?
+        else {
+            return Ok(alerts);
+        };
+        alerts.extend(get_saved_search_alerts(saved_search_id, &data, Utc::now()));
+        if data.search_after.is_empty() || search.offset.as_ref() == Some(&data.search_after) {
+            return Ok(alerts);
+        }
+        search.offset = Some(data.search_after);
+    }
+    tracing::warn!(
+        "Saved search {} stopped after {} result pages",
+        saved_search_id,
+        CATALOG_ALERT_MAX_PAGES
+    );
+    Ok(alerts)
+}
+
+/// Records the matched items, the alerts for the ones not matched before and the refresh time in one transaction,
+/// returning the alerts raised for the first time. The first evaluation of a saved search only records its current
+/// matches so that existing items are not reported as new.
+pub async fn save_saved_search_alerts(
+    transaction: &mut Transaction<'_, Postgres>,
+    saved_search: &SavedSearchModel,
+    alerts: Vec<CatalogAlert>,
+    refreshed_on: DateTime<Utc>,
+) -> Result<Vec<CatalogAlert>, anyhow::Error> {
+    let new_items: HashSet<(String, String, String)> =
+        save_saved_search_items(transaction, saved_search.id, &alerts)
+            .await?
+            .into_iter()
+            .map(|item| (item.subscriber_id, item.provider_id, item.item_id))
+            .collect();
+    let mut raised_alerts = vec![];
+    if saved_search.cache_refreshed_on.is_some() && !new_items.is_empty() {
+        let alerts: Vec<CatalogAlert> = alerts
+            .into_iter()
+            .filter(|alert| {
+                new_items.contains(&(
+                    alert.bpp_id.clone(),
+                    alert.provider_id.clone(),
+                    alert.item_id.clone(),
+                ))
+            })
+            .collect();
+        let dedup_keys: Vec<String> = alerts
+            .iter()
+            .map(|alert| {
+                format!(
+                    "{}:{}:{}:{}:{}",
+                    alert.alert_type,
+                    saved_search.id,
+                    alert.bpp_id,
+                    alert.provider_id,
+                    alert.item_id
+                )
+            })
+            .collect();
+        let saved_ids = save_catalog_alerts(
+            transaction,
+            saved_search.business_id,
+            saved_search.user_id,
+            &alerts,
+            &dedup_keys,
+        )
+        .await?;
+        raised_alerts = alerts
+            .into_iter()
+            .filter(|alert| saved_ids.contains(&alert.id))
+            .collect();
+    }
+    update_saved_search_cache_refresh(transaction, saved_search.id, refreshed_on).await?;
+    Ok(raised_alerts)
+}
+
+async fn process_saved_search(
+    context: &CatalogAlertContext,
+    mut saved_search: SavedSearchModel,
+    refreshed_on: DateTime<Utc>,
+) -> Result<(), anyhow::Error> {
+    let alerts = fetch_saved_search_alerts(context, &mut saved_search).await?;
+    let mut transaction = context
+        .pool
+        .begin()
+        .await
+        .context("Failed to acquire a Postgres connection from the pool")?;
+    let alerts =
+        save_saved_search_alerts(&mut transaction, &saved_search, alerts, refreshed_on).await?;
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit SQL transaction to save saved search alerts")?;
+    if !alerts.is_empty() {
+        if let Err(e) = send_catalog_alert_notification(
+            context,
+            saved_search.business_id,
+            saved_search.user_id,
+            &saved_search.channels,
+            &format!("New items for saved search {}", saved_search.name),
+            &alerts,
+        )
+        .await
+        {
+            tracing::error!("Failed to notify saved search {}: {:?}", saved_search.id, e);
+        }
+    }
+    Ok(())
+}
+
+/// Saved searches are re-run only once the item cache has been refreshed since their last run.
+pub async fn process_saved_search_alerts(
+    context: &CatalogAlertContext,
+) -> Result<(), anyhow::Error> {
+    let Some(refreshed_on) = fetch_last_item_cache_refresh(&context.pool).await? else {
+        return Ok(());
+    };
+    let saved_searches =
+        fetch_due_saved_searches(&context.pool, refreshed_on, CATALOG_ALERT_BATCH_SIZE).await?;
+    for saved_search in saved_searches {
+        let saved_search_id = saved_search.id;
+        if let Err(e) = process_saved_search(context, saved_search, refreshed_on).await {
+            tracing::error!(
+                "Failed to process saved search {}: {:?}",
+                saved_search_id,
+                e
+            );
+        }
+    }
+    Ok(())
+}
+
+pub async fn process_item_watch_alerts(context: &CatalogAlertContext) -> Result<(), anyhow::Error> {
+    let changes = fetch_item_watch_changes(&context.pool, CATALOG_ALERT_BATCH_SIZE).await?;
+    let mut evaluated_on = HashMap::new();
+    for change in &changes {
+        evaluated_on.insert(change.item_watch_id, change.created_on);
+        let alerts: Vec<CatalogAlert> = get_item_watch_alert_types(change)
+            .into_iter()
+            .map(|alert_type| CatalogAlert {
+                id: Uuid::new_v4(),
+                alert_type,
+                saved_search_id: None,
+                item_watch_id: Some(change.item_watch_id),
+                bpp_id: change.subscriber_id.clone(),
+                provider_id: change.provider_id.clone(),
+                item_id: change.item_id.clone(),
+                item_name: change.item_name.clone(),
+                currency: change.currency.clone(),
+                price: change.price_with_tax.clone(),
+                previous_price: change.previous_price_with_tax.clone(),
+                created_on: Utc::now(),
+            })
+            .collect();
+        if alerts.is_empty() {
+            continue;
+        }
+        let dedup_keys: Vec<String> = alerts
+            .iter()
+            .map(|alert| {
+                format!(
+                    "{}:{}:{}",
+                    alert.alert_type, change.item_watch_id, change.history_id
+                )
+            })
+            .collect();
+        let mut transaction = context
+            .pool
+            .begin()
+            .await
+            .context("Failed to acquire a Postgres connection from the pool")?;
+        let saved_ids = save_catalog_alerts(
+            &mut transaction,
+            change.business_id,
+            change.user_id,
+            &alerts,
+            &dedup_keys,
+        )
+        .await?;
+        transaction
+            .commit()
+            .await
+            .context("Failed to commit SQL transaction to save catalog alerts")?;
+        let alerts: Vec<CatalogAlert> = alerts
+            .into_iter()
+            .filter(|alert| saved_ids.contains(&alert.id))
+            .collect();
+        if alerts.is_empty() {
+            continue;
+        }
+        if let Err(e) = send_catalog_alert_notification(
+            context,
+            change.business_id,
+            change.user_id,
+            &change.channels,
+            "Watched item update",
+            &alerts,
+        )
+        .await
+        {
+            tracing::error!(
+                "Failed to notify item watch {}: {:?}",
+                change.item_watch_id,
+                e
+            );
+        }
+    }
+    update_item_watch_evaluation(&context.pool, &evaluated_on).await?;
+    Ok(())
+}
+
+pub fn start_catalog_alert_worker(context: CatalogAlertContext) {
+    tokio::spawn(async move {
+        let mut interval =
+            tokio::time::interval(std::time::Duration::from_secs(CATALOG_ALERT_INTERVAL));
+        loop {
+            interval.tick().await;
+            if let Err(e) = process_item_watch_alerts(&context).await {
+                tracing::error!("Failed to process item watch alerts: {:?}", e);
+            }
+            if let Err(e) = process_saved_search_alerts(&context).await {
+                tracing::error!("Failed to process saved search alerts: {:?}", e);
+            }
+        }
+    });
+}
@@ -0,0 +1,248 @@
+use actix_http::StatusCode;
+use actix_web::{web, HttpResponse};
+use anyhow::Context;
+use sqlx::PgPool;
+use utoipa::TupleUnit;
+
+use super::schemas::{
+    CommerceSettlement, SettlementListRequest, SettlementReconRequest, SettlementSettleRequest,
+};
+use super::utils::{
+    fetch_commerce_settlements, fetch_settled_order_nos, get_commerce_settlement_data,
+    mark_commerce_settlements_settled, save_commerce_settlements, validate_settlement_recon_orders,
+};
+use crate::configuration::ONDCConfig;
+use crate::errors::GenericError;
+use crate::kafka_client::KafkaClient;
+use crate::routes::ondc::utils::{get_ondc_receiver_recon_payload, send_ondc_payload};
+use crate::routes::ondc::ONDCActionType;
+use crate::routes::order::utils::{fetch_order_by_id, save_ondc_order_request};
+use crate::schemas::{GenericResponse, ONDCNetworkType, RequestMetaData, StartUpMap};
+use crate::user_client::{BusinessAccount, UserAccount};
+use crate::utils::{create_authorization_header, get_np_detail};
+
+#[utoipa::path(
+    post,
+    path = "/settlement/recon",
+    tag = "Settlement",
+    description="This API sends an RSF receiver_recon request to the seller app for the given buyer app collected orders.",
+    summary= "Settlement Recon Request",
+    request_body(content = SettlementReconRequest, description = "Request Body"),
+    responses(
+        (status=202, description= "Settlement Recon Response", body= GenericResponse<TupleUnit>),
+        (status=400, description= "Invalid Request body", body= GenericResponse<TupleUnit>),
+        (status=401, description= "Invalid Token", body= GenericResponse<TupleUnit>),
+	    (status=403, description= "Insufficient Previlege", body= GenericResponse<TupleUnit>),
+	    (status=410, description= "Data not found", body= GenericResponse<TupleUnit>),
+        (status=500, description= "Internal Server Error", body= GenericResponse<TupleUnit>),
+	    (status=501, description= "Not Implemented", body= GenericResponse<TupleUnit>),
+    )
+)]
+#[allow(clippy::too_many_arguments)]
+#[tracing::instrument(name = "settlement recon", skip(pool, kafka_client), fields(transaction_id=body.transaction_id.to_string()))]
+pub async fn settlement_recon(
+    body: SettlementReconRequest,
+    pool: web::Data<PgPool>,
+    ondc_obj: web::Data<ONDCConfig>,
+    user_account: UserAccount,
+    business_account: BusinessAccount,
+    meta_data: RequestMetaData,
+    maps: web::Data<StartUpMap>,
+    kafka_client: web::Data<KafkaClient>,
+) -> Result<HttpResponse, GenericError> {
+    let mut orders = vec![];
+    for order_transaction_id in &body.order_transaction_ids {
+        let order = fetch_order_by_id(&pool, *order_transaction_id)
+            .await
+            .map_err(|e| GenericError::DatabaseError(e.to_string(), e))?
+            .ok_or_else(|| {
+                GenericError::ValidationError(format!(
+                    "{} is not found in datbase",
+                    order_transaction_id
+                ))
+            })?;
+        orders.push(order);
+    }
+    validate_settlement_recon_orders(&orders, business_account.id)?;
+    let settlements = orders
+        .iter()
+        .map(get_commerce_settlement_data)
+        .collect::<Result<Vec<_>, GenericError>>()?;
+
+    let commerce_data_ids: Vec<_> = orders.iter().map(|order| order.id).collect();
+    let settled_order_nos = fetch_settled_order_nos(&pool, &commerce_data_ids)
+        .await
+        .map_err(|e| GenericError::DatabaseError(e.to_string(), e))?;
+    if !settled_order_nos.is_empty() {
+        return Err(GenericError::ValidationError(format!(
+            "Orders {} are already settled",
+            settled_order_nos.join(", ")
+        )));
+    }
+
+    let bap_detail = get_np_detail(
+        &pool,
+        &maps,
+        &business_account.subscriber_id,
+        &ONDCNetworkType::Bap,
+    )
+    .await
+    .map_err(|e| GenericError::DatabaseError(e.to_string(), e))?
+    .ok_or_else(|| {
+        GenericError::ValidationError(format!(
+            "{} is not found in datbase",
+            &business_account.subscriber_id
+        ))
+    })?;
+
+    let ondc_recon_payload = get_ondc_receiver_recon_payload(
+        body.transaction_id,
+        body.message_id,
+        &orders,
+        &settlements,
+    )?;
+    let recon_json_obj = serde_json::to_value(&ondc_recon_payload)?;
+    let ondc_recon_payload_str = serde_json::to_string(&ondc_recon_payload).map_err(|e| {
+        GenericError::SerializationError(format!("Failed to serialize ONDC recon payload: {}", e))
+    })?;
+    let header = create_authorization_header(&ondc_recon_payload_str, &bap_detail, None, None)?;
+    save_commerce_settlements(
+        &pool,
+        &settlements,
+        business_account.id,
+        user_account.id,
+        body.transaction_id,
+        body.message_id,
+    )
+    .await
+    .map_err(|e| GenericError::DatabaseError(e.to_string(), e))?;
+    let task_1 = save_ondc_order_request(
+        &pool,
+        &user_account,
+        &business_account,
+        &meta_data,
+        &recon_json_obj,
+        body.transaction_id,
+        body.message_id,
+        ONDCActionType::ReceiverRecon,
+    );
+    let task_2 = send_ondc_payload(
+        &orders[0].bpp.uri,
+        &ondc_recon_payload_str,
+        &header,
+        &ONDCActionType::ReceiverRecon,
+        &kafka_client,
+        &bap_detail.subscriber_id,
+        body.transaction_id,
+        ondc_obj.observability.is_enabled,
+    );
+    match tokio::try_join!(task_1, task_2) {
+        Ok(_) => (),
+        Err(e) => {
+            return Err(GenericError::DatabaseError(e.to_string(), e));
+        }
+    };
+
+    Ok(HttpResponse::Accepted().json(GenericResponse::success(
+        "Successfully send recon request",
+        StatusCode::ACCEPTED,
+        Some(()),
+    )))
+}
+
+#[utoipa::path(
+    post,
+    path = "/settlement/list",
+    tag = "Settlement",
+    description="This API returns the settlement records of the business.",
+    summary= "Settlement List Request",
+    request_body(content = SettlementListRequest, description = "Request Body"),
+    responses(
+        (status=200, description= "Settlement List Response", body= GenericResponse<Vec<CommerceSettlement>>),
+        (status=400, description= "Invalid Request body", body= GenericResponse<TupleUnit>),
+        (status=401, description= "Invalid Token", body= GenericResponse<TupleUnit>),
+	    (status=403, description= "Insufficient Previlege", body= GenericResponse<TupleUnit>),
+	    (status=410, description= "Data not found", body= GenericResponse<TupleUnit>),
+        (status=500, description= "Internal Server Error", body= GenericResponse<TupleUnit>),
+	    (status=501, description= "Not Implemented", body= GenericResponse<TupleUnit>),
+    )
+)]
+#[tracing::instrument(name = "settlement list", skip(pool))]
+pub async fn settlement_list(
+    body: SettlementListRequest,
+    pool: web::Data<PgPool>,
+    business_account: BusinessAccount,
+) -> Result<web::Json<GenericResponse<Vec<CommerceSettlement>>>, GenericError> {
+    let settlements = fetch_commerce_settlements(&pool, business_account.id, &body)
+        .await
+        .map_err(|e| GenericError::DatabaseError(e.to_string(), e))?
+        .into_iter()
+        .map(|settlement| settlement.schema())
+        .collect();
+    Ok(web::Json(GenericResponse::success(
+        "Successfully fetched settlements",
+        StatusCode::OK,
+        Some(settlements),
+    )))
+}
+
+#[utoipa::path(
+    post,
+    path = "/settlement/settle",
+    tag = "Settlement",
+    description="This API marks reconciled settlements as paid out to the seller.",
+    summary= "Settlement Settle Request",
+    request_body(content = SettlementSettleRequest, description = "Request Body"),
+    responses(
+        (status=200, description= "Settlement Settle Response", body= GenericResponse<TupleUnit>),
+        (status=400, description= "Invalid Request body", body= GenericResponse<TupleUnit>),
+        (status=401, description= "Invalid Token", body= GenericResponse<TupleUnit>),
+	    (status=403, description= "Insufficient Previlege", body= GenericResponse<TupleUnit>),
+	    (status=410, description= "Data not found", body= GenericResponse<TupleUnit>),
+        (status=500, description= "Internal Server Error", body= GenericResponse<TupleUnit>),
+	    (status=501, description= "Not Implemented", body= GenericResponse<TupleUnit>),
+    )
+)]
+#[tracing::instrument(name = "settlement settle", skip(pool))]
+pub async fn settlement_settle(
+    body: SettlementSettleRequest,
+    pool: web::Data<PgPool>,
+    user_account: UserAccount,
+    business_account: BusinessAccount,
+) -> Result<web::Json<GenericResponse<()>>, GenericError> {
+    if body.settlement_ids.is_empty() {
+        return Err(GenericError::ValidationError(
+            "Atleast one settlement is required".to_string(),
+        ));
+    }
+    let mut transaction = pool
+        .begin()
+        .await
+        .context("Failed to acquire a Postgres connection from the pool")
+        .map_err(|e| GenericError::DatabaseError(e.to_string(), e))?;
+    let updated_count = mark_commerce_settlements_settled(
+        &mut transaction,
+        business_account.id,
+        user_account.id,
+        &body,
+    )
+    .await
+    .map_err(|e| GenericError::DatabaseError(e.to_string(), e))?;
+    if updated_count != body.settlement_ids.len() as u64 {
+        return Err(GenericError::ValidationError(format!(
+            "Only {} of {} settlements were in reconciled state",
+            updated_count,
+            body.settlement_ids.len()
+        )));
+    }
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit SQL transaction to mark settlements settled")
+        .map_err(|e| GenericError::DatabaseError(e.to_string(), e))?;
+    Ok(web::Json(GenericResponse::success(
+        "Successfully marked settlements as settled",
+        StatusCode::OK,
+        Some(()),
+    )))
+}
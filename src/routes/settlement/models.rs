@@ -0,0 +1,64 @@
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use super::schemas::{CommerceSettlement, CommerceSettlementStatus};
+use crate::routes::order::schemas::SettlementBasis;
+use crate::schemas::CurrencyType;
+
+#[derive(Deserialize, Debug, FromRow)]
+pub struct CommerceSettlementModel {
+    pub id: Uuid,
+    pub commerce_data_id: Uuid,
+    pub order_no: String,
+    pub bpp_id: String,
+    pub provider_id: String,
+    pub currency_code: CurrencyType,
+    pub order_amount: BigDecimal,
+    pub buyer_finder_fee: BigDecimal,
+    pub withholding_amount: BigDecimal,
+    pub settlement_amount: BigDecimal,
+    pub settlement_basis: Option<SettlementBasis>,
+    pub settlement_window: Option<String>,
+    pub status: CommerceSettlementStatus,
+    pub recon_transaction_id: Option<Uuid>,
+    pub recon_sent_on: Option<DateTime<Utc>>,
+    pub recon_accord: Option<bool>,
+    pub diff_amount: Option<BigDecimal>,
+    pub remark: Option<String>,
+    pub settlement_reference: Option<String>,
+    pub settled_on: Option<DateTime<Utc>>,
+    pub created_on: DateTime<Utc>,
+    pub updated_on: Option<DateTime<Utc>>,
+}
+
+impl CommerceSettlementModel {
+    pub fn schema(self) -> CommerceSettlement {
+        CommerceSettlement {
+            id: self.id,
+            commerce_data_id: self.commerce_data_id,
+            order_no: self.order_no,
+            bpp_id: self.bpp_id,
+            provider_id: self.provider_id,
+            currency_code: self.currency_code,
+            order_amount: self.order_amount,
+            buyer_finder_fee: self.buyer_finder_fee,
+            withholding_amount: self.withholding_amount,
+            settlement_amount: self.settlement_amount,
+            settlement_basis: self.settlement_basis,
+            settlement_window: self.settlement_window,
+            status: self.status,
+            recon_transaction_id: self.recon_transaction_id,
+            recon_sent_on: self.recon_sent_on,
+            recon_accord: self.recon_accord,
+            diff_amount: self.diff_amount,
+            remark: self.remark,
+            settlement_reference: self.settlement_reference,
+            settled_on: self.settled_on,
+            created_on: self.created_on,
+            updated_on: self.updated_on,
+        }
+    }
+}
@@ -0,0 +1,40 @@
+use crate::middleware::{BusinessAccountValidation, BusinessPermissionValidation, RequireAuth};
+use crate::user_client::{CustomerType, PermissionType};
+use actix_web::web;
+
+use super::handlers::{settlement_list, settlement_recon, settlement_settle};
+pub fn settlement_route(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::resource("/recon")
+            .route(web::post().to(settlement_recon))
+            .wrap(BusinessPermissionValidation {
+                permission_list: vec![PermissionType::UpdateOrder],
+            })
+            .wrap(BusinessAccountValidation {
+                business_type_list: vec![CustomerType::RetailB2bBuyer],
+            })
+            .wrap(RequireAuth),
+    );
+    cfg.service(
+        web::resource("/list")
+            .route(web::post().to(settlement_list))
+            .wrap(BusinessPermissionValidation {
+                permission_list: vec![PermissionType::ReadOrder],
+            })
+            .wrap(BusinessAccountValidation {
+                business_type_list: vec![CustomerType::RetailB2bBuyer],
+            })
+            .wrap(RequireAuth),
+    );
+    cfg.service(
+        web::resource("/settle")
+            .route(web::post().to(settlement_settle))
+            .wrap(BusinessPermissionValidation {
+                permission_list: vec![PermissionType::UpdateOrder],
+            })
+            .wrap(BusinessAccountValidation {
+                business_type_list: vec![CustomerType::RetailB2bBuyer],
+            })
+            .wrap(RequireAuth),
+    );
+}
@@ -0,0 +1,152 @@
+use crate::errors::GenericError;
+use crate::routes::order::schemas::SettlementBasis;
+use crate::schemas::CurrencyType;
+use actix_http::Payload;
+use actix_web::{web, FromRequest, HttpRequest};
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Utc};
+use futures_util::future::LocalBoxFuture;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Deserialize, Debug, ToSchema, Serialize, sqlx::Type, Clone, PartialEq)]
+#[sqlx(type_name = "commerce_settlement_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum CommerceSettlementStatus {
+    Pending,
+    ReconSent,
+    Reconciled,
+    Disputed,
+    Settled,
+}
+
+#[derive(Deserialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SettlementReconRequest {
+    #[schema(value_type = String)]
+    pub transaction_id: Uuid,
+    #[schema(value_type = String)]
+    pub message_id: Uuid,
+    #[schema(value_type = Vec<String>)]
+    pub order_transaction_ids: Vec<Uuid>,
+}
+
+impl FromRequest for SettlementReconRequest {
+    type Error = GenericError;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let fut = web::Json::<Self>::from_request(req, payload);
+
+        Box::pin(async move {
+            match fut.await {
+                Ok(json) => Ok(json.into_inner()),
+                Err(e) => Err(GenericError::ValidationError(e.to_string())),
+            }
+        })
+    }
+}
+
+#[derive(Deserialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SettlementListRequest {
+    pub status: Option<CommerceSettlementStatus>,
+    pub bpp_id: Option<String>,
+    pub start_date: Option<DateTime<Utc>>,
+    pub end_date: Option<DateTime<Utc>>,
+    pub offset: i32,
+    pub limit: i32,
+}
+
+impl FromRequest for SettlementListRequest {
+    type Error = GenericError;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let fut = web::Json::<Self>::from_request(req, payload);
+
+        Box::pin(async move {
+            match fut.await {
+                Ok(json) => Ok(json.into_inner()),
+                Err(e) => Err(GenericError::ValidationError(e.to_string())),
+            }
+        })
+    }
+}
+
+#[derive(Deserialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SettlementSettleRequest {
+    #[schema(value_type = Vec<String>)]
+    pub settlement_ids: Vec<Uuid>,
+    pub settlement_reference: String,
+    pub settled_on: DateTime<Utc>,
+}
+
+impl FromRequest for SettlementSettleRequest {
+    type Error = GenericError;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let fut = web::Json::<Self>::from_request(req, payload);
+
+        Box::pin(async move {
+            match fut.await {
+                Ok(json) => Ok(json.into_inner()),
+                Err(e) => Err(GenericError::ValidationError(e.to_string())),
+            }
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct CommerceSettlementData {
+    pub id: Uuid,
+    pub commerce_data_id: Uuid,
+    pub commerce_payment_id: Uuid,
+    pub bpp_id: String,
+    pub provider_id: String,
+    pub currency_code: CurrencyType,
+    pub order_amount: BigDecimal,
+    pub buyer_finder_fee: BigDecimal,
+    pub withholding_amount: BigDecimal,
+    pub settlement_amount: BigDecimal,
+    pub settlement_basis: Option<SettlementBasis>,
+    pub settlement_window: Option<String>,
+}
+
+#[derive(Debug, ToSchema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommerceSettlement {
+    #[schema(value_type = String)]
+    pub id: Uuid,
+    #[schema(value_type = String)]
+    pub commerce_data_id: Uuid,
+    pub order_no: String,
+    pub bpp_id: String,
+    pub provider_id: String,
+    pub currency_code: CurrencyType,
+    #[schema(value_type = f64)]
+    pub order_amount: BigDecimal,
+    #[schema(value_type = f64)]
+    pub buyer_finder_fee: BigDecimal,
+    #[schema(value_type = f64)]
+    pub withholding_amount: BigDecimal,
+    #[schema(value_type = f64)]
+    pub settlement_amount: BigDecimal,
+    pub settlement_basis: Option<SettlementBasis>,
+    pub settlement_window: Option<String>,
+    pub status: CommerceSettlementStatus,
+    #[schema(value_type = Option<String>)]
+    pub recon_transaction_id: Option<Uuid>,
+    pub recon_sent_on: Option<DateTime<Utc>>,
+    pub recon_accord: Option<bool>,
+    #[schema(value_type = Option<f64>)]
+    pub diff_amount: Option<BigDecimal>,
+    pub remark: Option<String>,
+    pub settlement_reference: Option<String>,
+    pub settled_on: Option<DateTime<Utc>>,
+    pub created_on: DateTime<Utc>,
+    pub updated_on: Option<DateTime<Utc>>,
+}
@@ -0,0 +1,47 @@
+#[cfg(test)]
+mod tests {
+    use bigdecimal::BigDecimal;
+    use std::str::FromStr;
+    use uuid::Uuid;
+
+    use crate::{
+        routes::settlement::{
+            schemas::SettlementListRequest,
+            utils::{fetch_commerce_settlements, fetch_settled_order_nos, get_buyer_finder_fee},
+        },
+        schemas::FeeType,
+        tests::tests::get_test_pool,
+    };
+
+    #[test]
+    fn test_buyer_finder_fee() {
+        let order_amount = BigDecimal::from_str("1250.00").unwrap();
+        let percent_fee =
+            get_buyer_finder_fee(&order_amount, Some(&FeeType::Percent), Some("3")).unwrap();
+        assert_eq!(percent_fee, BigDecimal::from_str("37.5").unwrap());
+        let amount_fee =
+            get_buyer_finder_fee(&order_amount, Some(&FeeType::Amount), Some("20")).unwrap();
+        assert_eq!(amount_fee, BigDecimal::from(20));
+        let no_fee = get_buyer_finder_fee(&order_amount, None, None).unwrap();
+        assert_eq!(no_fee, BigDecimal::from(0));
+        assert!(get_buyer_finder_fee(&order_amount, Some(&FeeType::Amount), Some("abc")).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_settlement_sql() {
+        let pool = get_test_pool().await;
+        let body = SettlementListRequest {
+            status: None,
+            bpp_id: None,
+            start_date: None,
+            end_date: None,
+            offset: 0,
+            limit: 10,
+        };
+        let settlements = fetch_commerce_settlements(&pool, Uuid::new_v4(), &body).await;
+        assert!(settlements.is_ok());
+        let settled = fetch_settled_order_nos(&pool, &[Uuid::new_v4()]).await;
+        assert!(settled.is_ok());
+        assert!(settled.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,399 @@
+use super::models::CommerceSettlementModel;
+use super::schemas::{
+    CommerceSettlementData, CommerceSettlementStatus, SettlementListRequest,
+    SettlementSettleRequest,
+};
+use crate::errors::GenericError;
+use crate::routes::ondc::schemas::ONDCOnReceiverReconOrder;
+use crate::routes::order::schemas::{
+    Commerce, CommerceStatusType, PaymentCollectedBy, PaymentStatus, SettlementBasis,
+};
+use crate::routes::payment::utils::get_paid_amount;
+use crate::schemas::{CurrencyType, FeeType};
+use bigdecimal::{BigDecimal, Zero};
+use chrono::Utc;
+use sqlx::{PgPool, Postgres, Transaction};
+use std::str::FromStr;
+use uuid::Uuid;
+
+pub fn get_buyer_finder_fee(
+    order_amount: &BigDecimal,
+    fee_type: Option<&FeeType>,
+    fee_amount: Option<&str>,
+) -> Result<BigDecimal, GenericError> {
+    let fee_value = match fee_amount {
+        Some(value) => BigDecimal::from_str(value).map_err(|_| {
+            GenericError::ValidationError(format!("Invalid buyer finder fee {}", value))
+        })?,
+        None => return Ok(BigDecimal::zero()),
+    };
+    match fee_type {
+        Some(FeeType::Percent) => Ok((order_amount * fee_value / BigDecimal::from(100)).round(3)),
+        Some(FeeType::Amount) => Ok(fee_value),
+        None => Ok(BigDecimal::zero()),
+    }
+}
+
+/// Settles only what the buyer has paid so far, an instalment order settling its paid instalments.
+pub fn get_commerce_settlement_data(
+    order: &Commerce,
+) -> Result<CommerceSettlementData, GenericError> {
+    if order.record_status == CommerceStatusType::Cancelled {
+        return Err(GenericError::ValidationError(format!(
+            "Order {} is cancelled",
+            order.urn
+        )));
+    }
+    if !order
+        .payments
+        .iter()
+        .any(|payment| payment.collected_by == Some(PaymentCollectedBy::Bap))
+    {
+        return Err(GenericError::ValidationError(format!(
+            "Payment for order {} is not collected by buyer app",
+            order.urn
+        )));
+    }
+    let payment = order
+        .payments
+        .iter()
+        .find(|payment| {
+            payment.collected_by == Some(PaymentCollectedBy::Bap)
+                && payment.payment_status == PaymentStatus::Paid
+        })
+        .ok_or_else(|| {
+            GenericError::ValidationError(format!(
+                "Payment for order {} is not completed",
+                order.urn
+            ))
+        })?;
+    let order_amount = order.grand_total.clone().ok_or_else(|| {
+        GenericError::ValidationError(format!("Order {} has no grand total", order.urn))
+    })?;
+    let paid_amount = get_paid_amount(order);
+    let buyer_finder_fee = get_buyer_finder_fee(
+        &paid_amount,
+        payment.buyer_fee_type.as_ref(),
+        payment.buyer_fee_amount.as_deref(),
+    )?;
+    let withholding_amount = match payment.withholding_amount.as_deref() {
+        Some(value) => BigDecimal::from_str(value).map_err(|_| {
+            GenericError::ValidationError(format!("Invalid withholding amount {}", value))
+        })?,
+        None => BigDecimal::zero(),
+    };
+    let settlement_amount = &paid_amount - &buyer_finder_fee - &withholding_amount;
+    if settlement_amount < BigDecimal::zero() {
+        return Err(GenericError::ValidationError(format!(
+            "Settlement amount for order {} is negative",
+            order.urn
+        )));
+    }
+
+    Ok(CommerceSettlementData {
+        id: Uuid::new_v4(),
+        commerce_data_id: order.id,
+        commerce_payment_id: payment.id,
+        bpp_id: order.bpp.id.clone(),
+        provider_id: order.seller.id.clone(),
+        currency_code: order.currency_type.clone().unwrap_or(CurrencyType::Inr),
+        order_amount,
+        buyer_finder_fee,
+        withholding_amount,
+        settlement_amount,
+        settlement_basis: payment.settlement_basis.clone(),
+        settlement_window: payment.settlement_window.clone(),
+    })
+}
+
+pub fn validate_settlement_recon_orders(
+    orders: &[Commerce],
+    business_id: Uuid,
+) -> Result<(), GenericError> {
+    let first_order = orders.first().ok_or_else(|| {
+        GenericError::ValidationError("Atleast one order is required".to_string())
+    })?;
+    for order in orders {
+        if order.buyer_id != business_id {
+            return Err(GenericError::ValidationError(format!(
+                "Order {} does not belong to the business",
+                order.urn
+            )));
+        }
+        if order.bpp.id != first_order.bpp.id {
+            return Err(GenericError::ValidationError(
+                "All orders in a recon must belong to the same seller app".to_string(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[tracing::instrument(name = "fetch settled order nos", skip(pool))]
+pub async fn fetch_settled_order_nos(
+    pool: &PgPool,
+    commerce_data_ids: &[Uuid],
+) -> Result<Vec<String>, anyhow::Error> {
+    let rows = sqlx::query!(
+        r#"SELECT c.urn FROM commerce_settlement AS s
+        INNER JOIN commerce_data AS c ON c.id = s.commerce_data_id
+        WHERE s.commerce_data_id = ANY($1) AND s.status = $2"#,
+        commerce_data_ids,
+        CommerceSettlementStatus::Settled as CommerceSettlementStatus
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e).context("A database failure occurred while fetching settled orders")
+    })?;
+    Ok(rows.into_iter().map(|row| row.urn).collect())
+}
+
+#[tracing::instrument(name = "save commerce settlements", skip(pool, settlements))]
+pub async fn save_commerce_settlements(
+    pool: &PgPool,
+    settlements: &[CommerceSettlementData],
+    buyer_id: Uuid,
+    user_id: Uuid,
+    recon_transaction_id: Uuid,
+    recon_message_id: Uuid,
+) -> Result<(), anyhow::Error> {
+    let mut id_list = vec![];
+    let mut commerce_data_id_list = vec![];
+    let mut commerce_payment_id_list = vec![];
+    let mut bpp_id_list = vec![];
+    let mut provider_id_list = vec![];
+    let mut currency_code_list = vec![];
+    let mut order_amount_list = vec![];
+    let mut buyer_finder_fee_list = vec![];
+    let mut withholding_amount_list = vec![];
+    let mut settlement_amount_list = vec![];
+    let mut settlement_basis_list = vec![];
+    let mut settlement_window_list = vec![];
+    for settlement in settlements {
+        id_list.push(settlement.id);
+        commerce_data_id_list.push(settlement.commerce_data_id);
+        commerce_payment_id_list.push(settlement.commerce_payment_id);
+        bpp_id_list.push(settlement.bpp_id.as_str());
+        provider_id_list.push(settlement.provider_id.as_str());
+        currency_code_list.push(settlement.currency_code.clone());
+        order_amount_list.push(settlement.order_amount.clone());
+        buyer_finder_fee_list.push(settlement.buyer_finder_fee.clone());
+        withholding_amount_list.push(settlement.withholding_amount.clone());
+        settlement_amount_list.push(settlement.settlement_amount.clone());
+        settlement_basis_list.push(settlement.settlement_basis.clone());
+        settlement_window_list.push(settlement.settlement_window.as_deref());
+    }
+    sqlx::query!(
+        r#"
+        INSERT INTO commerce_settlement (id, commerce_data_id, commerce_payment_id, bpp_id,
+            provider_id, currency_code, order_amount, buyer_finder_fee, withholding_amount,
+            settlement_amount, settlement_basis, settlement_window, buyer_id, status,
+            recon_transaction_id, recon_message_id, recon_sent_on, created_by)
+        SELECT *, $13, $14, $15, $16, $17, $18
+        FROM UNNEST($1::uuid[], $2::uuid[], $3::uuid[], $4::text[], $5::text[],
+            $6::currency_code_type[], $7::decimal[], $8::decimal[], $9::decimal[],
+            $10::decimal[], $11::settlement_basis_type[], $12::text[])
+        ON CONFLICT (commerce_data_id) DO UPDATE SET
+            currency_code = EXCLUDED.currency_code,
+            order_amount = EXCLUDED.order_amount,
+            buyer_finder_fee = EXCLUDED.buyer_finder_fee,
+            withholding_amount = EXCLUDED.withholding_amount,
+            settlement_amount = EXCLUDED.settlement_amount,
+            settlement_basis = EXCLUDED.settlement_basis,
+            settlement_window = EXCLUDED.settlement_window,
+            status = EXCLUDED.status,
+            recon_transaction_id = EXCLUDED.recon_transaction_id,
+            recon_message_id = EXCLUDED.recon_message_id,
+            recon_sent_on = EXCLUDED.recon_sent_on,
+            recon_accord = NULL,
+            diff_amount = NULL,
+            remark = NULL,
+            updated_on = EXCLUDED.recon_sent_on,
+            updated_by = EXCLUDED.created_by
+        WHERE commerce_settlement.status <> 'settled'
+        "#,
+        &id_list[..] as &[Uuid],
+        &commerce_data_id_list[..] as &[Uuid],
+        &commerce_payment_id_list[..] as &[Uuid],
+        &bpp_id_list[..] as &[&str],
+        &provider_id_list[..] as &[&str],
+        &currency_code_list[..] as &[CurrencyType],
+        &order_amount_list[..] as &[BigDecimal],
+        &buyer_finder_fee_list[..] as &[BigDecimal],
+        &withholding_amount_list[..] as &[BigDecimal],
+        &settlement_amount_list[..] as &[BigDecimal],
+        &settlement_basis_list[..] as &[Option<SettlementBasis>],
+        &settlement_window_list[..] as &[Option<&str>],
+        buyer_id,
+        CommerceSettlementStatus::ReconSent as CommerceSettlementStatus,
+        recon_transaction_id,
+        recon_message_id,
+        Utc::now(),
+        user_id
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e)
+            .context("A database failure occurred while saving commerce settlements")
+    })?;
+    Ok(())
+}
+
+#[tracing::instrument(name = "fetch commerce settlements", skip(pool))]
+pub async fn fetch_commerce_settlements(
+    pool: &PgPool,
+    buyer_id: Uuid,
+    body: &SettlementListRequest,
+) -> Result<Vec<CommerceSettlementModel>, anyhow::Error> {
+    let rows = sqlx::query_as!(
+        CommerceSettlementModel,
+        r#"SELECT s.id, s.commerce_data_id, c.urn as order_no, s.bpp_id, s.provider_id,
+        s.currency_code as "currency_code: CurrencyType", s.order_amount, s.buyer_finder_fee,
+        s.withholding_amount, s.settlement_amount,
+        s.settlement_basis as "settlement_basis?: SettlementBasis", s.settlement_window,
+        s.status as "status: CommerceSettlementStatus", s.recon_transaction_id, s.recon_sent_on,
+        s.recon_accord, s.diff_amount, s.remark, s.settlement_reference, s.settled_on,
+        s.created_on, s.updated_on
+        FROM commerce_settlement AS s
+        INNER JOIN commerce_data AS c ON c.id = s.commerce_data_id
+        WHERE s.buyer_id = $1
+        AND ($2::commerce_settlement_status IS NULL OR s.status = $2)
+        AND ($3::text IS NULL OR s.bpp_id = $3)
+        AND ($4::timestamptz IS NULL OR s.created_on >= $4)
+        AND ($5::timestamptz IS NULL OR s.created_on <= $5)
+        ORDER BY s.created_on DESC LIMIT $6 OFFSET $7"#,
+        buyer_id,
+        body.status.as_ref() as Option<&CommerceSettlementStatus>,
+        body.bpp_id,
+        body.start_date,
+        body.end_date,
+        body.limit as i64,
+        body.offset as i64
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e)
+            .context("A database failure occurred while fetching commerce settlements")
+    })?;
+    Ok(rows)
+}
+
+#[tracing::instrument(name = "update commerce settlement on receiver recon", skip(pool))]
+pub async fn update_commerce_settlement_on_receiver_recon(
+    pool: &PgPool,
+    recon_transaction_id: Uuid,
+    orders: &[ONDCOnReceiverReconOrder],
+) -> Result<(), anyhow::Error> {
+    let mut order_no_list = vec![];
+    let mut recon_accord_list = vec![];
+    let mut diff_amount_list = vec![];
+    let mut remark_list = vec![];
+    for order in orders {
+        order_no_list.push(order.id.as_str());
+        recon_accord_list.push(order.recon_accord);
+        diff_amount_list.push(
+            order
+                .diff_amount
+                .as_ref()
+                .and_then(|amount| BigDecimal::from_str(&amount.value).ok()),
+        );
+        remark_list.push(order.remark.as_deref());
+    }
+    sqlx::query!(
+        r#"
+        UPDATE commerce_settlement AS s SET
+            recon_accord = data.recon_accord,
+            diff_amount = data.diff_amount,
+            remark = data.remark,
+            status = CASE WHEN data.recon_accord THEN $5::commerce_settlement_status
+                ELSE $6::commerce_settlement_status END,
+            updated_on = $7
+        FROM UNNEST($1::text[], $2::boolean[], $3::decimal[], $4::text[])
+            AS data(order_no, recon_accord, diff_amount, remark),
+            commerce_data AS c
+        WHERE s.commerce_data_id = c.id AND c.urn = data.order_no
+            AND s.recon_transaction_id = $8 AND s.status <> 'settled'
+        "#,
+        &order_no_list[..] as &[&str],
+        &recon_accord_list[..] as &[bool],
+        &diff_amount_list[..] as &[Option<BigDecimal>],
+        &remark_list[..] as &[Option<&str>],
+        CommerceSettlementStatus::Reconciled as CommerceSettlementStatus,
+        CommerceSettlementStatus::Disputed as CommerceSettlementStatus,
+        Utc::now(),
+        recon_transaction_id
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e)
+            .context("A database failure occurred while updating commerce settlement recon")
+    })?;
+    Ok(())
+}
+
+#[tracing::instrument(name = "update commerce settlement on recon error", skip(pool))]
+pub async fn update_commerce_settlement_on_recon_error(
+    pool: &PgPool,
+    recon_transaction_id: Uuid,
+    remark: &str,
+) -> Result<(), anyhow::Error> {
+    sqlx::query!(
+        r#"
+        UPDATE commerce_settlement SET status = $1, remark = $2, updated_on = $3
+        WHERE recon_transaction_id = $4 AND status = $5
+        "#,
+        CommerceSettlementStatus::Disputed as CommerceSettlementStatus,
+        remark,
+        Utc::now(),
+        recon_transaction_id,
+        CommerceSettlementStatus::ReconSent as CommerceSettlementStatus
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e)
+            .context("A database failure occurred while updating commerce settlement recon error")
+    })?;
+    Ok(())
+}
+
+#[tracing::instrument(name = "mark commerce settlements settled", skip(transaction))]
+pub async fn mark_commerce_settlements_settled(
+    transaction: &mut Transaction<'_, Postgres>,
+    buyer_id: Uuid,
+    user_id: Uuid,
+    body: &SettlementSettleRequest,
+) -> Result<u64, anyhow::Error> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE commerce_settlement SET status = $1, settlement_reference = $2, settled_on = $3,
+            updated_on = $4, updated_by = $5
+        WHERE id = ANY($6) AND buyer_id = $7 AND status = $8
+        "#,
+        CommerceSettlementStatus::Settled as CommerceSettlementStatus,
+        body.settlement_reference,
+        body.settled_on,
+        Utc::now(),
+        user_id,
+        &body.settlement_ids,
+        buyer_id,
+        CommerceSettlementStatus::Reconciled as CommerceSettlementStatus
+    )
+    .execute(&mut **transaction)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e)
+            .context("A database failure occurred while marking commerce settlements settled")
+    })?;
+    Ok(result.rows_affected())
+}
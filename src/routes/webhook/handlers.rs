@@ -0,0 +1,248 @@
+use actix_http::StatusCode;
+use actix_web::web;
+use sqlx::PgPool;
+use utoipa::TupleUnit;
+
+use super::schemas::{
+    WebhookDelivery, WebhookDeliveryListRequest, WebhookRedeliveryRequest, WebhookSubscription,
+    WebhookSubscriptionCreateRequest, WebhookSubscriptionCreateResponse,
+    WebhookSubscriptionStatusUpdateRequest,
+};
+use super::utils::{
+    fetch_webhook_deliveries, fetch_webhook_subscription_by_id, fetch_webhook_subscriptions,
+    generate_webhook_secret, reset_webhook_delivery, save_webhook_subscription,
+    update_webhook_subscription_status, validate_webhook_subscription_create_request,
+    validate_webhook_target,
+};
+use crate::errors::GenericError;
+use crate::schemas::GenericResponse;
+use crate::user_client::{BusinessAccount, UserAccount};
+
+#[utoipa::path(
+    post,
+    path = "/webhook/subscription/create",
+    tag = "Webhook",
+    description="This API registers a webhook endpoint which receives HMAC signed order events of the business.",
+    summary= "Webhook Subscription Create Request",
+    request_body(content = WebhookSubscriptionCreateRequest, description = "Request Body"),
+    responses(
+        (status=200, description= "Webhook Subscription Create Response", body= GenericResponse<WebhookSubscriptionCreateResponse>),
+        (status=400, description= "Invalid Request body", body= GenericResponse<TupleUnit>),
+        (status=401, description= "Invalid Token", body= GenericResponse<TupleUnit>),
+	    (status=403, description= "Insufficient Previlege", body= GenericResponse<TupleUnit>),
+	    (status=410, description= "Data not found", body= GenericResponse<TupleUnit>),
+        (status=500, description= "Internal Server Error", body= GenericResponse<TupleUnit>),
+	    (status=501, description= "Not Implemented", body= GenericResponse<TupleUnit>),
+    )
+)]
+#[tracing::instrument(name = "webhook subscription create", skip(pool), fields())]
+pub async fn webhook_subscription_create(
+    body: WebhookSubscriptionCreateRequest,
+    pool: web::Data<PgPool>,
+    user_account: UserAccount,
+    business_account: BusinessAccount,
+) -> Result<web::Json<GenericResponse<WebhookSubscriptionCreateResponse>>, GenericError> {
+    validate_webhook_subscription_create_request(&body)?;
+    validate_webhook_target(&body.url)
+        .await
+        .map_err(|e| GenericError::ValidationError(e.to_string()))?;
+    let secret = body.secret.clone().unwrap_or_else(generate_webhook_secret);
+    let id = save_webhook_subscription(&pool, &body, &secret, user_account.id, business_account.id)
+        .await
+        .map_err(|e| {
+            GenericError::DatabaseError("Failed to save webhook subscription".to_string(), e)
+        })?;
+    Ok(web::Json(GenericResponse::success(
+        "Successfully created webhook subscription",
+        StatusCode::OK,
+        Some(WebhookSubscriptionCreateResponse { id, secret }),
+    )))
+}
+
+#[utoipa::path(
+    post,
+    path = "/webhook/subscription/list",
+    tag = "Webhook",
+    description="This API lists the webhook subscriptions of the business.",
+    summary= "Webhook Subscription List Request",
+    responses(
+        (status=200, description= "Webhook Subscription List Response", body= GenericResponse<Vec<WebhookSubscription>>),
+        (status=400, description= "Invalid Request body", body= GenericResponse<TupleUnit>),
+        (status=401, description= "Invalid Token", body= GenericResponse<TupleUnit>),
+	    (status=403, description= "Insufficient Previlege", body= GenericResponse<TupleUnit>),
+	    (status=410, description= "Data not found", body= GenericResponse<TupleUnit>),
+        (status=500, description= "Internal Server Error", body= GenericResponse<TupleUnit>),
+	    (status=501, description= "Not Implemented", body= GenericResponse<TupleUnit>),
+    )
+)]
+#[tracing::instrument(name = "webhook subscription list", skip(pool), fields())]
+pub async fn webhook_subscription_list(
+    pool: web::Data<PgPool>,
+    user_account: UserAccount,
+    business_account: BusinessAccount,
+) -> Result<web::Json<GenericResponse<Vec<WebhookSubscription>>>, GenericError> {
+    let data = fetch_webhook_subscriptions(&pool, business_account.id)
+        .await
+        .map_err(|e| {
+            GenericError::DatabaseError("Failed to fetch webhook subscriptions".to_string(), e)
+        })?;
+    Ok(web::Json(GenericResponse::success(
+        "Successfully fetched webhook subscriptions",
+        StatusCode::OK,
+        Some(
+            data.into_iter()
+                .map(|subscription| subscription.schema())
+                .collect(),
+        ),
+    )))
+}
+
+#[utoipa::path(
+    post,
+    path = "/webhook/subscription/status/update",
+    tag = "Webhook",
+    description="This API pauses or resumes a webhook subscription.",
+    summary= "Webhook Subscription Status Update Request",
+    request_body(content = WebhookSubscriptionStatusUpdateRequest, description = "Request Body"),
+    responses(
+        (status=200, description= "Webhook Subscription Status Update Response", body= GenericResponse<TupleUnit>),
+        (status=400, description= "Invalid Request body", body= GenericResponse<TupleUnit>),
+        (status=401, description= "Invalid Token", body= GenericResponse<TupleUnit>),
+	    (status=403, description= "Insufficient Previlege", body= GenericResponse<TupleUnit>),
+	    (status=410, description= "Data not found", body= GenericResponse<TupleUnit>),
+        (status=500, description= "Internal Server Error", body= GenericResponse<TupleUnit>),
+	    (status=501, description= "Not Implemented", body= GenericResponse<TupleUnit>),
+    )
+)]
+#[tracing::instrument(name = "webhook subscription status update", skip(pool), fields(subscription_id = %body.subscription_id))]
+pub async fn webhook_subscription_status_update(
+    body: WebhookSubscriptionStatusUpdateRequest,
+    pool: web::Data<PgPool>,
+    user_account: UserAccount,
+    business_account: BusinessAccount,
+) -> Result<web::Json<GenericResponse<()>>, GenericError> {
+    let subscription = fetch_webhook_subscription_by_id(&pool, body.subscription_id)
+        .await
+        .map_err(|e| {
+            GenericError::DatabaseError("Failed to fetch webhook subscription".to_string(), e)
+        })?
+        .ok_or_else(|| {
+            GenericError::ValidationError("Webhook subscription not found".to_string())
+        })?;
+    if subscription.business_id != business_account.id {
+        return Err(GenericError::InsufficientPrevilegeError(
+            "You do not have sufficent preveliege to update the webhook subscription".to_owned(),
+        ));
+    }
+    update_webhook_subscription_status(
+        &pool,
+        body.subscription_id,
+        body.is_active,
+        user_account.id,
+    )
+    .await
+    .map_err(|e| {
+        GenericError::DatabaseError("Failed to update webhook subscription".to_string(), e)
+    })?;
+    Ok(web::Json(GenericResponse::success(
+        "Successfully updated webhook subscription",
+        StatusCode::OK,
+        Some(()),
+    )))
+}
+
+#[utoipa::path(
+    post,
+    path = "/webhook/delivery/list",
+    tag = "Webhook",
+    description="This API lists the delivery log of a webhook subscription.",
+    summary= "Webhook Delivery List Request",
+    request_body(content = WebhookDeliveryListRequest, description = "Request Body"),
+    responses(
+        (status=200, description= "Webhook Delivery List Response", body= GenericResponse<Vec<WebhookDelivery>>),
+        (status=400, description= "Invalid Request body", body= GenericResponse<TupleUnit>),
+        (status=401, description= "Invalid Token", body= GenericResponse<TupleUnit>),
+	    (status=403, description= "Insufficient Previlege", body= GenericResponse<TupleUnit>),
+	    (status=410, description= "Data not found", body= GenericResponse<TupleUnit>),
+        (status=500, description= "Internal Server Error", body= GenericResponse<TupleUnit>),
+	    (status=501, description= "Not Implemented", body= GenericResponse<TupleUnit>),
+    )
+)]
+#[tracing::instrument(name = "webhook delivery list", skip(pool), fields(subscription_id = %body.subscription_id))]
+pub async fn webhook_delivery_list(
+    body: WebhookDeliveryListRequest,
+    pool: web::Data<PgPool>,
+    user_account: UserAccount,
+    business_account: BusinessAccount,
+) -> Result<web::Json<GenericResponse<Vec<WebhookDelivery>>>, GenericError> {
+    let subscription = fetch_webhook_subscription_by_id(&pool, body.subscription_id)
+        .await
+        .map_err(|e| {
+            GenericError::DatabaseError("Failed to fetch webhook subscription".to_string(), e)
+        })?
+        .ok_or_else(|| {
+            GenericError::ValidationError("Webhook subscription not found".to_string())
+        })?;
+    if subscription.business_id != business_account.id {
+        return Err(GenericError::InsufficientPrevilegeError(
+            "You do not have sufficent preveliege to read the webhook deliveries".to_owned(),
+        ));
+    }
+    let data = fetch_webhook_deliveries(
+        &pool,
+        body.subscription_id,
+        body.status.as_ref(),
+        body.limit,
+        body.offset,
+    )
+    .await
+    .map_err(|e| {
+        GenericError::DatabaseError("Failed to fetch webhook deliveries".to_string(), e)
+    })?;
+    Ok(web::Json(GenericResponse::success(
+        "Successfully fetched webhook deliveries",
+        StatusCode::OK,
+        Some(data.into_iter().map(|delivery| delivery.schema()).collect()),
+    )))
+}
+
+#[utoipa::path(
+    post,
+    path = "/webhook/delivery/retry",
+    tag = "Webhook",
+    description="This API queues a webhook delivery to be sent again.",
+    summary= "Webhook Redelivery Request",
+    request_body(content = WebhookRedeliveryRequest, description = "Request Body"),
+    responses(
+        (status=200, description= "Webhook Redelivery Response", body= GenericResponse<TupleUnit>),
+        (status=400, description= "Invalid Request body", body= GenericResponse<TupleUnit>),
+        (status=401, description= "Invalid Token", body= GenericResponse<TupleUnit>),
+	    (status=403, description= "Insufficient Previlege", body= GenericResponse<TupleUnit>),
+	    (status=410, description= "Data not found", body= GenericResponse<TupleUnit>),
+        (status=500, description= "Internal Server Error", body= GenericResponse<TupleUnit>),
+	    (status=501, description= "Not Implemented", body= GenericResponse<TupleUnit>),
+    )
+)]
+#[tracing::instrument(name = "webhook redelivery", skip(pool), fields(delivery_id = %body.delivery_id))]
+pub async fn webhook_redeliver(
+    body: WebhookRedeliveryRequest,
+    pool: web::Data<PgPool>,
+    user_account: UserAccount,
+    business_account: BusinessAccount,
+) -> Result<web::Json<GenericResponse<()>>, GenericError> {
+    let is_reset = reset_webhook_delivery(&pool, body.delivery_id, business_account.id)
+        .await
+        .map_err(|e| {
+            GenericError::DatabaseError("Failed to queue webhook redelivery".to_string(), e)
+        })?;
+    if !is_reset {
+        return Err(GenericError::ValidationError(
+            "Webhook delivery not found".to_string(),
+        ));
+    }
+    Ok(web::Json(GenericResponse::success(
+        "Successfully queued webhook redelivery",
+        StatusCode::OK,
+        Some(()),
+    )))
+}
@@ -0,0 +1,81 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_json::Value;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use super::schemas::{
+    WebhookDelivery, WebhookDeliveryStatus, WebhookEventType, WebhookSubscription,
+};
+
+#[derive(Deserialize, Debug, FromRow)]
+pub struct WebhookSubscriptionModel {
+    pub id: Uuid,
+    pub business_id: Uuid,
+    pub url: String,
+    pub event_types: Vec<WebhookEventType>,
+    pub is_active: bool,
+    pub created_by: Uuid,
+    pub created_on: DateTime<Utc>,
+}
+
+impl WebhookSubscriptionModel {
+    pub fn schema(self) -> WebhookSubscription {
+        WebhookSubscription {
+            id: self.id,
+            url: self.url,
+            event_types: self.event_types,
+            is_active: self.is_active,
+            created_by: self.created_by,
+            created_on: self.created_on,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, FromRow)]
+pub struct WebhookDeliveryModel {
+    pub id: Uuid,
+    pub subscription_id: Uuid,
+    pub event_id: Uuid,
+    pub event_type: WebhookEventType,
+    pub payload: Value,
+    pub status: WebhookDeliveryStatus,
+    pub attempt_count: i32,
+    pub next_attempt_on: DateTime<Utc>,
+    pub last_attempted_on: Option<DateTime<Utc>>,
+    pub response_status: Option<i32>,
+    pub remark: Option<String>,
+    pub delivered_on: Option<DateTime<Utc>>,
+    pub created_on: DateTime<Utc>,
+}
+
+impl WebhookDeliveryModel {
+    pub fn schema(self) -> WebhookDelivery {
+        WebhookDelivery {
+            id: self.id,
+            subscription_id: self.subscription_id,
+            event_id: self.event_id,
+            event_type: self.event_type,
+            payload: self.payload,
+            status: self.status,
+            attempt_count: self.attempt_count,
+            next_attempt_on: self.next_attempt_on,
+            last_attempted_on: self.last_attempted_on,
+            response_status: self.response_status,
+            remark: self.remark,
+            delivered_on: self.delivered_on,
+            created_on: self.created_on,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, FromRow)]
+pub struct PendingWebhookDeliveryModel {
+    pub id: Uuid,
+    pub event_type: WebhookEventType,
+    pub payload: Value,
+    pub attempt_count: i32,
+    pub url: String,
+    pub secret: String,
+    pub is_active: bool,
+}
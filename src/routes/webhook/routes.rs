@@ -0,0 +1,65 @@
+use crate::middleware::{BusinessAccountValidation, BusinessPermissionValidation, RequireAuth};
+use crate::user_client::{CustomerType, PermissionType};
+use actix_web::web;
+
+use super::handlers::{
+    webhook_delivery_list, webhook_redeliver, webhook_subscription_create,
+    webhook_subscription_list, webhook_subscription_status_update,
+};
+pub fn webhook_route(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::resource("/subscription/create")
+            .route(web::post().to(webhook_subscription_create))
+            .wrap(BusinessPermissionValidation {
+                permission_list: vec![PermissionType::UpdateOrder],
+            })
+            .wrap(BusinessAccountValidation {
+                business_type_list: vec![CustomerType::RetailB2bBuyer],
+            })
+            .wrap(RequireAuth),
+    );
+    cfg.service(
+        web::resource("/subscription/list")
+            .route(web::post().to(webhook_subscription_list))
+            .wrap(BusinessPermissionValidation {
+                permission_list: vec![PermissionType::ReadOrder],
+            })
+            .wrap(BusinessAccountValidation {
+                business_type_list: vec![CustomerType::RetailB2bBuyer],
+            })
+            .wrap(RequireAuth),
+    );
+    cfg.service(
+        web::resource("/subscription/status/update")
+            .route(web::post().to(webhook_subscription_status_update))
+            .wrap(BusinessPermissionValidation {
+                permission_list: vec![PermissionType::UpdateOrder],
+            })
+            .wrap(BusinessAccountValidation {
+                business_type_list: vec![CustomerType::RetailB2bBuyer],
+            })
+            .wrap(RequireAuth),
+    );
+    cfg.service(
+        web::resource("/delivery/list")
+            .route(web::post().to(webhook_delivery_list))
+            .wrap(BusinessPermissionValidation {
+                permission_list: vec![PermissionType::ReadOrder],
+            })
+            .wrap(BusinessAccountValidation {
+                business_type_list: vec![CustomerType::RetailB2bBuyer],
+            })
+            .wrap(RequireAuth),
+    );
+    cfg.service(
+        web::resource("/delivery/retry")
+            .route(web::post().to(webhook_redeliver))
+            .wrap(BusinessPermissionValidation {
+                permission_list: vec![PermissionType::UpdateOrder],
+            })
+            .wrap(BusinessAccountValidation {
+                business_type_list: vec![CustomerType::RetailB2bBuyer],
+            })
+            .wrap(RequireAuth),
+    );
+}
@@ -0,0 +1,198 @@
+use crate::errors::GenericError;
+use actix_http::Payload;
+use actix_web::{web, FromRequest, HttpRequest};
+use chrono::{DateTime, Utc};
+use futures_util::future::LocalBoxFuture;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Deserialize, Debug, ToSchema, Serialize, sqlx::Type, Clone, PartialEq)]
+#[sqlx(type_name = "webhook_event_type", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEventType {
+    QuoteReceived,
+    OrderInitialized,
+    OrderAccepted,
+    FulfillmentStatusChanged,
+    OrderUpdated,
+    OrderCancelled,
+    PaymentUpdated,
+    RefundUpdated,
+}
+
+impl fmt::Display for WebhookEventType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            WebhookEventType::QuoteReceived => "quote_received",
+            WebhookEventType::OrderInitialized => "order_initialized",
+            WebhookEventType::OrderAccepted => "order_accepted",
+            WebhookEventType::FulfillmentStatusChanged => "fulfillment_status_changed",
+            WebhookEventType::OrderUpdated => "order_updated",
+            WebhookEventType::OrderCancelled => "order_cancelled",
+            WebhookEventType::PaymentUpdated => "payment_updated",
+            WebhookEventType::RefundUpdated => "refund_updated",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+#[derive(Deserialize, Debug, ToSchema, Serialize, sqlx::Type, Clone, PartialEq)]
+#[sqlx(type_name = "webhook_delivery_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookDeliveryStatus {
+    Pending,
+    Delivered,
+    Failed,
+}
+
+#[derive(Deserialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookSubscriptionCreateRequest {
+    pub url: String,
+    pub secret: Option<String>,
+    pub event_types: Vec<WebhookEventType>,
+}
+
+impl FromRequest for WebhookSubscriptionCreateRequest {
+    type Error = GenericError;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let fut = web::Json::<Self>::from_request(req, payload);
+
+        Box::pin(async move {
+            match fut.await {
+                Ok(json) => Ok(json.into_inner()),
+                Err(e) => Err(GenericError::ValidationError(e.to_string())),
+            }
+        })
+    }
+}
+
+#[derive(Deserialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookSubscriptionStatusUpdateRequest {
+    #[schema(value_type = String)]
+    pub subscription_id: Uuid,
+    pub is_active: bool,
+}
+
+impl FromRequest for WebhookSubscriptionStatusUpdateRequest {
+    type Error = GenericError;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let fut = web::Json::<Self>::from_request(req, payload);
+
+        Box::pin(async move {
+            match fut.await {
+                Ok(json) => Ok(json.into_inner()),
+                Err(e) => Err(GenericError::ValidationError(e.to_string())),
+            }
+        })
+    }
+}
+
+#[derive(Deserialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookDeliveryListRequest {
+    #[schema(value_type = String)]
+    pub subscription_id: Uuid,
+    pub status: Option<WebhookDeliveryStatus>,
+    pub offset: i32,
+    pub limit: i32,
+}
+
+impl FromRequest for WebhookDeliveryListRequest {
+    type Error = GenericError;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let fut = web::Json::<Self>::from_request(req, payload);
+
+        Box::pin(async move {
+            match fut.await {
+                Ok(json) => Ok(json.into_inner()),
+                Err(e) => Err(GenericError::ValidationError(e.to_string())),
+            }
+        })
+    }
+}
+
+#[derive(Deserialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookRedeliveryRequest {
+    #[schema(value_type = String)]
+    pub delivery_id: Uuid,
+}
+
+impl FromRequest for WebhookRedeliveryRequest {
+    type Error = GenericError;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let fut = web::Json::<Self>::from_request(req, payload);
+
+        Box::pin(async move {
+            match fut.await {
+                Ok(json) => Ok(json.into_inner()),
+                Err(e) => Err(GenericError::ValidationError(e.to_string())),
+            }
+        })
+    }
+}
+
+#[derive(Debug, ToSchema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookSubscriptionCreateResponse {
+    #[schema(value_type = String)]
+    pub id: Uuid,
+    pub secret: String,
+}
+
+#[derive(Debug, ToSchema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookSubscription {
+    #[schema(value_type = String)]
+    pub id: Uuid,
+    pub url: String,
+    pub event_types: Vec<WebhookEventType>,
+    pub is_active: bool,
+    #[schema(value_type = String)]
+    pub created_by: Uuid,
+    pub created_on: DateTime<Utc>,
+}
+
+#[derive(Debug, ToSchema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookDelivery {
+    #[schema(value_type = String)]
+    pub id: Uuid,
+    #[schema(value_type = String)]
+    pub subscription_id: Uuid,
+    #[schema(value_type = String)]
+    pub event_id: Uuid,
+    pub event_type: WebhookEventType,
+    pub payload: Value,
+    pub status: WebhookDeliveryStatus,
+    pub attempt_count: i32,
+    pub next_attempt_on: DateTime<Utc>,
+    pub last_attempted_on: Option<DateTime<Utc>>,
+    pub response_status: Option<i32>,
+    pub remark: Option<String>,
+    pub delivered_on: Option<DateTime<Utc>>,
+    pub created_on: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookEventPayload<'a> {
+    pub event_id: Uuid,
+    pub event_type: &'a WebhookEventType,
+    pub business_id: Uuid,
+    pub created_on: DateTime<Utc>,
+    pub data: &'a Value,
+}
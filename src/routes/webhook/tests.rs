@@ -0,0 +1,82 @@
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use crate::{
+        routes::webhook::utils::{
+            fetch_webhook_deliveries, fetch_webhook_subscriptions, get_webhook_client,
+            get_webhook_retry_delay, get_webhook_signature, reset_webhook_delivery,
+            validate_webhook_target,
+        },
+        tests::tests::get_test_pool,
+    };
+
+    #[test]
+    fn test_webhook_signature() {
+        let signature = get_webhook_signature("secret", 1700000000, b"{}");
+        assert!(signature.starts_with("sha256="));
+        assert_eq!(signature.len(), 7 + 64);
+        assert_eq!(
+            signature,
+            get_webhook_signature("secret", 1700000000, b"{}")
+        );
+        assert_ne!(
+            signature,
+            get_webhook_signature("secret", 1700000001, b"{}")
+        );
+        assert_ne!(
+            signature,
+            get_webhook_signature("other-secret", 1700000000, b"{}")
+        );
+    }
+
+    #[test]
+    fn test_webhook_retry_delay() {
+        let first = get_webhook_retry_delay(1);
+        assert_eq!(get_webhook_retry_delay(2), first * 2);
+        assert_eq!(get_webhook_retry_delay(4), first * 8);
+    }
+
+    #[tokio::test]
+    async fn test_webhook_sql() {
+        let pool = get_test_pool().await;
+        let subscriptions = fetch_webhook_subscriptions(&pool, Uuid::new_v4()).await;
+        assert!(subscriptions.is_ok());
+        let deliveries = fetch_webhook_deliveries(&pool, Uuid::new_v4(), None, 10, 0).await;
+        assert!(deliveries.is_ok());
+        let is_reset = reset_webhook_delivery(&pool, Uuid::new_v4(), Uuid::new_v4()).await;
+        assert!(is_reset.is_ok());
+        assert!(!is_reset.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_webhook_target_validation() {
+        for url in [
+            "http://127.0.0.1/hook",
+            "http://localhost:8080/hook",
+            "http://10.1.2.3/hook",
+            "http://192.168.1.10/hook",
+            "http://169.254.169.254/latest/meta-data",
+            "http://[::1]/hook",
+            "http://[fe80::1]/hook",
+            "http://[::ffff:127.0.0.1]/hook",
+            "http://0.0.0.0/hook",
+        ] {
+            assert!(validate_webhook_target(url).await.is_err(), "{}", url);
+        }
+        assert!(validate_webhook_target("https://8.8.8.8/hook")
+            .await
+            .is_ok());
+        assert!(validate_webhook_target("not a url").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_webhook_client_resolver() {
+        let error = get_webhook_client()
+            .post("http://localhost:9/hook")
+            .send()
+            .await
+            .unwrap_err();
+        assert!(format!("{:?}", error).contains("public address"));
+    }
+}
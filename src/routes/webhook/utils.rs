@@ -0,0 +1,525 @@
+use super::models::{PendingWebhookDeliveryModel, WebhookDeliveryModel, WebhookSubscriptionModel};
+use super::schemas::{
+    WebhookDeliveryStatus, WebhookEventPayload, WebhookEventType, WebhookSubscriptionCreateRequest,
+};
+use crate::constants::{
+    WEBHOOK_DELIVERY_BATCH_SIZE, WEBHOOK_DELIVERY_INTERVAL, WEBHOOK_DELIVERY_MAX_ATTEMPTS,
+    WEBHOOK_DELIVERY_TIMEOUT, WEBHOOK_RETRY_BASE_DELAY,
+};
+use crate::errors::GenericError;
+use actix_web::web;
+use anyhow::anyhow;
+use chrono::{DateTime, Duration, Utc};
+use hmac::{Hmac, Mac};
+use rand::Rng;
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use reqwest::{redirect, Client, Url};
+use serde_json::Value;
+use sha2::Sha256;
+use sqlx::PgPool;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub fn validate_webhook_subscription_create_request(
+    body: &WebhookSubscriptionCreateRequest,
+) -> Result<(), GenericError> {
+    if !body.url.starts_with("https://") && !body.url.starts_with("http://") {
+        return Err(GenericError::ValidationError(
+            "Webhook url must be a valid http(s) url".to_string(),
+        ));
+    }
+    if body.event_types.is_empty() {
+        return Err(GenericError::ValidationError(
+            "Atleast one event type is required".to_string(),
+        ));
+    }
+    if body.secret.as_ref().is_some_and(|secret| secret.len() < 16) {
+        return Err(GenericError::ValidationError(
+            "Webhook secret must be atleast 16 characters".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn is_public_ip(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let octets = ip.octets();
+            !(ip.is_private()
+                || ip.is_loopback()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_multicast()
+                || ip.is_documentation()
+                || (octets[0] == 100 && (octets[1] & 0xc0) == 64))
+        }
+        IpAddr::V6(ip) => {
+            if let Some(ip) = ip.to_ipv4_mapped() {
+                return is_public_ip(&IpAddr::V4(ip));
+            }
+            let segment = ip.segments()[0];
+            !(ip.is_loopback()
+                || ip.is_unspecified()
+                || ip.is_multicast()
+                || (segment & 0xfe00) == 0xfc00
+                || (segment & 0xffc0) == 0xfe80)
+        }
+    }
+}
+
+async fn resolve_public_addrs(host: &str, port: u16) -> Result<Vec<SocketAddr>, anyhow::Error> {
+    let addrs: Vec<SocketAddr> = match host.parse::<IpAddr>() {
+        Ok(ip) => vec![SocketAddr::new(ip, port)],
+        Err(_) => tokio::net::lookup_host((host, port))
+            .await
+            .map_err(|e| anyhow!("Failed to resolve webhook host {}: {}", host, e))?
+            .collect(),
+    };
+    if addrs.is_empty() || !addrs.iter().all(|addr| is_public_ip(&addr.ip())) {
+        return Err(anyhow!("Webhook url must resolve to a public address"));
+    }
+    Ok(addrs)
+}
+
+/// Resolves the webhook host and rejects loopback, private and link-local targets so that
+/// webhooks cannot be pointed at internal services.
+pub async fn validate_webhook_target(url: &str) -> Result<(), anyhow::Error> {
+    let url = Url::parse(url).map_err(|e| anyhow!("Webhook url is invalid: {}", e))?;
+    let host = url
+        .host_str()
+        .ok_or_else(|| anyhow!("Webhook url has no host"))?
+        .trim_start_matches('[')
+        .trim_end_matches(']');
+    resolve_public_addrs(host, url.port_or_known_default().unwrap_or(443)).await?;
+    Ok(())
+}
+
+/// DNS resolver of the delivery client, the addresses it connects to are the ones checked here so a
+/// rebinding host cannot pass validation and then resolve to an internal address.
+struct PublicAddrResolver;
+
+impl Resolve for PublicAddrResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let addrs = resolve_public_addrs(name.as_str(), 0).await?;
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+pub fn get_webhook_client() -> Client {
+    Client::builder()
+        .timeout(std::time::Duration::from_secs(WEBHOOK_DELIVERY_TIMEOUT))
+        .redirect(redirect::Policy::none())
+        .dns_resolver(Arc::new(PublicAddrResolver))
+        .build()
+        .unwrap()
+}
+
+pub fn generate_webhook_secret() -> String {
+    let bytes: [u8; 32] = rand::rng().random();
+    hex::encode(bytes)
+}
+
+pub fn get_webhook_signature(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+pub fn get_webhook_retry_delay(attempt_count: i32) -> Duration {
+    let exponent = attempt_count.clamp(1, 16) as u32 - 1;
+    Duration::seconds(WEBHOOK_RETRY_BASE_DELAY * 2_i64.pow(exponent))
+}
+
+#[tracing::instrument(name = "save webhook subscription", skip(pool, body, secret))]
+pub async fn save_webhook_subscription(
+    pool: &PgPool,
+    body: &WebhookSubscriptionCreateRequest,
+    secret: &str,
+    user_id: Uuid,
+    business_id: Uuid,
+) -> Result<Uuid, anyhow::Error> {
+    let id = Uuid::new_v4();
+    sqlx::query!(
+        r#"
+        INSERT INTO webhook_subscription (id, business_id, url, secret, event_types, is_active,
+        created_on, created_by)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        "#,
+        id,
+        business_id,
+        &body.url,
+        secret,
+        &body.event_types as &[WebhookEventType],
+        true,
+        Utc::now(),
+        user_id
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e)
+            .context("A database failure occurred while saving webhook subscription")
+    })?;
+    Ok(id)
+}
+
+#[tracing::instrument(name = "fetch webhook subscriptions", skip(pool))]
+pub async fn fetch_webhook_subscriptions(
+    pool: &PgPool,
+    business_id: Uuid,
+) -> Result<Vec<WebhookSubscriptionModel>, anyhow::Error> {
+    let rows = sqlx::query_as!(
+        WebhookSubscriptionModel,
+        r#"SELECT id, business_id, url, event_types as "event_types: Vec<WebhookEventType>",
+        is_active, created_by, created_on
+        FROM webhook_subscription WHERE business_id = $1
+        ORDER BY created_on DESC"#,
+        business_id
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e)
+            .context("A database failure occurred while fetching webhook subscriptions")
+    })?;
+    Ok(rows)
+}
+
+#[tracing::instrument(name = "fetch webhook subscription by id", skip(pool))]
+pub async fn fetch_webhook_subscription_by_id(
+    pool: &PgPool,
+    subscription_id: Uuid,
+) -> Result<Option<WebhookSubscriptionModel>, anyhow::Error> {
+    let row = sqlx::query_as!(
+        WebhookSubscriptionModel,
+        r#"SELECT id, business_id, url, event_types as "event_types: Vec<WebhookEventType>",
+        is_active, created_by, created_on
+        FROM webhook_subscription WHERE id = $1"#,
+        subscription_id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e)
+            .context("A database failure occurred while fetching webhook subscription")
+    })?;
+    Ok(row)
+}
+
+#[tracing::instrument(name = "update webhook subscription status", skip(pool))]
+pub async fn update_webhook_subscription_status(
+    pool: &PgPool,
+    subscription_id: Uuid,
+    is_active: bool,
+    user_id: Uuid,
+) -> Result<(), anyhow::Error> {
+    sqlx::query!(
+        r#"
+        UPDATE webhook_subscription SET is_active = $1, updated_on = $2, updated_by = $3
+        WHERE id = $4
+        "#,
+        is_active,
+        Utc::now(),
+        user_id,
+        subscription_id
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e)
+            .context("A database failure occurred while updating webhook subscription status")
+    })?;
+    Ok(())
+}
+
+#[tracing::instrument(name = "fetch webhook deliveries", skip(pool))]
+pub async fn fetch_webhook_deliveries(
+    pool: &PgPool,
+    subscription_id: Uuid,
+    status: Option<&WebhookDeliveryStatus>,
+    limit: i32,
+    offset: i32,
+) -> Result<Vec<WebhookDeliveryModel>, anyhow::Error> {
+    let rows = sqlx::query_as!(
+        WebhookDeliveryModel,
+        r#"SELECT id, subscription_id, event_id, event_type as "event_type: WebhookEventType",
+        payload, status as "status: WebhookDeliveryStatus", attempt_count, next_attempt_on,
+        last_attempted_on, response_status, remark, delivered_on, created_on
+        FROM webhook_delivery
+        WHERE subscription_id = $1 AND ($2::webhook_delivery_status IS NULL OR status = $2)
+        ORDER BY created_on DESC LIMIT $3 OFFSET $4"#,
+        subscription_id,
+        status as Option<&WebhookDeliveryStatus>,
+        limit as i64,
+        offset as i64
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e)
+            .context("A database failure occurred while fetching webhook deliveries")
+    })?;
+    Ok(rows)
+}
+
+#[tracing::instrument(name = "reset webhook delivery", skip(pool))]
+pub async fn reset_webhook_delivery(
+    pool: &PgPool,
+    delivery_id: Uuid,
+    business_id: Uuid,
+) -> Result<bool, anyhow::Error> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE webhook_delivery AS d SET status = $1, attempt_count = 0, next_attempt_on = $2,
+        remark = NULL
+        FROM webhook_subscription AS s
+        WHERE d.subscription_id = s.id AND d.id = $3 AND s.business_id = $4
+        "#,
+        WebhookDeliveryStatus::Pending as WebhookDeliveryStatus,
+        Utc::now(),
+        delivery_id,
+        business_id
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e)
+            .context("A database failure occurred while resetting webhook delivery")
+    })?;
+    Ok(result.rows_affected() > 0)
+}
+
+#[tracing::instrument(name = "enqueue webhook event", skip(pool, data))]
+pub async fn enqueue_webhook_event(
+    pool: &PgPool,
+    business_id: Uuid,
+    event_type: WebhookEventType,
+    data: &Value,
+) -> Result<(), anyhow::Error> {
+    let created_on = Utc::now();
+    let payload = WebhookEventPayload {
+        event_id: Uuid::new_v4(),
+        event_type: &event_type,
+        business_id,
+        created_on,
+        data,
+    };
+    sqlx::query!(
+        r#"
+        INSERT INTO webhook_delivery (id, subscription_id, event_id, event_type, payload, status,
+        attempt_count, next_attempt_on, created_on)
+        SELECT gen_random_uuid(), id, $1, $2, $3, $4, 0, $5, $5
+        FROM webhook_subscription
+        WHERE business_id = $6 AND is_active AND $2 = ANY(event_types)
+        "#,
+        payload.event_id,
+        &event_type as &WebhookEventType,
+        serde_json::to_value(&payload)?,
+        WebhookDeliveryStatus::Pending as WebhookDeliveryStatus,
+        created_on,
+        business_id
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e).context("A database failure occurred while enqueuing webhook event")
+    })?;
+    Ok(())
+}
+
+pub async fn emit_webhook_event(
+    pool: &PgPool,
+    business_id: Uuid,
+    event_type: WebhookEventType,
+    data: &Value,
+) {
+    if let Err(e) = enqueue_webhook_event(pool, business_id, event_type, data).await {
+        tracing::error!("Failed to emit webhook event: {:?}", e);
+    }
+}
+
+#[tracing::instrument(name = "claim pending webhook deliveries", skip(pool))]
+pub async fn claim_pending_webhook_deliveries(
+    pool: &PgPool,
+    run_on: DateTime<Utc>,
+    limit: i64,
+) -> Result<Vec<PendingWebhookDeliveryModel>, anyhow::Error> {
+    let lease_till = run_on + Duration::seconds(WEBHOOK_DELIVERY_TIMEOUT as i64 * 3);
+    let rows = sqlx::query_as!(
+        PendingWebhookDeliveryModel,
+        r#"
+        UPDATE webhook_delivery AS d SET next_attempt_on = $1
+        FROM webhook_subscription AS s
+        WHERE d.subscription_id = s.id AND d.id IN (
+            SELECT id FROM webhook_delivery
+            WHERE status = 'pending' AND next_attempt_on <= $2
+            ORDER BY next_attempt_on LIMIT $3
+            FOR UPDATE SKIP LOCKED
+        )
+        RETURNING d.id, d.event_type as "event_type: WebhookEventType", d.payload,
+        d.attempt_count, s.url, s.secret, s.is_active
+        "#,
+        lease_till,
+        run_on,
+        limit
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e)
+            .context("A database failure occurred while claiming pending webhook deliveries")
+    })?;
+    Ok(rows)
+}
+
+#[allow(clippy::too_many_arguments)]
+#[tracing::instrument(name = "update webhook delivery attempt", skip(pool))]
+pub async fn update_webhook_delivery_attempt(
+    pool: &PgPool,
+    delivery_id: Uuid,
+    status: WebhookDeliveryStatus,
+    attempt_count: i32,
+    next_attempt_on: DateTime<Utc>,
+    response_status: Option<i32>,
+    remark: Option<&str>,
+) -> Result<(), anyhow::Error> {
+    let attempted_on = Utc::now();
+    let delivered_on = (status == WebhookDeliveryStatus::Delivered).then_some(attempted_on);
+    sqlx::query!(
+        r#"
+        UPDATE webhook_delivery SET status = $1, attempt_count = $2, next_attempt_on = $3,
+        last_attempted_on = $4, response_status = $5, remark = $6, delivered_on = $7
+        WHERE id = $8
+        "#,
+        status as WebhookDeliveryStatus,
+        attempt_count,
+        next_attempt_on,
+        attempted_on,
+        response_status,
+        remark,
+        delivered_on,
+        delivery_id
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        anyhow::Error::new(e)
+            .context("A database failure occurred while updating webhook delivery attempt")
+    })?;
+    Ok(())
+}
+
+#[tracing::instrument(name = "send webhook delivery", skip(client, delivery), fields(delivery_id = %delivery.id))]
+pub async fn send_webhook_delivery(
+    client: &Client,
+    delivery: &PendingWebhookDeliveryModel,
+) -> Result<u16, (Option<u16>, String)> {
+    validate_webhook_target(&delivery.url)
+        .await
+        .map_err(|e| (None, e.to_string()))?;
+    let body = serde_json::to_vec(&delivery.payload).map_err(|e| (None, e.to_string()))?;
+    let timestamp = Utc::now().timestamp();
+    let response = client
+        .post(&delivery.url)
+        .header("Content-Type", "application/json")
+        .header("X-Webhook-Id", delivery.id.to_string())
+        .header("X-Webhook-Event", delivery.event_type.to_string())
+        .header("X-Webhook-Timestamp", timestamp.to_string())
+        .header(
+            "X-Webhook-Signature",
+            get_webhook_signature(&delivery.secret, timestamp, &body),
+        )
+        .body(body)
+        .send()
+        .await
+        .map_err(|e| (None, e.to_string()))?;
+    let status = response.status();
+    if status.is_success() {
+        Ok(status.as_u16())
+    } else {
+        Err((
+            Some(status.as_u16()),
+            format!("Webhook endpoint responded with {}", status),
+        ))
+    }
+}
+
+#[tracing::instrument(name = "process pending webhook deliveries", skip(pool, client))]
+pub async fn process_pending_webhook_deliveries(
+    pool: &PgPool,
+    client: &Client,
+) -> Result<(), anyhow::Error> {
+    let deliveries =
+        claim_pending_webhook_deliveries(pool, Utc::now(), WEBHOOK_DELIVERY_BATCH_SIZE).await?;
+    for delivery in deliveries {
+        let attempt_count = delivery.attempt_count + 1;
+        let result = if delivery.is_active {
+            send_webhook_delivery(client, &delivery).await
+        } else {
+            Err((None, "Webhook subscription is inactive".to_string()))
+        };
+        let (status, next_attempt_on, response_status, remark) = match result {
+            Ok(response_status) => (
+                WebhookDeliveryStatus::Delivered,
+                Utc::now(),
+                Some(response_status as i32),
+                None,
+            ),
+            Err((response_status, remark)) => {
+                let status =
+                    if !delivery.is_active || attempt_count >= WEBHOOK_DELIVERY_MAX_ATTEMPTS {
+                        WebhookDeliveryStatus::Failed
+                    } else {
+                        WebhookDeliveryStatus::Pending
+                    };
+                (
+                    status,
+                    Utc::now() + get_webhook_retry_delay(attempt_count),
+                    response_status.map(|s| s as i32),
+                    Some(remark),
+                )
+            }
+        };
+        update_webhook_delivery_attempt(
+            pool,
+            delivery.id,
+            status,
+            attempt_count,
+            next_attempt_on,
+            response_status,
+            remark.as_deref(),
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+pub fn start_webhook_delivery_worker(pool: web::Data<PgPool>) {
+    let client = get_webhook_client();
+    tokio::spawn(async move {
+        let mut interval =
+            tokio::time::interval(std::time::Duration::from_secs(WEBHOOK_DELIVERY_INTERVAL));
+        loop {
+            interval.tick().await;
+            if let Err(e) = process_pending_webhook_deliveries(&pool, &client).await {
+                tracing::error!("Failed to process webhook deliveries: {:?}", e);
+            }
+        }
+    });
+}
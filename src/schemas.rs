@@ -672,7 +672,7 @@ impl NetworkCall {
     }
 }
 
-#[derive(Debug, Deserialize, sqlx::Type, Serialize, Clone, ToSchema)]
+#[derive(Debug, Deserialize, sqlx::Type, Serialize, Clone, ToSchema, PartialEq)]
 #[serde(rename_all = "snake_case")]
 #[sqlx(type_name = "ondc_np_fee_type", rename_all = "snake_case")]
 pub enum FeeType {
@@ -753,7 +753,7 @@ pub struct ONDCAuthParams {
     pub signature: String,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, ToSchema, sqlx::Type)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema, sqlx::Type, PartialEq)]
 #[sqlx(type_name = "currency_code_type", rename_all = "UPPERCASE")]
 #[serde(rename_all = "UPPERCASE")]
 pub enum CurrencyType {
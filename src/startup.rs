@@ -4,6 +4,14 @@ use crate::{middleware::SaveRequestResponse, schemas::StartUpMap};
 // use crate::middleware::tracing_middleware;
 
 use crate::routes::main_route;
+use crate::routes::payable::utils::{start_payable_reminder_worker, PayableReminderContext};
+use crate::routes::payment::utils::{start_payment_reconciler, PaymentReconcileContext};
+use crate::routes::product::utils::start_catalog_cache_expiry_worker;
+use crate::routes::recurring_order::utils::{
+    start_recurring_order_scheduler, RecurringOrderContext,
+};
+use crate::routes::saved_search::utils::{start_catalog_alert_worker, CatalogAlertContext};
+use crate::routes::webhook::utils::start_webhook_delivery_worker;
 // use actix_session::storage::RedisSessionStore;
 // use actix_session::SessionMiddleware;
 // use actix_web::cookie::Key;
@@ -62,6 +70,8 @@ async fn run(
     let chat_client = web::Data::new(configuration.chat.client());
     let redis_app = web::Data::new(configuration.redis.client());
     let es_client = web::Data::new(configuration.elastic_search.client());
+    let search_config = web::Data::new(configuration.search);
+    let ranking_profile = web::Data::new(configuration.search_ranking);
     let payment_client = web::Data::new(configuration.payment.client());
     // es_client.send().await;
     // let kafka_producer = kafka_client.create_producer().await;
@@ -74,6 +84,7 @@ async fn run(
             ws_client.clone(),
             db_pool.clone(),
             es_client.clone(),
+            search_config.clone(),
         ),
         kafka_client.kafka_observability_consumer(
             db_pool.clone(),
@@ -83,6 +94,38 @@ async fn run(
     )?;
 
     let kafka_client = web::Data::new(kafka_client);
+    start_recurring_order_scheduler(RecurringOrderContext::new(
+        db_pool.clone(),
+        ondc_obj.clone(),
+        user_client.clone(),
+        chat_client.clone(),
+        start_up_map.clone(),
+        kafka_client.clone(),
+    ));
+    start_webhook_delivery_worker(db_pool.clone());
+    start_payment_reconciler(PaymentReconcileContext::new(
+        db_pool.clone(),
+        payment_client.clone(),
+        user_client.clone(),
+        ws_client.clone(),
+    ));
+    start_catalog_cache_expiry_worker(db_pool.clone(), es_client.clone(), search_config.clone());
+    start_payable_reminder_worker(PayableReminderContext::new(
+        db_pool.clone(),
+        user_client.clone(),
+        chat_client.clone(),
+        email_client.clone(),
+    ));
+    start_catalog_alert_worker(CatalogAlertContext::new(
+        db_pool.clone(),
+        es_client.clone(),
+        search_config.clone(),
+        ranking_profile.clone(),
+        user_client.clone(),
+        chat_client.clone(),
+        email_client.clone(),
+        ws_client.clone(),
+    ));
     let server = HttpServer::new(move || {
         App::new()
             .app_data(web::PayloadConfig::new(1 << 25))
@@ -98,6 +141,8 @@ async fn run(
             .app_data(chat_client.clone())
             .app_data(kafka_client.clone())
             .app_data(es_client.clone())
+            .app_data(search_config.clone())
+            .app_data(ranking_profile.clone())
             .app_data(payment_client.clone())
             .app_data(secret_obj.clone())
             .app_data(application_obj.clone())
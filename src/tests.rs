@@ -5,12 +5,14 @@ pub mod tests {
     use crate::configuration::get_configuration;
     use crate::constants::DUMMY_DOMAIN;
     use crate::database::get_connection_pool;
+    use crate::elastic_search_client::{get_index_version, get_mapping_hash};
     use crate::routes::order::schemas::{PaymentSettlementPhase, PaymentSettlementType};
     use crate::schemas::{FeeType, KycStatus, RegisteredNetworkParticipant, Status};
     use crate::user_client::{BusinessAccount, MaskingType, UserAccount, UserVector, VectorType};
-    use crate::utils::validate_business_account_active;
+    use crate::utils::{get_duration_from_iso8601, validate_business_account_active};
     use bigdecimal::BigDecimal;
     use dotenv::dotenv;
+    use serde_json::json;
     use sqlx::PgPool;
     use uuid::Uuid;
     pub async fn get_test_pool() -> PgPool {
@@ -154,4 +156,68 @@ pub mod tests {
         let validate_response = validate_business_account_active(&business_account);
         assert_eq!(validate_response, None);
     }
+
+    #[test]
+    fn test_duration_from_iso8601() {
+        assert_eq!(
+            get_duration_from_iso8601("P1D"),
+            Some(chrono::Duration::days(1))
+        );
+        assert_eq!(
+            get_duration_from_iso8601("PT30S"),
+            Some(chrono::Duration::seconds(30))
+        );
+        assert_eq!(
+            get_duration_from_iso8601("P1DT2H30M"),
+            Some(chrono::Duration::minutes(1590))
+        );
+        assert_eq!(get_duration_from_iso8601("P"), None);
+        assert_eq!(get_duration_from_iso8601("1D"), None);
+        assert_eq!(get_duration_from_iso8601("PT5X"), None);
+        assert_eq!(get_duration_from_iso8601("P100000000000000D"), None);
+        assert_eq!(get_duration_from_iso8601("P99999999999999999999D"), None);
+    }
+
+    #[test]
+    fn test_elastic_search_index_version() {
+        assert_eq!(
+            get_index_version("dev_provider_item", "dev_provider_item_v3"),
+            Some(3)
+        );
+        assert_eq!(
+            get_index_version("dev_provider_item", "dev_provider_item_v12"),
+            Some(12)
+        );
+        assert_eq!(
+            get_index_version("dev_provider_item", "dev_provider_item"),
+            None
+        );
+        assert_eq!(
+            get_index_version("dev_provider_item", "dev_provider_item_variant_v1"),
+            None
+        );
+        assert_eq!(
+            get_index_version("dev_provider_item", "dev_provider_v1"),
+            None
+        );
+        assert_eq!(
+            get_index_version("dev_provider_item", "dev_provider_item_vx"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_elastic_search_mapping_hash() {
+        let mapping = json!({ "mappings": { "properties": { "id": { "type": "keyword" } } } });
+        let changed_mapping = json!({ "mappings": { "properties": { "id": { "type": "text" } } } });
+        assert_eq!(
+            get_mapping_hash(&mapping),
+            get_mapping_hash(&mapping.clone())
+        );
+        assert_ne!(
+            get_mapping_hash(&mapping),
+            get_mapping_hash(&changed_mapping)
+        );
+        assert_eq!(get_mapping_hash(&mapping).len(), 64);
+    }
 }
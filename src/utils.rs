@@ -72,6 +72,43 @@ pub fn get_ondc_params_from_header(header: &str) -> Result<ONDCAuthParams, anyho
     })
 }
 
+/// Parses an ISO 8601 duration such as `P1D` or `PT30M`, treating a year as
+/// 365 days and a month as 30 days. Returns `None` when the duration overflows.
+pub fn get_duration_from_iso8601(value: &str) -> Option<Duration> {
+    let value = value.trim().strip_prefix('P')?;
+    let (date_part, time_part) = value.split_once('T').unwrap_or((value, ""));
+    if date_part.is_empty() && time_part.is_empty() {
+        return None;
+    }
+    let mut duration = Duration::zero();
+    for (part, is_time) in [(date_part, false), (time_part, true)] {
+        let mut number = String::new();
+        for ch in part.chars() {
+            if ch.is_ascii_digit() {
+                number.push(ch);
+                continue;
+            }
+            let amount: i64 = number.parse().ok()?;
+            number.clear();
+            let part_duration = match (ch, is_time) {
+                ('Y', false) => Duration::try_days(amount.checked_mul(365)?),
+                ('M', false) => Duration::try_days(amount.checked_mul(30)?),
+                ('W', false) => Duration::try_weeks(amount),
+                ('D', false) => Duration::try_days(amount),
+                ('H', true) => Duration::try_hours(amount),
+                ('M', true) => Duration::try_minutes(amount),
+                ('S', true) => Duration::try_seconds(amount),
+                _ => return None,
+            }?;
+            duration = duration.checked_add(&part_duration)?;
+        }
+        if !number.is_empty() {
+            return None;
+        }
+    }
+    Some(duration)
+}
+
 pub fn bytes_to_payload(buf: web::Bytes) -> Payload {
     let (_, mut pl) = h1::Payload::create(true);
     pl.unread_data(buf);
@@ -18,6 +18,7 @@ pub enum WebSocketActionType {
     OrderCancel,
     OrderUpdate,
     OrderPayment,
+    CatalogAlert,
 }
 
 #[derive(Debug, Serialize, PartialEq)]